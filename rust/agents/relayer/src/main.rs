@@ -17,6 +17,7 @@ mod merkle_tree_builder;
 mod msg;
 mod prover;
 mod relayer;
+mod server;
 mod settings;
 
 #[tokio::main(flavor = "current_thread")]
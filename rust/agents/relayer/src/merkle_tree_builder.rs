@@ -110,6 +110,20 @@ impl MerkleTreeBuilder {
         self.prover.count() as u32
     }
 
+    /// Forget the leaves from `count` onwards, e.g. because a reorg removed
+    /// their messages, by ingesting the leaves before them from the db again.
+    pub fn truncate(&mut self, count: u32) -> Result<(), MerkleTreeBuilderError> {
+        if count >= self.count() {
+            return Ok(());
+        }
+        self.prover = Prover::default();
+        self.incremental = IncrementalMerkle::default();
+        for nonce in 0..count {
+            self.ingest_nonce(nonce)?;
+        }
+        Ok(())
+    }
+
     #[instrument(err, skip(self), level = "debug")]
     pub async fn update_to_index(&mut self, index: u32) -> Result<(), MerkleTreeBuilderError> {
         if index >= self.count() {
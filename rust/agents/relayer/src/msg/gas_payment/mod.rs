@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use eyre::Result;
 use tracing::{debug, error, trace};
 
-use hyperlane_base::db::HyperlaneRocksDB;
+use hyperlane_base::db::{GasAccountKey, HyperlaneRocksDB};
 use hyperlane_core::{
     HyperlaneMessage, InterchainGasExpenditure, InterchainGasPayment, TxCostEstimate, TxOutcome,
     U256,
@@ -15,7 +15,7 @@ use crate::settings::{
     matching_list::MatchingList, GasPaymentEnforcementConf, GasPaymentEnforcementPolicy,
 };
 
-use self::policies::{GasPaymentPolicyMinimum, GasPaymentPolicyNone};
+use self::policies::{GasPaymentPolicyMinimum, GasPaymentPolicyNone, GasPaymentPolicyValueBased};
 
 mod policies;
 
@@ -30,6 +30,13 @@ pub trait GasPaymentPolicy: Debug + Send + Sync {
         current_expenditure: &InterchainGasExpenditure,
         tx_cost_estimate: &TxCostEstimate,
     ) -> Result<Option<U256>>;
+
+    /// The value of an amount of the destination chain's native token in the
+    /// origin chain's native token, for policies which value payments. The
+    /// gas accounts of senders are only kept for messages whose policy does.
+    fn origin_value_of(&self, _message: &HyperlaneMessage, _tokens: U256) -> Option<U256> {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -60,6 +67,20 @@ impl GasPaymentEnforcer {
                         gas_fraction_numerator: n,
                         gas_fraction_denominator: d,
                     } => Box::new(GasPaymentPolicyOnChainFeeQuoting::new(n, d)),
+                    GasPaymentEnforcementPolicy::ValueBased {
+                        token_prices,
+                        gas_prices,
+                        payment_fraction_numerator: n,
+                        payment_fraction_denominator: d,
+                        max_sender_deficit,
+                    } => Box::new(GasPaymentPolicyValueBased::new(
+                        token_prices,
+                        gas_prices,
+                        n,
+                        d,
+                        max_sender_deficit,
+                        db.clone(),
+                    )),
                 };
                 (p, cfg.matching_list)
             })
@@ -70,16 +91,9 @@ impl GasPaymentEnforcer {
 }
 
 impl GasPaymentEnforcer {
-    /// Returns Some(gas_limit) if the enforcer has approved the transaction or
-    /// None if the transaction is not approved.
-    pub async fn message_meets_gas_payment_requirement(
-        &self,
-        message: &HyperlaneMessage,
-        tx_cost_estimate: &TxCostEstimate,
-    ) -> Result<Option<U256>> {
-        let msg_id = message.id();
-        let current_payment = self.db.retrieve_gas_payment_by_message_id(msg_id)?;
-        let current_expenditure = self.db.retrieve_gas_expenditure_by_message_id(msg_id)?;
+    /// Returns the policy to use for a message, which is the first one whose
+    /// matching list it matches.
+    fn policy_for(&self, message: &HyperlaneMessage) -> Option<&dyn GasPaymentPolicy> {
         for (policy, whitelist) in &self.policies {
             if !whitelist.msg_matches(message, true) {
                 trace!(
@@ -97,50 +111,107 @@ impl GasPaymentEnforcer {
                 ?whitelist,
                 "Message matched whitelist for policy"
             );
-            debug!(
+            return Some(policy.as_ref());
+        }
+        None
+    }
+
+    /// Returns Some(gas_limit) if the enforcer has approved the transaction or
+    /// None if the transaction is not approved.
+    pub async fn message_meets_gas_payment_requirement(
+        &self,
+        message: &HyperlaneMessage,
+        tx_cost_estimate: &TxCostEstimate,
+    ) -> Result<Option<U256>> {
+        let Some(policy) = self.policy_for(message) else {
+            error!(
                 msg=%message,
-                ?policy,
-                ?current_payment,
-                ?current_expenditure,
-                "Evaluating if message meets gas payment requirement",
+                policies=?self.policies,
+                "No gas payment policy matched for message; consider adding a default policy to the end of the policies array which uses a wildcard whitelist."
             );
-            return policy
-                .message_meets_gas_payment_requirement(
-                    message,
-                    &current_payment,
-                    &current_expenditure,
-                    tx_cost_estimate,
-                )
-                .await;
-        }
+            return Ok(None);
+        };
 
-        error!(
+        let msg_id = message.id();
+        let current_payment = self.db.retrieve_gas_payment_by_message_id(msg_id)?;
+        let current_expenditure = self.db.retrieve_gas_expenditure_by_message_id(msg_id)?;
+        debug!(
             msg=%message,
-            policies=?self.policies,
-            "No gas payment policy matched for message; consider adding a default policy to the end of the policies array which uses a wildcard whitelist."
+            ?policy,
+            ?current_payment,
+            ?current_expenditure,
+            "Evaluating if message meets gas payment requirement",
         );
-        Ok(None)
+        policy
+            .message_meets_gas_payment_requirement(
+                message,
+                &current_payment,
+                &current_expenditure,
+                tx_cost_estimate,
+            )
+            .await
     }
 
+    /// Record the gas spent by a transaction that delivered or attempted to
+    /// deliver the message, and charge its cost to the sender's gas account if
+    /// the message's policy values payments. The sender is credited with
+    /// their payment once the message is delivered.
     pub fn record_tx_outcome(&self, message: &HyperlaneMessage, outcome: TxOutcome) -> Result<()> {
+        let tokens_used = outcome.gas_used * outcome.gas_price;
         self.db.process_gas_expenditure(InterchainGasExpenditure {
             message_id: message.id(),
             gas_used: outcome.gas_used,
-            tokens_used: outcome.gas_used * outcome.gas_price,
+            tokens_used,
         })?;
+
+        let Some(cost) = self
+            .policy_for(message)
+            .and_then(|policy| policy.origin_value_of(message, tokens_used))
+        else {
+            return Ok(());
+        };
+        let payment = if outcome.executed {
+            Some(
+                self.db
+                    .retrieve_gas_payment_by_message_id(message.id())?
+                    .payment,
+            )
+        } else {
+            None
+        };
+        let account = self.db.update_gas_account(
+            &GasAccountKey {
+                destination: message.destination,
+                sender: message.sender,
+            },
+            payment,
+            cost,
+        )?;
+        debug!(
+            msg=%message,
+            ?account,
+            surplus=%account.surplus(),
+            deficit=%account.deficit(),
+            "Updated gas account of message sender"
+        );
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
     use std::str::FromStr;
 
-    use hyperlane_base::db::{test_utils, HyperlaneRocksDB};
-    use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, TxCostEstimate, H160, H256, U256};
+    use hyperlane_base::db::{test_utils, GasAccount, GasAccountKey, HyperlaneRocksDB};
+    use hyperlane_core::{
+        HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, LogMeta, TxCostEstimate,
+        TxOutcome, H160, H256, H512, U256,
+    };
 
     use crate::settings::{
         matching_list::MatchingList, GasPaymentEnforcementConf, GasPaymentEnforcementPolicy,
+        NativeTokenPrice,
     };
 
     use super::GasPaymentEnforcer;
@@ -279,4 +350,76 @@ mod test {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn test_value_based_policy_keeps_gas_accounts() {
+        test_utils::run_test_db(|db| async move {
+            let hyperlane_db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("test_value_based_policy_keeps_gas_accounts"),
+                db,
+            );
+            // The origin's token is worth twice the destination's
+            let price = |price: u64| NativeTokenPrice {
+                price: U256::from(price),
+                decimals: 18,
+            };
+            let enforcer = GasPaymentEnforcer::new(
+                vec![GasPaymentEnforcementConf {
+                    policy: GasPaymentEnforcementPolicy::ValueBased {
+                        token_prices: HashMap::from([(1, price(2)), (2, price(1))]),
+                        gas_prices: HashMap::new(),
+                        payment_fraction_numerator: 1,
+                        payment_fraction_denominator: 1,
+                        max_sender_deficit: None,
+                    },
+                    matching_list: MatchingList::default(),
+                }],
+                hyperlane_db.clone(),
+            );
+            let message = HyperlaneMessage {
+                origin: 1,
+                destination: 2,
+                ..HyperlaneMessage::default()
+            };
+            hyperlane_db
+                .process_gas_payment(
+                    InterchainGasPayment {
+                        message_id: message.id(),
+                        payment: U256::from(1000),
+                        gas_amount: U256::zero(),
+                    },
+                    &LogMeta::default(),
+                )
+                .unwrap();
+            let outcome = |executed| TxOutcome {
+                transaction_id: H512::zero(),
+                executed,
+                gas_used: U256::from(100),
+                gas_price: U256::from(10),
+            };
+
+            // A reverted delivery is charged to the sender
+            enforcer
+                .record_tx_outcome(&message, outcome(false))
+                .unwrap();
+            // A successful one also credits the sender with their payment
+            enforcer.record_tx_outcome(&message, outcome(true)).unwrap();
+
+            let account = hyperlane_db
+                .retrieve_gas_account(&GasAccountKey {
+                    destination: 2,
+                    sender: H256::zero(),
+                })
+                .unwrap();
+            assert_eq!(
+                account,
+                GasAccount {
+                    payment: U256::from(1000),
+                    cost: U256::from(1000),
+                    delivered: 1,
+                }
+            );
+        })
+        .await;
+    }
 }
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use derive_new::new;
+use eyre::Result;
+use tracing::{debug, warn};
+
+use hyperlane_base::db::{GasAccountKey, HyperlaneRocksDB};
+use hyperlane_core::{
+    HyperlaneMessage, InterchainGasExpenditure, InterchainGasPayment, TxCostEstimate, U256,
+};
+
+use crate::msg::gas_payment::GasPaymentPolicy;
+use crate::settings::NativeTokenPrice;
+
+/// Compares the value of a payment in the origin chain's native token with
+/// the estimated cost of delivery in the destination chain's native token.
+/// Keeps the gas accounts of senders in the origin's database so senders whose
+/// deliveries cost more than they paid can be made to pay in full.
+#[derive(Debug, new)]
+pub struct GasPaymentPolicyValueBased {
+    /// Price of the native token of each domain
+    token_prices: HashMap<u32, NativeTokenPrice>,
+    /// Gas prices to use instead of the estimated gas price of a destination
+    gas_prices: HashMap<u32, U256>,
+    /// Numerator of the fraction of the estimated cost that must be paid
+    fractional_numerator: u64,
+    /// Denominator of the fraction of the estimated cost that must be paid
+    fractional_denominator: u64,
+    /// Deficit in the unit of the token prices, scaled by 10^18, above which a
+    /// sender must pay the full estimated cost
+    max_sender_deficit: Option<U256>,
+    db: HyperlaneRocksDB,
+}
+
+impl GasPaymentPolicyValueBased {
+    /// Value of an amount of a domain's native token in the unit of the token
+    /// prices, scaled by 10^18.
+    fn common_value(&self, domain: u32, amount: U256) -> Option<U256> {
+        let price = self.token_prices.get(&domain)?;
+        amount
+            .checked_mul(price.price)?
+            .checked_div(U256::exp10(price.decimals as usize))
+    }
+
+    /// Whether delivering the sender's messages has cost more than they paid
+    /// by more than the configured limit.
+    fn sender_over_deficit_limit(&self, message: &HyperlaneMessage) -> Result<bool> {
+        let Some(max_deficit) = self.max_sender_deficit else { return Ok(false) };
+        let account = self.db.retrieve_gas_account(&GasAccountKey {
+            destination: message.destination,
+            sender: message.sender,
+        })?;
+        match self.common_value(message.origin, account.deficit()) {
+            Some(deficit) if deficit > max_deficit => {
+                debug!(
+                    msg=%message,
+                    ?account,
+                    %deficit,
+                    "Sender's gas deficit is over the limit, requiring the full estimated cost to be paid"
+                );
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[async_trait]
+impl GasPaymentPolicy for GasPaymentPolicyValueBased {
+    async fn message_meets_gas_payment_requirement(
+        &self,
+        message: &HyperlaneMessage,
+        current_payment: &InterchainGasPayment,
+        current_expenditure: &InterchainGasExpenditure,
+        tx_cost_estimate: &TxCostEstimate,
+    ) -> Result<Option<U256>> {
+        let gas_price = self
+            .gas_prices
+            .get(&message.destination)
+            .copied()
+            .unwrap_or(tx_cost_estimate.gas_price);
+        let estimated_cost = tx_cost_estimate
+            .enforceable_gas_limit()
+            .saturating_mul(gas_price);
+        let (Some(estimated_cost), Some(spent)) = (
+            self.origin_value_of(message, estimated_cost),
+            self.origin_value_of(message, current_expenditure.tokens_used),
+        ) else {
+            warn!(
+                origin = message.origin,
+                destination = message.destination,
+                "Missing native token prices to value the gas payment of message"
+            );
+            return Ok(None);
+        };
+        let available = current_payment.payment.saturating_sub(spent);
+
+        let (numerator, denominator) = if self.fractional_numerator < self.fractional_denominator
+            && self.sender_over_deficit_limit(message)?
+        {
+            (1, 1)
+        } else {
+            (self.fractional_numerator, self.fractional_denominator)
+        };
+        if available.saturating_mul(denominator.into())
+            >= estimated_cost.saturating_mul(numerator.into())
+        {
+            Ok(Some(tx_cost_estimate.gas_limit))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn origin_value_of(&self, message: &HyperlaneMessage, tokens: U256) -> Option<U256> {
+        let origin = self.token_prices.get(&message.origin)?;
+        let destination = self.token_prices.get(&message.destination)?;
+        tokens
+            .checked_mul(destination.price)?
+            .checked_mul(U256::exp10(origin.decimals as usize))?
+            .checked_div(
+                origin
+                    .price
+                    .checked_mul(U256::exp10(destination.decimals as usize))?,
+            )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{HyperlaneDomain, H256};
+
+    use super::*;
+
+    const ORIGIN: u32 = 1;
+    const DESTINATION: u32 = 2;
+    const ETHER: U256 = U256([1_000_000_000_000_000_000, 0, 0, 0]);
+
+    /// The origin's token is worth 2000 times the destination's, so delivery
+    /// estimated at 10^15 destination wei costs 5 * 10^11 origin wei.
+    const COST_ESTIMATE: TxCostEstimate = TxCostEstimate {
+        gas_limit: U256([100_000, 0, 0, 0]),
+        gas_price: U256([10_000_000_000, 0, 0, 0]),
+        l2_gas_limit: None,
+    };
+    const ORIGIN_COST: U256 = U256([500_000_000_000, 0, 0, 0]);
+
+    fn policy(
+        db: HyperlaneRocksDB,
+        fraction: (u64, u64),
+        max_sender_deficit: Option<U256>,
+    ) -> GasPaymentPolicyValueBased {
+        let price = |whole: u64| NativeTokenPrice {
+            price: ETHER * whole,
+            decimals: 18,
+        };
+        GasPaymentPolicyValueBased::new(
+            HashMap::from([(ORIGIN, price(2000)), (DESTINATION, price(1))]),
+            HashMap::new(),
+            fraction.0,
+            fraction.1,
+            max_sender_deficit,
+            db,
+        )
+    }
+
+    fn message() -> HyperlaneMessage {
+        HyperlaneMessage {
+            origin: ORIGIN,
+            destination: DESTINATION,
+            ..HyperlaneMessage::default()
+        }
+    }
+
+    fn current_payment(payment: U256) -> InterchainGasPayment {
+        InterchainGasPayment {
+            message_id: H256::zero(),
+            payment,
+            gas_amount: U256::zero(),
+        }
+    }
+
+    fn current_expenditure(tokens_used: U256) -> InterchainGasExpenditure {
+        InterchainGasExpenditure {
+            message_id: H256::zero(),
+            gas_used: U256::zero(),
+            tokens_used,
+        }
+    }
+
+    fn test_db(db: hyperlane_base::db::DB) -> HyperlaneRocksDB {
+        HyperlaneRocksDB::new(&HyperlaneDomain::new_test_domain("test_value_based"), db)
+    }
+
+    #[tokio::test]
+    async fn test_payment_valued_in_origin_token() {
+        test_utils::run_test_db(|db| async move {
+            let policy = policy(test_db(db), (1, 1), None);
+            let meets = |payment, tokens_used| {
+                let policy = &policy;
+                async move {
+                    policy
+                        .message_meets_gas_payment_requirement(
+                            &message(),
+                            &current_payment(payment),
+                            &current_expenditure(tokens_used),
+                            &COST_ESTIMATE,
+                        )
+                        .await
+                        .unwrap()
+                }
+            };
+
+            assert_eq!(meets(ORIGIN_COST - 1, U256::zero()).await, None);
+            assert_eq!(
+                meets(ORIGIN_COST, U256::zero()).await,
+                Some(COST_ESTIMATE.gas_limit)
+            );
+            // 2000 destination wei already spent are worth 1 origin wei
+            assert_eq!(meets(ORIGIN_COST, U256::from(2000)).await, None);
+            assert_eq!(
+                meets(ORIGIN_COST + 1, U256::from(2000)).await,
+                Some(COST_ESTIMATE.gas_limit)
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_price() {
+        test_utils::run_test_db(|db| async move {
+            let policy = policy(test_db(db), (1, 1), None);
+            let message = HyperlaneMessage {
+                destination: 3,
+                ..message()
+            };
+
+            assert_eq!(
+                policy
+                    .message_meets_gas_payment_requirement(
+                        &message,
+                        &current_payment(ETHER),
+                        &current_expenditure(U256::zero()),
+                        &COST_ESTIMATE,
+                    )
+                    .await
+                    .unwrap(),
+                None
+            );
+            assert_eq!(policy.origin_value_of(&message, ETHER), None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_sender_over_deficit_limit_pays_in_full() {
+        test_utils::run_test_db(|db| async move {
+            let db = test_db(db);
+            // A deficit of one origin token is worth 2000 in the common unit
+            let policy = policy(db.clone(), (1, 2), Some(ETHER * 1000));
+            let meets = |payment| {
+                let policy = &policy;
+                async move {
+                    policy
+                        .message_meets_gas_payment_requirement(
+                            &message(),
+                            &current_payment(payment),
+                            &current_expenditure(U256::zero()),
+                            &COST_ESTIMATE,
+                        )
+                        .await
+                        .unwrap()
+                }
+            };
+
+            assert!(meets(ORIGIN_COST / 2).await.is_some());
+
+            let key = GasAccountKey {
+                destination: DESTINATION,
+                sender: H256::zero(),
+            };
+            db.update_gas_account(&key, None, ETHER / 2).unwrap();
+            assert!(meets(ORIGIN_COST / 2).await.is_some());
+
+            db.update_gas_account(&key, None, ETHER).unwrap();
+            assert!(meets(ORIGIN_COST / 2).await.is_none());
+            assert!(meets(ORIGIN_COST).await.is_some());
+        })
+        .await;
+    }
+}
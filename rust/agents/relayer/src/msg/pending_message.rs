@@ -1,16 +1,20 @@
+use std::collections::BTreeSet;
 use std::fmt::{Debug, Formatter};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use derive_new::new;
 use eyre::{Context, Result};
-use hyperlane_base::db::HyperlaneRocksDB;
-use prometheus::{IntCounter, IntGauge};
+use hyperlane_base::db::{unix_timestamp, HyperlaneRocksDB, MessageStatus};
+use prometheus::{Histogram, IntCounter, IntGauge};
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use hyperlane_base::CoreMetrics;
-use hyperlane_core::{HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox, U256};
+use hyperlane_core::{
+    BatchItem, ChainCommunicationError, HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox,
+    TxOutcome, H256, U256,
+};
 
 use super::{
     gas_payment::GasPaymentEnforcer,
@@ -26,6 +30,13 @@ const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
     Duration::from_secs(60 * 10)
 };
 
+/// Gas used by a batch transaction on top of the calls delivering its messages
+const BATCH_BASE_GAS_OVERHEAD: u64 = 30_000;
+
+/// Gas used by a batch transaction to make each call delivering a message, e.g.
+/// to encode the call and decode its result
+const BATCH_CALL_GAS_OVERHEAD: u64 = 10_000;
+
 /// The message context contains the links needed to submit a message. Each
 /// instance is for a unique origin -> destination pairing.
 pub struct MessageContext {
@@ -60,6 +71,25 @@ pub struct PendingMessage {
     last_attempted_at: Instant,
     #[new(default)]
     next_attempt_after: Option<Instant>,
+    #[new(default)]
+    timeline: MessageTimeline,
+}
+
+/// When a message reached each stage of delivery, for measuring latencies.
+#[derive(Debug, Default, Clone, Copy, new)]
+pub struct MessageTimeline {
+    /// Unix timestamp of when the message was indexed. Only set the first
+    /// time a message is queued, so its latency isn't measured again after a
+    /// restart.
+    indexed_at: Option<u64>,
+    /// Unix timestamp the message is waiting to be delivered since.
+    undelivered_since: Option<u64>,
+    /// When the message was last prepared for submission.
+    #[new(default)]
+    prepared_at: Option<Instant>,
+    /// When the transaction delivering the message was included.
+    #[new(default)]
+    submitted_at: Option<Instant>,
 }
 
 /// State for the next submission attempt generated by a prepare call.
@@ -100,10 +130,27 @@ impl Eq for PendingMessage {}
 
 #[async_trait]
 impl PendingOperation for PendingMessage {
+    fn id(&self) -> H256 {
+        self.message.id()
+    }
+
     fn domain(&self) -> &HyperlaneDomain {
         self.ctx.destination_mailbox.domain()
     }
 
+    fn num_retries(&self) -> u32 {
+        self.num_retries
+    }
+
+    fn retry_now(&mut self) {
+        self.reset_attempts();
+    }
+
+    fn on_drop(&mut self, reason: &str) {
+        self.record_status(MessageStatus::Dropped(reason.to_owned()));
+        self.stop_waiting_for_delivery();
+    }
+
     #[instrument]
     async fn prepare(&mut self) -> PendingOperationResult {
         make_op_try!(|| self.on_reprepare());
@@ -142,6 +189,7 @@ impl PendingOperation for PendingMessage {
                 recipient=?self.message.recipient,
                 "Dropping message because recipient is not a contract"
             );
+            self.on_drop("Recipient is not a contract");
             return PendingOperationResult::Drop;
         }
 
@@ -161,6 +209,7 @@ impl PendingOperation for PendingMessage {
             "building metadata"
         ) else {
             info!("Could not fetch metadata");
+            self.record_status(MessageStatus::MetadataUnavailable);
             return self.on_reprepare();
         };
 
@@ -186,6 +235,7 @@ impl PendingOperation for PendingMessage {
         )
             else {
                 info!(?tx_cost_estimate, "Gas payment requirement not met yet");
+                self.record_status(MessageStatus::GasUnderpaid);
                 return self.on_reprepare();
             };
 
@@ -200,6 +250,7 @@ impl PendingOperation for PendingMessage {
         if let Some(max_limit) = self.ctx.transaction_gas_limit {
             if gas_limit > max_limit {
                 info!("Message delivery estimated gas exceeds max gas limit");
+                self.record_status(MessageStatus::GasLimitExceeded);
                 return self.on_reprepare();
             }
         }
@@ -208,6 +259,13 @@ impl PendingOperation for PendingMessage {
             metadata,
             gas_limit,
         }));
+        if let Some(indexed_at) = self.timeline.indexed_at.take() {
+            self.ctx
+                .metrics
+                .index_to_prepare
+                .observe(unix_timestamp().saturating_sub(indexed_at) as f64);
+        }
+        self.timeline.prepared_at = Some(Instant::now());
         PendingOperationResult::Success
     }
 
@@ -245,6 +303,8 @@ impl PendingOperation for PendingMessage {
                 txid=?tx_outcome.transaction_id,
                 "Message successfully processed by transaction"
             );
+            self.record_status(MessageStatus::Submitted);
+            self.record_submission();
             self.submitted = true;
             self.reset_attempts();
             self.next_attempt_after = Some(Instant::now() + CONFIRM_DELAY);
@@ -254,6 +314,7 @@ impl PendingOperation for PendingMessage {
                 txid=?tx_outcome.transaction_id,
                 "Transaction attempting to process message reverted"
             );
+            self.record_status(MessageStatus::Reverted);
             self.on_reprepare()
         }
     }
@@ -289,12 +350,13 @@ impl PendingOperation for PendingMessage {
             );
             PendingOperationResult::Success
         } else {
+            self.record_status(MessageStatus::Queued);
             self.reset_attempts();
             self.on_reprepare()
         }
     }
 
-    fn _next_attempt_after(&self) -> Option<Instant> {
+    fn next_attempt_after(&self) -> Option<Instant> {
         self.next_attempt_after
     }
 
@@ -304,6 +366,160 @@ impl PendingOperation for PendingMessage {
     }
 }
 
+/// Submits prepared operations for the same destination, delivering as many
+/// of them together in a single transaction as the destination's transaction
+/// gas limit allows. Returns each operation along with the result of
+/// submitting it, or an error if a critical failure occurred.
+///
+/// Operations are submitted individually instead if the destination mailbox
+/// doesn't support batching, or if a batch transaction fails outright or
+/// doesn't deliver any of its messages.
+pub(crate) async fn submit_batch(
+    ops: Vec<Box<DynPendingOperation>>,
+) -> Result<Vec<(Box<DynPendingOperation>, PendingOperationResult)>> {
+    // Group the operations so that each group's total gas fits in a transaction.
+    let mut groups: Vec<Vec<Box<DynPendingOperation>>> = vec![];
+    let mut singles = vec![];
+    let mut group_gas_limit = U256::zero();
+    for mut op in ops {
+        let msg = as_pending_message(&mut op);
+        let Some(gas_limit) = msg.batch_gas_limit() else {
+            // e.g. it was already delivered, so there is nothing to batch
+            singles.push(vec![op]);
+            continue;
+        };
+        let max_gas_limit = msg.ctx.transaction_gas_limit;
+        let call_gas_limit = batch_call_gas_limit(gas_limit);
+        let total_gas_limit = group_gas_limit.saturating_add(call_gas_limit);
+        match groups.last_mut() {
+            Some(group)
+                if max_gas_limit.map_or(true, |max| {
+                    total_gas_limit.saturating_add(BATCH_BASE_GAS_OVERHEAD.into()) <= max
+                }) =>
+            {
+                group.push(op);
+                group_gas_limit = total_gas_limit;
+            }
+            _ => {
+                groups.push(vec![op]);
+                group_gas_limit = call_gas_limit;
+            }
+        }
+    }
+    groups.extend(singles);
+
+    let mut results = Vec::new();
+    for mut group in groups {
+        if group.len() > 1 {
+            if let Some(group_results) = submit_group(&mut group).await? {
+                results.extend(group.into_iter().zip(group_results));
+                continue;
+            }
+        }
+        for mut op in group {
+            let result = op.submit().await;
+            results.push((op, result));
+        }
+    }
+    Ok(results)
+}
+
+/// Delivers a group of prepared messages in a single transaction. Returns
+/// `None` if they should be submitted individually instead.
+async fn submit_group(
+    ops: &mut [Box<DynPendingOperation>],
+) -> Result<Option<Vec<PendingOperationResult>>> {
+    let mut msgs: Vec<&mut PendingMessage> =
+        ops.iter_mut().map(|op| as_pending_message(op)).collect();
+    let ctx = msgs[0].ctx.clone();
+    let items: Vec<BatchItem> = msgs
+        .iter()
+        .map(|msg| {
+            let state = msg
+                .submission_data
+                .as_ref()
+                .expect("Pending message must be prepared before it can be submitted");
+            BatchItem {
+                message: msg.message.clone(),
+                metadata: state.metadata.clone(),
+                gas_limit: state.gas_limit,
+            }
+        })
+        .collect();
+    let tx_gas_limit = batch_tx_gas_limit(items.iter().map(|item| item.gas_limit));
+
+    let tx_outcomes = match ctx
+        .destination_mailbox
+        .process_batch(&items, Some(tx_gas_limit))
+        .await
+    {
+        Ok(tx_outcomes) => tx_outcomes,
+        Err(ChainCommunicationError::BatchingNotSupported) => {
+            debug!("Destination doesn't support batching, submitting messages individually");
+            return Ok(None);
+        }
+        Err(e) => {
+            warn!(error=?e, "Error when processing message batch, submitting messages individually");
+            return Ok(None);
+        }
+    };
+
+    // The gas of a batch that didn't deliver anything isn't recorded against
+    // its messages, since it would be recorded again when they are submitted
+    // individually.
+    if !tx_outcomes.iter().any(|tx_outcome| tx_outcome.executed) {
+        info!(
+            txid=?tx_outcomes.first().map(|tx_outcome| tx_outcome.transaction_id),
+            batch_size=msgs.len(),
+            "Transaction attempting to process message batch reverted, submitting messages individually"
+        );
+        return Ok(None);
+    }
+    // The messages may come from different origins, each with its own database
+    for (msg, tx_outcome) in msgs.iter().zip(&tx_outcomes) {
+        msg.ctx
+            .origin_gas_payment_enforcer
+            .record_tx_outcome(&msg.message, *tx_outcome)
+            .context("When recording tx outcome")?;
+    }
+
+    Ok(Some(
+        msgs.iter_mut()
+            .zip(tx_outcomes)
+            .map(|(msg, tx_outcome)| msg.on_batch_tx_outcome(tx_outcome))
+            .collect(),
+    ))
+}
+
+/// The gas limit of a transaction delivering messages with the given gas
+/// limits together.
+fn batch_tx_gas_limit(gas_limits: impl IntoIterator<Item = U256>) -> U256 {
+    gas_limits
+        .into_iter()
+        .fold(U256::from(BATCH_BASE_GAS_OVERHEAD), |acc, gas_limit| {
+            acc.saturating_add(batch_call_gas_limit(gas_limit))
+        })
+}
+
+/// The gas a batch transaction needs to deliver a message with the given gas
+/// limit. Besides the overhead of making the call, a call is only forwarded
+/// 63/64 of the gas left (EIP-150), so the message's gas limit is increased by
+/// 1/63 to make sure it's available to the message.
+fn batch_call_gas_limit(gas_limit: U256) -> U256 {
+    let gas_limit = gas_limit.saturating_add(BATCH_CALL_GAS_OVERHEAD.into());
+    // Rounded up
+    gas_limit
+        .saturating_mul(64.into())
+        .saturating_add(62.into())
+        / 63
+}
+
+fn as_pending_message(op: &mut DynPendingOperation) -> &mut PendingMessage {
+    match op {
+        DynPendingOperation::PendingMessage(msg) => msg,
+    }
+}
+
 impl PendingMessage {
     /// Constructor that tries reading the retry count from the HyperlaneDB in order to recompute the `next_attempt_after`.
     /// In case of failure, behaves like `Self::new(...)`.
@@ -327,6 +543,43 @@ impl PendingMessage {
         pm
     }
 
+    /// The gas limit this message contributes to a batch transaction, or `None`
+    /// if it isn't waiting to be submitted.
+    fn batch_gas_limit(&self) -> Option<U256> {
+        if self.submitted {
+            return None;
+        }
+        self.submission_data.as_ref().map(|state| state.gas_limit)
+    }
+
+    /// Updates this message after a batch transaction it was part of was
+    /// included. The outcome has already been recorded.
+    fn on_batch_tx_outcome(&mut self, tx_outcome: TxOutcome) -> PendingOperationResult {
+        if tx_outcome.executed {
+            info!(
+                id=?self.message.id(),
+                txid=?tx_outcome.transaction_id,
+                "Message successfully processed by batch transaction"
+            );
+            self.record_status(MessageStatus::Submitted);
+            self.record_submission();
+            self.submission_data = None;
+            self.submitted = true;
+            self.reset_attempts();
+            self.next_attempt_after = Some(Instant::now() + CONFIRM_DELAY);
+            PendingOperationResult::Success
+        } else {
+            info!(
+                id=?self.message.id(),
+                txid=?tx_outcome.transaction_id,
+                "Batch transaction failed to process message"
+            );
+            self.record_status(MessageStatus::Reverted);
+            self.submission_data = None;
+            self.on_reprepare()
+        }
+    }
+
     fn on_reprepare(&mut self) -> PendingOperationResult {
         self.inc_attempts();
         self.submitted = false;
@@ -350,11 +603,49 @@ impl PendingMessage {
         self.ctx
             .origin_db
             .store_processed_by_nonce(&self.message.nonce, &true)?;
+        self.ctx
+            .origin_db
+            .update_message_status(&self.message.id(), MessageStatus::Confirmed)?;
         self.ctx.metrics.update_nonce(&self.message);
         self.ctx.metrics.messages_processed.inc();
+        if let Some(submitted_at) = self.timeline.submitted_at {
+            self.ctx
+                .metrics
+                .submit_to_confirm
+                .observe(submitted_at.elapsed().as_secs_f64());
+        }
+        self.stop_waiting_for_delivery();
         Ok(())
     }
 
+    /// Set when the message was indexed and since when it is waiting to be
+    /// delivered.
+    pub fn with_timeline(mut self, timeline: MessageTimeline) -> Self {
+        if let Some(since) = timeline.undelivered_since {
+            self.ctx.metrics.wait_for_delivery(since, self.message.id());
+        }
+        self.timeline = timeline;
+        self
+    }
+
+    fn record_submission(&mut self) {
+        if let Some(prepared_at) = self.timeline.prepared_at {
+            self.ctx
+                .metrics
+                .prepare_to_submit
+                .observe(prepared_at.elapsed().as_secs_f64());
+        }
+        self.timeline.submitted_at = Some(Instant::now());
+    }
+
+    fn stop_waiting_for_delivery(&mut self) {
+        if let Some(since) = self.timeline.undelivered_since.take() {
+            self.ctx
+                .metrics
+                .stop_waiting_for_delivery(since, self.message.id());
+        }
+    }
+
     fn reset_attempts(&mut self) {
         self.set_retries(0);
         self.next_attempt_after = None;
@@ -373,6 +664,16 @@ impl PendingMessage {
         self.persist_retries();
     }
 
+    fn record_status(&self, status: MessageStatus) {
+        if let Err(e) = self
+            .ctx
+            .origin_db
+            .update_message_status(&self.message.id(), status)
+        {
+            warn!(message_id = ?self.message.id(), err = %e, "Persisting the status failed for message");
+        }
+    }
+
     fn persist_retries(&self) {
         if let Err(e) = self
             .ctx
@@ -405,6 +706,14 @@ pub struct MessageSubmissionMetrics {
     // Fields are public for testing purposes
     pub last_known_nonce: IntGauge,
     pub messages_processed: IntCounter,
+    pub dispatch_to_index: Histogram,
+    pub index_to_prepare: Histogram,
+    pub prepare_to_submit: Histogram,
+    pub submit_to_confirm: Histogram,
+    pub oldest_undelivered_age: IntGauge,
+    /// Messages waiting to be delivered, ordered by the unix timestamp they are
+    /// waiting since.
+    pub undelivered: Mutex<BTreeSet<(u64, H256)>>,
 }
 
 impl MessageSubmissionMetrics {
@@ -424,9 +733,53 @@ impl MessageSubmissionMetrics {
             messages_processed: metrics
                 .messages_processed_count()
                 .with_label_values(&[origin, destination]),
+            dispatch_to_index: metrics.message_latency().with_label_values(&[
+                "dispatch_to_index",
+                origin,
+                destination,
+            ]),
+            index_to_prepare: metrics.message_latency().with_label_values(&[
+                "index_to_prepare",
+                origin,
+                destination,
+            ]),
+            prepare_to_submit: metrics.message_latency().with_label_values(&[
+                "prepare_to_submit",
+                origin,
+                destination,
+            ]),
+            submit_to_confirm: metrics.message_latency().with_label_values(&[
+                "submit_to_confirm",
+                origin,
+                destination,
+            ]),
+            oldest_undelivered_age: metrics
+                .oldest_undelivered_message_age()
+                .with_label_values(&[origin, destination]),
+            undelivered: Default::default(),
         }
     }
 
+    fn wait_for_delivery(&self, since: u64, id: H256) {
+        self.undelivered.lock().unwrap().insert((since, id));
+    }
+
+    fn stop_waiting_for_delivery(&self, since: u64, id: H256) {
+        self.undelivered.lock().unwrap().remove(&(since, id));
+    }
+
+    /// Set the age of the oldest message waiting to be delivered.
+    pub fn update_oldest_undelivered_age(&self) {
+        let oldest = self
+            .undelivered
+            .lock()
+            .unwrap()
+            .first()
+            .map(|(since, _)| *since);
+        self.oldest_undelivered_age
+            .set(oldest.map_or(0, |since| unix_timestamp().saturating_sub(since)) as i64);
+    }
+
     fn update_nonce(&self, msg: &HyperlaneMessage) {
         // this is technically a race condition between `.get` and `.set` but worst case
         // the gauge should get corrected on the next update and is not an issue
@@ -435,3 +788,198 @@ impl MessageSubmissionMetrics {
             .set(std::cmp::max(self.last_known_nonce.get(), msg.nonce as i64));
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::ops::Range;
+
+    use hyperlane_base::db::{test_utils, HyperlaneRocksDB};
+    use hyperlane_core::{TxOutcome, H512};
+    use hyperlane_test::mocks::MockMailboxContract;
+
+    use super::*;
+    use crate::msg::processor::test::{
+        dummy_domain, dummy_hyperlane_message, dummy_metadata_builder, dummy_submission_metrics,
+    };
+
+    const MESSAGE_GAS_LIMIT: u64 = 100_000;
+
+    fn dummy_context(
+        mailbox: MockMailboxContract,
+        db: &HyperlaneRocksDB,
+        transaction_gas_limit: Option<U256>,
+    ) -> Arc<MessageContext> {
+        Arc::new(MessageContext {
+            destination_mailbox: Arc::new(mailbox),
+            origin_db: db.clone(),
+            metadata_builder: dummy_metadata_builder(&dummy_domain(0, "dummy_origin_domain"), db),
+            origin_gas_payment_enforcer: Arc::new(GasPaymentEnforcer::new([], db.clone())),
+            transaction_gas_limit,
+            metrics: dummy_submission_metrics(),
+        })
+    }
+
+    /// Pending messages that are ready to be submitted.
+    fn prepared_ops(
+        ctx: &Arc<MessageContext>,
+        nonces: Range<u32>,
+    ) -> Vec<Box<DynPendingOperation>> {
+        let destination = dummy_domain(1, "dummy_destination_domain");
+        nonces
+            .map(|nonce| {
+                let mut msg =
+                    PendingMessage::new(dummy_hyperlane_message(&destination, nonce), ctx.clone());
+                msg.submission_data = Some(Box::new(SubmissionData {
+                    metadata: vec![],
+                    gas_limit: MESSAGE_GAS_LIMIT.into(),
+                }));
+                Box::new(msg.into())
+            })
+            .collect()
+    }
+
+    fn tx_outcome(executed: bool, gas_used: u64) -> TxOutcome {
+        TxOutcome {
+            transaction_id: H512::zero(),
+            executed,
+            gas_used: gas_used.into(),
+            gas_price: 1.into(),
+        }
+    }
+
+    fn recorded_gas(db: &HyperlaneRocksDB, op: &mut DynPendingOperation) -> U256 {
+        db.retrieve_gas_expenditure_by_message_id(as_pending_message(op).message.id())
+            .unwrap()
+            .gas_used
+    }
+
+    #[test]
+    fn test_batch_tx_gas_limit_includes_overhead() {
+        // 53_000 gas for the message and 10_000 to make the call is 63_000, of
+        // which 64/63 have to be left to forward it, plus 30_000 for the batch
+        assert_eq!(batch_tx_gas_limit([U256::from(53_000)]), U256::from(94_000));
+        assert_eq!(
+            batch_tx_gas_limit([U256::from(53_000), U256::from(53_000)]),
+            U256::from(158_000)
+        );
+        assert_eq!(batch_tx_gas_limit([]), U256::from(BATCH_BASE_GAS_OVERHEAD));
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_delivers_messages_together() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(0, "dummy_origin_domain"), db);
+            let mut mailbox = MockMailboxContract::new();
+            let expected_gas_limit = batch_tx_gas_limit([MESSAGE_GAS_LIMIT.into(); 2]);
+            mailbox
+                .expect_process_batch()
+                .withf(move |items, tx_gas_limit| {
+                    items.len() == 2 && *tx_gas_limit == Some(expected_gas_limit)
+                })
+                .times(1)
+                .returning(|items, _| Ok(items.iter().map(|_| tx_outcome(true, 50_000)).collect()));
+            let ctx = dummy_context(mailbox, &db, None);
+
+            let results = submit_batch(prepared_ops(&ctx, 0..2)).await.unwrap();
+
+            assert_eq!(results.len(), 2);
+            for (mut op, result) in results {
+                assert!(matches!(result, PendingOperationResult::Success));
+                assert_eq!(recorded_gas(&db, &mut op), U256::from(50_000));
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_splits_groups_at_transaction_gas_limit() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(0, "dummy_origin_domain"), db);
+            let mut mailbox = MockMailboxContract::new();
+            mailbox
+                .expect_process_batch()
+                .withf(|items, _| items.len() == 2)
+                .times(1)
+                .returning(|items, _| Ok(items.iter().map(|_| tx_outcome(true, 50_000)).collect()));
+            mailbox
+                .expect_process()
+                .times(1)
+                .returning(|_, _, _| Ok(tx_outcome(true, 50_000)));
+            // Room for two messages, but not for three
+            let max_gas_limit = batch_tx_gas_limit([MESSAGE_GAS_LIMIT.into(); 2]);
+            let ctx = dummy_context(mailbox, &db, Some(max_gas_limit));
+
+            let results = submit_batch(prepared_ops(&ctx, 0..3)).await.unwrap();
+
+            assert_eq!(results.len(), 3);
+            assert!(results
+                .iter()
+                .all(|(_, result)| matches!(result, PendingOperationResult::Success)));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_falls_back_without_recording_gas_twice() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(0, "dummy_origin_domain"), db);
+            let mut mailbox = MockMailboxContract::new();
+            mailbox
+                .expect_process_batch()
+                .times(1)
+                .returning(|items, _| {
+                    Ok(items.iter().map(|_| tx_outcome(false, 80_000)).collect())
+                });
+            mailbox
+                .expect_process()
+                .times(2)
+                .returning(|_, _, _| Ok(tx_outcome(true, 60_000)));
+            let ctx = dummy_context(mailbox, &db, None);
+
+            let results = submit_batch(prepared_ops(&ctx, 0..2)).await.unwrap();
+
+            assert_eq!(results.len(), 2);
+            for (mut op, result) in results {
+                assert!(matches!(result, PendingOperationResult::Success));
+                // Only the gas of the individual transaction is recorded
+                assert_eq!(recorded_gas(&db, &mut op), U256::from(60_000));
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_records_gas_in_the_origin_of_each_message() {
+        test_utils::run_test_db(|db| async move {
+            let origin_db =
+                HyperlaneRocksDB::new(&dummy_domain(0, "dummy_origin_domain"), db.clone());
+            let other_origin_db =
+                HyperlaneRocksDB::new(&dummy_domain(2, "other_origin_domain"), db);
+            let mut mailbox = MockMailboxContract::new();
+            mailbox
+                .expect_process_batch()
+                .withf(|items, _| items.len() == 2)
+                .times(1)
+                .returning(|items, _| Ok(items.iter().map(|_| tx_outcome(true, 50_000)).collect()));
+            let ctx = dummy_context(mailbox, &origin_db, None);
+            let other_ctx = dummy_context(MockMailboxContract::new(), &other_origin_db, None);
+            let mut ops = prepared_ops(&ctx, 0..1);
+            ops.extend(prepared_ops(&other_ctx, 1..2));
+
+            let mut results = submit_batch(ops).await.unwrap();
+
+            assert_eq!(results.len(), 2);
+            assert!(results
+                .iter()
+                .all(|(_, result)| matches!(result, PendingOperationResult::Success)));
+            let [(op, _), (other_op, _)] = results.as_mut_slice() else {
+                panic!("Expected a result per message");
+            };
+            assert_eq!(recorded_gas(&origin_db, op), U256::from(50_000));
+            assert_eq!(recorded_gas(&origin_db, other_op), U256::zero());
+            assert_eq!(recorded_gas(&other_origin_db, other_op), U256::from(50_000));
+            assert_eq!(recorded_gas(&other_origin_db, op), U256::zero());
+        })
+        .await;
+    }
+}
@@ -5,7 +5,7 @@ use async_trait::async_trait;
 use enum_dispatch::enum_dispatch;
 use eyre::Report;
 
-use hyperlane_core::HyperlaneDomain;
+use hyperlane_core::{HyperlaneDomain, H256};
 
 #[allow(unused_imports)] // required for enum_dispatch
 use super::pending_message::PendingMessage;
@@ -32,9 +32,23 @@ use super::pending_message::PendingMessage;
 #[async_trait]
 #[enum_dispatch]
 pub trait PendingOperation {
+    /// The id of this operation, e.g. the id of the message it delivers.
+    fn id(&self) -> H256;
+
     /// The domain this operation will take place on.
     fn domain(&self) -> &HyperlaneDomain;
 
+    /// The number of times this operation has been retried.
+    fn num_retries(&self) -> u32;
+
+    /// Forget about previous failures so the operation is attempted again as
+    /// soon as possible.
+    fn retry_now(&mut self);
+
+    /// Record that the operation was dropped from outside of the submitter,
+    /// e.g. by an operator, and won't be attempted again.
+    fn on_drop(&mut self, reason: &str);
+
     /// Prepare to submit this operation. This will be called before every
     /// submission and will usually have a very short gap between it and the
     /// submit call.
@@ -51,9 +65,9 @@ pub trait PendingOperation {
 
     /// Get the earliest instant at which this should next be attempted.
     ///
-    /// This is only used for sorting, the functions are responsible for
-    /// returning `NotReady` if it is too early and matters.
-    fn _next_attempt_after(&self) -> Option<Instant>;
+    /// This is only used for sorting and reporting, the functions are
+    /// responsible for returning `NotReady` if it is too early and matters.
+    fn next_attempt_after(&self) -> Option<Instant>;
 
     #[cfg(test)]
     /// Set the number of times this operation has been retried.
@@ -83,7 +97,7 @@ impl Ord for DynPendingOperation {
     fn cmp(&self, other: &Self) -> Ordering {
         use DynPendingOperation::*;
         use Ordering::*;
-        match (self._next_attempt_after(), other._next_attempt_after()) {
+        match (self.next_attempt_after(), other.next_attempt_after()) {
             (Some(a), Some(b)) => a.cmp(&b),
             // No time means it should come before
             (None, Some(_)) => Less,
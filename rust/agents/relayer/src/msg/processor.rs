@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::{Debug, Formatter},
     sync::Arc,
     time::Duration,
@@ -7,14 +7,22 @@ use std::{
 
 use derive_new::new;
 use eyre::Result;
-use hyperlane_base::{db::HyperlaneRocksDB, CoreMetrics};
-use hyperlane_core::{HyperlaneDomain, HyperlaneMessage};
+use hyperlane_base::{
+    db::{unix_timestamp, HyperlaneRocksDB, MessageRollback, MessageStatus, MessageStatusRecord},
+    CoreMetrics,
+};
+use hyperlane_core::{
+    HyperlaneDomain, HyperlaneMessage, HyperlaneMessageStore, HyperlaneProvider, H256,
+};
 use prometheus::IntGauge;
 use tokio::{
-    sync::{mpsc::UnboundedSender, RwLock},
+    sync::{
+        mpsc::{UnboundedReceiver, UnboundedSender},
+        RwLock,
+    },
     task::JoinHandle,
 };
-use tracing::{debug, info_span, instrument, instrument::Instrumented, trace, Instrument};
+use tracing::{debug, info_span, instrument, instrument::Instrumented, trace, warn, Instrument};
 
 use super::pending_message::*;
 use crate::{
@@ -26,9 +34,15 @@ use crate::{
 /// for to the appropriate destination.
 #[derive(new)]
 pub struct MessageProcessor {
+    /// Messages that reorgs removed from the db. Declared before `db` so that
+    /// `new` subscribes to them before taking the db.
+    #[new(value = "db.subscribe_message_rollbacks()")]
+    message_rollbacks: UnboundedReceiver<MessageRollback>,
     db: HyperlaneRocksDB,
+    /// Provider of the origin chain, to look up when messages were dispatched
+    origin_provider: Arc<dyn HyperlaneProvider>,
     whitelist: Arc<MatchingList>,
-    blacklist: Arc<MatchingList>,
+    blacklist: Arc<RwLock<MatchingList>>,
     metrics: MessageProcessorMetrics,
     prover_sync: Arc<RwLock<MerkleTreeBuilder>>,
     /// channel for each destination chain to send operations (i.e. message
@@ -38,6 +52,10 @@ pub struct MessageProcessor {
     destination_ctxs: HashMap<u32, Arc<MessageContext>>,
     #[new(default)]
     message_nonce: u32,
+    /// Ids of messages that reorgs removed after they were processed, which
+    /// are still queued if a reorg brings them back
+    #[new(default)]
+    processed_before_rollback: HashSet<H256>,
 }
 
 impl Debug for MessageProcessor {
@@ -61,7 +79,24 @@ impl MessageProcessor {
 
     pub fn spawn(self) -> Instrumented<JoinHandle<Result<()>>> {
         let span = info_span!("MessageProcessor");
-        tokio::spawn(async move { self.main_loop().await }).instrument(span)
+        let resolver = self.dispatch_timestamp_resolver();
+        tokio::spawn(async move {
+            tokio::select! {
+                result = self.main_loop() => result,
+                _ = resolver.run() => unreachable!("Dispatch timestamp resolver stopped"),
+            }
+        })
+        .instrument(span)
+    }
+
+    /// Looks up when this processor's messages were dispatched while they're
+    /// being processed.
+    fn dispatch_timestamp_resolver(&self) -> DispatchTimestampResolver {
+        DispatchTimestampResolver::new(
+            self.db.clone(),
+            self.origin_provider.clone(),
+            self.destination_ctxs.clone(),
+        )
     }
 
     #[instrument(ret, err, skip(self), level = "info", fields(domain=%self.domain()))]
@@ -111,9 +146,36 @@ impl MessageProcessor {
         }
     }
 
+    /// Rewind to the first nonce whose message a reorg removed from the db, so
+    /// that the message indexed for it instead is processed, and remove the
+    /// removed messages from the merkle tree.
+    async fn rollback_removed_messages(&mut self) -> Result<()> {
+        while let Ok(rollback) = self.message_rollbacks.try_recv() {
+            let Some(from_nonce) = rollback.from_nonce() else { continue };
+            for (nonce, id) in rollback.removed {
+                if nonce < self.message_nonce {
+                    self.processed_before_rollback.insert(id);
+                }
+            }
+            warn!(
+                from_nonce,
+                message_nonce = self.message_nonce,
+                "Messages were removed by a reorg, rewinding"
+            );
+            self.message_nonce = self.message_nonce.min(from_nonce);
+            self.prover_sync.write().await.truncate(from_nonce)?;
+        }
+        Ok(())
+    }
+
     /// One round of processing, extracted from infinite work loop for
     /// testing purposes.
     async fn tick(&mut self) -> Result<()> {
+        for ctx in self.destination_ctxs.values() {
+            ctx.metrics.update_oldest_undelivered_age();
+        }
+        self.rollback_removed_messages().await?;
+
         // Scan until we find next nonce without delivery confirmation.
         if let Some(msg) = self.try_get_unprocessed_message()? {
             debug!(?msg, "Processor working on message");
@@ -127,7 +189,7 @@ impl MessageProcessor {
             }
 
             // Skip if the message is blacklisted
-            if self.blacklist.msg_matches(&msg, false) {
+            if self.blacklist.read().await.msg_matches(&msg, false) {
                 debug!(?msg, blacklist=?self.blacklist, "Message blacklisted, skipping");
                 self.message_nonce += 1;
                 return Ok(());
@@ -154,13 +216,46 @@ impl MessageProcessor {
                 .update_to_index(msg.nonce)
                 .await?;
 
+            // A message a reorg removed and brought back is still queued
+            if self.processed_before_rollback.remove(&msg.id()) {
+                debug!(?msg, "Message was processed before a reorg, skipping");
+                self.message_nonce += 1;
+                return Ok(());
+            }
+
+            // Messages the relayer gave up on stay dead-lettered, e.g. across
+            // restarts, rather than being queued again.
+            let first_queued = match self.db.retrieve_message_status(&msg.id()) {
+                Ok(Some(MessageStatusRecord {
+                    status: MessageStatus::Dropped(reason),
+                    ..
+                })) => {
+                    debug!(?msg, ?reason, "Message was dropped, skipping");
+                    self.message_nonce += 1;
+                    return Ok(());
+                }
+                Ok(status) => status.is_none(),
+                Err(err) => {
+                    warn!(id=?msg.id(), ?err, "Failed to retrieve message status");
+                    false
+                }
+            };
+
             debug!(%msg, "Sending message to submitter");
+            if let Err(err) = self
+                .db
+                .update_message_status(&msg.id(), MessageStatus::Queued)
+            {
+                warn!(id=?msg.id(), ?err, "Failed to persist message status");
+            }
+            let timeline = self.message_timeline(&msg, first_queued);
 
             // Finally, build the submit arg and dispatch it to the submitter.
             let pending_msg = PendingMessage::from_persisted_retries(
                 msg,
                 self.destination_ctxs[&destination].clone(),
-            );
+            )
+            .with_timeline(timeline);
             self.send_channels[&destination].send(Box::new(pending_msg.into()))?;
             self.message_nonce += 1;
         } else {
@@ -168,6 +263,131 @@ impl MessageProcessor {
         }
         Ok(())
     }
+
+    /// When a message was indexed and since when it is waiting to be
+    /// delivered, which is when it was dispatched if that is already known.
+    fn message_timeline(&self, msg: &HyperlaneMessage, first_queued: bool) -> MessageTimeline {
+        let id = msg.id();
+        let (indexed_at, dispatched_at) = match (
+            self.db.retrieve_indexed_at_by_message_id(&id),
+            self.db.retrieve_dispatched_at_by_message_id(&id),
+        ) {
+            (Ok(indexed_at), Ok(dispatched_at)) => (indexed_at, dispatched_at),
+            (Err(err), _) | (_, Err(err)) => {
+                warn!(
+                    ?id,
+                    ?err,
+                    "Failed to retrieve when message was dispatched and indexed"
+                );
+                (None, None)
+            }
+        };
+        let undelivered_since = dispatched_at.or(indexed_at).unwrap_or_else(unix_timestamp);
+        MessageTimeline::new(indexed_at.filter(|_| first_queued), Some(undelivered_since))
+    }
+}
+
+/// Looks up the timestamps of the blocks an origin's messages were dispatched
+/// in and stores them as when the messages were dispatched, observing how long
+/// they took to be indexed. This runs alongside the `MessageProcessor` so
+/// processing messages doesn't wait on these lookups, which are made once per
+/// block. A message the processor gets to before its timestamp is known is
+/// treated as waiting for delivery since it was indexed instead.
+#[derive(new)]
+struct DispatchTimestampResolver {
+    /// Messages that reorgs removed from the db. Declared before `db` so that
+    /// `new` subscribes to them before taking the db.
+    #[new(value = "db.subscribe_message_rollbacks()")]
+    message_rollbacks: UnboundedReceiver<MessageRollback>,
+    db: HyperlaneRocksDB,
+    origin_provider: Arc<dyn HyperlaneProvider>,
+    destination_ctxs: HashMap<u32, Arc<MessageContext>>,
+    #[new(default)]
+    message_nonce: u32,
+    /// Hash and timestamp of the last block a message was dispatched in
+    #[new(default)]
+    last_dispatch_block: Option<(H256, u64)>,
+}
+
+impl DispatchTimestampResolver {
+    async fn run(mut self) {
+        loop {
+            self.tick().await;
+        }
+    }
+
+    async fn tick(&mut self) {
+        // Resolve the messages indexed instead of those removed by reorgs
+        while let Ok(rollback) = self.message_rollbacks.try_recv() {
+            if let Some(from_nonce) = rollback.from_nonce() {
+                self.message_nonce = self.message_nonce.min(from_nonce);
+            }
+        }
+        match self.db.retrieve_message_by_nonce(self.message_nonce) {
+            Ok(Some(msg)) => {
+                if let Err(err) = self.resolve(&msg).await {
+                    warn!(?msg, ?err, "Failed to resolve when message was dispatched");
+                }
+                self.message_nonce += 1;
+            }
+            Ok(None) => tokio::time::sleep(Duration::from_secs(1)).await,
+            Err(err) => {
+                warn!(
+                    nonce = self.message_nonce,
+                    ?err,
+                    "Failed to retrieve message"
+                );
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+
+    async fn resolve(&mut self, msg: &HyperlaneMessage) -> Result<()> {
+        let id = msg.id();
+        if self.db.retrieve_dispatched_at_by_message_id(&id)?.is_some()
+            || self
+                .db
+                .retrieve_processed_by_nonce(&msg.nonce)?
+                .unwrap_or(false)
+        {
+            return Ok(());
+        }
+        let (Some(block_hash), Some(block_number)) = (
+            self.db.retrieve_dispatched_block_hash_by_message_id(&id)?,
+            self.db.retrieve_dispatched_block_number(msg.nonce).await?,
+        ) else {
+            return Ok(());
+        };
+        let dispatched_at = self.block_timestamp(block_hash, block_number).await?;
+        self.db
+            .store_dispatched_at_by_message_id(&id, &dispatched_at)?;
+
+        let indexed_at = self.db.retrieve_indexed_at_by_message_id(&id)?;
+        if let (Some(indexed_at), Some(ctx)) =
+            (indexed_at, self.destination_ctxs.get(&msg.destination))
+        {
+            ctx.metrics
+                .dispatch_to_index
+                .observe(indexed_at.saturating_sub(dispatched_at) as f64);
+        }
+        Ok(())
+    }
+
+    /// The timestamp of a block. The last one looked up is remembered since
+    /// consecutive messages are often dispatched in the same block.
+    async fn block_timestamp(&mut self, block_hash: H256, block_number: u64) -> Result<u64> {
+        if let Some((hash, timestamp)) = self.last_dispatch_block {
+            if hash == block_hash {
+                return Ok(timestamp);
+            }
+        }
+        let block = self
+            .origin_provider
+            .get_block(&block_hash, block_number)
+            .await?;
+        self.last_dispatch_block = Some((block_hash, block.timestamp));
+        Ok(block.timestamp)
+    }
 }
 
 #[derive(Debug)]
@@ -207,15 +427,19 @@ impl MessageProcessorMetrics {
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
+    use std::collections::BTreeSet;
     use std::time::Instant;
 
     use hyperlane_base::{
         db::{test_utils, HyperlaneRocksDB},
         settings::{ChainConf, ChainConnectionConf, Settings},
     };
-    use hyperlane_test::mocks::{MockMailboxContract, MockValidatorAnnounceContract};
-    use prometheus::{IntCounter, Registry};
+    use hyperlane_core::{BlockInfo, HyperlaneLogStore, LogMeta};
+    use hyperlane_test::mocks::{
+        MockHyperlaneProvider, MockMailboxContract, MockValidatorAnnounceContract,
+    };
+    use prometheus::{Histogram, HistogramOpts, IntCounter, Registry};
     use tokio::{
         sync::mpsc::{self, UnboundedReceiver},
         time::sleep,
@@ -241,13 +465,24 @@ mod test {
         }
     }
 
-    fn dummy_submission_metrics() -> MessageSubmissionMetrics {
+    pub(crate) fn dummy_submission_metrics() -> MessageSubmissionMetrics {
         MessageSubmissionMetrics {
             last_known_nonce: IntGauge::new("last_known_nonce_gauge", "help string").unwrap(),
             messages_processed: IntCounter::new("message_processed_gauge", "help string").unwrap(),
+            dispatch_to_index: dummy_histogram(),
+            index_to_prepare: dummy_histogram(),
+            prepare_to_submit: dummy_histogram(),
+            submit_to_confirm: dummy_histogram(),
+            oldest_undelivered_age: IntGauge::new("oldest_undelivered_age_gauge", "help string")
+                .unwrap(),
+            undelivered: Default::default(),
         }
     }
 
+    fn dummy_histogram() -> Histogram {
+        Histogram::with_opts(HistogramOpts::new("dummy_histogram", "help string")).unwrap()
+    }
+
     fn dummy_chain_conf(domain: &HyperlaneDomain) -> ChainConf {
         ChainConf {
             domain: domain.clone(),
@@ -259,10 +494,11 @@ mod test {
             }),
             metrics_conf: Default::default(),
             index: Default::default(),
+            gas_escalator: Default::default(),
         }
     }
 
-    fn dummy_metadata_builder(
+    pub(crate) fn dummy_metadata_builder(
         domain: &HyperlaneDomain,
         db: &HyperlaneRocksDB,
     ) -> BaseMetadataBuilder {
@@ -304,6 +540,7 @@ mod test {
         (
             MessageProcessor::new(
                 db.clone(),
+                Arc::new(MockHyperlaneProvider::new()),
                 Default::default(),
                 Default::default(),
                 dummy_processor_metrics(origin_domain.id()),
@@ -315,7 +552,10 @@ mod test {
         )
     }
 
-    fn dummy_hyperlane_message(destination: &HyperlaneDomain, nonce: u32) -> HyperlaneMessage {
+    pub(crate) fn dummy_hyperlane_message(
+        destination: &HyperlaneDomain,
+        nonce: u32,
+    ) -> HyperlaneMessage {
         HyperlaneMessage {
             version: Default::default(),
             nonce,
@@ -336,7 +576,7 @@ mod test {
         }
     }
 
-    fn dummy_domain(domain_id: u32, name: &str) -> HyperlaneDomain {
+    pub(crate) fn dummy_domain(domain_id: u32, name: &str) -> HyperlaneDomain {
         let test_domain = HyperlaneDomain::new_test_domain(name);
         HyperlaneDomain::Unknown {
             domain_id,
@@ -437,7 +677,7 @@ mod test {
                     // Round up the actuall backoff because it was calculated with an `Instant::now()` that was a fraction of a second ago
                     let expected_backoff = PendingMessage::calculate_msg_backoff(*expected_retries)
                         .map(|b| b.as_secs_f32().round());
-                    let actual_backoff = pm._next_attempt_after().map(|instant| {
+                    let actual_backoff = pm.next_attempt_after().map(|instant| {
                         instant.duration_since(Instant::now()).as_secs_f32().round()
                     });
                     assert_eq!(expected_backoff, actual_backoff);
@@ -445,4 +685,170 @@ mod test {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn test_tracks_undelivered_messages_since_resolved_dispatch() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+
+            // Two messages dispatched in the same block
+            let block_hash = H256::from_low_u64_be(1);
+            let messages: Vec<_> = (0..2)
+                .map(|nonce| {
+                    let meta = LogMeta {
+                        block_hash,
+                        ..Default::default()
+                    };
+                    (dummy_hyperlane_message(&destination_domain, nonce), meta)
+                })
+                .collect();
+            db.store_logs(&messages).await.unwrap();
+
+            let (mut message_processor, _receive_channel) =
+                dummy_message_processor(&origin_domain, &destination_domain, &db);
+            let mut provider = MockHyperlaneProvider::new();
+            provider
+                .expect__get_block()
+                .times(1)
+                .returning(move |_, _| {
+                    Ok(BlockInfo {
+                        hash: block_hash,
+                        timestamp: 1000,
+                        number: 1,
+                    })
+                });
+            message_processor.origin_provider = Arc::new(provider);
+            let ctx = message_processor.destination_ctxs[&destination_domain.id()].clone();
+
+            // The block's timestamp is only looked up once, and not by the
+            // processor itself
+            let mut resolver = message_processor.dispatch_timestamp_resolver();
+            resolver.tick().await;
+            resolver.tick().await;
+            message_processor.tick().await.unwrap();
+            message_processor.tick().await.unwrap();
+
+            assert_eq!(
+                *ctx.metrics.undelivered.lock().unwrap(),
+                messages
+                    .iter()
+                    .map(|(msg, _)| (1000, msg.id()))
+                    .collect::<BTreeSet<_>>()
+            );
+            assert_eq!(ctx.metrics.dispatch_to_index.get_sample_count(), 2);
+            ctx.metrics.update_oldest_undelivered_age();
+            let age = ctx.metrics.oldest_undelivered_age.get() as u64;
+            let now = unix_timestamp();
+            assert!((now - 1001..=now - 1000).contains(&age));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_dropped_messages_are_not_requeued_on_restart() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+
+            // Before the restart, the first of two messages was dropped
+            persist_retried_messages(&[0, 0], &db, &destination_domain);
+            let dropped = dummy_hyperlane_message(&destination_domain, 0);
+            let reason = "Recipient is not a contract".to_owned();
+            db.update_message_status(&dropped.id(), MessageStatus::Dropped(reason.clone()))
+                .unwrap();
+
+            let (mut message_processor, mut receive_channel) =
+                dummy_message_processor(&origin_domain, &destination_domain, &db);
+            message_processor.tick().await.unwrap();
+            message_processor.tick().await.unwrap();
+
+            let queued = receive_channel.try_recv().unwrap();
+            assert_eq!(
+                queued.id(),
+                dummy_hyperlane_message(&destination_domain, 1).id()
+            );
+            assert!(receive_channel.try_recv().is_err());
+            assert_eq!(
+                db.retrieve_message_status(&dropped.id())
+                    .unwrap()
+                    .unwrap()
+                    .status,
+                MessageStatus::Dropped(reason)
+            );
+            assert_eq!(db.retrieve_dead_letters().unwrap().len(), 1);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_rewinds_to_messages_removed_by_reorgs() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let at_block = |block_number| LogMeta {
+                block_number,
+                ..Default::default()
+            };
+            let messages: Vec<_> = (0..3)
+                .map(|nonce| {
+                    let msg = dummy_hyperlane_message(&destination_domain, nonce);
+                    (msg, at_block(10 + u64::from(nonce)))
+                })
+                .collect();
+            db.store_logs(&messages).await.unwrap();
+
+            let (mut message_processor, mut receive_channel) =
+                dummy_message_processor(&origin_domain, &destination_domain, &db);
+            for _ in 0..3 {
+                message_processor.tick().await.unwrap();
+            }
+            assert_eq!(message_processor.prover_sync.read().await.count(), 3);
+
+            // A reorg removes the blocks of the last two messages. The first
+            // of them is dispatched again, the second is replaced.
+            HyperlaneLogStore::<HyperlaneMessage>::rollback_logs(&db, 11)
+                .await
+                .unwrap();
+            let replacement = HyperlaneMessage {
+                body: vec![1],
+                ..messages[2].0.clone()
+            };
+            db.store_logs(&[
+                (messages[1].0.clone(), at_block(13)),
+                (replacement.clone(), at_block(13)),
+            ])
+            .await
+            .unwrap();
+            for _ in 0..2 {
+                message_processor.tick().await.unwrap();
+            }
+
+            // Only the replacement is queued, the message dispatched again
+            // still is
+            let queued: Vec<_> = std::iter::from_fn(|| receive_channel.try_recv().ok())
+                .map(|op| op.id())
+                .collect();
+            let expected: Vec<_> = messages
+                .iter()
+                .map(|(msg, _)| msg.id())
+                .chain([replacement.id()])
+                .collect();
+            assert_eq!(queued, expected);
+
+            // The merkle tree has the replacement instead of the removed message
+            let mut expected_tree = MerkleTreeBuilder::new(db.clone());
+            expected_tree.update_to_index(2).await.unwrap();
+            let prover_sync = message_processor.prover_sync.read().await;
+            assert_eq!(prover_sync.count(), 3);
+            assert_eq!(
+                prover_sync.get_proof(2, 2).unwrap(),
+                expected_tree.get_proof(2, 2).unwrap()
+            );
+        })
+        .await;
+    }
 }
@@ -6,6 +6,7 @@ use std::time::Duration;
 use derive_new::new;
 use eyre::{bail, Result};
 use futures_util::future::try_join_all;
+use futures_util::stream::{FuturesUnordered, StreamExt};
 use prometheus::{IntCounter, IntGauge};
 use tokio::spawn;
 use tokio::sync::{
@@ -19,9 +20,21 @@ use tracing::{debug, info_span, instrument, instrument::Instrumented, trace, Ins
 use hyperlane_base::CoreMetrics;
 use hyperlane_core::HyperlaneDomain;
 
+use super::pending_message::submit_batch;
 use super::pending_operation::*;
 
-type OpQueue = Arc<Mutex<BinaryHeap<Reverse<Box<DynPendingOperation>>>>>;
+pub(crate) type OpQueue = Arc<Mutex<BinaryHeap<Reverse<Box<DynPendingOperation>>>>>;
+
+/// The queues a submitter keeps operations in while they wait for their next
+/// attempt. These are shared so the operations can be inspected and managed
+/// from outside the submitter.
+#[derive(Debug, Clone, Default)]
+pub struct SubmitterQueues {
+    /// Operations waiting to be prepared and submitted.
+    pub prepare: OpQueue,
+    /// Operations waiting for their submission to be confirmed.
+    pub confirm: OpQueue,
+}
 
 /// SerialSubmitter accepts operations over a channel. It is responsible for
 /// executing the right strategy to deliver those messages to the destination
@@ -31,10 +44,27 @@ type OpQueue = Arc<Mutex<BinaryHeap<Reverse<Box<DynPendingOperation>>>>>;
 /// speculatively batch > 1 messages with a sequence of nonces, which entails
 /// harder to manage error recovery, could lead to head of line blocking, etc.
 ///
-/// The single transaction execution slot is (likely) a bottlenecked resource
+/// Destinations where that's too much of a bottleneck can be configured to
+/// allow up to `max_in_flight` simultaneous submissions instead. This relies on
+/// the destination's mailbox assigning transaction nonces itself rather than
+/// waiting on the chain to advance them (e.g. the EVM mailbox tracks its own),
+/// and on every submission failure, whether the transaction reverted, was
+/// dropped, replaced or got stuck, being retried by going back through
+/// preparation. Preparation always checks the message's delivery status first,
+/// so a transaction that did land after we gave up on it is never duplicated.
+/// Note that with more than one execution slot, operations may be delivered
+/// out of order with respect to each other.
+///
+/// Each submission may also deliver up to `max_batch_size` prepared operations
+/// in a single transaction when the destination supports it (e.g. through
+/// Multicall3 on EVM chains), as long as their combined gas fits within the
+/// transaction gas limit. Batches that fail outright or don't deliver any of
+/// their operations fall back to submitting them individually.
+///
+/// The transaction execution slots are (likely) a bottlenecked resource
 /// under steady state traffic, so the SerialSubmitter implemented in this file
 /// carefully schedules work items onto the constrained
-/// resource (transaction execution slot) according to a policy that
+/// resource (transaction execution slots) according to a policy that
 /// incorporates both user-visible metrics and message operation readiness
 /// checks.
 ///
@@ -66,7 +96,7 @@ type OpQueue = Arc<Mutex<BinaryHeap<Reverse<Box<DynPendingOperation>>>>>;
 /// prefer this even if they do not strictly rely on it for correctness.
 ///
 /// 3. Be [work-conserving](https://en.wikipedia.org/wiki/Work-conserving_scheduler) w.r.t.
-/// the single execution slot, i.e. so long as there is at least one message
+/// the execution slots, i.e. so long as there is at least one message
 /// eligible for submission, we should be working on it within reason. This
 /// must be balanced with the cost of making RPCs that will almost certainly
 /// fail and potentially block new messages from being sent immediately.
@@ -76,6 +106,12 @@ pub struct SerialSubmitter {
     domain: HyperlaneDomain,
     /// Receiver for new messages to submit.
     rx: mpsc::UnboundedReceiver<Box<DynPendingOperation>>,
+    /// Maximum number of submissions (transactions) in flight at once.
+    max_in_flight: usize,
+    /// Maximum number of operations delivered by a single submission.
+    max_batch_size: usize,
+    /// Queues for operations waiting on their next attempt.
+    queues: SubmitterQueues,
     /// Metrics for serial submitter.
     metrics: SerialSubmitterMetrics,
 }
@@ -91,15 +127,20 @@ impl SerialSubmitter {
             domain,
             metrics,
             rx: rx_prepare,
+            max_in_flight,
+            max_batch_size,
+            queues,
         } = self;
-        let prepare_queue: OpQueue = Default::default();
-        let confirm_queue: OpQueue = Default::default();
+        let SubmitterQueues {
+            prepare: prepare_queue,
+            confirm: confirm_queue,
+        } = queues;
 
         // This is a channel because we want to only have a small number of messages
         // sitting ready to go at a time and this acts as a synchronization tool
         // to slow down the preparation of messages when the submitter gets
         // behind.
-        let (tx_submit, rx_submit) = mpsc::channel(1);
+        let (tx_submit, rx_submit) = mpsc::channel(max_in_flight * max_batch_size);
 
         let tasks = [
             spawn(receive_task(
@@ -116,6 +157,8 @@ impl SerialSubmitter {
             spawn(submit_task(
                 domain.clone(),
                 rx_submit,
+                max_in_flight,
+                max_batch_size,
                 prepare_queue.clone(),
                 confirm_queue.clone(),
                 metrics.clone(),
@@ -204,34 +247,59 @@ async fn prepare_task(
 async fn submit_task(
     domain: HyperlaneDomain,
     mut rx_submit: mpsc::Receiver<Box<DynPendingOperation>>,
+    max_in_flight: usize,
+    max_batch_size: usize,
     prepare_queue: OpQueue,
     confirm_queue: OpQueue,
     metrics: SerialSubmitterMetrics,
 ) -> Result<()> {
-    while let Some(mut op) = rx_submit.recv().await {
-        trace!(?op, "Submitting operation");
-        debug_assert_eq!(*op.domain(), domain);
-
-        match op.submit().await {
-            PendingOperationResult::Success => {
-                debug!(?op, "Operation submitted");
-                metrics.ops_submitted.inc();
-                confirm_queue.lock().await.push(Reverse(op));
-            }
-            PendingOperationResult::NotReady => {
-                panic!("Pending operation was prepared and therefore must be ready")
-            }
-            PendingOperationResult::Reprepare => {
-                metrics.ops_failed.inc();
-                prepare_queue.lock().await.push(Reverse(op));
-            }
-            PendingOperationResult::Drop => {
-                metrics.ops_dropped.inc();
+    // Submissions that are underway, each of which resolves to the operations
+    // and the results of submitting them.
+    let mut in_flight = FuturesUnordered::new();
+    loop {
+        metrics.submissions_in_flight.set(in_flight.len() as i64);
+        tokio::select! {
+            // Only take on another operation if there is a free execution slot.
+            op = rx_submit.recv(), if in_flight.len() < max_in_flight => {
+                let Some(op) = op else {
+                    bail!("Internal submitter channel was closed");
+                };
+                // Batch up any other operations that are already prepared.
+                let mut batch = vec![op];
+                while batch.len() < max_batch_size {
+                    let Ok(op) = rx_submit.try_recv() else {
+                        break;
+                    };
+                    batch.push(op);
+                }
+                for op in &batch {
+                    trace!(?op, "Submitting operation");
+                    debug_assert_eq!(*op.domain(), domain);
+                }
+                in_flight.push(submit_batch(batch));
             }
-            PendingOperationResult::CriticalFailure(e) => return Err(e),
+            Some(results) = in_flight.next() => for (op, result) in results? {
+                match result {
+                    PendingOperationResult::Success => {
+                        debug!(?op, "Operation submitted");
+                        metrics.ops_submitted.inc();
+                        confirm_queue.lock().await.push(Reverse(op));
+                    }
+                    PendingOperationResult::NotReady => {
+                        panic!("Pending operation was prepared and therefore must be ready")
+                    }
+                    PendingOperationResult::Reprepare => {
+                        metrics.ops_failed.inc();
+                        prepare_queue.lock().await.push(Reverse(op));
+                    }
+                    PendingOperationResult::Drop => {
+                        metrics.ops_dropped.inc();
+                    }
+                    PendingOperationResult::CriticalFailure(e) => return Err(e),
+                }
+            },
         }
     }
-    bail!("Internal submitter channel was closed");
 }
 
 #[instrument(skip_all, fields(%domain))]
@@ -281,6 +349,7 @@ async fn confirm_task(
 pub struct SerialSubmitterMetrics {
     prepare_queue_length: IntGauge,
     confirm_queue_length: IntGauge,
+    submissions_in_flight: IntGauge,
 
     ops_prepared: IntCounter,
     ops_submitted: IntCounter,
@@ -300,6 +369,9 @@ impl SerialSubmitterMetrics {
             confirm_queue_length: metrics
                 .submitter_queue_length()
                 .with_label_values(&[destination, "confirm_queue"]),
+            submissions_in_flight: metrics
+                .submitter_queue_length()
+                .with_label_values(&[destination, "submit_in_flight"]),
             ops_prepared: metrics
                 .operations_processed_count()
                 .with_label_values(&["prepared", destination]),
@@ -19,9 +19,11 @@ use tracing::{info, info_span, instrument::Instrumented, Instrument};
 
 use hyperlane_base::{
     db::{HyperlaneRocksDB, DB},
-    run_all, BaseAgent, ContractSyncMetrics, CoreMetrics, HyperlaneAgentCore,
+    run_all, BaseAgent, ContractSyncMetrics, CoreMetrics, HttpRoutes, HyperlaneAgentCore,
+};
+use hyperlane_core::{
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, InterchainGasPayment, LogSubscriber, U256,
 };
-use hyperlane_core::{HyperlaneDomain, InterchainGasPayment, U256};
 
 use crate::msg::pending_message::MessageSubmissionMetrics;
 use crate::{
@@ -32,8 +34,9 @@ use crate::{
         pending_message::MessageContext,
         pending_operation::DynPendingOperation,
         processor::{MessageProcessor, MessageProcessorMetrics},
-        serial_submitter::{SerialSubmitter, SerialSubmitterMetrics},
+        serial_submitter::{SerialSubmitter, SerialSubmitterMetrics, SubmitterQueues},
     },
+    server::OperatorApi,
     settings::{matching_list::MatchingList, RelayerSettings},
 };
 
@@ -49,31 +52,43 @@ pub struct Relayer {
     destination_chains: HashSet<HyperlaneDomain>,
     core: HyperlaneAgentCore,
     message_syncs: HashMap<HyperlaneDomain, Arc<MessageContractSync>>,
+    origin_providers: HashMap<HyperlaneDomain, Arc<dyn HyperlaneProvider>>,
     interchain_gas_payment_syncs:
         HashMap<HyperlaneDomain, Arc<WatermarkContractSync<InterchainGasPayment>>>,
+    /// Subscribers to the logs of origin chains indexed using subscriptions
+    message_subscribers: HashMap<HyperlaneDomain, Arc<dyn LogSubscriber<HyperlaneMessage>>>,
+    interchain_gas_payment_subscribers:
+        HashMap<HyperlaneDomain, Arc<dyn LogSubscriber<InterchainGasPayment>>>,
     /// Context data for each (origin, destination) chain pair a message can be
     /// sent between
     msg_ctxs: HashMap<ContextKey, Arc<MessageContext>>,
     prover_syncs: HashMap<HyperlaneDomain, Arc<RwLock<MerkleTreeBuilder>>>,
     dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
     whitelist: Arc<MatchingList>,
-    blacklist: Arc<MatchingList>,
+    blacklist: Arc<RwLock<MatchingList>>,
+    /// Queues of the submitter for each destination chain
+    submitter_queues: HashMap<HyperlaneDomain, SubmitterQueues>,
     transaction_gas_limit: Option<U256>,
     skip_transaction_gas_limit_for: HashSet<u32>,
+    max_submissions_in_flight: HashMap<u32, usize>,
+    max_batch_size: HashMap<u32, usize>,
     allow_local_checkpoint_syncers: bool,
+    operator_api_token: Option<String>,
 }
 
 impl Debug for Relayer {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, whitelist: {:?}, blacklist: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, allow_local_checkpoint_syncers: {:?} }}",
+            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, whitelist: {:?}, blacklist: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, max_submissions_in_flight: {:?}, max_batch_size: {:?}, allow_local_checkpoint_syncers: {:?} }}",
             self.origin_chains,
             self.destination_chains,
             self.whitelist,
             self.blacklist,
             self.transaction_gas_limit,
             self.skip_transaction_gas_limit_for,
+            self.max_submissions_in_flight,
+            self.max_batch_size,
             self.allow_local_checkpoint_syncers
         )
     }
@@ -110,6 +125,9 @@ impl BaseAgent for Relayer {
         let validator_announces = settings
             .build_validator_announces(settings.origin_chains.iter(), &metrics)
             .await?;
+        let origin_providers = settings
+            .build_providers(settings.origin_chains.iter(), &metrics)
+            .await?;
 
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
 
@@ -134,8 +152,20 @@ impl BaseAgent for Relayer {
             )
             .await?;
 
+        let mut message_subscribers = HashMap::new();
+        let mut interchain_gas_payment_subscribers = HashMap::new();
+        for origin in &settings.origin_chains {
+            let chain_setup = settings.chain_setup(origin)?;
+            if let Some(subscriber) = chain_setup.build_message_subscriber()? {
+                message_subscribers.insert(origin.clone(), subscriber);
+            }
+            if let Some(subscriber) = chain_setup.build_interchain_gas_payment_subscriber()? {
+                interchain_gas_payment_subscribers.insert(origin.clone(), subscriber);
+            }
+        }
+
         let whitelist = Arc::new(settings.whitelist);
-        let blacklist = Arc::new(settings.blacklist);
+        let blacklist = settings.blacklist;
         let skip_transaction_gas_limit_for = settings.skip_transaction_gas_limit_for;
         let transaction_gas_limit = settings.transaction_gas_limit;
 
@@ -144,8 +174,11 @@ impl BaseAgent for Relayer {
             %blacklist,
             ?transaction_gas_limit,
             ?skip_transaction_gas_limit_for,
+            max_submissions_in_flight=?settings.max_submissions_in_flight,
+            max_batch_size=?settings.max_batch_size,
             "Whitelist configuration"
         );
+        let blacklist = Arc::new(RwLock::new(blacklist));
 
         // provers by origin chain
         let prover_syncs = settings
@@ -216,6 +249,12 @@ impl BaseAgent for Relayer {
             }
         }
 
+        let submitter_queues = settings
+            .destination_chains
+            .iter()
+            .map(|destination| (destination.clone(), SubmitterQueues::default()))
+            .collect();
+
         Ok(Self {
             dbs,
             origin_chains: settings.origin_chains,
@@ -223,16 +262,36 @@ impl BaseAgent for Relayer {
             msg_ctxs,
             core,
             message_syncs,
+            origin_providers,
             interchain_gas_payment_syncs,
+            message_subscribers,
+            interchain_gas_payment_subscribers,
             prover_syncs,
             whitelist,
             blacklist,
+            submitter_queues,
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
+            max_submissions_in_flight: settings.max_submissions_in_flight,
+            max_batch_size: settings.max_batch_size,
             allow_local_checkpoint_syncers: settings.allow_local_checkpoint_syncers,
+            operator_api_token: settings.operator_api_token,
         })
     }
 
+    fn http_routes(&self) -> Option<HttpRoutes> {
+        let token = self.operator_api_token.clone()?;
+        Some(
+            OperatorApi::new(
+                token,
+                self.submitter_queues.clone(),
+                self.blacklist.clone(),
+                self.dbs.clone(),
+            )
+            .routes(),
+        )
+    }
+
     #[allow(clippy::async_yields_async)]
     async fn run(self) -> Instrumented<JoinHandle<Result<()>>> {
         let mut tasks = vec![];
@@ -250,6 +309,7 @@ impl BaseAgent for Relayer {
         for origin in &self.origin_chains {
             tasks.push(self.run_message_sync(origin).await);
             tasks.push(self.run_interchain_gas_payment_sync(origin).await);
+            tasks.extend(self.run_log_subscriptions(origin));
         }
 
         // each message process attempts to send messages from a chain
@@ -290,9 +350,45 @@ impl Relayer {
             .get(origin)
             .unwrap()
             .clone();
-        let cursor = contract_sync.rate_limited_cursor(index_settings).await;
-        tokio::spawn(async move { contract_sync.clone().sync("gas_payments", cursor).await })
-            .instrument(info_span!("ContractSync"))
+        tokio::spawn(async move {
+            let cursor = contract_sync.rate_limited_cursor(index_settings).await?;
+            contract_sync.sync("gas_payments", cursor).await
+        })
+        .instrument(info_span!("ContractSync"))
+    }
+
+    /// Subscribe to the logs of an origin chain indexed using subscriptions,
+    /// alongside the syncs that fill any gaps.
+    fn run_log_subscriptions(
+        &self,
+        origin: &HyperlaneDomain,
+    ) -> Vec<Instrumented<JoinHandle<eyre::Result<()>>>> {
+        let mut tasks = vec![];
+        if let Some(subscriber) = self.message_subscribers.get(origin).cloned() {
+            let contract_sync = self.message_syncs.get(origin).unwrap().clone();
+            tasks.push(
+                tokio::spawn(async move {
+                    contract_sync
+                        .subscribe("dispatched_messages", subscriber)
+                        .await
+                })
+                .instrument(info_span!("ContractSubscription")),
+            );
+        }
+        if let Some(subscriber) = self.interchain_gas_payment_subscribers.get(origin).cloned() {
+            let contract_sync = self
+                .interchain_gas_payment_syncs
+                .get(origin)
+                .unwrap()
+                .clone();
+            tasks.push(
+                tokio::spawn(
+                    async move { contract_sync.subscribe("gas_payments", subscriber).await },
+                )
+                .instrument(info_span!("ContractSubscription")),
+            );
+        }
+        tasks
     }
 
     fn run_message_processor(
@@ -322,6 +418,7 @@ impl Relayer {
             .collect();
         let message_processor = MessageProcessor::new(
             self.dbs.get(origin).unwrap().clone(),
+            self.origin_providers[origin].clone(),
             self.whitelist.clone(),
             self.blacklist.clone(),
             metrics,
@@ -347,9 +444,22 @@ impl Relayer {
         destination: &HyperlaneDomain,
         receiver: UnboundedReceiver<Box<DynPendingOperation>>,
     ) -> Instrumented<JoinHandle<Result<()>>> {
+        let max_in_flight = self
+            .max_submissions_in_flight
+            .get(&destination.id())
+            .copied()
+            .unwrap_or(1);
+        let max_batch_size = self
+            .max_batch_size
+            .get(&destination.id())
+            .copied()
+            .unwrap_or(1);
         let serial_submitter = SerialSubmitter::new(
             destination.clone(),
             receiver,
+            max_in_flight,
+            max_batch_size,
+            self.submitter_queues[destination].clone(),
             SerialSubmitterMetrics::new(&self.core.metrics, destination),
         );
         let span = info_span!("SerialSubmitter", destination=%destination);
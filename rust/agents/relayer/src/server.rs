@@ -0,0 +1,516 @@
+//! HTTP API for operators to inspect and manage the messages the relayer is
+//! working on. It is served next to the metrics and is only enabled when an
+//! `operatorApiToken` is configured.
+//!
+//! Every request must carry an `Authorization: Bearer <token>` header.
+//!
+//! - `GET /operator/operations`: queued operations by destination chain
+//! - `GET /operator/messages/{id}`: the queued operation for a message
+//! - `POST /operator/messages/{id}/retry`: attempt a message again right away
+//! - `POST /operator/messages/{id}/drop`: stop trying to deliver a message
+//! - `GET /operator/messages/{id}/status`: the recorded status of a message
+//! - `GET /operator/dead-letters`: messages the relayer gave up on, by origin
+//!   chain
+//! - `GET /operator/gas-accounts`: what senders paid for delivery and what
+//!   it cost, for messages under a value based gas payment policy
+//! - `GET /operator/blacklist`: the rules of the blacklist
+//! - `POST /operator/blacklist`: add rules, in the format of the `blacklist`
+//!   setting
+//! - `DELETE /operator/blacklist/{index}`: remove a rule
+//!
+//! Operations being prepared, submitted or confirmed at the moment of a
+//! request are briefly not in any queue and won't be found.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use derive_new::new;
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tracing::info;
+use warp::{http::StatusCode, hyper::body::Bytes, Filter, Rejection, Reply};
+
+use hyperlane_base::{
+    db::{DbError, GasAccount, HyperlaneRocksDB, MessageStatusRecord},
+    HttpRoutes,
+};
+use hyperlane_core::{HyperlaneDomain, H256, U256};
+
+use crate::{
+    msg::{
+        pending_operation::{DynPendingOperation, PendingOperation},
+        serial_submitter::{OpQueue, SubmitterQueues},
+    },
+    settings::matching_list::MatchingList,
+};
+
+/// Serves the operator API.
+#[derive(new)]
+pub struct OperatorApi {
+    /// Token requests must be authorized with.
+    token: String,
+    /// The submitter queues for each destination chain.
+    queues: HashMap<HyperlaneDomain, SubmitterQueues>,
+    /// Blacklist shared with the message processors.
+    blacklist: Arc<RwLock<MatchingList>>,
+    /// The database of each origin chain.
+    dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
+}
+
+/// Which of a submitter's queues an operation is waiting in.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum QueueName {
+    Prepare,
+    Confirm,
+}
+
+/// A queued operation as reported by the API.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OperationInfo {
+    id: H256,
+    destination: String,
+    queue: QueueName,
+    num_retries: u32,
+    /// Seconds until the operation will next be attempted, if it is waiting
+    /// on a backoff.
+    next_attempt_in_secs: Option<u64>,
+    origin: u32,
+    nonce: u32,
+    sender: H256,
+    recipient: H256,
+}
+
+impl OperationInfo {
+    fn new(destination: &HyperlaneDomain, queue: QueueName, op: &DynPendingOperation) -> Self {
+        let DynPendingOperation::PendingMessage(pending) = op;
+        Self {
+            id: op.id(),
+            destination: destination.name().to_owned(),
+            queue,
+            num_retries: op.num_retries(),
+            next_attempt_in_secs: op
+                .next_attempt_after()
+                .map(|at| at.saturating_duration_since(Instant::now()).as_secs()),
+            origin: pending.message.origin,
+            nonce: pending.message.nonce,
+            sender: pending.message.sender,
+            recipient: pending.message.recipient,
+        }
+    }
+}
+
+/// The recorded status of a message as reported by the API.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusInfo {
+    id: H256,
+    origin: String,
+    #[serde(flatten)]
+    record: MessageStatusRecord,
+}
+
+/// The gas account of a sender as reported by the API. Amounts are in the
+/// origin chain's native token.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GasAccountInfo {
+    origin: String,
+    destination: u32,
+    sender: H256,
+    payment: U256,
+    cost: U256,
+    delivered: u32,
+    surplus: U256,
+    deficit: U256,
+}
+
+impl GasAccountInfo {
+    fn new(origin: &HyperlaneDomain, destination: u32, sender: H256, account: GasAccount) -> Self {
+        Self {
+            origin: origin.name().to_owned(),
+            destination,
+            sender,
+            payment: account.payment,
+            cost: account.cost,
+            delivered: account.delivered,
+            surplus: account.surplus(),
+            deficit: account.deficit(),
+        }
+    }
+}
+
+/// Rejection for requests without a valid token.
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+impl OperatorApi {
+    /// The routes of the API, to be served by the agent's HTTP server.
+    pub fn routes(self) -> HttpRoutes {
+        let api = Arc::new(self);
+        let authorized = warp::header::optional::<String>("authorization")
+            .and(warp::any().map(move || api.clone()))
+            .and_then(|header: Option<String>, api: Arc<Self>| async move {
+                if api.is_authorized(header.as_deref()) {
+                    Ok(api)
+                } else {
+                    Err(warp::reject::custom(Unauthorized))
+                }
+            });
+
+        let operations = warp::path!("operations")
+            .and(warp::get())
+            .and(authorized.clone())
+            .then(|api: Arc<Self>| async move { reply_json(&api.list_operations().await) });
+        let message = warp::path!("messages" / H256)
+            .and(warp::get())
+            .and(authorized.clone())
+            .then(|id: H256, api: Arc<Self>| async move {
+                reply_if_queued(api.find_operation(id).await)
+            });
+        let retry_message = warp::path!("messages" / H256 / "retry")
+            .and(warp::post())
+            .and(authorized.clone())
+            .then(|id: H256, api: Arc<Self>| async move {
+                let op = api
+                    .update_operation(id, |op| {
+                        op.retry_now();
+                        true
+                    })
+                    .await;
+                if op.is_some() {
+                    info!(?id, "Operator requested an immediate retry of message");
+                }
+                reply_if_queued(op)
+            });
+        let drop_message = warp::path!("messages" / H256 / "drop")
+            .and(warp::post())
+            .and(authorized.clone())
+            .then(|id: H256, api: Arc<Self>| async move {
+                let op = api
+                    .update_operation(id, |op| {
+                        op.on_drop("Dropped by operator");
+                        false
+                    })
+                    .await;
+                if op.is_some() {
+                    info!(?id, "Operator dropped message");
+                }
+                reply_if_queued(op)
+            });
+        let message_status = warp::path!("messages" / H256 / "status")
+            .and(warp::get())
+            .and(authorized.clone())
+            .then(|id: H256, api: Arc<Self>| async move {
+                match api.message_status(id) {
+                    Ok(Some(status)) => reply_json(&status),
+                    Ok(None) => {
+                        reply_status("No status recorded for message", StatusCode::NOT_FOUND)
+                    }
+                    Err(e) => reply_status(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR),
+                }
+            });
+        let dead_letters = warp::path!("dead-letters")
+            .and(warp::get())
+            .and(authorized.clone())
+            .then(|api: Arc<Self>| async move {
+                match api.dead_letters() {
+                    Ok(dead_letters) => reply_json(&dead_letters),
+                    Err(e) => reply_status(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR),
+                }
+            });
+        let gas_accounts = warp::path!("gas-accounts")
+            .and(warp::get())
+            .and(authorized.clone())
+            .then(|api: Arc<Self>| async move {
+                match api.gas_accounts() {
+                    Ok(accounts) => reply_json(&accounts),
+                    Err(e) => reply_status(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR),
+                }
+            });
+        let blacklist = warp::path!("blacklist")
+            .and(warp::get())
+            .and(authorized.clone())
+            .then(|api: Arc<Self>| async move { reply_json(&api.blacklist.read().await.rules()) });
+        let add_to_blacklist = warp::path!("blacklist")
+            .and(warp::post())
+            .and(authorized.clone())
+            .and(warp::body::bytes())
+            .then(|api: Arc<Self>, body: Bytes| async move {
+                // Parsed here rather than with `warp::body::json` because the
+                // list's deserializer borrows from the input.
+                let rules = match serde_json::from_slice::<MatchingList>(&body) {
+                    Ok(rules) => rules,
+                    Err(e) => return reply_status(e.to_string(), StatusCode::BAD_REQUEST),
+                };
+                info!(%rules, "Operator added blacklist rules");
+                let mut blacklist = api.blacklist.write().await;
+                blacklist.extend(rules);
+                reply_json(&blacklist.rules())
+            });
+        let remove_from_blacklist = warp::path!("blacklist" / usize)
+            .and(warp::delete())
+            .and(authorized)
+            .then(|index: usize, api: Arc<Self>| async move {
+                let mut blacklist = api.blacklist.write().await;
+                if blacklist.remove(index) {
+                    info!(index, "Operator removed blacklist rule");
+                    reply_json(&blacklist.rules())
+                } else {
+                    reply_status("No blacklist rule at this index", StatusCode::NOT_FOUND)
+                }
+            });
+
+        warp::path("operator")
+            .and(
+                operations
+                    .or(message)
+                    .unify()
+                    .or(retry_message)
+                    .unify()
+                    .or(drop_message)
+                    .unify()
+                    .or(message_status)
+                    .unify()
+                    .or(dead_letters)
+                    .unify()
+                    .or(gas_accounts)
+                    .unify()
+                    .or(blacklist)
+                    .unify()
+                    .or(add_to_blacklist)
+                    .unify()
+                    .or(remove_from_blacklist)
+                    .unify(),
+            )
+            .recover(handle_rejection)
+            .unify()
+            .boxed()
+    }
+
+    fn is_authorized(&self, header: Option<&str>) -> bool {
+        let Some(token) = header.and_then(|h| h.strip_prefix("Bearer ")) else {
+            return false;
+        };
+        // Compare every byte so the response time doesn't reveal how much of
+        // the token was right
+        token.len() == self.token.len()
+            && token
+                .bytes()
+                .zip(self.token.bytes())
+                .fold(0, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+
+    fn queues(&self) -> impl Iterator<Item = (&HyperlaneDomain, QueueName, &OpQueue)> {
+        self.queues.iter().flat_map(|(destination, queues)| {
+            [
+                (destination, QueueName::Prepare, &queues.prepare),
+                (destination, QueueName::Confirm, &queues.confirm),
+            ]
+        })
+    }
+
+    /// All queued operations by destination name, in the order they will be
+    /// attempted.
+    async fn list_operations(&self) -> HashMap<String, Vec<OperationInfo>> {
+        let mut operations: HashMap<String, Vec<OperationInfo>> = HashMap::new();
+        for (destination, queue_name, queue) in self.queues() {
+            let queue = queue.lock().await;
+            let mut ops = queue.iter().map(|Reverse(op)| op).collect::<Vec<_>>();
+            ops.sort();
+            operations
+                .entry(destination.name().to_owned())
+                .or_default()
+                .extend(
+                    ops.into_iter()
+                        .map(|op| OperationInfo::new(destination, queue_name, op)),
+                );
+        }
+        operations
+    }
+
+    async fn find_operation(&self, id: H256) -> Option<OperationInfo> {
+        for (destination, queue_name, queue) in self.queues() {
+            let queue = queue.lock().await;
+            if let Some(Reverse(op)) = queue.iter().find(|Reverse(op)| op.id() == id) {
+                return Some(OperationInfo::new(destination, queue_name, op));
+            }
+        }
+        None
+    }
+
+    fn message_status(&self, id: H256) -> Result<Option<StatusInfo>, DbError> {
+        for (origin, db) in &self.dbs {
+            if let Some(record) = db.retrieve_message_status(&id)? {
+                return Ok(Some(StatusInfo {
+                    id,
+                    origin: origin.name().to_owned(),
+                    record,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn dead_letters(&self) -> Result<Vec<StatusInfo>, DbError> {
+        let mut dead_letters = vec![];
+        for (origin, db) in &self.dbs {
+            dead_letters.extend(db.retrieve_dead_letters()?.into_iter().map(|(id, record)| {
+                StatusInfo {
+                    id,
+                    origin: origin.name().to_owned(),
+                    record,
+                }
+            }));
+        }
+        Ok(dead_letters)
+    }
+
+    fn gas_accounts(&self) -> Result<Vec<GasAccountInfo>, DbError> {
+        let mut accounts = vec![];
+        for (origin, db) in &self.dbs {
+            accounts.extend(
+                db.retrieve_gas_accounts()?
+                    .into_iter()
+                    .map(|(key, account)| {
+                        GasAccountInfo::new(origin, key.destination, key.sender, account)
+                    }),
+            );
+        }
+        Ok(accounts)
+    }
+
+    /// Apply `update` to the queued operation with the given id. The operation
+    /// is removed from its queue if `update` returns false.
+    async fn update_operation(
+        &self,
+        id: H256,
+        update: impl Fn(&mut DynPendingOperation) -> bool,
+    ) -> Option<OperationInfo> {
+        for (destination, queue_name, queue) in self.queues() {
+            let mut queue = queue.lock().await;
+            let Some(index) = queue.iter().position(|Reverse(op)| op.id() == id) else {
+                continue;
+            };
+            // Operations can't be changed in place without breaking the order of
+            // the heap, so it is rebuilt around the change
+            let mut ops = std::mem::take(&mut *queue).into_vec();
+            let keep = update(&mut ops[index].0);
+            let info = OperationInfo::new(destination, queue_name, &ops[index].0);
+            if !keep {
+                ops.swap_remove(index);
+            }
+            *queue = ops.into();
+            return Some(info);
+        }
+        None
+    }
+}
+
+fn reply_json<T: Serialize>(value: &T) -> Box<dyn Reply> {
+    Box::new(warp::reply::json(value))
+}
+
+fn reply_status(body: impl Into<String>, status: StatusCode) -> Box<dyn Reply> {
+    Box::new(warp::reply::with_status(body.into(), status))
+}
+
+fn reply_if_queued(op: Option<OperationInfo>) -> Box<dyn Reply> {
+    match op {
+        Some(op) => reply_json(&op),
+        None => reply_status(
+            "Message is not queued, it may be in flight or already delivered",
+            StatusCode::NOT_FOUND,
+        ),
+    }
+}
+
+async fn handle_rejection(rejection: Rejection) -> Result<Box<dyn Reply>, Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        Ok(reply_status("Unauthorized", StatusCode::UNAUTHORIZED))
+    } else {
+        Err(rejection)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn routes() -> HttpRoutes {
+        OperatorApi::new(
+            "secret".into(),
+            HashMap::new(),
+            Default::default(),
+            HashMap::new(),
+        )
+        .routes()
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_token() {
+        let routes = routes();
+        let res = warp::test::request()
+            .path("/operator/blacklist")
+            .reply(&routes)
+            .await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let res = warp::test::request()
+            .path("/operator/blacklist")
+            .header("authorization", "Bearer wrong!")
+            .reply(&routes)
+            .await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn manages_blacklist() {
+        let routes = routes();
+        let res = warp::test::request()
+            .method("POST")
+            .path("/operator/blacklist")
+            .header("authorization", "Bearer secret")
+            .body(r#"[{"originDomain": 1}, {"destinationDomain": 2}]"#)
+            .reply(&routes)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let rules: Vec<String> = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(rules.len(), 2);
+
+        let res = warp::test::request()
+            .method("DELETE")
+            .path("/operator/blacklist/0")
+            .header("authorization", "Bearer secret")
+            .reply(&routes)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let rules: Vec<String> = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert!(rules[0].contains("destinationDomain: 2"));
+
+        let res = warp::test::request()
+            .method("DELETE")
+            .path("/operator/blacklist/1")
+            .header("authorization", "Bearer secret")
+            .reply(&routes)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn unknown_message_is_not_found() {
+        let res = warp::test::request()
+            .path(&format!("/operator/messages/{:?}", H256::zero()))
+            .header("authorization", "Bearer secret")
+            .reply(&routes())
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
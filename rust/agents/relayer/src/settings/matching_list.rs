@@ -2,7 +2,8 @@ use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
 
-use serde::de::{Error, SeqAccess, Visitor};
+use ethers::utils::hex;
+use serde::de::{Error, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer};
 
 use hyperlane_core::config::StrOrInt;
@@ -16,6 +17,13 @@ use hyperlane_core::{HyperlaneMessage, H160, H256};
 /// - wildcard "*"
 /// - single value in decimal or hex (must start with `0x`) format
 /// - list of values in decimal or hex format
+/// - for domains and the version, an inclusive range `{"min": 1, "max": 10}`,
+///   where either bound may be left out
+/// - a negation `{"not": ...}` of any of the above
+///
+/// Besides the origin, sender, destination and recipient, rules can match the
+/// `messageId`, the `version` and a `bodyPrefix`, e.g. a function selector in
+/// hex.
 #[derive(Debug, Deserialize, Default, Clone)]
 #[serde(transparent)]
 pub struct MatchingList(Option<Vec<ListElement>>);
@@ -24,6 +32,10 @@ pub struct MatchingList(Option<Vec<ListElement>>);
 enum Filter<T> {
     Wildcard,
     Enumerated(Vec<T>),
+    /// Inclusive range of values
+    Range(T, T),
+    /// Matches everything the inner filter does not
+    Not(Box<Filter<T>>),
 }
 
 impl<T> Default for Filter<T> {
@@ -32,15 +44,35 @@ impl<T> Default for Filter<T> {
     }
 }
 
-impl<T: PartialEq> Filter<T> {
-    fn matches(&self, v: &T) -> bool {
+impl<T> Filter<T> {
+    /// Check the filter given whether a single one of its values matches and
+    /// whether a range of them contains what is being matched.
+    fn matches_with(
+        &self,
+        is_match: &impl Fn(&T) -> bool,
+        in_range: &impl Fn(&T, &T) -> bool,
+    ) -> bool {
         match self {
             Filter::Wildcard => true,
-            Filter::Enumerated(list) => list.iter().any(|i| i == v),
+            Filter::Enumerated(list) => list.iter().any(is_match),
+            Filter::Range(min, max) => in_range(min, max),
+            Filter::Not(filter) => !filter.matches_with(is_match, in_range),
         }
     }
 }
 
+impl<T: PartialOrd> Filter<T> {
+    fn matches(&self, v: &T) -> bool {
+        self.matches_with(&|i| i == v, &|min, max| min <= v && v <= max)
+    }
+}
+
+impl Filter<BodyPrefix> {
+    fn matches_body(&self, body: &[u8]) -> bool {
+        self.matches_with(&|prefix| body.starts_with(&prefix.0), &|_, _| false)
+    }
+}
+
 impl<T: Debug> Display for Filter<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -53,16 +85,29 @@ impl<T: Debug> Display for Filter<T> {
                 }
                 write!(f, "]")
             }
+            Self::Range(min, max) => write!(f, "{min:?}..={max:?}"),
+            Self::Not(filter) => write!(f, "!{filter}"),
         }
     }
 }
 
+/// The first bytes of a message body, e.g. a function selector or the message
+/// type of a warp route.
+#[derive(Clone, PartialEq)]
+struct BodyPrefix(Vec<u8>);
+
+impl Debug for BodyPrefix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(&self.0))
+    }
+}
+
 struct FilterVisitor<T>(PhantomData<T>);
 impl<'de> Visitor<'de> for FilterVisitor<u32> {
     type Value = Filter<u32>;
 
     fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
-        write!(fmt, "Expecting either a wildcard \"*\", decimal/hex value string, or list of decimal/hex value strings")
+        write!(fmt, "Expecting either a wildcard \"*\", decimal/hex value string, list of decimal/hex value strings, a range {{\"min\": ..., \"max\": ...}} or a negation {{\"not\": ...}}")
     }
 
     fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E>
@@ -104,6 +149,42 @@ impl<'de> Visitor<'de> for FilterVisitor<u32> {
         }
         Ok(Self::Value::Enumerated(values))
     }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut min: Option<u32> = None;
+        let mut max: Option<u32> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "not" if min.is_none() && max.is_none() => return visit_negation(key, map),
+                "not" => return Err(A::Error::custom("A negation can't have other fields")),
+                "min" => {
+                    min = Some(
+                        map.next_value::<StrOrInt>()?
+                            .try_into()
+                            .map_err(to_serde_err)?,
+                    )
+                }
+                "max" => {
+                    max = Some(
+                        map.next_value::<StrOrInt>()?
+                            .try_into()
+                            .map_err(to_serde_err)?,
+                    )
+                }
+                _ => return Err(A::Error::unknown_field(&key, &["min", "max", "not"])),
+            }
+        }
+        if min.is_none() && max.is_none() {
+            return Err(A::Error::custom("A range needs a \"min\" or \"max\""));
+        }
+        Ok(Self::Value::Range(
+            min.unwrap_or_default(),
+            max.unwrap_or(u32::MAX),
+        ))
+    }
 }
 
 impl<'de> Visitor<'de> for FilterVisitor<H256> {
@@ -112,7 +193,7 @@ impl<'de> Visitor<'de> for FilterVisitor<H256> {
     fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
         write!(
             fmt,
-            "Expecting either a wildcard \"*\", hex address string, or list of hex address strings"
+            "Expecting either a wildcard \"*\", hex address string, list of hex address strings or a negation {{\"not\": ...}}"
         )
     }
 
@@ -137,6 +218,77 @@ impl<'de> Visitor<'de> for FilterVisitor<H256> {
         }
         Ok(Self::Value::Enumerated(values))
     }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match map.next_key::<String>()? {
+            Some(key) => visit_negation(key, map),
+            None => Err(A::Error::missing_field("not")),
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for FilterVisitor<BodyPrefix> {
+    type Value = Filter<BodyPrefix>;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "Expecting either a wildcard \"*\", hex prefix string, list of hex prefix strings or a negation {{\"not\": ...}}"
+        )
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(if v == "*" {
+            Self::Value::Wildcard
+        } else {
+            Self::Value::Enumerated(vec![parse_body_prefix(v)?])
+        })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        while let Some(i) = seq.next_element::<String>()? {
+            values.push(parse_body_prefix(&i)?)
+        }
+        Ok(Self::Value::Enumerated(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match map.next_key::<String>()? {
+            Some(key) => visit_negation(key, map),
+            None => Err(A::Error::missing_field("not")),
+        }
+    }
+}
+
+/// Finish visiting a `{"not": ...}` filter whose first key has been read.
+fn visit_negation<'de, A, T>(key: String, mut map: A) -> Result<Filter<T>, A::Error>
+where
+    A: MapAccess<'de>,
+    Filter<T>: Deserialize<'de>,
+{
+    if key != "not" {
+        return Err(A::Error::unknown_field(&key, &["not"]));
+    }
+    let filter = map.next_value::<Filter<T>>()?;
+    if let Some(key) = map.next_key::<String>()? {
+        return Err(A::Error::custom(format!(
+            "A negation can't have other fields, found \"{key}\""
+        )));
+    }
+    Ok(Filter::Not(Box::new(filter)))
 }
 
 impl<'de> Deserialize<'de> for Filter<u32> {
@@ -157,6 +309,15 @@ impl<'de> Deserialize<'de> for Filter<H256> {
     }
 }
 
+impl<'de> Deserialize<'de> for Filter<BodyPrefix> {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        d.deserialize_any(FilterVisitor::<BodyPrefix>(Default::default()))
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[serde(tag = "type")]
 struct ListElement {
@@ -168,17 +329,26 @@ struct ListElement {
     destination_domain: Filter<u32>,
     #[serde(default, rename = "recipientAddress")]
     recipient_address: Filter<H256>,
+    #[serde(default, rename = "messageId")]
+    message_id: Filter<H256>,
+    #[serde(default)]
+    version: Filter<u32>,
+    #[serde(default, rename = "bodyPrefix")]
+    body_prefix: Filter<BodyPrefix>,
 }
 
 impl Display for ListElement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{originDomain: {}, senderAddress: {}, destinationDomain: {}, recipientAddress: {}}}",
+            "{{originDomain: {}, senderAddress: {}, destinationDomain: {}, recipientAddress: {}, messageId: {}, version: {}, bodyPrefix: {}}}",
             self.origin_domain,
             self.sender_address,
             self.destination_domain,
-            self.recipient_address
+            self.recipient_address,
+            self.message_id,
+            self.version,
+            self.body_prefix
         )
     }
 }
@@ -189,15 +359,22 @@ struct MatchInfo<'a> {
     src_addr: &'a H256,
     dst_domain: u32,
     dst_addr: &'a H256,
+    /// Only computed if a rule filters on message ids, as it hashes the message
+    id: Option<H256>,
+    version: u8,
+    body: &'a [u8],
 }
 
-impl<'a> From<&'a HyperlaneMessage> for MatchInfo<'a> {
-    fn from(msg: &'a HyperlaneMessage) -> Self {
+impl<'a> MatchInfo<'a> {
+    fn new(msg: &'a HyperlaneMessage, with_id: bool) -> Self {
         Self {
             src_domain: msg.origin,
             src_addr: &msg.sender,
             dst_domain: msg.destination,
             dst_addr: &msg.recipient,
+            id: with_id.then(|| msg.id()),
+            version: msg.version,
+            body: &msg.body,
         }
     }
 }
@@ -206,7 +383,39 @@ impl MatchingList {
     /// Check if a message matches any of the rules.
     /// - `default`: What to return if the the matching list is empty.
     pub fn msg_matches(&self, msg: &HyperlaneMessage, default: bool) -> bool {
-        self.matches(msg.into(), default)
+        let filters_ids = self
+            .0
+            .iter()
+            .flatten()
+            .any(|rule| rule.message_id != Filter::Wildcard);
+        self.matches(MatchInfo::new(msg, filters_ids), default)
+    }
+
+    /// The rules of this list, formatted for display.
+    pub fn rules(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .flatten()
+            .map(|rule| rule.to_string())
+            .collect()
+    }
+
+    /// Add the rules of another list to the end of this one.
+    pub fn extend(&mut self, other: MatchingList) {
+        if let Some(rules) = other.0 {
+            self.0.get_or_insert_with(Vec::new).extend(rules);
+        }
+    }
+
+    /// Remove the rule at `index`, returning false if there is no such rule.
+    pub fn remove(&mut self, index: usize) -> bool {
+        match &mut self.0 {
+            Some(rules) if index < rules.len() => {
+                rules.remove(index);
+                true
+            }
+            _ => false,
+        }
     }
 
     /// Check if a message matches any of the rules.
@@ -226,6 +435,12 @@ fn matches_any_rule<'a>(mut rules: impl Iterator<Item = &'a ListElement>, info:
             && rule.sender_address.matches(info.src_addr)
             && rule.destination_domain.matches(&info.dst_domain)
             && rule.recipient_address.matches(info.dst_addr)
+            && match &info.id {
+                Some(id) => rule.message_id.matches(id),
+                None => rule.message_id == Filter::Wildcard,
+            }
+            && rule.version.matches(&u32::from(info.version))
+            && rule.body_prefix.matches_body(info.body)
     })
 }
 
@@ -247,6 +462,12 @@ fn to_serde_err<IE: ToString, OE: Error>(e: IE) -> OE {
     OE::custom(e.to_string())
 }
 
+fn parse_body_prefix<E: Error>(prefix_str: &str) -> Result<BodyPrefix, E> {
+    hex::decode(prefix_str.strip_prefix("0x").unwrap_or(prefix_str))
+        .map(BodyPrefix)
+        .map_err(to_serde_err)
+}
+
 fn parse_addr<E: Error>(addr_str: &str) -> Result<H256, E> {
     if addr_str.len() <= 42 {
         addr_str.parse::<H160>().map(H256::from)
@@ -258,7 +479,7 @@ fn parse_addr<E: Error>(addr_str: &str) -> Result<H256, E> {
 
 #[cfg(test)]
 mod test {
-    use hyperlane_core::{H160, H256};
+    use hyperlane_core::{HyperlaneMessage, H160, H256};
 
     use crate::settings::matching_list::MatchInfo;
 
@@ -286,7 +507,10 @@ mod test {
                 src_domain: 0,
                 src_addr: &H256::default(),
                 dst_domain: 0,
-                dst_addr: &H256::default()
+                dst_addr: &H256::default(),
+                id: None,
+                version: 0,
+                body: &[],
             },
             false
         ));
@@ -299,7 +523,10 @@ mod test {
                     .unwrap()
                     .into(),
                 dst_domain: 5456,
-                dst_addr: &H256::default()
+                dst_addr: &H256::default(),
+                id: None,
+                version: 0,
+                body: &[],
             },
             false
         ))
@@ -339,7 +566,10 @@ mod test {
                 dst_addr: &"9d4454B023096f34B160D6B654540c56A1F81688"
                     .parse::<H160>()
                     .unwrap()
-                    .into()
+                    .into(),
+                id: None,
+                version: 0,
+                body: &[],
             },
             false
         ));
@@ -352,7 +582,10 @@ mod test {
                     .unwrap()
                     .into(),
                 dst_domain: 5456,
-                dst_addr: &H256::default()
+                dst_addr: &H256::default(),
+                id: None,
+                version: 0,
+                body: &[],
             },
             false
         ));
@@ -378,10 +611,118 @@ mod test {
             src_addr: &H256::default(),
             dst_domain: 0,
             dst_addr: &H256::default(),
+            id: None,
+            version: 0,
+            body: &[],
         };
         // whitelist use
         assert!(MatchingList(None).matches(info, true));
         // blacklist use
         assert!(!MatchingList(None).matches(info, false));
     }
+
+    #[test]
+    fn extend_and_remove_rules() {
+        let info = MatchInfo {
+            src_domain: 1,
+            src_addr: &H256::default(),
+            dst_domain: 2,
+            dst_addr: &H256::default(),
+            id: None,
+            version: 0,
+            body: &[],
+        };
+        let mut list = MatchingList::default();
+        list.extend(serde_json::from_str(r#"[{"originDomain": 3}]"#).unwrap());
+        list.extend(serde_json::from_str(r#"[{"originDomain": 1}]"#).unwrap());
+        assert_eq!(list.rules().len(), 2);
+        assert!(list.matches(info, false));
+
+        assert!(list.remove(1));
+        assert!(!list.remove(1));
+        assert_eq!(list.rules().len(), 1);
+        assert!(!list.matches(info, false));
+    }
+
+    #[test]
+    fn config_with_ranges_and_negations() {
+        let list: MatchingList = serde_json::from_str(
+            r#"[{"originDomain": {"min": 100, "max": "200"}, "destinationDomain": {"not": [1, 2]}, "recipientAddress": {"not": "0x9d4454B023096f34B160D6B654540c56A1F81688"}}]"#,
+        )
+        .unwrap();
+        let elem = &list.0.as_ref().unwrap()[0];
+        assert_eq!(elem.origin_domain, Range(100, 200));
+        assert_eq!(
+            elem.destination_domain,
+            Not(Box::new(Enumerated(vec![1, 2])))
+        );
+
+        let msg = HyperlaneMessage {
+            origin: 150,
+            destination: 3,
+            ..Default::default()
+        };
+        assert!(list.msg_matches(&msg, false));
+        assert!(!list.msg_matches(
+            &HyperlaneMessage {
+                origin: 201,
+                ..msg.clone()
+            },
+            false
+        ));
+        assert!(!list.msg_matches(
+            &HyperlaneMessage {
+                destination: 2,
+                ..msg.clone()
+            },
+            false
+        ));
+        let recipient = "0x9d4454B023096f34B160D6B654540c56A1F81688"
+            .parse::<H160>()
+            .unwrap()
+            .into();
+        assert!(!list.msg_matches(&HyperlaneMessage { recipient, ..msg }, false));
+
+        let open_range: MatchingList =
+            serde_json::from_str(r#"[{"originDomain": {"min": 100}}]"#).unwrap();
+        assert_eq!(open_range.0.unwrap()[0].origin_domain, Range(100, u32::MAX));
+        assert!(serde_json::from_str::<MatchingList>(r#"[{"originDomain": {}}]"#).is_err());
+        assert!(serde_json::from_str::<MatchingList>(
+            r#"[{"originDomain": {"min": 1, "not": 2}}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn config_with_message_fields() {
+        let msg = HyperlaneMessage {
+            version: 3,
+            body: vec![0xa9, 0x05, 0x9c, 0xbb, 0x01],
+            ..Default::default()
+        };
+        let list: MatchingList = serde_json::from_str(&format!(
+            r#"[{{"messageId": "{:?}", "version": 3, "bodyPrefix": ["0x1234", "0xa9059cbb"]}}]"#,
+            msg.id()
+        ))
+        .unwrap();
+        assert!(list.msg_matches(&msg, false));
+        assert!(!list.msg_matches(
+            &HyperlaneMessage {
+                version: 0,
+                ..msg.clone()
+            },
+            false
+        ));
+
+        let list: MatchingList =
+            serde_json::from_str(r#"[{"bodyPrefix": {"not": "a9059cbb"}}]"#).unwrap();
+        assert!(!list.msg_matches(&msg, false));
+        assert!(list.msg_matches(
+            &HyperlaneMessage {
+                body: vec![0xa9],
+                ..msg
+            },
+            false
+        ));
+    }
 }
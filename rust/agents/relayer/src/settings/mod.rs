@@ -1,6 +1,9 @@
 //! Configuration
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 use eyre::{eyre, Context};
 use hyperlane_base::{decl_settings, settings::Settings};
@@ -26,6 +29,37 @@ pub enum GasPaymentEnforcementPolicy {
         gas_fraction_numerator: u64,
         gas_fraction_denominator: u64,
     },
+    /// The value of the payment in the origin chain's native token covers a
+    /// fraction of the estimated cost of delivery in the destination chain's
+    /// native token, converted using configured token prices.
+    ValueBased {
+        /// Price of the native token of each domain
+        token_prices: HashMap<u32, NativeTokenPrice>,
+        /// Gas prices to use instead of the estimated gas price of a
+        /// destination
+        gas_prices: HashMap<u32, U256>,
+        payment_fraction_numerator: u64,
+        payment_fraction_denominator: u64,
+        /// Once delivering a sender's messages has cost this much more than
+        /// they paid, in the unit of the token prices scaled by 10^18, they
+        /// must pay the full estimated cost.
+        max_sender_deficit: Option<U256>,
+    },
+}
+
+/// Price of a chain's native token in a unit shared by all chains, e.g. USD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NativeTokenPrice {
+    /// Price of one whole token scaled by 10^18
+    pub price: U256,
+    /// Number of decimals of the token
+    pub decimals: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawNativeTokenPrice {
+    price: Option<String>,
+    decimals: Option<u8>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -42,6 +76,24 @@ enum RawGasPaymentEnforcementPolicy {
         #[serde(default = "default_gasfraction")]
         gasfraction: String,
     },
+    #[serde(rename_all = "camelCase")]
+    ValueBased {
+        /// Price of the native token of each domain, keyed by domain id. Prices
+        /// are decimal strings in a unit shared by all chains, e.g. USD.
+        #[serde(default)]
+        token_prices: HashMap<String, RawNativeTokenPrice>,
+        /// Optional gas prices to use instead of the estimated gas price, keyed
+        /// by domain id.
+        #[serde(default)]
+        gas_prices: HashMap<String, StrOrInt>,
+        /// Optional fraction of the estimated cost which must be paid before
+        /// attempting to run the transaction. Must be written as `"numerator /
+        /// denominator"` where both are integers.
+        #[serde(default = "default_paymentfraction")]
+        payment_fraction: String,
+        /// Optional decimal amount in the unit of the token prices.
+        max_sender_deficit: Option<String>,
+    },
     #[serde(other)]
     Unknown,
 }
@@ -65,21 +117,66 @@ impl FromRawConf<RawGasPaymentEnforcementPolicy> for GasPaymentEnforcementPolicy
                     .into_config_result(|| cwp + "payment")?,
             }),
             OnChainFeeQuoting { gasfraction } => {
-                let (numerator, denominator) =
-                    gasfraction
-                        .replace(' ', "")
-                        .split_once('/')
-                        .map(|(a, b)| (a.to_owned(), b.to_owned()))
-                        .ok_or_else(|| eyre!("Invalid `gasfraction` for OnChainFeeQuoting gas payment enforcement policy; expected `numerator / denominator`"))
-                        .into_config_result(|| cwp + "gasfraction")?;
-
+                let (numerator, denominator) = parse_fraction(&gasfraction)
+                    .context("Invalid `gasfraction` for OnChainFeeQuoting gas payment enforcement policy")
+                    .into_config_result(|| cwp + "gasfraction")?;
                 Ok(Self::OnChainFeeQuoting {
-                    gas_fraction_numerator: numerator
-                        .parse()
-                        .into_config_result(|| cwp + "gasfraction")?,
-                    gas_fraction_denominator: denominator
-                        .parse()
-                        .into_config_result(|| cwp + "gasfraction")?,
+                    gas_fraction_numerator: numerator,
+                    gas_fraction_denominator: denominator,
+                })
+            }
+            ValueBased {
+                token_prices,
+                gas_prices,
+                payment_fraction,
+                max_sender_deficit,
+            } => {
+                let mut err = ConfigParsingError::default();
+                let token_prices = token_prices
+                    .into_iter()
+                    .filter_map(|(domain, raw)| {
+                        let cwp = cwp + "tokenprices" + &domain;
+                        let domain = domain.parse::<u32>().take_err(&mut err, || cwp.clone());
+                        let price = raw
+                            .price
+                            .ok_or_else(|| eyre!("Missing `price` for native token"))
+                            .and_then(|p| parse_decimal(&p))
+                            .take_err(&mut err, || &cwp + "price");
+                        let decimals = raw.decimals.unwrap_or(18);
+                        Some((
+                            domain?,
+                            NativeTokenPrice {
+                                price: price?,
+                                decimals,
+                            },
+                        ))
+                    })
+                    .collect();
+                let gas_prices = gas_prices
+                    .into_iter()
+                    .filter_map(|(domain, raw)| {
+                        let cwp = cwp + "gasprices" + &domain;
+                        let domain = domain.parse::<u32>().take_err(&mut err, || cwp.clone());
+                        let gas_price: Option<u64> = raw.try_into().take_err(&mut err, || cwp);
+                        Some((domain?, U256::from(gas_price?)))
+                    })
+                    .collect();
+                let fraction = parse_fraction(&payment_fraction)
+                    .context(
+                        "Invalid `paymentFraction` for ValueBased gas payment enforcement policy",
+                    )
+                    .take_err(&mut err, || cwp + "paymentfraction");
+                let max_sender_deficit = max_sender_deficit.and_then(|d| {
+                    parse_decimal(&d).take_err(&mut err, || cwp + "maxsenderdeficit")
+                });
+
+                let Some((numerator, denominator)) = fraction else { return Err(err) };
+                err.into_result(Self::ValueBased {
+                    token_prices,
+                    gas_prices,
+                    payment_fraction_numerator: numerator,
+                    payment_fraction_denominator: denominator,
+                    max_sender_deficit,
                 })
             }
             Unknown => Err(eyre!("Unknown gas payment enforcement policy"))
@@ -147,9 +244,19 @@ decl_settings!(Relayer,
         transaction_gas_limit: Option<U256>,
         /// List of domain ids to skip transaction gas for.
         skip_transaction_gas_limit_for: HashSet<u32>,
+        /// The number of transactions the submitter for a destination may have in
+        /// flight at once, by domain id. Destinations not listed submit one at a time.
+        max_submissions_in_flight: HashMap<u32, usize>,
+        /// The number of messages the submitter for a destination may deliver in a
+        /// single transaction, by domain id. Destinations not listed deliver
+        /// messages one at a time.
+        max_batch_size: HashMap<u32, usize>,
         /// If true, allows local storage based checkpoint syncers.
         /// Not intended for production use.
         allow_local_checkpoint_syncers: bool,
+        /// Bearer token required by the operator API. The API is only served
+        /// when this is set.
+        operator_api_token: Option<String>,
     },
     Raw {
         /// Database path (path on the fs)
@@ -175,10 +282,22 @@ decl_settings!(Relayer,
         transactiongaslimit: Option<StrOrInt>,
         /// Comma separated List of domain ids to skip transaction gas for.
         skiptransactiongaslimitfor: Option<String>,
+        /// This is optional. JSON object of destination domain ids to the number of
+        /// transactions that may be in flight at once when delivering to it. Any
+        /// destination not listed will have at most one transaction in flight.
+        maxsubmissionsinflight: Option<String>,
+        /// This is optional. JSON object of destination domain ids to the number of
+        /// messages that may be delivered in a single batch transaction. Any
+        /// destination not listed will have each message delivered on its own.
+        maxbatchsize: Option<String>,
         /// If true, allows local storage based checkpoint syncers.
         /// Not intended for production use. Defaults to false.
         #[serde(default)]
         allowlocalcheckpointsyncers: bool,
+        /// This is optional. Bearer token operators must present to use the HTTP API
+        /// for inspecting and managing the message queues, which is only served if
+        /// this is set.
+        operatorapitoken: Option<String>,
     }
 );
 
@@ -238,6 +357,30 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             })
             .unwrap_or_default();
 
+        let max_submissions_in_flight: HashMap<u32, usize> = raw
+            .maxsubmissionsinflight
+            .and_then(|j| {
+                serde_json::from_str(&j).take_err(&mut err, || cwp + "maxsubmissionsinflight")
+            })
+            .unwrap_or_default();
+        if max_submissions_in_flight.values().any(|&n| n == 0) {
+            err.push(
+                cwp + "maxsubmissionsinflight",
+                eyre!("The number of submissions in flight must be at least 1"),
+            );
+        }
+
+        let max_batch_size: HashMap<u32, usize> = raw
+            .maxbatchsize
+            .and_then(|j| serde_json::from_str(&j).take_err(&mut err, || cwp + "maxbatchsize"))
+            .unwrap_or_default();
+        if max_batch_size.values().any(|&n| n == 0) {
+            err.push(
+                cwp + "maxbatchsize",
+                eyre!("The batch size must be at least 1"),
+            );
+        }
+
         let mut origin_chain_names = {
             #[allow(deprecated)]
             raw.originchainname
@@ -376,7 +519,10 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             blacklist,
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
+            max_submissions_in_flight,
+            max_batch_size,
             allow_local_checkpoint_syncers: raw.allowlocalcheckpointsyncers,
+            operator_api_token: raw.operatorapitoken.filter(|t| !t.is_empty()),
         })
     }
 }
@@ -385,6 +531,45 @@ fn default_gasfraction() -> String {
     "1/2".into()
 }
 
+fn default_paymentfraction() -> String {
+    "1/1".into()
+}
+
+/// Parse a fraction written as `"numerator / denominator"`.
+fn parse_fraction(fraction: &str) -> eyre::Result<(u64, u64)> {
+    let (numerator, denominator) = fraction
+        .replace(' ', "")
+        .split_once('/')
+        .map(|(a, b)| (a.to_owned(), b.to_owned()))
+        .ok_or_else(|| eyre!("Expected `numerator / denominator`"))?;
+    let denominator: u64 = denominator.parse()?;
+    if denominator == 0 {
+        return Err(eyre!("Denominator must not be zero"));
+    }
+    Ok((numerator.parse()?, denominator))
+}
+
+/// Parse a non-negative decimal number such as `"1850.25"` into an integer
+/// scaled by 10^18.
+fn parse_decimal(decimal: &str) -> eyre::Result<U256> {
+    const DECIMALS: usize = 18;
+    let decimal = decimal.trim();
+    let (whole, fraction) = decimal.split_once('.').unwrap_or((decimal, ""));
+    if whole.is_empty() && fraction.is_empty()
+        || fraction.len() > DECIMALS
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(eyre!(
+            "Expected a non-negative decimal number with at most {DECIMALS} decimals, got `{decimal}`"
+        ));
+    }
+    let digits = format!("{whole}{fraction:0<DECIMALS$}");
+    Ok(U256::from_dec_str(&digits)?)
+}
+
 fn parse_chains(chains_str: String) -> Vec<String> {
     chains_str.split(',').map(str::to_ascii_lowercase).collect()
 }
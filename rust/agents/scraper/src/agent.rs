@@ -5,7 +5,7 @@ use hyperlane_base::{
     run_all, settings::IndexSettings, BaseAgent, ContractSyncMetrics, CoreMetrics,
     HyperlaneAgentCore,
 };
-use hyperlane_core::HyperlaneDomain;
+use hyperlane_core::{HyperlaneDomain, IndexMode};
 use tokio::task::JoinHandle;
 use tracing::{info_span, instrument::Instrumented, trace, Instrument};
 
@@ -141,7 +141,7 @@ impl AsRef<HyperlaneAgentCore> for Scraper {
 
 /// Create a function to spawn task that syncs contract events
 macro_rules! spawn_sync_task {
-    ($name:ident, $cursor: ident, $label:literal) => {
+    ($name:ident, $sequence_count:ident, $label:literal) => {
         async fn $name(
             &self,
             domain: HyperlaneDomain,
@@ -161,10 +161,18 @@ macro_rules! spawn_sync_task {
                 )
                 .await
                 .unwrap();
-            let cursor = sync
-                .$cursor(index_settings.clone())
-                .await;
                 tokio::spawn(async move {
+                    // The block cursor is shared by all event types, so it
+                    // can't be used as a sequence watermark. Sequences are
+                    // contiguous though, so the number of stored events is the
+                    // next sequence to index.
+                    let cursor = if let IndexMode::Sequence = index_settings.mode {
+                        let next_sequence = db.$sequence_count().await?;
+                        sync.sequence_cursor(next_sequence, index_settings.poll_interval)
+                            .await?
+                    } else {
+                        sync.rate_limited_cursor(index_settings).await?
+                    };
                     sync
                         .sync($label, cursor)
                         .await
@@ -210,14 +218,10 @@ impl Scraper {
         )
     }
 
-    spawn_sync_task!(
-        build_delivery_indexer,
-        rate_limited_cursor,
-        "message_delivery"
-    );
+    spawn_sync_task!(build_delivery_indexer, delivery_count, "message_delivery");
     spawn_sync_task!(
         build_interchain_gas_payment_indexer,
-        rate_limited_cursor,
+        gas_payment_count,
         "gas_payment"
     );
 }
@@ -9,11 +9,12 @@ use hyperlane_base::settings::IndexSettings;
 use hyperlane_core::{
     BlockInfo, Delivery, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage,
     HyperlaneMessageStore, HyperlaneProvider, HyperlaneWatermarkedLogStore, InterchainGasPayment,
-    LogMeta, H256,
+    LogMeta, H256, H512,
 };
 use itertools::Itertools;
 use tracing::trace;
 
+use crate::conversions::address_to_bytes;
 use crate::db::{
     BasicBlock, BlockCursor, ScraperDb, StorableDelivery, StorableMessage, StorablePayment,
     StorableTxn,
@@ -68,6 +69,21 @@ impl HyperlaneSqlDb {
             .await
     }
 
+    /// The number of deliveries stored for this domain's mailbox.
+    pub async fn delivery_count(&self) -> Result<u32> {
+        let count = self
+            .db
+            .deliveries_count(self.domain.id(), address_to_bytes(&self.mailbox_address))
+            .await?;
+        Ok(count.try_into()?)
+    }
+
+    /// The number of gas payments stored for this domain.
+    pub async fn gas_payment_count(&self) -> Result<u32> {
+        let count = self.db.payments_count(self.domain.id()).await?;
+        Ok(count.try_into()?)
+    }
+
     /// Takes a list of txn and block hashes and ensure they are all in the
     /// database. If any are not it will fetch the data and insert them.
     ///
@@ -76,21 +92,14 @@ impl HyperlaneSqlDb {
         &self,
         log_meta: impl Iterator<Item = &LogMeta>,
     ) -> Result<impl Iterator<Item = TxnWithId>> {
-        let block_hash_by_txn_hash: HashMap<H256, H256> = log_meta
-            .map(|meta| {
-                (
-                    meta.transaction_id
-                        .try_into()
-                        .expect("256-bit transaction ids are the maximum supported at this time"),
-                    meta.block_hash,
-                )
-            })
+        let block_by_txn_hash: HashMap<H512, (H256, u64)> = log_meta
+            .map(|meta| (meta.transaction_id, (meta.block_hash, meta.block_number)))
             .collect();
 
         // all blocks we care about
         // hash of block maps to the block id and timestamp
         let blocks: HashMap<_, _> = self
-            .ensure_blocks(block_hash_by_txn_hash.values().copied())
+            .ensure_blocks(block_by_txn_hash.values().copied())
             .await?
             .map(|block| (block.hash, block))
             .collect();
@@ -98,8 +107,8 @@ impl HyperlaneSqlDb {
 
         // all txns we care about
         let txns_with_ids =
-            self.ensure_txns(block_hash_by_txn_hash.into_iter().map(
-                move |(txn_hash, block_hash)| {
+            self.ensure_txns(block_by_txn_hash.into_iter().map(
+                move |(txn_hash, (block_hash, _))| {
                     let block_info = *blocks.get(&block_hash).as_ref().unwrap();
                     TxnWithBlockId {
                         txn_hash,
@@ -123,7 +132,7 @@ impl HyperlaneSqlDb {
         txns: impl Iterator<Item = TxnWithBlockId>,
     ) -> Result<impl Iterator<Item = TxnWithId>> {
         // mapping of txn hash to (txn_id, block_id).
-        let mut txns: HashMap<H256, (Option<i64>, i64)> = txns
+        let mut txns: HashMap<H512, (Option<i64>, i64)> = txns
             .map(|TxnWithBlockId { txn_hash, block_id }| (txn_hash, (None, block_id)))
             .collect();
 
@@ -147,9 +156,9 @@ impl HyperlaneSqlDb {
         let mut txns_to_fetch = txns.iter_mut().filter(|(_, id)| id.0.is_none());
 
         let mut txns_to_insert: Vec<StorableTxn> = Vec::with_capacity(CHUNK_SIZE);
-        let mut hashes_to_insert: Vec<&H256> = Vec::with_capacity(CHUNK_SIZE);
+        let mut hashes_to_insert: Vec<&H512> = Vec::with_capacity(CHUNK_SIZE);
 
-        for mut chunk in as_chunks::<(&H256, &mut (Option<i64>, i64))>(txns_to_fetch, CHUNK_SIZE) {
+        for mut chunk in as_chunks::<(&H512, &mut (Option<i64>, i64))>(txns_to_fetch, CHUNK_SIZE) {
             for (hash, (_, block_id)) in chunk.iter() {
                 let info = self.provider.get_txn_by_hash(hash).await?;
                 hashes_to_insert.push(*hash);
@@ -183,13 +192,14 @@ impl HyperlaneSqlDb {
     ///     inserting it into the database.
     async fn ensure_blocks(
         &self,
-        block_hashes: impl Iterator<Item = H256>,
+        blocks: impl Iterator<Item = (H256, u64)>,
     ) -> Result<impl Iterator<Item = BasicBlock>> {
-        // mapping of block hash to the database id and block timestamp. Optionals are
-        // in place because we will find the timestamp first if the block was not
-        // already in the db.
-        let mut blocks: HashMap<H256, Option<BasicBlock>> =
-            block_hashes.map(|b| (b, None)).collect();
+        // mapping of block hash to the block number and database id. Optionals
+        // are in place because we will find the timestamp first if the block
+        // was not already in the db.
+        let mut blocks: HashMap<H256, (u64, Option<BasicBlock>)> = blocks
+            .map(|(hash, number)| (hash, (number, None)))
+            .collect();
 
         let db_blocks: Vec<BasicBlock> = if !blocks.is_empty() {
             // check database to see which blocks we already know and fetch their IDs
@@ -202,6 +212,7 @@ impl HyperlaneSqlDb {
             let _ = blocks
                 .get_mut(&block.hash)
                 .expect("We found a block that we did not request")
+                .1
                 .insert(block);
         }
 
@@ -212,15 +223,15 @@ impl HyperlaneSqlDb {
         // inserted into db.
         let blocks_to_fetch = blocks
             .iter_mut()
-            .filter(|(_, block_info)| block_info.is_none());
+            .filter(|(_, (_, block_info))| block_info.is_none());
 
         let mut blocks_to_insert: Vec<(&mut BasicBlock, Option<BlockInfo>)> =
             Vec::with_capacity(CHUNK_SIZE);
         let mut hashes_to_insert: Vec<&H256> = Vec::with_capacity(CHUNK_SIZE);
         for chunk in as_chunks(blocks_to_fetch, CHUNK_SIZE) {
             debug_assert!(!chunk.is_empty());
-            for (hash, block_info) in chunk {
-                let info = self.provider.get_block_by_hash(hash).await?;
+            for (hash, (number, block_info)) in chunk {
+                let info = self.provider.get_block(hash, *number).await?;
                 let basic_info_ref = block_info.insert(BasicBlock {
                     id: -1,
                     hash: *hash,
@@ -254,7 +265,7 @@ impl HyperlaneSqlDb {
         // ensure we have updated all the block ids and that we have info for all of
         // them.
         #[cfg(debug_assertions)]
-        for (hash, block) in blocks.iter() {
+        for (hash, (_, block)) in blocks.iter() {
             let block = block.as_ref().unwrap();
             assert_eq!(hash, &block.hash);
             assert!(block.id > 0);
@@ -262,7 +273,7 @@ impl HyperlaneSqlDb {
 
         Ok(blocks
             .into_iter()
-            .map(|(hash, block_info)| block_info.unwrap()))
+            .map(|(hash, (_, block_info))| block_info.unwrap()))
     }
 }
 
@@ -273,19 +284,13 @@ impl HyperlaneLogStore<HyperlaneMessage> for HyperlaneSqlDb {
         if messages.is_empty() {
             return Ok(0);
         }
-        let txns: HashMap<H256, TxnWithId> = self
+        let txns: HashMap<H512, TxnWithId> = self
             .ensure_blocks_and_txns(messages.iter().map(|r| &r.1))
             .await?
             .map(|t| (t.hash, t))
             .collect();
         let storable = messages.iter().map(|m| {
-            let txn = txns
-                .get(
-                    &m.1.transaction_id
-                        .try_into()
-                        .expect("256-bit transaction ids are the maximum supported at this time"),
-                )
-                .unwrap();
+            let txn = txns.get(&m.1.transaction_id).unwrap();
             StorableMessage {
                 msg: m.0.clone(),
                 meta: &m.1,
@@ -306,21 +311,13 @@ impl HyperlaneLogStore<Delivery> for HyperlaneSqlDb {
         if deliveries.is_empty() {
             return Ok(0);
         }
-        let txns: HashMap<Delivery, TxnWithId> = self
+        let txns: HashMap<H512, TxnWithId> = self
             .ensure_blocks_and_txns(deliveries.iter().map(|r| &r.1))
             .await?
             .map(|t| (t.hash, t))
             .collect();
         let storable = deliveries.iter().map(|(message_id, meta)| {
-            let txn_id = txns
-                .get(
-                    &meta
-                        .transaction_id
-                        .try_into()
-                        .expect("256-bit transaction ids are the maximum supported at this time"),
-                )
-                .unwrap()
-                .id;
+            let txn_id = txns.get(&meta.transaction_id).unwrap().id;
             StorableDelivery {
                 message_id: *message_id,
                 meta,
@@ -342,21 +339,13 @@ impl HyperlaneLogStore<InterchainGasPayment> for HyperlaneSqlDb {
         if payments.is_empty() {
             return Ok(0);
         }
-        let txns: HashMap<H256, TxnWithId> = self
+        let txns: HashMap<H512, TxnWithId> = self
             .ensure_blocks_and_txns(payments.iter().map(|r| &r.1))
             .await?
             .map(|t| (t.hash, t))
             .collect();
         let storable = payments.iter().map(|(payment, meta)| {
-            let txn_id = txns
-                .get(
-                    &meta
-                        .transaction_id
-                        .try_into()
-                        .expect("256-bit transaction ids are the maximum supported at this time"),
-                )
-                .unwrap()
-                .id;
+            let txn_id = txns.get(&meta.transaction_id).unwrap().id;
             StorablePayment {
                 payment,
                 meta,
@@ -417,13 +406,13 @@ where
 
 #[derive(Debug, Clone)]
 struct TxnWithId {
-    hash: H256,
+    hash: H512,
     id: i64,
 }
 
 #[derive(Debug, Clone)]
 struct TxnWithBlockId {
-    txn_hash: H256,
+    txn_hash: H512,
     block_id: i64,
 }
 
@@ -118,7 +118,7 @@ impl ScraperDb {
         Ok(tx_id)
     }
 
-    async fn deliveries_count(&self, domain: u32, destination_mailbox: Vec<u8>) -> Result<u64> {
+    pub async fn deliveries_count(&self, domain: u32, destination_mailbox: Vec<u8>) -> Result<u64> {
         Ok(delivered_message::Entity::find()
             .filter(delivered_message::Column::Domain.eq(domain))
             .filter(delivered_message::Column::DestinationMailbox.eq(destination_mailbox.clone()))
@@ -69,7 +69,7 @@ impl ScraperDb {
         Ok(difference)
     }
 
-    async fn payments_count(&self, domain: u32) -> Result<u64> {
+    pub async fn payments_count(&self, domain: u32) -> Result<u64> {
         Ok(gas_payment::Entity::find()
             .filter(gas_payment::Column::Domain.eq(domain))
             .count(&self.0)
@@ -3,6 +3,8 @@
 use std::{path::PathBuf, time::Duration};
 
 use eyre::{eyre, Context};
+use serde::Deserialize;
+
 use hyperlane_base::{
     decl_settings,
     settings::{
@@ -12,6 +14,72 @@ use hyperlane_base::{
 };
 use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol};
 
+/// Config for rotating the validator to a new key. The validator announces
+/// the new key and signs checkpoints with both keys until the overlap ends,
+/// then only with the new key. Once the new key is in use, it can replace
+/// the current key in the config.
+#[derive(Debug)]
+pub struct KeyRotationConf {
+    /// The signer of the key to rotate to
+    pub validator: SignerConf,
+    /// The checkpoint syncer to publish checkpoints signed with the new key
+    /// to, which must differ from the one of the current key
+    pub checkpoint_syncer: CheckpointSyncerConf,
+    /// How long to sign checkpoints with both keys once the new key is
+    /// announced
+    pub overlap: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawKeyRotationConf {
+    #[serde(default)]
+    validator: RawSignerConf,
+    checkpointsyncer: Option<RawCheckpointSyncerConf>,
+    overlap: Option<StrOrInt>,
+}
+
+impl FromRawConf<RawKeyRotationConf> for KeyRotationConf {
+    fn from_config_filtered(
+        raw: RawKeyRotationConf,
+        cwp: &ConfigPath,
+        _filter: (),
+    ) -> ConfigResult<Self> {
+        let mut err = ConfigParsingError::default();
+
+        let validator = raw
+            .validator
+            .parse_config::<SignerConf>(&cwp.join("validator"))
+            .take_config_err(&mut err);
+
+        let checkpoint_syncer = raw
+            .checkpointsyncer
+            .ok_or_else(|| eyre!("Missing `checkpointsyncer`"))
+            .take_err(&mut err, || cwp + "checkpointsyncer")
+            .and_then(|r| {
+                r.parse_config(&cwp.join("checkpointsyncer"))
+                    .take_config_err(&mut err)
+            });
+
+        let overlap = raw
+            .overlap
+            .ok_or_else(|| eyre!("Missing `overlap`"))
+            .take_err(&mut err, || cwp + "overlap")
+            .and_then(|r| {
+                r.try_into()
+                    .map(Duration::from_secs)
+                    .take_err(&mut err, || cwp + "overlap")
+            });
+
+        cfg_unwrap_all!(cwp, err: [validator, checkpoint_syncer, overlap]);
+        err.into_result(Self {
+            validator,
+            checkpoint_syncer,
+            overlap,
+        })
+    }
+}
+
 decl_settings!(Validator,
     Parsed {
         /// Database path
@@ -26,6 +94,11 @@ decl_settings!(Validator,
         reorg_period: u64,
         /// How frequently to check for new checkpoints
         interval: Duration,
+        /// Whether to only sign checkpoints that match a merkle tree built
+        /// from the indexed messages
+        verify_checkpoints: bool,
+        /// The key the validator is rotating to, if any
+        rotation: Option<KeyRotationConf>,
     },
     Raw {
         /// Database path (path on the fs)
@@ -41,6 +114,15 @@ decl_settings!(Validator,
         reorgperiod: Option<StrOrInt>,
         /// How frequently to check for new checkpoints
         interval: Option<StrOrInt>,
+        /// If true, only signs checkpoints whose root matches a merkle tree
+        /// built from the indexed messages and whose message id matches the
+        /// indexed message. Defaults to false.
+        #[serde(default)]
+        verifycheckpoints: bool,
+        /// This is optional. The key to rotate to, which is announced and signs
+        /// checkpoints along with the current key for the overlap window in
+        /// seconds, after which the current key stops signing.
+        rotation: Option<RawKeyRotationConf>,
     },
 );
 
@@ -81,6 +163,11 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             })
             .unwrap_or(Duration::from_secs(5));
 
+        let rotation = raw.rotation.and_then(|r| {
+            r.parse_config(&cwp.join("rotation"))
+                .take_config_err(&mut err)
+        });
+
         let Some(origin_chain_name) = raw
             .originchainname
             .ok_or_else(|| eyre!("Missing `originchainname`"))
@@ -130,6 +217,8 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             checkpoint_syncer,
             reorg_period,
             interval,
+            verify_checkpoints: raw.verifycheckpoints,
+            rotation,
         })
     }
 }
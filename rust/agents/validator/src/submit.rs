@@ -1,18 +1,26 @@
+use std::collections::{BTreeMap, HashMap};
 use std::num::NonZeroU64;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use std::vec;
 
-use eyre::Result;
-use prometheus::IntGauge;
+use eyre::{bail, Result};
+use prometheus::{IntCounter, IntCounterVec, IntGauge, IntGaugeVec};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Mutex;
 use tokio::time::sleep;
 use tracing::instrument;
-use tracing::{debug, info};
+use tracing::{debug, error, info, warn};
 
-use hyperlane_base::{db::HyperlaneRocksDB, CheckpointSyncer, CoreMetrics};
+use hyperlane_base::{
+    db::{HyperlaneRocksDB, SigningJournalEntry},
+    CheckpointBundle, CheckpointBundleManifest, CheckpointSyncer, CoreMetrics,
+    CHECKPOINT_BUNDLE_SIZE,
+};
 use hyperlane_core::{
     accumulator::incremental::IncrementalMerkle, Checkpoint, CheckpointWithMessageId,
-    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneSignerExt, Mailbox,
+    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneSigner, HyperlaneSignerExt,
+    Mailbox, SignedCheckpoint, SignedCheckpointWithMessageId, H160,
 };
 use hyperlane_ethereum::SingletonSignerHandle;
 
@@ -24,6 +32,16 @@ pub(crate) struct ValidatorSubmitter {
     mailbox: Arc<dyn Mailbox>,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
     message_db: HyperlaneRocksDB,
+    /// Serializes journaling what is signed between the submitters, which
+    /// sign concurrently
+    signing_journal_lock: Arc<Mutex<()>>,
+    /// Whether to verify checkpoints against a merkle tree built from the
+    /// indexed messages before signing them
+    verify_checkpoints: bool,
+    /// When to stop signing, if the validator is rotating away from this key
+    retire_at: Option<SystemTime>,
+    /// Where to send the published checkpoints to be bundled, if they are
+    bundler: Option<UnboundedSender<SignedCheckpointWithMessageId>>,
     metrics: ValidatorSubmitterMetrics,
 }
 
@@ -35,6 +53,7 @@ impl ValidatorSubmitter {
         signer: SingletonSignerHandle,
         checkpoint_syncer: Arc<dyn CheckpointSyncer>,
         message_db: HyperlaneRocksDB,
+        verify_checkpoints: bool,
         metrics: ValidatorSubmitterMetrics,
     ) -> Self {
         Self {
@@ -44,10 +63,67 @@ impl ValidatorSubmitter {
             signer,
             checkpoint_syncer,
             message_db,
+            signing_journal_lock: Default::default(),
+            verify_checkpoints,
+            retire_at: None,
+            bundler: None,
+            metrics,
+        }
+    }
+
+    /// A submitter that signs the same checkpoints with another key and
+    /// publishes them to the checkpoint syncer of that key. It shares the
+    /// signing journal with this submitter.
+    pub(crate) fn for_key(
+        &self,
+        signer: SingletonSignerHandle,
+        checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        metrics: ValidatorSubmitterMetrics,
+    ) -> Self {
+        Self {
+            signer,
+            checkpoint_syncer,
+            retire_at: None,
+            bundler: None,
             metrics,
+            ..self.clone()
         }
     }
 
+    /// Send the checkpoints this submitter and its clones publish to the
+    /// returned bundler.
+    pub(crate) fn with_bundler(self) -> (Self, CheckpointBundler) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let bundler = CheckpointBundler {
+            interval: self.interval,
+            checkpoint_syncer: self.checkpoint_syncer.clone(),
+            published_checkpoints: receiver,
+        };
+        let submitter = Self {
+            bundler: Some(sender),
+            ..self
+        };
+        (submitter, bundler)
+    }
+
+    /// Stop signing at `retire_at`, once the validator rotated to a new key.
+    pub(crate) fn retiring_at(self, retire_at: SystemTime) -> Self {
+        Self {
+            retire_at: Some(retire_at),
+            ..self
+        }
+    }
+
+    /// The address of the key the submitter signs with
+    pub(crate) fn validator(&self) -> H160 {
+        self.signer.eth_address()
+    }
+
+    fn retired(&self) -> bool {
+        self.retire_at
+            .map_or(false, |retire_at| SystemTime::now() >= retire_at)
+    }
+
     pub(crate) fn checkpoint(&self, tree: &IncrementalMerkle) -> Checkpoint {
         Checkpoint {
             root: tree.root(),
@@ -68,6 +144,14 @@ impl ValidatorSubmitter {
         let mut reached_target = false;
 
         while !reached_target {
+            if self.retired() {
+                info!(
+                    validator = ?self.validator(),
+                    "Key rotation overlap ended, stopped signing checkpoints"
+                );
+                break;
+            }
+
             let correctness_checkpoint = if let Some(c) = target_checkpoint {
                 c
             } else {
@@ -79,71 +163,291 @@ impl ValidatorSubmitter {
                 latest_checkpoint
             };
 
-            // ingest available messages from DB
-            while let Some(message) = self
-                .message_db
-                .retrieve_message_by_nonce(tree.count() as u32)?
-            {
-                debug!(index = message.nonce, "Ingesting leaf to tree");
-                let message_id = message.id();
-                tree.ingest(message_id);
-
-                let checkpoint = self.checkpoint(&tree);
-
-                checkpoint_queue.push(CheckpointWithMessageId {
-                    checkpoint,
-                    message_id,
-                });
-
-                // compare against every queued checkpoint to prevent ingesting past target
-                if checkpoint == correctness_checkpoint {
-                    debug!(index = checkpoint.index, "Reached tree consistency");
-
-                    // drain and sign all checkpoints in the queue
-                    for queued_checkpoint in checkpoint_queue.drain(..) {
-                        let existing = self
-                            .checkpoint_syncer
-                            .fetch_checkpoint(queued_checkpoint.index)
-                            .await?;
-                        if existing.is_some() {
-                            debug!(
-                                index = queued_checkpoint.index,
-                                "Checkpoint already submitted"
-                            );
-                            continue;
-                        }
+            let reached_checkpoint = self
+                .submit_checkpoints_up_to(&mut tree, &mut checkpoint_queue, &correctness_checkpoint)
+                .await?;
+            // break out of submitter loop if target checkpoint is reached
+            reached_target = reached_checkpoint && target_checkpoint.is_some();
 
-                        let signed_checkpoint = self.signer.sign(queued_checkpoint).await?;
-                        self.checkpoint_syncer
-                            .write_checkpoint(&signed_checkpoint)
-                            .await?;
+            sleep(self.interval).await;
+        }
+
+        // TODO: remove this once validator is tolerant of tasks exiting
+        loop {
+            sleep(Duration::from_secs(u64::MAX)).await;
+        }
+    }
+
+    /// Ingests the indexed messages into `tree`, queueing the checkpoint of
+    /// each, until the tree reaches `correctness_checkpoint`. Then signs and
+    /// submits the queued checkpoints and returns true. Returns false if the
+    /// messages aren't indexed up to it yet, or if verifying checkpoints and
+    /// they don't match it.
+    async fn submit_checkpoints_up_to(
+        &self,
+        tree: &mut IncrementalMerkle,
+        checkpoint_queue: &mut Vec<CheckpointWithMessageId>,
+        correctness_checkpoint: &Checkpoint,
+    ) -> Result<bool> {
+        // ingest available messages from DB
+        while let Some(message) = self
+            .message_db
+            .retrieve_message_by_nonce(tree.count() as u32)?
+        {
+            debug!(index = message.nonce, "Ingesting leaf to tree");
+            let message_id = message.id();
+            tree.ingest(message_id);
+
+            let checkpoint = self.checkpoint(tree);
+
+            checkpoint_queue.push(CheckpointWithMessageId {
+                checkpoint,
+                message_id,
+            });
+
+            // compare against every queued checkpoint to prevent ingesting past target
+            if checkpoint == *correctness_checkpoint {
+                debug!(index = checkpoint.index, "Reached tree consistency");
+
+                if self.verify_checkpoints && !self.verify_message_ids(checkpoint_queue)? {
+                    // the indexed messages changed since they were ingested
+                    self.discard_queued_checkpoints(tree, checkpoint_queue)?;
+                    return Ok(false);
+                }
+
+                // drain and sign all checkpoints in the queue
+                for queued_checkpoint in checkpoint_queue.drain(..) {
+                    let existing = self
+                        .checkpoint_syncer
+                        .fetch_checkpoint(queued_checkpoint.index)
+                        .await?;
+                    if let Some(existing) = existing {
+                        self.check_submitted_checkpoint(&existing, &queued_checkpoint)?;
                         debug!(
                             index = queued_checkpoint.index,
-                            "Signed and submitted checkpoint"
+                            "Checkpoint already submitted"
                         );
-
-                        // small sleep before signing next checkpoint to avoid rate limiting
-                        sleep(Duration::from_millis(100)).await;
+                        self.bundle(existing);
+                        continue;
                     }
 
-                    info!(index = checkpoint.index, "Signed all queued checkpoints");
+                    let signed_checkpoint = self.sign_checkpoint(queued_checkpoint).await?;
+                    self.checkpoint_syncer
+                        .write_checkpoint(&signed_checkpoint)
+                        .await?;
+                    self.bundle(signed_checkpoint);
+                    debug!(
+                        index = queued_checkpoint.index,
+                        "Signed and submitted checkpoint"
+                    );
+
+                    // small sleep before signing next checkpoint to avoid rate limiting
+                    sleep(Duration::from_millis(100)).await;
+                }
 
-                    self.metrics
-                        .latest_checkpoint_processed
-                        .set(checkpoint.index as i64);
+                info!(index = checkpoint.index, "Signed all queued checkpoints");
 
-                    // break out of submitter loop if target checkpoint is reached
-                    reached_target = target_checkpoint.is_some();
-                    break;
-                }
+                self.metrics
+                    .latest_checkpoint_processed
+                    .set(checkpoint.index as i64);
+                return Ok(true);
+            } else if self.verify_checkpoints && checkpoint.index == correctness_checkpoint.index {
+                self.report_root_mismatch(&checkpoint, correctness_checkpoint);
+                // none of the queued checkpoints match the onchain tree
+                self.discard_queued_checkpoints(tree, checkpoint_queue)?;
+                return Ok(false);
             }
+        }
+        Ok(false)
+    }
 
-            sleep(self.interval).await;
+    /// Discards the queued checkpoints, which don't match the onchain tree,
+    /// and rebuilds `tree` from the indexed messages up to the first of them.
+    /// The leaves ingested for them may not be indexed anymore, e.g. if they
+    /// were rolled back after a reorg, so they are ingested again once they
+    /// are indexed anew. Only used when verifying checkpoints, in which case
+    /// the tree is built from the indexed messages from the first index on.
+    fn discard_queued_checkpoints(
+        &self,
+        tree: &mut IncrementalMerkle,
+        checkpoint_queue: &mut Vec<CheckpointWithMessageId>,
+    ) -> Result<()> {
+        let count = tree.count() - checkpoint_queue.len();
+        checkpoint_queue.clear();
+        let mut rebuilt = IncrementalMerkle::default();
+        while rebuilt.count() < count {
+            let Some(message_id) = self
+                .message_db
+                .retrieve_message_id_by_nonce(&(rebuilt.count() as u32))?
+            else {
+                break;
+            };
+            rebuilt.ingest(message_id);
         }
+        debug!(
+            count = rebuilt.count(),
+            "Rebuilt tree from the indexed messages"
+        );
+        *tree = rebuilt;
+        Ok(())
+    }
 
-        // TODO: remove this once validator is tolerant of tasks exiting
-        loop {
-            sleep(Duration::from_secs(u64::MAX)).await;
+    /// Checks that the message id of each queued checkpoint is still the id
+    /// of the message indexed at its index, which may not be the case if
+    /// messages were rolled back after a reorg since they were ingested into
+    /// the tree. Reports the first mismatch and returns whether there was
+    /// none.
+    fn verify_message_ids(&self, checkpoints: &[CheckpointWithMessageId]) -> Result<bool> {
+        for checkpoint in checkpoints {
+            let indexed = self
+                .message_db
+                .retrieve_message_id_by_nonce(&checkpoint.index)?;
+            if indexed != Some(checkpoint.message_id) {
+                self.metrics.message_id_mismatches.inc();
+                error!(
+                    index = checkpoint.index,
+                    message_id = ?checkpoint.message_id,
+                    indexed_message_id = ?indexed,
+                    "Message id of checkpoint doesn't match the indexed message, not signing"
+                );
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Ingests the indexed messages into `tree` up to the index of the onchain
+    /// `checkpoint` and returns whether the checkpoint of the tree matches
+    /// it, or None if the messages aren't indexed up to it yet.
+    fn verify_legacy_checkpoint(
+        &self,
+        tree: &mut IncrementalMerkle,
+        checkpoint: &Checkpoint,
+    ) -> Result<Option<bool>> {
+        while tree.count() <= checkpoint.index as usize {
+            let Some(message_id) = self
+                .message_db
+                .retrieve_message_id_by_nonce(&(tree.count() as u32))?
+            else {
+                return Ok(None)
+            };
+            tree.ingest(message_id);
+        }
+        if tree.index() != checkpoint.index {
+            warn!(
+                index = checkpoint.index,
+                tree_index = tree.index(),
+                "Can't verify a checkpoint behind the indexed messages"
+            );
+            return Ok(Some(false));
+        }
+        let local = self.checkpoint(tree);
+        if local != *checkpoint {
+            self.report_root_mismatch(&local, checkpoint);
+            return Ok(Some(false));
+        }
+        Ok(Some(true))
+    }
+
+    fn report_root_mismatch(&self, local: &Checkpoint, onchain: &Checkpoint) {
+        self.metrics.root_mismatches.inc();
+        error!(
+            index = onchain.index,
+            root = ?local.root,
+            onchain_root = ?onchain.root,
+            "Root of the merkle tree built from the indexed messages doesn't match the onchain root, not signing"
+        );
+    }
+
+    /// Signs the checkpoint after journaling it in the database. Refuses to
+    /// sign a checkpoint that conflicts with one signed before for the same
+    /// index, which happens if the origin chain or its RPC provider served
+    /// data that was reorged out or wrong, and returns an error so that the
+    /// validator halts.
+    async fn sign_checkpoint(
+        &self,
+        checkpoint: CheckpointWithMessageId,
+    ) -> Result<SignedCheckpointWithMessageId> {
+        let entry = SigningJournalEntry {
+            root: checkpoint.root,
+            message_id: checkpoint.message_id,
+        };
+        let journaled = {
+            let _guard = self.signing_journal_lock.lock().await;
+            self.message_db.journal_signing(checkpoint.index, entry)?
+        };
+        if let Some(journaled) = journaled {
+            self.metrics.signing_conflicts.inc();
+            error!(
+                index = checkpoint.index,
+                ?journaled,
+                conflicting = ?entry,
+                "Refusing to sign a checkpoint that conflicts with a signed one, halting"
+            );
+            bail!(
+                "Checkpoint at index {} conflicts with a previously signed one",
+                checkpoint.index
+            );
+        }
+        Ok(self.signer.sign(checkpoint).await?)
+    }
+
+    /// Checks that the checkpoint already submitted for the index of the
+    /// queued checkpoint has the same root and message id. If not, the
+    /// validator signed a checkpoint before that conflicts with the one it
+    /// would sign now, and returns an error so that the validator halts.
+    fn check_submitted_checkpoint(
+        &self,
+        submitted: &SignedCheckpointWithMessageId,
+        queued: &CheckpointWithMessageId,
+    ) -> Result<()> {
+        if submitted.value.root == queued.root && submitted.value.message_id == queued.message_id {
+            return Ok(());
+        }
+        self.metrics.signing_conflicts.inc();
+        error!(
+            index = queued.index,
+            submitted_root = ?submitted.value.root,
+            submitted_message_id = ?submitted.value.message_id,
+            root = ?queued.root,
+            message_id = ?queued.message_id,
+            "Submitted checkpoint conflicts with the queued one, halting"
+        );
+        bail!(
+            "Checkpoint at index {} conflicts with the one already submitted",
+            queued.index
+        );
+    }
+
+    /// Signs the legacy checkpoint after journaling it in the database, like
+    /// `sign_checkpoint`.
+    async fn legacy_sign_checkpoint(&self, checkpoint: Checkpoint) -> Result<SignedCheckpoint> {
+        let journaled = {
+            let _guard = self.signing_journal_lock.lock().await;
+            self.message_db
+                .journal_legacy_signing(checkpoint.index, checkpoint.root)?
+        };
+        if let Some(journaled_root) = journaled {
+            self.metrics.signing_conflicts.inc();
+            error!(
+                index = checkpoint.index,
+                ?journaled_root,
+                conflicting_root = ?checkpoint.root,
+                "Refusing to sign a legacy checkpoint that conflicts with a signed one, halting"
+            );
+            bail!(
+                "Legacy checkpoint at index {} conflicts with a previously signed one",
+                checkpoint.index
+            );
+        }
+        Ok(self.signer.sign(checkpoint).await?)
+    }
+
+    /// Send a published checkpoint to the bundler, if there is one.
+    fn bundle(&self, signed_checkpoint: SignedCheckpointWithMessageId) {
+        if let Some(bundler) = &self.bundler {
+            // The bundler only stops once all submitters are dropped
+            let _ = bundler.send(signed_checkpoint);
         }
     }
 
@@ -165,6 +469,10 @@ impl ValidatorSubmitter {
         // Returns whether checkpoint info should be logged based off the
         // checkpoint_info_log_period having elapsed since the last log.
         // Sets latest_checkpoint_info_log to the current instant if true.
+        // The tree built from the indexed messages to verify checkpoints
+        // against, if verifying them
+        let mut verification_tree = self.verify_checkpoints.then(IncrementalMerkle::default);
+
         let mut should_log_checkpoint_info = || {
             if let Some(instant) = latest_checkpoint_info_log {
                 if instant.elapsed() < checkpoint_info_log_period {
@@ -176,6 +484,14 @@ impl ValidatorSubmitter {
         };
 
         loop {
+            if self.retired() {
+                info!(
+                    validator = ?self.validator(),
+                    "Key rotation overlap ended, stopped signing legacy checkpoints"
+                );
+                break;
+            }
+
             // Check the latest checkpoint
             let latest_checkpoint = self.mailbox.latest_checkpoint(self.reorg_period).await?;
 
@@ -207,7 +523,25 @@ impl ValidatorSubmitter {
                 .map(|i| i < latest_checkpoint.index)
                 .unwrap_or(true)
             {
-                let signed_checkpoint = self.signer.sign(latest_checkpoint).await?;
+                if let Some(tree) = verification_tree.as_mut() {
+                    match self.verify_legacy_checkpoint(tree, &latest_checkpoint)? {
+                        Some(true) => {}
+                        Some(false) => {
+                            sleep(self.interval).await;
+                            continue;
+                        }
+                        None => {
+                            debug!(
+                                index = latest_checkpoint.index,
+                                "Waiting for messages to be indexed to verify checkpoint"
+                            );
+                            sleep(self.interval).await;
+                            continue;
+                        }
+                    }
+                }
+
+                let signed_checkpoint = self.legacy_sign_checkpoint(latest_checkpoint).await?;
 
                 info!(signed_checkpoint = ?signed_checkpoint, signer=?self.signer, "Signed new latest checkpoint");
                 current_index = Some(latest_checkpoint.index);
@@ -222,6 +556,161 @@ impl ValidatorSubmitter {
 
             sleep(self.interval).await;
         }
+
+        // TODO: remove this once validator is tolerant of tasks exiting
+        loop {
+            sleep(Duration::from_secs(u64::MAX)).await;
+        }
+    }
+}
+
+/// Bundles the checkpoints published by the submitters of a key, so that
+/// relayers can fetch them at once. A range is only bundled once all of its
+/// checkpoints are published, and never again after that.
+///
+/// It runs in its own task so that signing never waits for bundling, and is
+/// the only writer of the manifest of its checkpoint syncer, which it keeps.
+pub(crate) struct CheckpointBundler {
+    interval: Duration,
+    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    published_checkpoints: UnboundedReceiver<SignedCheckpointWithMessageId>,
+}
+
+impl CheckpointBundler {
+    #[instrument(err, skip(self))]
+    pub(crate) async fn run(self) -> Result<()> {
+        self.bundle_published_checkpoints().await?;
+
+        // TODO: remove this once validator is tolerant of tasks exiting
+        loop {
+            sleep(Duration::from_secs(u64::MAX)).await;
+        }
+    }
+
+    /// Bundles the published checkpoints until all submitters are dropped.
+    async fn bundle_published_checkpoints(mut self) -> Result<()> {
+        let mut manifest = self
+            .checkpoint_syncer
+            .fetch_bundle_manifest()
+            .await?
+            .unwrap_or_default();
+        // The published checkpoints of each range that isn't bundled yet, by
+        // the first index of the range
+        let mut unbundled: HashMap<u32, BTreeMap<u32, SignedCheckpointWithMessageId>> =
+            HashMap::new();
+
+        while let Some(signed_checkpoint) = self.published_checkpoints.recv().await {
+            let index = signed_checkpoint.value.index;
+            let range = CheckpointBundle::range_containing(index);
+            if manifest.contains(&range) {
+                continue;
+            }
+            let checkpoints = unbundled.entry(*range.start()).or_default();
+            checkpoints.insert(index, signed_checkpoint);
+            if checkpoints.len() < CHECKPOINT_BUNDLE_SIZE as usize {
+                continue;
+            }
+            let checkpoints = unbundled.remove(range.start()).unwrap_or_default();
+            let bundle = CheckpointBundle::new(range, checkpoints.into_values());
+            self.publish(&mut manifest, &bundle).await;
+        }
+        Ok(())
+    }
+
+    /// Writes the bundle and then the manifest with the bundle added to it,
+    /// retrying until both are written.
+    async fn publish(&self, manifest: &mut CheckpointBundleManifest, bundle: &CheckpointBundle) {
+        let (start, end) = (*bundle.range.start(), *bundle.range.end());
+        while let Err(err) = self.checkpoint_syncer.write_bundle(bundle).await {
+            warn!(start, end, ?err, "Failed to write checkpoint bundle");
+            sleep(self.interval).await;
+        }
+        manifest.add(bundle.range.clone());
+        while let Err(err) = self.checkpoint_syncer.write_bundle_manifest(manifest).await {
+            warn!(
+                start,
+                end,
+                ?err,
+                "Failed to write checkpoint bundle manifest"
+            );
+            sleep(self.interval).await;
+        }
+        info!(start, end, "Bundled published checkpoints");
+    }
+}
+
+/// The metrics of the submitters of a validator, labelled by the key each
+/// submitter signs with.
+#[derive(Clone)]
+pub(crate) struct ValidatorMetrics {
+    chain_name: String,
+    latest_checkpoint: IntGaugeVec,
+    signing_conflicts: IntCounterVec,
+    checkpoint_mismatches: IntCounterVec,
+}
+
+impl ValidatorMetrics {
+    pub fn new(metrics: &CoreMetrics, mailbox_chain: &HyperlaneDomain) -> Self {
+        let signing_conflicts = metrics
+            .new_int_counter(
+                "validator_signing_conflicts",
+                "Number of checkpoints the validator refused to sign because they conflict with a checkpoint it signed before",
+                &["chain", "validator"],
+            )
+            .expect("failed to register validator_signing_conflicts metric");
+        let checkpoint_mismatches = metrics
+            .new_int_counter(
+                "validator_checkpoint_mismatches",
+                "Number of checkpoints the validator didn't sign because they don't match the messages it indexed",
+                &["mismatch", "chain", "validator"],
+            )
+            .expect("failed to register validator_checkpoint_mismatches metric");
+        Self {
+            chain_name: mailbox_chain.name().to_owned(),
+            latest_checkpoint: metrics.latest_checkpoint(),
+            signing_conflicts,
+            checkpoint_mismatches,
+        }
+    }
+
+    /// The metrics of the submitter signing with the key of `validator`
+    pub fn submitter(&self, validator: H160) -> ValidatorSubmitterMetrics {
+        let chain_name = self.chain_name.as_str();
+        let validator = format!("{validator:#x}");
+        let validator = validator.as_str();
+        ValidatorSubmitterMetrics {
+            legacy_latest_checkpoint_observed: self.latest_checkpoint.with_label_values(&[
+                "legacy_validator_observed",
+                chain_name,
+                validator,
+            ]),
+            legacy_latest_checkpoint_processed: self.latest_checkpoint.with_label_values(&[
+                "legacy_validator_processed",
+                chain_name,
+                validator,
+            ]),
+            latest_checkpoint_observed: self.latest_checkpoint.with_label_values(&[
+                "validator_observed",
+                chain_name,
+                validator,
+            ]),
+            latest_checkpoint_processed: self.latest_checkpoint.with_label_values(&[
+                "validator_processed",
+                chain_name,
+                validator,
+            ]),
+            signing_conflicts: self
+                .signing_conflicts
+                .with_label_values(&[chain_name, validator]),
+            root_mismatches: self
+                .checkpoint_mismatches
+                .with_label_values(&["root", chain_name, validator]),
+            message_id_mismatches: self.checkpoint_mismatches.with_label_values(&[
+                "message_id",
+                chain_name,
+                validator,
+            ]),
+        }
     }
 }
 
@@ -231,24 +720,297 @@ pub(crate) struct ValidatorSubmitterMetrics {
     latest_checkpoint_processed: IntGauge,
     legacy_latest_checkpoint_observed: IntGauge,
     legacy_latest_checkpoint_processed: IntGauge,
+    signing_conflicts: IntCounter,
+    root_mismatches: IntCounter,
+    message_id_mismatches: IntCounter,
 }
 
-impl ValidatorSubmitterMetrics {
-    pub fn new(metrics: &CoreMetrics, mailbox_chain: &HyperlaneDomain) -> Self {
-        let chain_name = mailbox_chain.name();
-        Self {
-            legacy_latest_checkpoint_observed: metrics
-                .latest_checkpoint()
-                .with_label_values(&["legacy_validator_observed", chain_name]),
-            legacy_latest_checkpoint_processed: metrics
-                .latest_checkpoint()
-                .with_label_values(&["legacy_validator_processed", chain_name]),
-            latest_checkpoint_observed: metrics
-                .latest_checkpoint()
-                .with_label_values(&["validator_observed", chain_name]),
-            latest_checkpoint_processed: metrics
-                .latest_checkpoint()
-                .with_label_values(&["validator_processed", chain_name]),
+#[cfg(test)]
+mod test {
+    use ethers::signers::LocalWallet;
+    use prometheus::Registry;
+
+    use std::time::UNIX_EPOCH;
+
+    use hyperlane_base::{db::test_utils, LocalStorage};
+    use hyperlane_core::{HyperlaneMessage, KnownHyperlaneDomain, Signature, H256, U256};
+    use hyperlane_ethereum::{Signers, SingletonSigner};
+    use hyperlane_test::mocks::MockMailboxContract;
+
+    use super::*;
+    use crate::validator::key_rotation_end;
+
+    fn dummy_domain() -> HyperlaneDomain {
+        HyperlaneDomain::Known(KnownHyperlaneDomain::Test1)
+    }
+
+    fn dummy_submitter(
+        db: &HyperlaneRocksDB,
+        checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        verify_checkpoints: bool,
+    ) -> ValidatorSubmitter {
+        let mut mailbox = MockMailboxContract::new();
+        mailbox.expect__address().return_const(H256::repeat_byte(1));
+        mailbox.expect__domain().return_const(dummy_domain());
+        let wallet: LocalWallet =
+            "1111111111111111111111111111111111111111111111111111111111111111"
+                .parse()
+                .unwrap();
+        let validator = wallet.address().into();
+        let (signer, signer_handle) = SingletonSigner::new(Signers::Local(wallet));
+        tokio::spawn(signer.run());
+        let core_metrics = CoreMetrics::new("dummy_validator", 37582, Registry::new()).unwrap();
+        ValidatorSubmitter::new(
+            Duration::from_millis(10),
+            0,
+            Arc::new(mailbox),
+            signer_handle,
+            checkpoint_syncer,
+            db.clone(),
+            verify_checkpoints,
+            ValidatorMetrics::new(&core_metrics, &dummy_domain()).submitter(validator),
+        )
+    }
+
+    fn dummy_message(nonce: u32, body: u8) -> HyperlaneMessage {
+        HyperlaneMessage {
+            nonce,
+            body: vec![body],
+            ..Default::default()
+        }
+    }
+
+    /// The checkpoint of the onchain tree of `messages`
+    fn onchain_checkpoint(
+        submitter: &ValidatorSubmitter,
+        messages: &[&HyperlaneMessage],
+    ) -> Checkpoint {
+        let mut tree = IncrementalMerkle::default();
+        for message in messages {
+            tree.ingest(message.id());
+        }
+        submitter.checkpoint(&tree)
+    }
+
+    #[tokio::test]
+    async fn test_rebuilds_tree_from_indexed_messages_after_mismatch() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(), db);
+            let dir = tempfile::tempdir().unwrap();
+            let storage: Arc<dyn CheckpointSyncer> =
+                Arc::new(LocalStorage::new(dir.path().to_owned(), None).unwrap());
+            let submitter = dummy_submitter(&db, storage.clone(), true);
+            let mut tree = IncrementalMerkle::default();
+            let mut checkpoint_queue = vec![];
+
+            let first = dummy_message(0, 0);
+            db.store_message(&first, 1).unwrap();
+            let checkpoint = onchain_checkpoint(&submitter, &[&first]);
+            assert!(submitter
+                .submit_checkpoints_up_to(&mut tree, &mut checkpoint_queue, &checkpoint)
+                .await
+                .unwrap());
+
+            // The indexed messages are reorged out of the origin chain
+            let (reorged, reorged_next) = (dummy_message(1, 1), dummy_message(2, 2));
+            let (message, next_message) = (dummy_message(1, 11), dummy_message(2, 12));
+            db.store_message(&reorged, 2).unwrap();
+            db.store_message(&reorged_next, 2).unwrap();
+            let checkpoint = onchain_checkpoint(&submitter, &[&first, &message, &next_message]);
+            assert!(!submitter
+                .submit_checkpoints_up_to(&mut tree, &mut checkpoint_queue, &checkpoint)
+                .await
+                .unwrap());
+            assert_eq!(submitter.metrics.root_mismatches.get(), 1);
+            assert!(checkpoint_queue.is_empty());
+            assert_eq!(tree.count(), 1);
+            assert!(storage.fetch_checkpoint(1).await.unwrap().is_none());
+
+            // Once the messages are indexed anew, the rebuilt tree matches
+            db.remove_messages_from_block(2).unwrap();
+            db.store_message(&message, 3).unwrap();
+            db.store_message(&next_message, 3).unwrap();
+            assert!(submitter
+                .submit_checkpoints_up_to(&mut tree, &mut checkpoint_queue, &checkpoint)
+                .await
+                .unwrap());
+            let mut submitted = vec![];
+            for index in 0..3 {
+                let signed = storage.fetch_checkpoint(index).await.unwrap().unwrap();
+                submitted.push(signed.value.message_id);
+            }
+            assert_eq!(submitted, vec![first.id(), message.id(), next_message.id()]);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_only_reports_mismatches_when_verifying_checkpoints() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(), db);
+            let dir = tempfile::tempdir().unwrap();
+            let storage: Arc<dyn CheckpointSyncer> =
+                Arc::new(LocalStorage::new(dir.path().to_owned(), None).unwrap());
+            let submitter = dummy_submitter(&db, storage, false);
+            let mut tree = IncrementalMerkle::default();
+            let mut checkpoint_queue = vec![];
+
+            let (first, indexed) = (dummy_message(0, 0), dummy_message(1, 1));
+            db.store_message(&first, 1).unwrap();
+            db.store_message(&indexed, 1).unwrap();
+            let checkpoint = onchain_checkpoint(&submitter, &[&first, &dummy_message(1, 11)]);
+            assert!(!submitter
+                .submit_checkpoints_up_to(&mut tree, &mut checkpoint_queue, &checkpoint)
+                .await
+                .unwrap());
+            assert_eq!(submitter.metrics.root_mismatches.get(), 0);
+            assert_eq!(checkpoint_queue.len(), 2);
+            assert_eq!(tree.count(), 2);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_stores_key_rotation_start_once() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(), db);
+            let next_validator = H256::repeat_byte(2);
+            let overlap = Duration::from_secs(3600);
+
+            let retire_at = key_rotation_end(&db, &next_validator, overlap).unwrap();
+            let started_at = db
+                .retrieve_key_rotation_started_at_by_validator(&next_validator)
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                retire_at,
+                UNIX_EPOCH + Duration::from_secs(started_at) + overlap
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_old_key_stops_signing_once_the_stored_overlap_ends() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(), db);
+            let next_validator = H256::repeat_byte(2);
+            let message = dummy_message(0, 0);
+            db.store_message(&message, 1).unwrap();
+
+            // The rotation started two hours ago, before the validator restarted
+            let started_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                - 2 * 3600;
+            db.store_key_rotation_started_at_by_validator(&next_validator, &started_at)
+                .unwrap();
+
+            for (overlap_hours, signs) in [(1, false), (3, true)] {
+                let overlap = Duration::from_secs(overlap_hours * 3600);
+                let retire_at = key_rotation_end(&db, &next_validator, overlap).unwrap();
+                let dir = tempfile::tempdir().unwrap();
+                let storage: Arc<dyn CheckpointSyncer> =
+                    Arc::new(LocalStorage::new(dir.path().to_owned(), None).unwrap());
+                let submitter = dummy_submitter(&db, storage.clone(), false).retiring_at(retire_at);
+                let checkpoint = onchain_checkpoint(&submitter, &[&message]);
+
+                // The submitter never returns, see the TODO at its end
+                let _ = tokio::time::timeout(
+                    Duration::from_millis(500),
+                    submitter.checkpoint_submitter(IncrementalMerkle::default(), Some(checkpoint)),
+                )
+                .await;
+                assert_eq!(storage.fetch_checkpoint(0).await.unwrap().is_some(), signs);
+            }
+
+            // Restarting doesn't reset the start of the rotation
+            assert_eq!(
+                db.retrieve_key_rotation_started_at_by_validator(&next_validator)
+                    .unwrap(),
+                Some(started_at)
+            );
+        })
+        .await;
+    }
+
+    fn signed_checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+        SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    mailbox_address: H256::repeat_byte(1),
+                    mailbox_domain: 1,
+                    root: H256::from_low_u64_be(index.into()),
+                    index,
+                },
+                message_id: H256::from_low_u64_be(index.into()),
+            },
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            },
         }
     }
+
+    async fn bundle(
+        checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        indices: impl Iterator<Item = u32>,
+    ) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let bundler = CheckpointBundler {
+            interval: Duration::from_millis(10),
+            checkpoint_syncer,
+            published_checkpoints: receiver,
+        };
+        for index in indices {
+            sender.send(signed_checkpoint(index)).unwrap();
+        }
+        drop(sender);
+        bundler.bundle_published_checkpoints().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bundles_only_complete_ranges_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage: Arc<dyn CheckpointSyncer> =
+            Arc::new(LocalStorage::new(dir.path().to_owned(), None).unwrap());
+        let first_range = 0..=CHECKPOINT_BUNDLE_SIZE - 1;
+        let second_range = CHECKPOINT_BUNDLE_SIZE..=2 * CHECKPOINT_BUNDLE_SIZE - 1;
+
+        // The second range misses a checkpoint, and the first is published
+        // out of order and partly twice
+        let indices = (CHECKPOINT_BUNDLE_SIZE / 2..2 * CHECKPOINT_BUNDLE_SIZE)
+            .chain(0..CHECKPOINT_BUNDLE_SIZE)
+            .filter(|&index| index != CHECKPOINT_BUNDLE_SIZE + 1);
+        bundle(storage.clone(), indices).await;
+
+        let manifest = storage.fetch_bundle_manifest().await.unwrap().unwrap();
+        assert_eq!(manifest.bundles, vec![first_range.clone()]);
+        let bundle_checkpoints = storage
+            .fetch_bundle(first_range.clone())
+            .await
+            .unwrap()
+            .unwrap()
+            .checkpoints;
+        assert_eq!(
+            bundle_checkpoints
+                .iter()
+                .map(|checkpoint| checkpoint.value.index)
+                .collect::<Vec<_>>(),
+            first_range.clone().collect::<Vec<_>>()
+        );
+        assert!(storage.fetch_bundle(second_range).await.unwrap().is_none());
+
+        // Once in the manifest a range isn't bundled again, e.g. after a restart
+        std::fs::remove_file(dir.path().join(format!(
+            "bundle_{}_{}.json",
+            first_range.start(),
+            first_range.end()
+        )))
+        .unwrap();
+        bundle(storage.clone(), first_range.clone()).await;
+        assert!(storage.fetch_bundle(first_range).await.unwrap().is_none());
+    }
 }
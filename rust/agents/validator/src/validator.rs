@@ -1,9 +1,9 @@
 use std::num::NonZeroU64;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
-use eyre::Result;
+use eyre::{bail, Result};
 use tokio::{task::JoinHandle, time::sleep};
 use tracing::{error, info, info_span, instrument::Instrumented, warn, Instrument};
 
@@ -19,9 +19,7 @@ use hyperlane_core::{
 };
 use hyperlane_ethereum::{SingletonSigner, SingletonSignerHandle};
 
-use crate::{
-    settings::ValidatorSettings, submit::ValidatorSubmitter, submit::ValidatorSubmitterMetrics,
-};
+use crate::{settings::ValidatorSettings, submit::ValidatorMetrics, submit::ValidatorSubmitter};
 
 /// A validator agent
 #[derive(Debug)]
@@ -37,7 +35,20 @@ pub struct Validator {
     signer_instance: Option<Box<SingletonSigner>>,
     reorg_period: u64,
     interval: Duration,
+    verify_checkpoints: bool,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    rotation: Option<KeyRotation>,
+}
+
+/// The key a validator is rotating to, which signs checkpoints along with the
+/// current key until the overlap ends
+#[derive(Debug)]
+struct KeyRotation {
+    signer: SingletonSignerHandle,
+    // temporary holder until `run` is called
+    signer_instance: Option<Box<SingletonSigner>>,
+    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    overlap: Duration,
 }
 
 impl AsRef<HyperlaneAgentCore> for Validator {
@@ -63,7 +74,32 @@ impl BaseAgent for Validator {
         let (signer_instance, signer) = SingletonSigner::new(settings.validator.build().await?);
 
         let core = settings.build_hyperlane_core(metrics.clone());
-        let checkpoint_syncer = settings.checkpoint_syncer.build(None)?.into();
+        let checkpoint_syncer: Arc<dyn CheckpointSyncer> =
+            settings.checkpoint_syncer.build(None)?.into();
+
+        let rotation = match &settings.rotation {
+            Some(rotation) => {
+                let (signer_instance, next_signer) =
+                    SingletonSigner::new(rotation.validator.build().await?);
+                let next_checkpoint_syncer: Arc<dyn CheckpointSyncer> =
+                    rotation.checkpoint_syncer.build(None)?.into();
+                if next_signer.eth_address() == signer.eth_address() {
+                    bail!("The key to rotate to must differ from the current key");
+                }
+                if next_checkpoint_syncer.announcement_location()
+                    == checkpoint_syncer.announcement_location()
+                {
+                    bail!("The key to rotate to must publish checkpoints to another location");
+                }
+                Some(KeyRotation {
+                    signer: next_signer,
+                    signer_instance: Some(Box::new(signer_instance)),
+                    checkpoint_syncer: next_checkpoint_syncer,
+                    overlap: rotation.overlap,
+                })
+            }
+            None => None,
+        };
 
         let mailbox = settings
             .build_mailbox(&settings.origin_chain, &metrics)
@@ -96,7 +132,9 @@ impl BaseAgent for Validator {
             signer_instance: Some(Box::new(signer_instance)),
             reorg_period: settings.reorg_period,
             interval: settings.interval,
+            verify_checkpoints: settings.verify_checkpoints,
             checkpoint_syncer,
+            rotation,
         })
     }
 
@@ -113,9 +151,29 @@ impl BaseAgent for Validator {
                 .instrument(info_span!("SingletonSigner")),
             );
         }
+        if let Some(signer_instance) = self
+            .rotation
+            .as_mut()
+            .and_then(|rotation| rotation.signer_instance.take())
+        {
+            tasks.push(
+                tokio::spawn(async move {
+                    signer_instance.run().await;
+                    Ok(())
+                })
+                .instrument(info_span!("NextSingletonSigner")),
+            );
+        }
 
         // announce the validator after spawning the signer task
-        self.announce().await.expect("Failed to announce validator");
+        self.announce(&self.signer, &self.checkpoint_syncer)
+            .await
+            .expect("Failed to announce validator");
+        if let Some(rotation) = &self.rotation {
+            self.announce(&rotation.signer, &rotation.checkpoint_syncer)
+                .await
+                .expect("Failed to announce the key to rotate to");
+        }
 
         let reorg_period = NonZeroU64::new(self.reorg_period);
 
@@ -133,8 +191,12 @@ impl BaseAgent for Validator {
         }
 
         tasks.push(self.run_message_sync().await);
-        for checkpoint_sync_task in self.run_checkpoint_submitters().await {
-            tasks.push(checkpoint_sync_task);
+        match self.run_checkpoint_submitters().await {
+            Ok(checkpoint_sync_tasks) => tasks.extend(checkpoint_sync_tasks),
+            // Fail the agent like a failing task would
+            Err(err) => tasks.push(
+                tokio::spawn(async move { Err(err) }).instrument(info_span!("CheckpointSubmitter")),
+            ),
         }
 
         run_all(tasks)
@@ -159,7 +221,8 @@ impl Validator {
         .instrument(info_span!("MailboxMessageSyncer"))
     }
 
-    async fn run_checkpoint_submitters(&self) -> Vec<Instrumented<JoinHandle<Result<()>>>> {
+    async fn run_checkpoint_submitters(&self) -> Result<Vec<Instrumented<JoinHandle<Result<()>>>>> {
+        let metrics = ValidatorMetrics::new(&self.core.metrics, &self.origin_chain);
         let submitter = ValidatorSubmitter::new(
             self.interval,
             self.reorg_period,
@@ -167,39 +230,87 @@ impl Validator {
             self.signer.clone(),
             self.checkpoint_syncer.clone(),
             self.db.clone(),
-            ValidatorSubmitterMetrics::new(&self.core.metrics, &self.origin_chain),
+            self.verify_checkpoints,
+            metrics.submitter(self.signer.eth_address()),
         );
 
-        let empty_tree = IncrementalMerkle::default();
-        let reorg_period = NonZeroU64::new(self.reorg_period);
-        let tip_tree = self
-            .mailbox
-            .tree(reorg_period)
-            .await
-            .expect("failed to get mailbox tree");
-        assert!(tip_tree.count() > 0, "mailbox tree is empty");
-        let backfill_target = submitter.checkpoint(&tip_tree);
+        let submitters = match &self.rotation {
+            Some(rotation) => {
+                let retire_at = key_rotation_end(
+                    &self.db,
+                    &rotation.signer.eth_address().into(),
+                    rotation.overlap,
+                )?;
+                info!(
+                    validator = ?submitter.validator(),
+                    next_validator = ?rotation.signer.eth_address(),
+                    ?retire_at,
+                    "Rotating validator key, signing checkpoints with both keys until the overlap ends"
+                );
+                vec![
+                    submitter.for_key(
+                        rotation.signer.clone(),
+                        rotation.checkpoint_syncer.clone(),
+                        metrics.submitter(rotation.signer.eth_address()),
+                    ),
+                    submitter.retiring_at(retire_at),
+                ]
+            }
+            None => vec![submitter],
+        };
+
+        let mut tasks = vec![];
+        for submitter in submitters {
+            tasks.extend(self.spawn_checkpoint_submitters(submitter).await);
+        }
+        Ok(tasks)
+    }
 
+    async fn spawn_checkpoint_submitters(
+        &self,
+        submitter: ValidatorSubmitter,
+    ) -> Vec<Instrumented<JoinHandle<Result<()>>>> {
+        let validator = submitter.validator();
+        let (submitter, bundler) = submitter.with_bundler();
         let legacy_submitter = submitter.clone();
-        let backfill_submitter = submitter.clone();
+        let mut tasks = vec![tokio::spawn(async move { bundler.run().await })
+            .instrument(info_span!("CheckpointBundler", ?validator))];
+
+        let tip_tree = if self.verify_checkpoints {
+            // Build the tree from the indexed messages instead of trusting the
+            // onchain tree, signing every checkpoint from the first index on
+            // once it matches the onchain checkpoint
+            IncrementalMerkle::default()
+        } else {
+            let reorg_period = NonZeroU64::new(self.reorg_period);
+            let tip_tree = self
+                .mailbox
+                .tree(reorg_period)
+                .await
+                .expect("failed to get mailbox tree");
+            assert!(tip_tree.count() > 0, "mailbox tree is empty");
+            let backfill_target = submitter.checkpoint(&tip_tree);
 
-        let mut tasks = vec![];
-        tasks.push(
-            tokio::spawn(async move {
-                backfill_submitter
-                    .checkpoint_submitter(empty_tree, Some(backfill_target))
-                    .await
-            })
-            .instrument(info_span!("BackfillCheckpointSubmitter")),
-        );
+            let empty_tree = IncrementalMerkle::default();
+            let backfill_submitter = submitter.clone();
+            tasks.push(
+                tokio::spawn(async move {
+                    backfill_submitter
+                        .checkpoint_submitter(empty_tree, Some(backfill_target))
+                        .await
+                })
+                .instrument(info_span!("BackfillCheckpointSubmitter", ?validator)),
+            );
+            tip_tree
+        };
 
         tasks.push(
             tokio::spawn(async move { submitter.checkpoint_submitter(tip_tree, None).await })
-                .instrument(info_span!("TipCheckpointSubmitter")),
+                .instrument(info_span!("TipCheckpointSubmitter", ?validator)),
         );
         tasks.push(
             tokio::spawn(async move { legacy_submitter.legacy_checkpoint_submitter().await })
-                .instrument(info_span!("LegacyCheckpointSubmitter")),
+                .instrument(info_span!("LegacyCheckpointSubmitter", ?validator)),
         );
 
         tasks
@@ -226,16 +337,20 @@ impl Validator {
         }
     }
 
-    async fn announce(&self) -> Result<()> {
+    async fn announce(
+        &self,
+        signer: &SingletonSignerHandle,
+        checkpoint_syncer: &Arc<dyn CheckpointSyncer>,
+    ) -> Result<()> {
         // Sign and post the validator announcement
         let announcement = Announcement {
-            validator: self.signer.eth_address(),
+            validator: signer.eth_address(),
             mailbox_address: self.mailbox.address(),
             mailbox_domain: self.mailbox.domain().id(),
-            storage_location: self.checkpoint_syncer.announcement_location(),
+            storage_location: checkpoint_syncer.announcement_location(),
         };
-        let signed_announcement = self.signer.sign(announcement.clone()).await?;
-        self.checkpoint_syncer
+        let signed_announcement = signer.sign(announcement.clone()).await?;
+        checkpoint_syncer
             .write_announcement(&signed_announcement)
             .await?;
 
@@ -243,7 +358,7 @@ impl Validator {
         // the main validator submit loop. This is to avoid a situation in
         // which the validator is signing checkpoints but has not announced
         // their locations, which makes them functionally unusable.
-        let validators: [H256; 1] = [self.signer.eth_address().into()];
+        let validators: [H256; 1] = [signer.eth_address().into()];
         loop {
             info!("Checking for validator announcement");
             if let Some(locations) = self
@@ -252,7 +367,7 @@ impl Validator {
                 .await?
                 .first()
             {
-                if locations.contains(&self.checkpoint_syncer.announcement_location()) {
+                if locations.contains(&checkpoint_syncer.announcement_location()) {
                     info!("Validator has announced signature storage location");
                     break;
                 }
@@ -294,5 +409,24 @@ impl Validator {
     }
 }
 
+/// When the overlap of the rotation to `validator` ends. It starts once the
+/// key to rotate to is announced for the first time, which is stored so that
+/// restarts don't extend it.
+pub(crate) fn key_rotation_end(
+    db: &HyperlaneRocksDB,
+    validator: &H256,
+    overlap: Duration,
+) -> Result<SystemTime> {
+    let started_at = match db.retrieve_key_rotation_started_at_by_validator(validator)? {
+        Some(started_at) => started_at,
+        None => {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            db.store_key_rotation_started_at_by_validator(validator, &now)?;
+            now
+        }
+    };
+    Ok(UNIX_EPOCH + Duration::from_secs(started_at) + overlap)
+}
+
 #[cfg(test)]
 mod test {}
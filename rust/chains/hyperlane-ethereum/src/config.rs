@@ -1,4 +1,9 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use eyre::eyre;
 use hyperlane_core::config::*;
+use hyperlane_core::{H160, U256};
 use serde::Deserialize;
 use url::Url;
 
@@ -10,6 +15,15 @@ pub enum ConnectionConf {
         /// List of urls to connect to
         urls: Vec<Url>,
     },
+    /// An HTTP-only set whose critical reads have to be agreed on by a
+    /// quorum.
+    HttpCrossChecked {
+        /// List of urls to connect to
+        urls: Vec<Url>,
+        /// How many of the urls have to agree on the response to a critical
+        /// read
+        quorum: usize,
+    },
     /// An HTTP-only fallback set.
     HttpFallback {
         /// List of urls to connect to in order of priority
@@ -38,6 +52,9 @@ pub struct RawConnectionConf {
     url: Option<String>,
     /// A comma separated list of urls to connect to
     urls: Option<String>,
+    /// How many of the urls have to agree for `httpCrossChecked`, defaults to
+    /// a majority
+    quorum: Option<StrOrInt>,
 }
 
 /// Error type when parsing a connection configuration.
@@ -64,6 +81,27 @@ pub enum ConnectionConfError {
     /// The urls were empty
     #[error("The `urls` value is empty")]
     EmptyUrls,
+    /// The quorum could not be parsed
+    #[error("Invalid `quorum` for connection configuration: {0}")]
+    InvalidQuorum(StrOrIntParseError),
+    /// The quorum is not between one and the number of urls
+    #[error("The `quorum` must be between 1 and the number of urls ({1}), got {0}")]
+    QuorumOutOfRange(usize, usize),
+}
+
+impl ConnectionConf {
+    /// Create an `HttpCrossChecked` connection configuration, requiring a
+    /// majority of the urls to agree if no quorum is given.
+    pub fn http_cross_checked(
+        urls: Vec<Url>,
+        quorum: Option<usize>,
+    ) -> Result<Self, ConnectionConfError> {
+        let quorum = quorum.unwrap_or(urls.len() / 2 + 1);
+        if quorum == 0 || quorum > urls.len() {
+            return Err(ConnectionConfError::QuorumOutOfRange(quorum, urls.len()));
+        }
+        Ok(Self::HttpCrossChecked { urls, quorum })
+    }
 }
 
 impl FromRawConf<RawConnectionConf> for ConnectionConf {
@@ -112,6 +150,20 @@ impl FromRawConf<RawConnectionConf> for ConnectionConf {
 
         match connection_type {
             "httpQuorum" => make_with_urls!(HttpQuorum),
+            "httpCrossChecked" => {
+                let quorum = raw
+                    .quorum
+                    .map(|q| u32::try_from(q).map(|q| q as usize))
+                    .transpose()
+                    .map_err(InvalidQuorum)
+                    .into_config_result(|| cwp + "quorum")?;
+                let urls = match (urls, url) {
+                    (Ok(urls), _) => urls,
+                    (Err(_), Ok(url)) => vec![url],
+                    (Err(err), Err(_)) => return Err(err),
+                };
+                Self::http_cross_checked(urls, quorum).into_config_result(|| cwp + "quorum")
+            }
             "httpFallback" => make_with_urls!(HttpFallback),
             "http" => Ok(Self::Http { url: url? }),
             "ws" => Ok(Self::Ws { url: url? }),
@@ -119,3 +171,115 @@ impl FromRawConf<RawConnectionConf> for ConnectionConf {
         }
     }
 }
+
+/// Configuration for signing with a key held by a remote signer, e.g.
+/// Web3Signer, over JSON-RPC.
+#[derive(Debug, Clone)]
+pub struct RemoteSignerConf {
+    /// Url of the remote signer's JSON-RPC endpoint
+    pub url: Url,
+    /// Address of the key to sign with
+    pub address: H160,
+    /// Client certificate to authenticate to the remote signer with
+    pub tls: Option<RemoteSignerTlsConf>,
+}
+
+/// TLS client authentication for a remote signer
+#[derive(Debug, Clone)]
+pub struct RemoteSignerTlsConf {
+    /// Path to the PEM encoded client certificate
+    pub client_cert: PathBuf,
+    /// Path to the PEM encoded private key of the client certificate
+    pub client_key: PathBuf,
+    /// Path to a PEM encoded CA certificate to trust in addition to the
+    /// system's, e.g. when the remote signer's certificate is self-signed
+    pub ca_cert: Option<PathBuf>,
+}
+
+/// Configuration for replacing transactions that are stuck waiting to be
+/// included with copies that pay a higher gas price.
+///
+/// Every `bump_interval` a transaction hasn't been included it is re-broadcast
+/// with the same nonce and its gas price (or max fee and max priority fee per
+/// gas) raised by `bump_percent`, up to `max_bumps` times and never above
+/// `max_gas_price`. The default is to never replace transactions.
+#[derive(Debug, Clone, Default)]
+pub struct GasEscalatorConf {
+    /// How long to wait for a transaction to be included before replacing it.
+    pub bump_interval: Duration,
+    /// The percentage to raise the gas price by with each replacement. Nodes
+    /// usually require at least 10% to accept a replacement.
+    pub bump_percent: u32,
+    /// The maximum number of times to replace a transaction. Zero disables
+    /// replacements.
+    pub max_bumps: u32,
+    /// The gas price, or max fee per gas, that replacements may not exceed.
+    pub max_gas_price: Option<U256>,
+}
+
+impl GasEscalatorConf {
+    /// Whether transactions should ever be replaced.
+    pub fn is_enabled(&self) -> bool {
+        self.max_bumps > 0
+    }
+}
+
+/// Raw gas escalator configuration
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawGasEscalatorConf {
+    /// Seconds to wait for a transaction to be included before replacing it
+    bump_interval_secs: Option<StrOrInt>,
+    /// The percentage to raise the gas price by with each replacement
+    bump_percent: Option<StrOrInt>,
+    /// The maximum number of times to replace a transaction
+    max_bumps: Option<StrOrInt>,
+    /// The gas price, or max fee per gas, in wei that replacements may not exceed
+    max_gas_price: Option<StrOrInt>,
+}
+
+impl FromRawConf<RawGasEscalatorConf> for GasEscalatorConf {
+    fn from_config_filtered(
+        raw: RawGasEscalatorConf,
+        cwp: &ConfigPath,
+        _filter: (),
+    ) -> ConfigResult<Self> {
+        let mut err = ConfigParsingError::default();
+
+        let bump_interval = raw
+            .bump_interval_secs
+            .and_then(|v| {
+                v.try_into()
+                    .take_err(&mut err, || cwp + "bump_interval_secs")
+            })
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60));
+
+        let bump_percent: u32 = raw
+            .bump_percent
+            .and_then(|v| v.try_into().take_err(&mut err, || cwp + "bump_percent"))
+            .unwrap_or(12);
+        if bump_percent < 10 {
+            err.push(
+                cwp + "bump_percent",
+                eyre!("Replacements must raise the gas price by at least 10%"),
+            );
+        }
+
+        let max_bumps = raw
+            .max_bumps
+            .and_then(|v| v.try_into().take_err(&mut err, || cwp + "max_bumps"))
+            .unwrap_or(0);
+
+        let max_gas_price = raw
+            .max_gas_price
+            .and_then(|v| v.try_into().take_err(&mut err, || cwp + "max_gas_price"));
+
+        err.into_result(Self {
+            bump_interval,
+            bump_percent,
+            max_bumps,
+            max_gas_price,
+        })
+    }
+}
@@ -11,12 +11,13 @@ use tracing::instrument;
 use hyperlane_core::{
     BlockRange, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneAbi,
     HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider, IndexRange, Indexer,
-    InterchainGasPaymaster, InterchainGasPayment, LogMeta, H160, H256,
+    InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceIndexer, H160, H256,
 };
 
 use crate::contracts::i_interchain_gas_paymaster::{
     IInterchainGasPaymaster as EthereumInterchainGasPaymasterInternal, IINTERCHAINGASPAYMASTER_ABI,
 };
+use crate::provider::fetch_block_hash;
 use crate::trait_builder::BuildableWithProvider;
 use crate::EthereumProvider;
 
@@ -36,7 +37,7 @@ pub struct InterchainGasPaymasterIndexerBuilder {
 
 #[async_trait]
 impl BuildableWithProvider for InterchainGasPaymasterIndexerBuilder {
-    type Output = Box<dyn Indexer<InterchainGasPayment>>;
+    type Output = Box<dyn SequenceIndexer<InterchainGasPayment>>;
 
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
@@ -128,6 +129,24 @@ where
             .as_u32()
             .saturating_sub(self.finality_blocks))
     }
+
+    async fn fetch_block_hash(&self, block_number: u32) -> ChainResult<Option<H256>> {
+        fetch_block_hash(&*self.provider, block_number)
+            .await
+            .map(Some)
+    }
+}
+
+#[async_trait]
+impl<M> SequenceIndexer<InterchainGasPayment> for EthereumInterchainGasPaymasterIndexer<M>
+where
+    M: Middleware + 'static,
+{
+    async fn sequence_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // The IGP does not number gas payments
+        let tip = self.get_finalized_block_number().await?;
+        Ok((None, tip))
+    }
 }
 
 pub struct InterchainGasPaymasterBuilder {}
@@ -12,13 +12,18 @@ use ethers::prelude::{abi, Lazy, Middleware};
 pub use self::{
     aggregation_ism::*, ccip_read_ism::*, config::*, config::*, interchain_gas::*,
     interchain_gas::*, interchain_security_module::*, interchain_security_module::*, mailbox::*,
-    mailbox::*, multisig_ism::*, provider::*, routing_ism::*, rpc_clients::*, signers::*,
-    singleton_signer::*, trait_builder::*, validator_announce::*,
+    mailbox::*, multisig_ism::*, nonce_manager::*, provider::*, remote_signer::*, routing_ism::*,
+    rpc_clients::*, signers::*, singleton_signer::*, subscription::*, trait_builder::*,
+    validator_announce::*,
 };
 
 #[cfg(not(doctest))]
 mod tx;
 
+/// Transaction nonce management
+#[cfg(not(doctest))]
+mod nonce_manager;
+
 /// Mailbox abi
 #[cfg(not(doctest))]
 mod mailbox;
@@ -58,6 +63,10 @@ mod validator_announce;
 #[cfg(not(doctest))]
 mod aggregation_ism;
 
+/// Websocket log subscriptions
+#[cfg(not(doctest))]
+mod subscription;
+
 /// Generated contract bindings.
 #[cfg(not(doctest))]
 mod contracts;
@@ -67,6 +76,9 @@ mod rpc_clients;
 
 mod signers;
 
+/// Signer whose key is held by a remote signer
+mod remote_signer;
+
 #[cfg(not(doctest))]
 mod singleton_signer;
 
@@ -1,34 +1,50 @@
 #![allow(clippy::enum_variant_names)]
 #![allow(missing_docs)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroU64;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use ethers::abi::AbiEncode;
+use ethers::abi::Detokenize;
 use ethers::prelude::Middleware;
 use ethers_contract::builders::ContractCall;
-use tracing::instrument;
+use ethers_contract::EthEvent;
+use tokio::sync::OnceCell;
+use tracing::{instrument, warn};
 
 use hyperlane_core::accumulator::incremental::IncrementalMerkle;
 use hyperlane_core::accumulator::TREE_DEPTH;
 use hyperlane_core::{
-    utils::fmt_bytes, BlockRange, ChainCommunicationError, ChainResult, Checkpoint,
+    utils::fmt_bytes, BatchItem, BlockRange, ChainCommunicationError, ChainResult, Checkpoint,
     ContractLocator, HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
     HyperlaneMessage, HyperlaneProtocolError, HyperlaneProvider, IndexRange, Indexer, LogMeta,
-    Mailbox, MessageIndexer, RawHyperlaneMessage, TxCostEstimate, TxOutcome, H160, H256, U256,
+    Mailbox, MessageIndexer, RawHyperlaneMessage, SequenceIndexer, TxCostEstimate, TxOutcome, H160,
+    H256, U256,
 };
 
 use crate::contracts::arbitrum_node_interface::ArbitrumNodeInterface;
-use crate::contracts::i_mailbox::{IMailbox as EthereumMailboxInternal, ProcessCall, IMAILBOX_ABI};
+use crate::contracts::i_mailbox::{
+    IMailbox as EthereumMailboxInternal, ProcessCall, ProcessIdFilter, IMAILBOX_ABI,
+};
+use crate::contracts::i_multicall_3::{Call3, IMulticall3};
+use crate::provider::fetch_block_hash;
 use crate::trait_builder::BuildableWithProvider;
-use crate::tx::{fill_tx_gas_params, report_tx};
-use crate::EthereumProvider;
+use crate::tx::{fill_tx_gas_params, report_tx_with_escalator};
+use crate::{EthereumProvider, GasEscalatorConf, TxNonceManager};
 
 /// derived from `forge inspect Mailbox storage --pretty`
 const MERKLE_TREE_CONTRACT_SLOT: u32 = 152;
 
+/// The address Multicall3 is deployed to on nearly every EVM chain, see
+/// https://github.com/mds1/multicall#multicall3-contract-addresses. Whether it
+/// is deployed on a given chain is checked before batching messages.
+const MULTICALL3_ADDRESS: H160 = H160([
+    0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17,
+    0x39, 0x76, 0xca, 0x11,
+]);
+
 impl<M> std::fmt::Display for EthereumMailboxInternal<M>
 where
     M: Middleware,
@@ -65,7 +81,7 @@ pub struct DeliveryIndexerBuilder {
 
 #[async_trait]
 impl BuildableWithProvider for DeliveryIndexerBuilder {
-    type Output = Box<dyn Indexer<H256>>;
+    type Output = Box<dyn SequenceIndexer<H256>>;
 
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
@@ -129,6 +145,12 @@ where
         self.get_finalized_block_number().await
     }
 
+    async fn fetch_block_hash(&self, block_number: u32) -> ChainResult<Option<H256>> {
+        fetch_block_hash(&*self.provider, block_number)
+            .await
+            .map(Some)
+    }
+
     #[instrument(err, skip(self))]
     async fn fetch_logs(&self, range: IndexRange) -> ChainResult<Vec<(HyperlaneMessage, LogMeta)>> {
         let BlockRange(range) = range else {
@@ -177,6 +199,12 @@ where
         self.get_finalized_block_number().await
     }
 
+    async fn fetch_block_hash(&self, block_number: u32) -> ChainResult<Option<H256>> {
+        fetch_block_hash(&*self.provider, block_number)
+            .await
+            .map(Some)
+    }
+
     #[instrument(err, skip(self))]
     async fn fetch_logs(&self, range: IndexRange) -> ChainResult<Vec<(H256, LogMeta)>> {
         let BlockRange(range) = range else {
@@ -197,7 +225,21 @@ where
             .collect())
     }
 }
-pub struct MailboxBuilder {}
+
+#[async_trait]
+impl<M> SequenceIndexer<H256> for EthereumMailboxIndexer<M>
+where
+    M: Middleware + 'static,
+{
+    async fn sequence_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // The mailbox does not number processed messages
+        let tip = Indexer::<H256>::get_finalized_block_number(self as _).await?;
+        Ok((None, tip))
+    }
+}
+pub struct MailboxBuilder {
+    pub gas_escalator: GasEscalatorConf,
+}
 
 #[async_trait]
 impl BuildableWithProvider for MailboxBuilder {
@@ -208,7 +250,23 @@ impl BuildableWithProvider for MailboxBuilder {
         provider: M,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMailbox::new(Arc::new(provider), locator))
+        Box::new(EthereumMailbox::new(
+            Arc::new(provider),
+            locator,
+            self.gas_escalator.clone(),
+        ))
+    }
+
+    async fn build_with_signing_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        nonce_manager: Arc<TxNonceManager>,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(
+            EthereumMailbox::new(Arc::new(provider), locator, self.gas_escalator.clone())
+                .with_nonce_manager(nonce_manager),
+        )
     }
 }
 
@@ -222,6 +280,10 @@ where
     domain: HyperlaneDomain,
     provider: Arc<M>,
     arbitrum_node_interface: Option<Arc<ArbitrumNodeInterface<M>>>,
+    multicall: Arc<IMulticall3<M>>,
+    multicall_deployed: OnceCell<bool>,
+    nonce_manager: Option<Arc<TxNonceManager>>,
+    gas_escalator: GasEscalatorConf,
 }
 
 impl<M> EthereumMailbox<M>
@@ -229,8 +291,13 @@ where
     M: Middleware + 'static,
 {
     /// Create a reference to a mailbox at a specific Ethereum address on some
-    /// chain
-    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+    /// chain. Transactions to process messages that get stuck are replaced
+    /// according to `gas_escalator`.
+    pub fn new(
+        provider: Arc<M>,
+        locator: &ContractLocator,
+        gas_escalator: GasEscalatorConf,
+    ) -> Self {
         // Arbitrum Nitro based chains are a special case for transaction cost estimation.
         // The gas amount that eth_estimateGas returns considers both L1 and L2 gas costs.
         // We use the NodeInterface, found at address(0xC8), to isolate the L2 gas costs.
@@ -248,11 +315,42 @@ where
                 provider.clone(),
             )),
             domain: locator.domain.clone(),
+            multicall: Arc::new(IMulticall3::new(MULTICALL3_ADDRESS, provider.clone())),
+            multicall_deployed: OnceCell::new(),
             provider,
             arbitrum_node_interface,
+            nonce_manager: None,
+            gas_escalator,
         }
     }
 
+    /// Assign the nonces of transactions from `nonce_manager` up front rather
+    /// than leaving it to the provider, so several of them can be in flight
+    /// at once.
+    pub fn with_nonce_manager(mut self, nonce_manager: Arc<TxNonceManager>) -> Self {
+        self.nonce_manager = Some(nonce_manager);
+        self
+    }
+
+    /// Whether Multicall3 is deployed to its usual address on this chain, which
+    /// batching messages relies on. Only checked once.
+    async fn multicall_deployed(&self) -> ChainResult<bool> {
+        self.multicall_deployed
+            .get_or_try_init(|| async {
+                let code = self
+                    .provider
+                    .get_code(MULTICALL3_ADDRESS, None)
+                    .await
+                    .map_err(ChainCommunicationError::from_other)?;
+                if code.is_empty() {
+                    warn!(domain=%self.domain, address=?MULTICALL3_ADDRESS, "Multicall3 is not deployed, messages will not be batched");
+                }
+                Ok::<_, ChainCommunicationError>(!code.is_empty())
+            })
+            .await
+            .copied()
+    }
+
     /// Returns a ContractCall that processes the provided message.
     /// If the provided tx_gas_limit is None, gas estimation occurs.
     async fn process_contract_call(
@@ -267,6 +365,39 @@ where
         );
         fill_tx_gas_params(tx, tx_gas_limit, self.provider.clone(), message.destination).await
     }
+
+    /// Assigns the transaction's nonce, dispatches it and waits for its receipt,
+    /// replacing it if it gets stuck.
+    async fn send_with_nonce<D: Detokenize>(
+        &self,
+        mut contract_call: ContractCall<M, D>,
+    ) -> ChainResult<ethers::types::TransactionReceipt> {
+        let Some(nonce_manager) = &self.nonce_manager else {
+            return report_tx_with_escalator(
+                contract_call,
+                self.provider.as_ref(),
+                &self.gas_escalator,
+            )
+            .await;
+        };
+        // Assign the nonce ourselves so concurrent transactions don't have to wait
+        // for each other to be included.
+        let nonce = nonce_manager
+            .acquire(self.provider.as_ref())
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        contract_call.tx.set_nonce(nonce.nonce());
+        let receipt =
+            report_tx_with_escalator(contract_call, self.provider.as_ref(), &self.gas_escalator)
+                .await;
+        // Reverted transactions still consume their nonce. If the transaction
+        // wasn't included the nonce is read from the chain again once the other
+        // transactions in flight are done.
+        if receipt.is_ok() {
+            nonce.consumed();
+        }
+        receipt
+    }
 }
 
 impl<M> HyperlaneChain for EthereumMailbox<M>
@@ -433,10 +564,77 @@ where
         let contract_call = self
             .process_contract_call(message, metadata, tx_gas_limit)
             .await?;
-        let receipt = report_tx(contract_call).await?;
+        let receipt = self.send_with_nonce(contract_call).await?;
         Ok(receipt.into())
     }
 
+    #[instrument(skip(self, items), fields(batch_size = items.len()))]
+    async fn process_batch(
+        &self,
+        items: &[BatchItem],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<Vec<TxOutcome>> {
+        if items.is_empty() {
+            return Ok(vec![]);
+        }
+        if !self.multicall_deployed().await? {
+            return Err(ChainCommunicationError::BatchingNotSupported);
+        }
+        // Failing calls are allowed so that one bad message doesn't prevent the
+        // rest of the batch from being delivered.
+        let mut calls = Vec::with_capacity(items.len());
+        for item in items {
+            calls.push(Call3 {
+                target: self.contract.address(),
+                allow_failure: true,
+                call_data: self
+                    .process_calldata(&item.message, &item.metadata)
+                    .await?
+                    .into(),
+            });
+        }
+        let contract_call = fill_tx_gas_params(
+            self.multicall.aggregate_3(calls),
+            tx_gas_limit,
+            self.provider.clone(),
+            self.domain.id(),
+        )
+        .await?;
+        let receipt = self.send_with_nonce(contract_call).await?;
+
+        // A message was delivered iff the mailbox emitted its ProcessId event
+        let mailbox_address = self.contract.address();
+        let delivered: HashSet<H256> = receipt
+            .logs
+            .iter()
+            .filter(|log| {
+                log.address == mailbox_address
+                    && log.topics.first() == Some(&ProcessIdFilter::signature())
+            })
+            .filter_map(|log| log.topics.get(1).map(|id| H256::from(*id)))
+            .collect();
+        let batch_outcome = TxOutcome::from(receipt);
+
+        let total_gas_limit = items
+            .iter()
+            .fold(U256::zero(), |acc, item| acc.saturating_add(item.gas_limit));
+        Ok(items
+            .iter()
+            .map(|item| {
+                let gas_used = if total_gas_limit.is_zero() {
+                    batch_outcome.gas_used / items.len()
+                } else {
+                    batch_outcome.gas_used.saturating_mul(item.gas_limit) / total_gas_limit
+                };
+                TxOutcome {
+                    executed: batch_outcome.executed && delivered.contains(&item.message.id()),
+                    gas_used,
+                    ..batch_outcome
+                }
+            })
+            .collect())
+    }
+
     #[instrument(skip(self), fields(msg=%message, metadata=%fmt_bytes(metadata)))]
     async fn process_estimate_costs(
         &self,
@@ -484,13 +682,17 @@ where
         })
     }
 
-    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+    async fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
         let process_call = ProcessCall {
             message: RawHyperlaneMessage::from(message).to_vec().into(),
             metadata: metadata.to_vec().into(),
         };
 
-        AbiEncode::encode(process_call)
+        Ok(AbiEncode::encode(process_call))
     }
 }
 
@@ -536,6 +738,7 @@ mod test {
                 // Address doesn't matter because we're using a MockProvider
                 address: H256::default(),
             },
+            Default::default(),
         );
 
         let message = HyperlaneMessage::default();
@@ -0,0 +1,339 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use ethers::prelude::{FromErr, Middleware, PendingTransaction};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, BlockId, BlockNumber, U256};
+use tokio::sync::{Mutex as AsyncMutex, Notify};
+use tracing::debug;
+
+/// Hands out the nonces of a single signer's transactions. One is shared by
+/// the signer's `TxNonceManagerMiddleware`, which assigns a nonce to any
+/// transaction sent without one, and by contracts that assign nonces up front
+/// so several of their transactions can be in flight at once. This way there is
+/// only ever one counter per signer.
+///
+/// The next nonce is read from the chain's pending transaction count the first
+/// time it's needed and is tracked locally from then on. If a transaction fails
+/// to be included the local count may now have a gap, so it's read from the
+/// chain again. This only happens once none of the signer's other transactions
+/// are in flight, since until then the pending count may not account for them;
+/// new nonces aren't handed out in the meantime. With a single transaction in
+/// flight at a time this is the same as reading the pending count again after
+/// every failure.
+#[derive(Debug)]
+pub struct TxNonceManager {
+    address: Address,
+    state: Mutex<NonceState>,
+    /// Held while reading the next nonce from the chain
+    sync: AsyncMutex<()>,
+    /// Notified whenever the last in flight transaction finishes
+    drained: Notify,
+}
+
+#[derive(Debug, Default)]
+struct NonceState {
+    /// The next nonce to hand out, if it's known
+    next_nonce: Option<U256>,
+    /// Whether `next_nonce` has to be read from the chain again
+    stale: bool,
+    /// How many of the handed out nonces are still in flight
+    in_flight: usize,
+}
+
+impl TxNonceManager {
+    /// Create a nonce manager for the transactions sent by `address`.
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            state: Mutex::default(),
+            sync: AsyncMutex::default(),
+            drained: Notify::new(),
+        }
+    }
+
+    /// The address whose transactions this assigns nonces to.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Hands out the nonce of the next transaction. It counts as in flight
+    /// until the returned `InFlightNonce` is dropped, which is treated as the
+    /// transaction failing to be included unless it's marked as `consumed`
+    /// first.
+    pub async fn acquire<M: Middleware>(
+        &self,
+        provider: &M,
+    ) -> Result<InFlightNonce<'_>, M::Error> {
+        loop {
+            // Register for the notification before looking at the state so a
+            // drain in between isn't missed.
+            let drained = self.drained.notified();
+            {
+                let mut state = self.state.lock().expect("nonce state poisoned");
+                match state.next_nonce {
+                    Some(nonce) if !state.stale => {
+                        state.next_nonce = Some(nonce + 1);
+                        state.in_flight += 1;
+                        return Ok(InFlightNonce {
+                            manager: self,
+                            nonce,
+                            consumed: false,
+                        });
+                    }
+                    _ if state.in_flight > 0 => {}
+                    _ => {
+                        drop(state);
+                        self.sync_with_chain(provider).await?;
+                        continue;
+                    }
+                }
+            }
+            drained.await;
+        }
+    }
+
+    /// Reads the next nonce from the chain's pending transaction count unless
+    /// someone else did while we waited for our turn.
+    async fn sync_with_chain<M: Middleware>(&self, provider: &M) -> Result<(), M::Error> {
+        let _sync = self.sync.lock().await;
+        {
+            let state = self.state.lock().expect("nonce state poisoned");
+            if state.in_flight > 0 || (state.next_nonce.is_some() && !state.stale) {
+                return Ok(());
+            }
+        }
+        let nonce = provider
+            .get_transaction_count(self.address, Some(BlockNumber::Pending.into()))
+            .await?;
+        debug!(address=?self.address, ?nonce, "Synced transaction nonce with the chain");
+
+        let mut state = self.state.lock().expect("nonce state poisoned");
+        state.next_nonce = Some(nonce);
+        state.stale = false;
+        Ok(())
+    }
+
+    fn finish(&self, consumed: bool) {
+        let mut state = self.state.lock().expect("nonce state poisoned");
+        state.in_flight -= 1;
+        if !consumed {
+            state.stale = true;
+        }
+        if state.in_flight == 0 {
+            self.drained.notify_waiters();
+        }
+    }
+}
+
+/// A nonce handed out by a `TxNonceManager` whose transaction is in flight.
+#[derive(Debug)]
+#[must_use]
+pub struct InFlightNonce<'a> {
+    manager: &'a TxNonceManager,
+    nonce: U256,
+    consumed: bool,
+}
+
+impl InFlightNonce<'_> {
+    /// The nonce to send the transaction with.
+    pub fn nonce(&self) -> U256 {
+        self.nonce
+    }
+
+    /// Marks the nonce as used by a transaction that was included, whether or
+    /// not it reverted.
+    pub fn consumed(mut self) {
+        self.consumed = true;
+    }
+}
+
+impl Drop for InFlightNonce<'_> {
+    fn drop(&mut self) {
+        self.manager.finish(self.consumed);
+    }
+}
+
+/// Assigns nonces from a shared `TxNonceManager` to the transactions of its
+/// signer that are sent without one. Since it can't know whether they get
+/// included, their nonces are treated as consumed as soon as they're sent.
+/// If sending fails, the nonce is read from the chain again.
+#[derive(Debug)]
+pub struct TxNonceManagerMiddleware<M> {
+    inner: M,
+    nonces: Arc<TxNonceManager>,
+}
+
+impl<M: Middleware> TxNonceManagerMiddleware<M> {
+    /// Wrap `inner`, assigning nonces from `nonces`.
+    pub fn new(inner: M, nonces: Arc<TxNonceManager>) -> Self {
+        Self { inner, nonces }
+    }
+
+    /// Assigns a nonce to `tx` if the signer sends it without one. The nonce
+    /// is in flight until the returned `InFlightNonce` is dropped.
+    async fn assign_nonce(
+        &self,
+        tx: &mut TypedTransaction,
+    ) -> Result<Option<InFlightNonce<'_>>, TxNonceManagerMiddlewareError<M::Error>> {
+        let from = tx.from().copied().unwrap_or_else(|| self.nonces.address());
+        if tx.nonce().is_some() || from != self.nonces.address() {
+            return Ok(None);
+        }
+        let nonce = self.nonces.acquire(&self.inner).await?;
+        tx.set_nonce(nonce.nonce());
+        Ok(Some(nonce))
+    }
+}
+
+#[async_trait]
+impl<M: Middleware> Middleware for TxNonceManagerMiddleware<M> {
+    type Error = TxNonceManagerMiddlewareError<M::Error>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        // The caller sends the transaction, so it's not known whether it fails
+        if let Some(nonce) = self.assign_nonce(tx).await? {
+            nonce.consumed();
+        }
+        Ok(self.inner.fill_transaction(tx, block).await?)
+    }
+
+    async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        let mut tx = tx.into();
+        let nonce = self.assign_nonce(&mut tx).await?;
+        // If sending fails the nonce is dropped unused, so it's read from the
+        // chain again
+        let pending = self.inner.send_transaction(tx, block).await?;
+        if let Some(nonce) = nonce {
+            nonce.consumed();
+        }
+        Ok(pending)
+    }
+}
+
+/// A thin wrapper around the error of the middleware wrapped by a
+/// `TxNonceManagerMiddleware`.
+pub struct TxNonceManagerMiddlewareError<E>(E);
+
+impl<E: Debug> Debug for TxNonceManagerMiddlewareError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<E: Display> Display for TxNonceManagerMiddlewareError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<E: Error> Error for TxNonceManagerMiddlewareError<E> {}
+
+impl<E> FromErr<E> for TxNonceManagerMiddlewareError<E> {
+    fn from(src: E) -> Self {
+        Self(src)
+    }
+}
+
+impl<E> From<E> for TxNonceManagerMiddlewareError<E> {
+    fn from(e: E) -> Self {
+        Self(e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use ethers::prelude::{Middleware, Provider};
+    use ethers::types::{Address, TransactionRequest, U256};
+    use futures_util::future::join_all;
+
+    use super::{TxNonceManager, TxNonceManagerMiddleware};
+
+    #[tokio::test]
+    async fn test_concurrent_nonces_are_unique_and_sequential() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(5)).unwrap();
+        let manager = TxNonceManager::new(Address::zero());
+
+        let acquired = join_all((0..10).map(|_| manager.acquire(&provider))).await;
+        let mut nonces = acquired
+            .iter()
+            .map(|nonce| nonce.as_ref().unwrap().nonce().as_u64())
+            .collect::<Vec<_>>();
+        nonces.sort();
+        assert_eq!(nonces, (5..15).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_consumed_nonces_dont_resync() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(5)).unwrap();
+        let manager = TxNonceManager::new(Address::zero());
+
+        manager.acquire(&provider).await.unwrap().consumed();
+        // The mock has no responses left, so this fails if it reads the chain
+        let nonce = manager.acquire(&provider).await.unwrap();
+        assert_eq!(nonce.nonce(), U256::from(6));
+    }
+
+    #[tokio::test]
+    async fn test_resyncs_only_once_in_flight_transactions_drain() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(5)).unwrap();
+        let manager = TxNonceManager::new(Address::zero());
+
+        let failed = manager.acquire(&provider).await.unwrap();
+        let in_flight = manager.acquire(&provider).await.unwrap();
+        assert_eq!(failed.nonce(), U256::from(5));
+        assert_eq!(in_flight.nonce(), U256::from(6));
+        drop(failed);
+
+        // No nonces are handed out while the other transaction is in flight
+        let mut next = Box::pin(manager.acquire(&provider));
+        assert!(futures_util::poll!(&mut next).is_pending());
+
+        mock.push(U256::from(9)).unwrap();
+        in_flight.consumed();
+        let next = next.await.unwrap();
+        assert_eq!(next.nonce(), U256::from(9));
+    }
+
+    #[tokio::test]
+    async fn test_failed_sends_resync() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(5)).unwrap();
+        let manager = Arc::new(TxNonceManager::new(Address::zero()));
+        let middleware = TxNonceManagerMiddleware::new(provider.clone(), manager.clone());
+
+        // The mock has no response left to send the transaction, so it fails
+        let tx = TransactionRequest::new()
+            .from(Address::zero())
+            .to(Address::zero())
+            .gas(21_000)
+            .gas_price(1);
+        assert!(middleware.send_transaction(tx, None).await.is_err());
+
+        // The unused nonce is read from the chain again
+        mock.push(U256::from(5)).unwrap();
+        let nonce = manager.acquire(&provider).await.unwrap();
+        assert_eq!(nonce.nonce(), U256::from(5));
+    }
+}
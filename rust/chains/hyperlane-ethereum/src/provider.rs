@@ -13,6 +13,7 @@ use tracing::instrument;
 use hyperlane_core::{
     BlockInfo, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
     HyperlaneDomain, HyperlaneProvider, HyperlaneProviderError, TxnInfo, TxnReceiptInfo, H256,
+    H512,
 };
 
 use crate::BuildableWithProvider;
@@ -50,7 +51,7 @@ where
     M: Middleware + 'static,
 {
     #[instrument(err, skip(self))]
-    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+    async fn get_block(&self, hash: &H256, _number: u64) -> ChainResult<BlockInfo> {
         let block = get_with_retry_on_none(hash, |h| {
             let eth_h256: ethers_core_types::H256 = h.into();
             self.provider.get_block(eth_h256)
@@ -67,11 +68,12 @@ where
     }
 
     #[instrument(err, skip(self))]
-    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
-        let txn = get_with_retry_on_none(hash, |h| self.provider.get_transaction(*h)).await?;
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        let txn_hash: H256 = (*hash).into();
+        let txn = get_with_retry_on_none(&txn_hash, |h| self.provider.get_transaction(*h)).await?;
         let receipt = self
             .provider
-            .get_transaction_receipt(*hash)
+            .get_transaction_receipt(txn_hash)
             .await
             .map_err(ChainCommunicationError::from_other)?
             .map(|r| -> Result<_, HyperlaneProviderError> {
@@ -167,3 +169,19 @@ where
     }
     Err(HyperlaneProviderError::CouldNotFindObjectByHash(*hash).into())
 }
+
+/// Get the hash of the block at a height of the chain, which indexers report
+/// so that reorgs of indexed blocks can be detected.
+pub(crate) async fn fetch_block_hash<M>(provider: &M, block_number: u32) -> ChainResult<H256>
+where
+    M: Middleware + 'static,
+{
+    let block_number = u64::from(block_number);
+    provider
+        .get_block(block_number)
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .and_then(|block| block.hash)
+        .map(Into::into)
+        .ok_or_else(|| HyperlaneProviderError::CouldNotFindBlockByNumber(block_number).into())
+}
@@ -0,0 +1,380 @@
+use std::fs;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ethers::prelude::{
+    Address, Http, Middleware, Provider, ProviderError, Signature, SignatureError,
+};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers_signers::Signer;
+use reqwest::{Certificate, Client, Identity};
+
+use crate::RemoteSignerConf;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A signer whose key is held by a remote signer, e.g. Web3Signer, which signs
+/// messages with `eth_sign` over JSON-RPC. It only signs messages, such as
+/// checkpoints and announcements, not transactions.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    provider: Provider<Http>,
+    address: Address,
+    chain_id: u64,
+}
+
+impl RemoteSigner {
+    /// Connect to the remote signer described by `conf`, authenticating with
+    /// its TLS client certificate if there is one.
+    pub fn connect(conf: &RemoteSignerConf) -> Result<Self, RemoteSignerError> {
+        let mut builder = Client::builder().timeout(REQUEST_TIMEOUT);
+        if let Some(tls) = &conf.tls {
+            let mut pem = fs::read(&tls.client_cert)?;
+            pem.push(b'\n');
+            pem.extend(fs::read(&tls.client_key)?);
+            builder = builder.use_rustls_tls().identity(Identity::from_pem(&pem)?);
+            if let Some(ca_cert) = &tls.ca_cert {
+                builder = builder.add_root_certificate(Certificate::from_pem(&fs::read(ca_cert)?)?);
+            }
+        }
+        let client = builder.build()?;
+
+        Ok(Self {
+            provider: Provider::new(Http::new_with_client(conf.url.clone(), client)),
+            address: conf.address.into(),
+            chain_id: 1,
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    type Error = RemoteSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        // `eth_sign` prefixes the message like `Signer::sign_message` does
+        let message = message.as_ref();
+        let signature = self.provider.sign(message.to_vec(), &self.address).await?;
+        // A misrouted or misconfigured remote signer may sign with another key
+        let signer = signature.recover(message)?;
+        if signer != self.address {
+            return Err(RemoteSignerError::WrongSigner {
+                expected: self.address,
+                actual: signer,
+            });
+        }
+        Ok(signature)
+    }
+
+    async fn sign_transaction(&self, _: &TypedTransaction) -> Result<Signature, Self::Error> {
+        Err(RemoteSignerError::Unsupported("transactions"))
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        _: &T,
+    ) -> Result<Signature, Self::Error> {
+        Err(RemoteSignerError::Unsupported("typed data"))
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+/// Error types for the remote signer
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+    /// A TLS certificate or key could not be read
+    #[error("Failed to read TLS certificate or key: {0}")]
+    Io(#[from] std::io::Error),
+    /// The HTTP client could not be built, e.g. due to an invalid certificate
+    #[error("{0}")]
+    Client(#[from] reqwest::Error),
+    /// The remote signer failed to sign
+    #[error("{0}")]
+    Provider(#[from] ProviderError),
+    /// The remote signer returned an invalid signature
+    #[error("{0}")]
+    Signature(#[from] SignatureError),
+    /// The remote signer signed with another key than the configured one
+    #[error("Remote signer signed with {actual:?} instead of {expected:?}")]
+    WrongSigner {
+        /// The address of the configured key
+        expected: Address,
+        /// The address of the key that signed
+        actual: Address,
+    },
+    /// The remote signer is asked to sign something other than a message
+    #[error("Remote signer does not sign {0}")]
+    Unsupported(&'static str),
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use ethers::types::Bytes;
+    use ethers_signers::LocalWallet;
+    use serde_json::{json, Value};
+    use tempfile::TempDir;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+    use tokio_rustls::rustls::{self, RootCertStore, ServerConfig};
+    use tokio_rustls::TlsAcceptor;
+
+    use hyperlane_core::{
+        Announcement, Checkpoint, HyperlaneSigner, HyperlaneSignerExt, H160, H256,
+    };
+
+    use super::*;
+    use crate::signers::Signers;
+    use crate::RemoteSignerTlsConf;
+
+    /// Serve `eth_sign` requests like a remote signer holding `wallet`'s key
+    /// would, one request per connection. It signs with `wallet`'s key
+    /// whichever address it's asked to sign for. With `tls` connections are only
+    /// served once the TLS handshake, which may require a client certificate,
+    /// succeeds.
+    async fn serve_eth_sign(listener: TcpListener, tls: Option<TlsAcceptor>, wallet: LocalWallet) {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            match &tls {
+                Some(tls) => {
+                    if let Ok(stream) = tls.accept(stream).await {
+                        answer_eth_sign(stream, &wallet).await;
+                    }
+                }
+                None => answer_eth_sign(stream, &wallet).await,
+            }
+        }
+    }
+
+    async fn answer_eth_sign<S: AsyncRead + AsyncWrite + Unpin>(
+        mut stream: S,
+        wallet: &LocalWallet,
+    ) {
+        let mut request = vec![];
+        let body = loop {
+            let mut chunk = [0; 1024];
+            let read = stream.read(&mut chunk).await.unwrap();
+            request.extend_from_slice(&chunk[..read]);
+            let Some(headers_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+                continue;
+            };
+            let headers = String::from_utf8_lossy(&request[..headers_end]).to_lowercase();
+            let content_length: usize = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .unwrap()
+                .trim()
+                .parse()
+                .unwrap();
+            let body = &request[headers_end + 4..];
+            if body.len() >= content_length {
+                break body.to_vec();
+            }
+        };
+
+        let request: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(request["method"], "eth_sign");
+        let message: Bytes = serde_json::from_value(request["params"][1].clone()).unwrap();
+        let signature = wallet.sign_message(message.as_ref()).await.unwrap();
+
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": format!("0x{signature}"),
+        })
+        .to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{response}",
+            response.len()
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        stream.shutdown().await.unwrap();
+    }
+
+    /// A remote signer for `address` whose server signs with `wallet`'s key.
+    async fn remote_signer(address: Address, wallet: &LocalWallet) -> RemoteSigner {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve_eth_sign(listener, None, wallet.clone()));
+
+        RemoteSigner::connect(&RemoteSignerConf {
+            url: url.parse().unwrap(),
+            address: address.into(),
+            tls: None,
+        })
+        .unwrap()
+    }
+
+    fn ca() -> rcgen::Certificate {
+        let mut params = rcgen::CertificateParams::default();
+        params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        rcgen::Certificate::from_params(params).unwrap()
+    }
+
+    /// A remote signer served over TLS for `localhost` that only accepts
+    /// clients with a certificate signed by its CA, and the files to configure
+    /// a client with such a certificate.
+    struct MutualTls {
+        dir: TempDir,
+        ca: rcgen::Certificate,
+        acceptor: TlsAcceptor,
+    }
+
+    impl MutualTls {
+        fn new() -> Self {
+            let ca = ca();
+            let server = rcgen::generate_simple_self_signed(vec!["localhost".to_owned()]).unwrap();
+            let mut client_roots = RootCertStore::empty();
+            client_roots
+                .add(&rustls::Certificate(ca.serialize_der().unwrap()))
+                .unwrap();
+            let config = ServerConfig::builder()
+                .with_safe_defaults()
+                .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(client_roots).boxed())
+                .with_single_cert(
+                    vec![rustls::Certificate(
+                        server.serialize_der_with_signer(&ca).unwrap(),
+                    )],
+                    rustls::PrivateKey(server.serialize_private_key_der()),
+                )
+                .unwrap();
+
+            let dir = tempfile::tempdir().unwrap();
+            fs::write(dir.path().join("ca.pem"), ca.serialize_pem().unwrap()).unwrap();
+            Self {
+                dir,
+                ca,
+                acceptor: TlsAcceptor::from(Arc::new(config)),
+            }
+        }
+
+        /// Write a client certificate signed by `issuer` and return the TLS
+        /// config of a client authenticating with it.
+        fn client(&self, name: &str, issuer: &rcgen::Certificate) -> RemoteSignerTlsConf {
+            let client = rcgen::generate_simple_self_signed(vec![name.to_owned()]).unwrap();
+            let client_cert = self.dir.path().join(format!("{name}.pem"));
+            let client_key = self.dir.path().join(format!("{name}.key"));
+            fs::write(
+                &client_cert,
+                client.serialize_pem_with_signer(issuer).unwrap(),
+            )
+            .unwrap();
+            fs::write(&client_key, client.serialize_private_key_pem()).unwrap();
+            RemoteSignerTlsConf {
+                client_cert,
+                client_key,
+                ca_cert: Some(self.dir.path().join("ca.pem")),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signs_checkpoints_and_announcements() {
+        let wallet: LocalWallet =
+            "1111111111111111111111111111111111111111111111111111111111111111"
+                .parse()
+                .unwrap();
+        let signer: Signers = remote_signer(wallet.address(), &wallet).await.into();
+        assert_eq!(signer.eth_address(), H160::from(wallet.address()));
+
+        let checkpoint = Checkpoint {
+            mailbox_address: H256::repeat_byte(2),
+            mailbox_domain: 5,
+            root: H256::repeat_byte(1),
+            index: 123,
+        };
+        let signed = signer.sign(checkpoint).await.unwrap();
+        assert!(signed.signature.v == 27 || signed.signature.v == 28);
+        signed.verify(signer.eth_address()).unwrap();
+
+        let announcement = Announcement {
+            validator: signer.eth_address(),
+            mailbox_address: H256::repeat_byte(2),
+            mailbox_domain: 5,
+            storage_location: "s3://bucket/region".to_owned(),
+        };
+        let signed = signer.sign(announcement).await.unwrap();
+        signed.verify(signer.eth_address()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_authenticates_with_client_certificate() {
+        let wallet = LocalWallet::new(&mut ethers::core::rand::thread_rng());
+        let tls = MutualTls::new();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "https://localhost:{}",
+            listener.local_addr().unwrap().port()
+        );
+        tokio::spawn(serve_eth_sign(
+            listener,
+            Some(tls.acceptor.clone()),
+            wallet.clone(),
+        ));
+        let signer = |tls: RemoteSignerTlsConf| -> Signers {
+            RemoteSigner::connect(&RemoteSignerConf {
+                url: url.parse().unwrap(),
+                address: wallet.address().into(),
+                tls: Some(tls),
+            })
+            .unwrap()
+            .into()
+        };
+        let checkpoint = Checkpoint {
+            mailbox_address: H256::repeat_byte(2),
+            mailbox_domain: 5,
+            root: H256::repeat_byte(1),
+            index: 123,
+        };
+
+        let trusted = signer(tls.client("validator", &tls.ca));
+        let signed = trusted.sign(checkpoint).await.unwrap();
+        signed.verify(trusted.eth_address()).unwrap();
+
+        // The remote signer doesn't accept certificates it doesn't trust
+        let untrusted = signer(tls.client("intruder", &ca()));
+        assert!(untrusted.sign(checkpoint).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_does_not_sign_transactions() {
+        let wallet = LocalWallet::new(&mut ethers::core::rand::thread_rng());
+        let signer = remote_signer(wallet.address(), &wallet).await;
+        assert!(signer
+            .sign_transaction(&TypedTransaction::default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_signatures_by_another_key() {
+        let wallet = LocalWallet::new(&mut ethers::core::rand::thread_rng());
+        let other_wallet = LocalWallet::new(&mut ethers::core::rand::thread_rng());
+        let signer = remote_signer(wallet.address(), &other_wallet).await;
+
+        let result = signer.sign_message(b"checkpoint").await;
+        assert!(matches!(
+            result,
+            Err(RemoteSignerError::WrongSigner { expected, actual })
+                if expected == wallet.address() && actual == other_wallet.address()
+        ));
+    }
+}
@@ -0,0 +1,590 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use ethers::providers::{JsonRpcClient, ProviderError};
+use ethers::types::U64;
+use futures_util::future::join_all;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tracing::{info, instrument, warn};
+
+use ethers_prometheus::json_rpc_client::{JsonRpcClientMetrics, PrometheusJsonRpcClientConfigExt};
+
+/// Methods whose responses a quorum of providers has to agree on. These are
+/// the reads that checkpoints and indexed logs are derived from: `eth_call`
+/// for the mailbox's `latestCheckpoint`, `count` and `delivered`,
+/// `eth_getLogs` and looking up blocks by hash. Those that read the latest
+/// block are pinned to a block number a quorum agreed on first, which is also
+/// how `eth_blockNumber` is cross checked.
+const CROSS_CHECKED_METHODS: &[&str] = &["eth_call", "eth_getLogs", "eth_getBlockByHash"];
+
+/// How many responses in a row a provider may disagree with the quorum before
+/// it is demoted.
+const MAX_CONSECUTIVE_DISAGREEMENTS: u32 = 3;
+
+struct CrossCheckedProviders<T> {
+    providers: Vec<T>,
+    /// How many responses in a row each provider disagreed with the quorum
+    disagreements: Mutex<Vec<u32>>,
+}
+
+/// A provider that sends the critical reads in `CROSS_CHECKED_METHODS` to all
+/// of its providers and only returns a response `quorum` of them agreed on.
+///
+/// Providers that keep disagreeing with the quorum are demoted: their
+/// responses don't count towards the quorum until they agree with it again, so
+/// a single misbehaving RPC can neither make up a response nor help one
+/// through. Other requests are sent to one provider at a time, demoted
+/// providers last, until one succeeds.
+pub struct CrossCheckingProvider<T> {
+    inner: Arc<CrossCheckedProviders<T>>,
+    quorum: usize,
+    metrics: JsonRpcClientMetrics,
+}
+
+impl<T> Clone for CrossCheckingProvider<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            quorum: self.quorum,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl<C> Debug for CrossCheckingProvider<C>
+where
+    C: JsonRpcClient + PrometheusJsonRpcClientConfigExt,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CrossCheckingProvider")
+            .field(
+                "chain_name",
+                &self
+                    .inner
+                    .providers
+                    .get(0)
+                    .map(|v| v.chain_name())
+                    .unwrap_or("None"),
+            )
+            .field(
+                "hosts",
+                &self
+                    .inner
+                    .providers
+                    .iter()
+                    .map(|v| v.node_host())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+            .field("quorum", &self.quorum)
+            .finish()
+    }
+}
+
+impl<T> CrossCheckingProvider<T> {
+    /// Create a provider that requires `quorum` of `providers` to agree on the
+    /// responses to critical reads.
+    pub fn new(
+        providers: impl IntoIterator<Item = T>,
+        quorum: usize,
+        metrics: JsonRpcClientMetrics,
+    ) -> Self {
+        let providers: Vec<_> = providers.into_iter().collect();
+        let disagreements = Mutex::new(vec![0; providers.len()]);
+        Self {
+            inner: Arc::new(CrossCheckedProviders {
+                providers,
+                disagreements,
+            }),
+            quorum,
+            metrics,
+        }
+    }
+
+    fn demoted_providers(&self) -> Vec<bool> {
+        self.inner
+            .disagreements
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|&disagreements| disagreements >= MAX_CONSECUTIVE_DISAGREEMENTS)
+            .collect()
+    }
+}
+
+impl<C> CrossCheckingProvider<C>
+where
+    C: JsonRpcClient + PrometheusJsonRpcClientConfigExt,
+{
+    async fn request_value(
+        provider: &C,
+        method: &str,
+        params: &Value,
+    ) -> Result<Value, ProviderError> {
+        let res = match params {
+            Value::Null => provider.request(method, ()).await,
+            _ => provider.request(method, params).await,
+        };
+        res.map_err(Into::into)
+    }
+
+    /// Ask all providers for their latest block number and return the highest
+    /// one at least `quorum` of the providers that aren't demoted have
+    /// reached. Providers are rarely at exactly the same height, so they aren't
+    /// expected to agree on the latest block itself.
+    async fn quorum_block_number(&self) -> Result<U64, CrossCheckingError> {
+        let responses = join_all(
+            self.inner
+                .providers
+                .iter()
+                .map(|provider| Self::request_value(provider, "eth_blockNumber", &Value::Null)),
+        )
+        .await;
+        let demoted = self.demoted_providers();
+
+        let mut block_numbers = vec![];
+        let mut errors = vec![];
+        for (response, demoted) in responses.into_iter().zip(demoted) {
+            match response
+                .and_then(|value| serde_json::from_value::<U64>(value).map_err(Into::into))
+            {
+                Ok(block_number) if !demoted => block_numbers.push(block_number),
+                Ok(_) => {}
+                Err(err) => errors.push(err),
+            }
+        }
+        block_numbers.sort_unstable_by(|a, b| b.cmp(a));
+        let Some(&block_number) = block_numbers.get(self.quorum.saturating_sub(1)) else {
+            warn!(
+                quorum = self.quorum,
+                responses = block_numbers.len(),
+                errors = errors.len(),
+                "Too few providers reported a block number"
+            );
+            return Err(CrossCheckingError::NoQuorum {
+                quorum: self.quorum,
+                errors,
+            });
+        };
+        Ok(block_number)
+    }
+
+    /// Send a request to all providers and return the response that at least
+    /// `quorum` of the providers that aren't demoted agreed on.
+    async fn cross_checked_request(
+        &self,
+        method: &str,
+        params: &Value,
+    ) -> Result<Value, CrossCheckingError> {
+        let responses = join_all(
+            self.inner
+                .providers
+                .iter()
+                .map(|provider| Self::request_value(provider, method, params)),
+        )
+        .await;
+        let demoted = self.demoted_providers();
+
+        let mut values = Vec::with_capacity(responses.len());
+        let mut errors = vec![];
+        for response in responses {
+            match response {
+                Ok(value) => values.push(Some(value)),
+                Err(err) => {
+                    values.push(None);
+                    errors.push(err);
+                }
+            }
+        }
+
+        let votes = values
+            .iter()
+            .zip(&demoted)
+            .filter_map(|(value, demoted)| value.as_ref().filter(|_| !demoted))
+            .collect::<Vec<_>>();
+        let agreed = votes
+            .iter()
+            .find(|&&candidate| votes.iter().filter(|&&v| v == candidate).count() >= self.quorum)
+            .map(|&value| value.clone());
+        let Some(agreed) = agreed else {
+            warn!(
+                method,
+                quorum = self.quorum,
+                responses = values.iter().flatten().count(),
+                errors = errors.len(),
+                "Providers did not agree on a response"
+            );
+            return Err(CrossCheckingError::NoQuorum {
+                quorum: self.quorum,
+                errors,
+            });
+        };
+
+        for (index, value) in values.iter().enumerate() {
+            if let Some(value) = value {
+                self.record_agreement(index, method, *value == agreed);
+            }
+        }
+        Ok(agreed)
+    }
+
+    /// Send a request to one provider at a time, demoted providers last, and
+    /// return the first successful response.
+    async fn ordered_request(
+        &self,
+        method: &str,
+        params: &Value,
+    ) -> Result<Value, CrossCheckingError> {
+        let demoted = self.demoted_providers();
+        let order = (0..demoted.len())
+            .filter(|&index| !demoted[index])
+            .chain((0..demoted.len()).filter(|&index| demoted[index]));
+
+        let mut errors = vec![];
+        for index in order {
+            let provider = &self.inner.providers[index];
+            match Self::request_value(provider, method, params).await {
+                Ok(value) => return Ok(value),
+                Err(err) => {
+                    warn!(
+                        provider_index = index,
+                        ?provider,
+                        error = %err,
+                        "Provider request failed"
+                    );
+                    errors.push(err);
+                }
+            }
+        }
+        Err(CrossCheckingError::AllProvidersFailed(errors))
+    }
+
+    /// Record whether a provider's response agreed with the quorum, demoting
+    /// it once it disagreed too many times in a row and restoring it once it
+    /// agrees again.
+    fn record_agreement(&self, index: usize, method: &str, agreed: bool) {
+        let provider = &self.inner.providers[index];
+        let mut disagreements = self.inner.disagreements.lock().unwrap();
+        let was_demoted = disagreements[index] >= MAX_CONSECUTIVE_DISAGREEMENTS;
+        if agreed {
+            disagreements[index] = 0;
+        } else {
+            disagreements[index] = disagreements[index].saturating_add(1);
+            self.metrics.increment_disagreements(
+                provider.node_host(),
+                provider.chain_name(),
+                method,
+            );
+        }
+
+        let demoted = disagreements[index] >= MAX_CONSECUTIVE_DISAGREEMENTS;
+        if demoted != was_demoted {
+            if demoted {
+                warn!(
+                    provider_index = index,
+                    ?provider,
+                    "Demoting provider that keeps disagreeing with the quorum"
+                );
+            } else {
+                info!(
+                    provider_index = index,
+                    ?provider,
+                    "Restoring provider that agrees with the quorum again"
+                );
+            }
+            self.metrics
+                .set_demoted(provider.node_host(), provider.chain_name(), demoted);
+        }
+    }
+}
+
+/// Errors specific to the cross checking provider.
+#[derive(Error, Debug)]
+pub enum CrossCheckingError {
+    /// Not enough providers agreed on a response
+    #[error("Fewer than {quorum} providers agreed on a response. (Errors: {errors:?})")]
+    NoQuorum {
+        /// The number of providers that have to agree
+        quorum: usize,
+        /// The errors of the providers that failed
+        errors: Vec<ProviderError>,
+    },
+    /// All providers failed
+    #[error("All providers failed. (Errors: {0:?})")]
+    AllProvidersFailed(Vec<ProviderError>),
+}
+
+impl From<CrossCheckingError> for ProviderError {
+    fn from(src: CrossCheckingError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(src))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<C> JsonRpcClient for CrossCheckingProvider<C>
+where
+    C: JsonRpcClient + PrometheusJsonRpcClientConfigExt,
+{
+    type Error = ProviderError;
+
+    #[instrument]
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let mut params = serde_json::to_value(params).expect("valid");
+        let value = if method == "eth_blockNumber" {
+            serde_json::to_value(self.quorum_block_number().await?)?
+        } else if CROSS_CHECKED_METHODS.contains(&method) {
+            if reads_latest_block(method, &params) {
+                let block_number = self.quorum_block_number().await?;
+                pin_latest_block(method, &mut params, block_number);
+            }
+            self.cross_checked_request(method, &params).await?
+        } else {
+            self.ordered_request(method, &params).await?
+        };
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Whether a block parameter, which defaults to `latest`, refers to the latest
+/// block.
+fn is_latest(block: Option<&Value>) -> bool {
+    match block {
+        None | Some(Value::Null) => true,
+        Some(block) => block == "latest",
+    }
+}
+
+/// Whether a request reads the latest block, which providers at different
+/// heights answer for different blocks.
+fn reads_latest_block(method: &str, params: &Value) -> bool {
+    match method {
+        "eth_call" => is_latest(params.get(1)),
+        "eth_getLogs" => params.get(0).map_or(false, |filter| {
+            filter.get("blockHash").is_none()
+                && (is_latest(filter.get("fromBlock")) || is_latest(filter.get("toBlock")))
+        }),
+        _ => false,
+    }
+}
+
+/// Replace the references to the latest block in the params of a request
+/// with `block_number`.
+fn pin_latest_block(method: &str, params: &mut Value, block_number: U64) {
+    let block = serde_json::to_value(block_number).expect("valid");
+    match method {
+        "eth_call" => {
+            if let Some(params) = params.as_array_mut().filter(|params| !params.is_empty()) {
+                if params.len() > 1 {
+                    params[1] = block;
+                } else {
+                    params.push(block);
+                }
+            }
+        }
+        "eth_getLogs" => {
+            if let Some(filter) = params.get_mut(0).and_then(Value::as_object_mut) {
+                for key in ["fromBlock", "toBlock"] {
+                    if is_latest(filter.get(key)) {
+                        filter.insert(key.to_owned(), block.clone());
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::providers::HttpClientError;
+    use ethers_prometheus::json_rpc_client::JsonRpcClientMetricsBuilder;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct ProviderMock {
+        response: Mutex<u64>,
+        block_number: Mutex<u64>,
+        request_count: Mutex<usize>,
+        /// The params of the last request that wasn't for the block number
+        last_params: Mutex<Value>,
+    }
+
+    impl ProviderMock {
+        fn new(response: u64) -> Self {
+            Self {
+                response: Mutex::new(response),
+                block_number: Mutex::new(0),
+                request_count: Mutex::new(0),
+                last_params: Mutex::new(Value::Null),
+            }
+        }
+
+        fn set_block_number(&self, block_number: u64) {
+            *self.block_number.lock().unwrap() = block_number;
+        }
+
+        fn last_params(&self) -> Value {
+            self.last_params.lock().unwrap().clone()
+        }
+
+        fn set_response(&self, response: u64) {
+            *self.response.lock().unwrap() = response;
+        }
+
+        fn request_count(&self) -> usize {
+            *self.request_count.lock().unwrap()
+        }
+    }
+
+    #[async_trait]
+    impl JsonRpcClient for ProviderMock {
+        type Error = HttpClientError;
+
+        async fn request<T: Debug + Serialize + Send + Sync, R: DeserializeOwned>(
+            &self,
+            method: &str,
+            params: T,
+        ) -> Result<R, Self::Error> {
+            *self.request_count.lock().unwrap() += 1;
+            let response = if method == "eth_blockNumber" {
+                serde_json::to_value(U64::from(*self.block_number.lock().unwrap())).unwrap()
+            } else {
+                *self.last_params.lock().unwrap() = serde_json::to_value(params).unwrap();
+                Value::from(*self.response.lock().unwrap())
+            };
+            serde_json::from_value(response.clone()).map_err(|err| HttpClientError::SerdeJson {
+                err,
+                text: response.to_string(),
+            })
+        }
+    }
+
+    impl PrometheusJsonRpcClientConfigExt for ProviderMock {
+        fn node_host(&self) -> &str {
+            "mock"
+        }
+
+        fn chain_name(&self) -> &str {
+            "test"
+        }
+    }
+
+    fn cross_checking_provider(
+        responses: &[u64],
+        quorum: usize,
+    ) -> CrossCheckingProvider<ProviderMock> {
+        CrossCheckingProvider::new(
+            responses
+                .iter()
+                .map(|&response| ProviderMock::new(response)),
+            quorum,
+            JsonRpcClientMetricsBuilder::default().build().unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_returns_response_a_quorum_agreed_on() {
+        let provider = cross_checking_provider(&[1, 2, 1], 2);
+        let response: u64 = provider.request("eth_call", ()).await.unwrap();
+        assert_eq!(response, 1);
+
+        let provider = cross_checking_provider(&[1, 2, 3], 2);
+        assert!(provider.request::<_, u64>("eth_call", ()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_demotes_provider_that_keeps_disagreeing() {
+        let provider = cross_checking_provider(&[1, 1, 2], 2);
+        for _ in 0..MAX_CONSECUTIVE_DISAGREEMENTS {
+            provider.request::<_, u64>("eth_getLogs", ()).await.unwrap();
+        }
+        assert_eq!(provider.demoted_providers(), vec![false, false, true]);
+
+        // The demoted provider's response doesn't count towards the quorum
+        provider.inner.providers[1].set_response(2);
+        assert!(provider.request::<_, u64>("eth_getLogs", ()).await.is_err());
+
+        // Until it agrees with the quorum again
+        provider.inner.providers[1].set_response(1);
+        provider.inner.providers[2].set_response(1);
+        provider.request::<_, u64>("eth_getLogs", ()).await.unwrap();
+        assert_eq!(provider.demoted_providers(), vec![false, false, false]);
+    }
+
+    #[tokio::test]
+    async fn test_block_number_is_the_highest_a_quorum_reached() {
+        let provider = cross_checking_provider(&[0, 0, 0], 2);
+        for (mock, block_number) in provider.inner.providers.iter().zip([10, 12, 11]) {
+            mock.set_block_number(block_number);
+        }
+        let block_number: U64 = provider.request("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(block_number, U64::from(11));
+
+        let provider = cross_checking_provider(&[0], 2);
+        assert!(provider
+            .request::<_, U64>("eth_blockNumber", ())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reads_of_the_latest_block_are_pinned_to_a_quorum_block() {
+        let provider = cross_checking_provider(&[1, 1, 1], 2);
+        for (mock, block_number) in provider.inner.providers.iter().zip([10, 12, 11]) {
+            mock.set_block_number(block_number);
+        }
+        let call = serde_json::json!({ "to": "0x0000000000000000000000000000000000000001" });
+
+        provider
+            .request::<_, u64>("eth_call", (&call, "latest"))
+            .await
+            .unwrap();
+        for mock in &provider.inner.providers {
+            assert_eq!(mock.last_params(), serde_json::json!([call, "0xb"]));
+        }
+        provider
+            .request::<_, u64>("eth_getLogs", [serde_json::json!({ "fromBlock": "0x1" })])
+            .await
+            .unwrap();
+        for mock in &provider.inner.providers {
+            assert_eq!(
+                mock.last_params(),
+                serde_json::json!([{ "fromBlock": "0x1", "toBlock": "0xb" }])
+            );
+        }
+
+        // Reads of a specific block are sent as they are
+        provider
+            .request::<_, u64>("eth_call", (&call, "0x5"))
+            .await
+            .unwrap();
+        for mock in &provider.inner.providers {
+            assert_eq!(mock.last_params(), serde_json::json!([call, "0x5"]));
+            // Two block number requests for the two reads of the latest block
+            assert_eq!(mock.request_count(), 5);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_other_requests_are_not_cross_checked() {
+        let provider = cross_checking_provider(&[1, 2, 3], 2);
+        let response: u64 = provider.request("eth_gasPrice", ()).await.unwrap();
+        assert_eq!(response, 1);
+        let request_counts: Vec<_> = provider
+            .inner
+            .providers
+            .iter()
+            .map(|p| p.request_count())
+            .collect();
+        assert_eq!(request_counts, vec![1, 0, 0]);
+    }
+}
@@ -1,24 +1,40 @@
 use derive_new::new;
 use std::fmt::{Debug, Formatter};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 
 use async_trait::async_trait;
 use ethers::providers::{HttpClientError, JsonRpcClient, ProviderError};
 use ethers_core::types::U64;
+use futures_util::future::join_all;
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 use thiserror::Error;
-use tokio::time::sleep;
+use tokio::time::{sleep, MissedTickBehavior};
 use tracing::{info, instrument, warn_span};
 
-use ethers_prometheus::json_rpc_client::PrometheusJsonRpcClientConfigExt;
+use ethers_prometheus::json_rpc_client::{
+    JsonRpcClientMetrics, JsonRpcClientMetricsBuilder, PrometheusJsonRpcClientConfigExt,
+};
 
 use crate::rpc_clients::{categorize_client_response, CategorizedResponse};
 
 const MAX_BLOCK_TIME: Duration = Duration::from_secs(2 * 60);
 const BLOCK_NUMBER_RPC: &str = "eth_blockNumber";
+/// Weight of the newest measurement in the rolling averages of an inner
+/// provider's latency and error rate.
+const HEALTH_SMOOTHING: f64 = 0.2;
+/// Latency in seconds that halves the health of an inner provider.
+const LATENCY_SCALE_SECS: f64 = 1.0;
+/// Number of blocks behind the highest block seen that halves the health of
+/// an inner provider.
+const BLOCK_LAG_SCALE: f64 = 5.0;
+/// How much less healthy than the healthiest inner provider the one in use
+/// has to be for requests to switch over, so that providers of similar health
+/// don't take turns.
+const SWITCH_MARGIN: f64 = 0.2;
 
 #[derive(Clone, Copy, new)]
 struct PrioritizedProviderInner {
@@ -27,15 +43,46 @@ struct PrioritizedProviderInner {
     // Tuple of the block number and the time when it was queried
     #[new(value = "(0, Instant::now())")]
     last_block_height: (u64, Instant),
+    // Whether the block number stopped increasing
+    #[new(default)]
+    stalled: bool,
+    // Blocks behind the highest block number seen when last probed
+    #[new(default)]
+    block_lag: u64,
+    // Rolling average of the request latency in seconds
+    #[new(default)]
+    latency: f64,
+    // Rolling average of the share of requests that failed
+    #[new(default)]
+    error_rate: f64,
 }
 
 impl PrioritizedProviderInner {
-    fn from_block_height(index: usize, block_height: u64) -> Self {
-        Self {
-            index,
-            last_block_height: (block_height, Instant::now()),
+    fn record_response(&mut self, latency: Duration, success: bool) {
+        let error = if success { 0.0 } else { 1.0 };
+        self.latency += HEALTH_SMOOTHING * (latency.as_secs_f64() - self.latency);
+        self.error_rate += HEALTH_SMOOTHING * (error - self.error_rate);
+    }
+
+    fn record_block_height(&mut self, block_height: u64, max_block_time: Duration) {
+        if block_height > self.last_block_height.0 {
+            self.last_block_height = (block_height, Instant::now());
+            self.stalled = false;
+        } else if self.last_block_height.1.elapsed() > max_block_time {
+            self.stalled = true;
         }
     }
+
+    /// Health between 0 and 1 from the latency, error rate and block lag. A
+    /// stalled provider has no health at all.
+    fn health(&self) -> f64 {
+        if self.stalled {
+            return 0.0;
+        }
+        (1.0 - self.error_rate)
+            / (1.0 + self.latency / LATENCY_SCALE_SECS)
+            / (1.0 + self.block_lag as f64 / BLOCK_LAG_SCALE)
+    }
 }
 
 struct PrioritizedProviders<T> {
@@ -43,13 +90,31 @@ struct PrioritizedProviders<T> {
     /// most fallback.
     providers: Vec<T>,
     priorities: RwLock<Vec<PrioritizedProviderInner>>,
+    /// The task probing the providers, if one was spawned. It's aborted once
+    /// the providers are dropped.
+    prober: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl<T> Drop for PrioritizedProviders<T> {
+    fn drop(&mut self) {
+        if let Some(prober) = self.prober.get_mut().unwrap().take() {
+            prober.abort();
+        }
+    }
 }
 
-/// A provider that bundles multiple providers and attempts to call the first,
-/// then the second, and so on until a response is received.
+/// A provider that bundles multiple providers and attempts to call the
+/// healthiest first, then the next healthiest, and so on until a response is
+/// received.
+///
+/// The health of each inner provider is a rolling score of its latency, error
+/// rate and how far its block number lags behind the others'. Inner providers
+/// that requests aren't sent to are kept up to date by probing them with
+/// `spawn_probing_on_interval`, which is also how demoted providers recover.
 pub struct FallbackProvider<T> {
     inner: Arc<PrioritizedProviders<T>>,
     max_block_time: Duration,
+    metrics: JsonRpcClientMetrics,
 }
 
 impl<T> Clone for FallbackProvider<T> {
@@ -57,6 +122,7 @@ impl<T> Clone for FallbackProvider<T> {
         Self {
             inner: self.inner.clone(),
             max_block_time: self.max_block_time,
+            metrics: self.metrics.clone(),
         }
     }
 }
@@ -105,8 +171,73 @@ impl<T> FallbackProvider<T> {
 
 impl<C> FallbackProvider<C>
 where
-    C: JsonRpcClient,
+    C: JsonRpcClient + PrometheusJsonRpcClientConfigExt + 'static,
+{
+    /// Spawn a task that probes the block number and latency of every inner
+    /// provider on an interval, so that the health of providers requests
+    /// aren't sent to stays up to date. There is only ever one such task per
+    /// provider and its clones: spawning another aborts the previous one, and
+    /// it's aborted once the provider and all its clones are dropped.
+    pub fn spawn_probing_on_interval(&self, period: Duration) {
+        let inner = Arc::downgrade(&self.inner);
+        let max_block_time = self.max_block_time;
+        let metrics = self.metrics.clone();
+
+        let prober = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let Some(inner) = inner.upgrade() else {
+                    return;
+                };
+                let zelf = Self {
+                    inner,
+                    max_block_time,
+                    metrics: metrics.clone(),
+                };
+                zelf.probe().await;
+            }
+        });
+        if let Some(previous) = self.inner.prober.lock().unwrap().replace(prober) {
+            previous.abort();
+        }
+    }
+}
+
+impl<C> FallbackProvider<C>
+where
+    C: JsonRpcClient + PrometheusJsonRpcClientConfigExt,
 {
+    async fn probe(&self) {
+        let responses = join_all(self.inner.providers.iter().map(|provider| async move {
+            let start = Instant::now();
+            let block_height = provider
+                .request(BLOCK_NUMBER_RPC, ())
+                .await
+                .map(|r: U64| r.as_u64())
+                .ok();
+            (block_height, start.elapsed())
+        }))
+        .await;
+        let highest_block_height = responses
+            .iter()
+            .filter_map(|(block_height, _)| *block_height)
+            .max()
+            .unwrap_or_default();
+
+        let mut priorities = self.inner.priorities.write().await;
+        for priority in priorities.iter_mut() {
+            let (block_height, latency) = responses[priority.index];
+            priority.record_response(latency, block_height.is_some());
+            if let Some(block_height) = block_height {
+                priority.block_lag = highest_block_height - block_height;
+                priority.record_block_height(block_height, self.max_block_time);
+            }
+        }
+        self.rank_by_health(&mut priorities);
+    }
+
     async fn handle_stalled_provider(
         &self,
         priority: &PrioritizedProviderInner,
@@ -128,32 +259,55 @@ where
             .unwrap_or(priority.last_block_height.0);
         if current_block_height <= priority.last_block_height.0 {
             // The `max_block_time` elapsed but the block number returned by the provider has not increased
-            self.deprioritize_provider(*priority).await;
             info!(
                 provider_index=%priority.index,
                 ?provider,
-                "Deprioritizing an inner provider in FallbackProvider",
+                "Deprioritizing a stalled inner provider in FallbackProvider",
             );
-        } else {
-            self.update_last_seen_block(priority.index, current_block_height)
-                .await;
         }
+        self.update_health(priority.index, |p| {
+            p.record_block_height(current_block_height, self.max_block_time)
+        })
+        .await;
         Ok(())
     }
 
-    async fn deprioritize_provider(&self, priority: PrioritizedProviderInner) {
-        // De-prioritize the current provider by moving it to the end of the queue
+    async fn update_health(
+        &self,
+        provider_index: usize,
+        update: impl FnOnce(&mut PrioritizedProviderInner),
+    ) {
         let mut priorities = self.inner.priorities.write().await;
-        priorities.retain(|&p| p.index != priority.index);
-        priorities.push(priority);
+        // Get provider position in the up-to-date priorities vec
+        if let Some(priority) = priorities.iter_mut().find(|p| p.index == provider_index) {
+            update(priority);
+        }
+        self.rank_by_health(&mut priorities);
     }
 
-    async fn update_last_seen_block(&self, provider_index: usize, current_block_height: u64) {
-        let mut priorities = self.inner.priorities.write().await;
-        // Get provider position in the up-to-date priorities vec
-        if let Some(position) = priorities.iter().position(|p| p.index == provider_index) {
-            priorities[position] =
-                PrioritizedProviderInner::from_block_height(provider_index, current_block_height);
+    /// Move the healthiest providers to the front once the one in use is
+    /// clearly less healthy than the healthiest, and export the health scores.
+    fn rank_by_health(&self, priorities: &mut [PrioritizedProviderInner]) {
+        let healthiest = priorities.iter().map(|p| p.health()).fold(0.0, f64::max);
+        if let Some(primary) = priorities.first().copied() {
+            if primary.health() < healthiest * (1.0 - SWITCH_MARGIN) {
+                // A stable sort keeps the current order of equally healthy providers
+                priorities.sort_by(|a, b| b.health().total_cmp(&a.health()));
+                info!(
+                    previous_provider_index=%primary.index,
+                    provider_index=%priorities[0].index,
+                    provider=?self.inner.providers[priorities[0].index],
+                    "Switching to a healthier inner provider in FallbackProvider",
+                );
+            }
+        }
+        for priority in priorities.iter() {
+            let provider = &self.inner.providers[priority.index];
+            self.metrics.set_health_score(
+                provider.node_host(),
+                provider.chain_name(),
+                priority.health(),
+            );
         }
     }
 
@@ -168,6 +322,7 @@ where
 pub struct FallbackProviderBuilder<T> {
     providers: Vec<T>,
     max_block_time: Duration,
+    metrics: Option<JsonRpcClientMetrics>,
 }
 
 impl<T> Default for FallbackProviderBuilder<T> {
@@ -175,6 +330,7 @@ impl<T> Default for FallbackProviderBuilder<T> {
         Self {
             providers: Vec::new(),
             max_block_time: MAX_BLOCK_TIME,
+            metrics: None,
         }
     }
 }
@@ -193,6 +349,12 @@ impl<T> FallbackProviderBuilder<T> {
         self
     }
 
+    /// Export the health scores of the providers with these metrics.
+    pub fn metrics(mut self, metrics: JsonRpcClientMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     #[cfg(test)]
     pub fn with_max_block_time(mut self, max_block_time: Duration) -> Self {
         self.max_block_time = max_block_time;
@@ -210,10 +372,14 @@ impl<T> FallbackProviderBuilder<T> {
                     .map(PrioritizedProviderInner::new)
                     .collect(),
             ),
+            prober: Mutex::default(),
         };
         FallbackProvider {
             inner: Arc::new(prioritized_providers),
             max_block_time: self.max_block_time,
+            metrics: self
+                .metrics
+                .unwrap_or_else(|| JsonRpcClientMetricsBuilder::default().build().unwrap()),
         }
     }
 }
@@ -258,19 +424,30 @@ where
             let priorities_snapshot = self.take_priorities_snapshot().await;
             for (idx, priority) in priorities_snapshot.iter().enumerate() {
                 let provider = &self.inner.providers[priority.index];
+                let start = Instant::now();
                 let fut = match params {
                     Value::Null => provider.request(method, ()),
                     _ => provider.request(method, &params),
                 };
                 let resp = fut.await;
+                let latency = start.elapsed();
                 self.handle_stalled_provider(priority, provider).await?;
-                let _span =
-                    warn_span!("request_with_fallback", fallback_count=%idx, provider_index=%priority.index, ?provider).entered();
-
-                match categorize_client_response(method, resp) {
-                    IsOk(v) => return Ok(serde_json::from_value(v)?),
-                    RetryableErr(e) | RateLimitErr(e) => errors.push(e.into()),
-                    NonRetryableErr(e) => return Err(e.into()),
+                // Errors that aren't the provider's fault still count as responses
+                let (result, success) = {
+                    let _span =
+                        warn_span!("request_with_fallback", fallback_count=%idx, provider_index=%priority.index, ?provider).entered();
+                    match categorize_client_response(method, resp) {
+                        IsOk(v) => (Ok(v), true),
+                        RetryableErr(e) | RateLimitErr(e) => (Err(e), false),
+                        NonRetryableErr(e) => (Err(e), true),
+                    }
+                };
+                self.update_health(priority.index, |p| p.record_response(latency, success))
+                    .await;
+                match result {
+                    Ok(v) => return Ok(serde_json::from_value(v)?),
+                    Err(e) if success => return Err(e.into()),
+                    Err(e) => errors.push(e.into()),
                 }
             }
         }
@@ -282,7 +459,6 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Mutex;
 
     #[derive(Debug)]
     struct ProviderMock {
@@ -337,11 +513,11 @@ mod tests {
 
     impl PrometheusJsonRpcClientConfigExt for ProviderMock {
         fn node_host(&self) -> &str {
-            todo!()
+            "mock"
         }
 
         fn chain_name(&self) -> &str {
-            todo!()
+            "test"
         }
     }
 
@@ -397,6 +573,63 @@ mod tests {
         assert_eq!(provider_call_count, vec![0, 0, 2]);
     }
 
+    #[test]
+    fn test_health_accounts_for_latency_errors_and_lag() {
+        let mut priority = PrioritizedProviderInner::new(0);
+        assert_eq!(priority.health(), 1.0);
+        priority.record_response(Duration::from_secs(5), true);
+        assert!((priority.health() - 0.5).abs() < 1e-9);
+        priority.record_response(Duration::from_secs(1), false);
+        assert!((priority.health() - 0.4).abs() < 1e-9);
+        priority.block_lag = BLOCK_LAG_SCALE as u64;
+        assert!((priority.health() - 0.2).abs() < 1e-9);
+        priority.stalled = true;
+        assert_eq!(priority.health(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_switches_to_healthier_provider() {
+        let fallback_provider = FallbackProviderBuilder::default()
+            .add_providers(vec![ProviderMock::new(), ProviderMock::new()])
+            .build();
+        let provider_order = || async {
+            fallback_provider
+                .take_priorities_snapshot()
+                .await
+                .iter()
+                .map(|p| p.index)
+                .collect::<Vec<_>>()
+        };
+
+        // A slightly less healthy provider keeps being used
+        fallback_provider
+            .update_health(0, |p| p.record_response(Duration::from_millis(500), true))
+            .await;
+        assert_eq!(provider_order().await, vec![0, 1]);
+
+        fallback_provider
+            .update_health(0, |p| p.record_response(Duration::from_millis(500), false))
+            .await;
+        assert_eq!(provider_order().await, vec![1, 0]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_only_the_latest_prober_runs_until_dropped() {
+        let provider = ProviderMock::new();
+        let requests = provider.requests.clone();
+        let fallback_provider = FallbackProvider::new(vec![provider]);
+        fallback_provider.spawn_probing_on_interval(Duration::from_secs(1));
+        fallback_provider.spawn_probing_on_interval(Duration::from_secs(1));
+
+        // Probed right away and once more after a period, by one prober only
+        sleep(Duration::from_millis(1500)).await;
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        drop(fallback_provider);
+        sleep(Duration::from_secs(5)).await;
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
     // TODO: make `categorize_client_response` generic over `ProviderError` to allow testing
     // two stalled providers (so that the for loop in `request` doesn't stop after the first provider)
 }
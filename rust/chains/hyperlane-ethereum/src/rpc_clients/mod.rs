@@ -1,8 +1,9 @@
 use ethers::providers::HttpClientError;
 use tracing::{info, trace, warn};
 
-pub use self::{fallback::*, retrying::*};
+pub use self::{cross_checking::*, fallback::*, retrying::*};
 
+mod cross_checking;
 mod fallback;
 mod retrying;
 
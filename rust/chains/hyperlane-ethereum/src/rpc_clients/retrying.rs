@@ -196,6 +196,19 @@ impl JsonRpcClient for RetryingProvider<PrometheusJsonRpcClient<Http>> {
     }
 }
 
+impl<P> PrometheusJsonRpcClientConfigExt for RetryingProvider<P>
+where
+    P: PrometheusJsonRpcClientConfigExt,
+{
+    fn node_host(&self) -> &str {
+        self.inner.node_host()
+    }
+
+    fn chain_name(&self) -> &str {
+        self.inner.chain_name()
+    }
+}
+
 impl<P> FromStr for RetryingProvider<P>
 where
     P: JsonRpcClient + FromStr,
@@ -8,6 +8,8 @@ use hyperlane_core::{
     HyperlaneSigner, HyperlaneSignerError, Signature as HyperlaneSignature, H160, H256,
 };
 
+use crate::{RemoteSigner, RemoteSignerError};
+
 /// Ethereum-supported signer types
 #[derive(Debug, Clone)]
 pub enum Signers {
@@ -15,6 +17,8 @@ pub enum Signers {
     Local(LocalWallet),
     /// A signer using a key stored in aws kms
     Aws(AwsSigner),
+    /// A signer using a key held by a remote signer
+    Remote(RemoteSigner),
 }
 
 impl From<LocalWallet> for Signers {
@@ -29,6 +33,12 @@ impl From<AwsSigner> for Signers {
     }
 }
 
+impl From<RemoteSigner> for Signers {
+    fn from(s: RemoteSigner) -> Self {
+        Signers::Remote(s)
+    }
+}
+
 #[async_trait]
 impl Signer for Signers {
     type Error = SignersError;
@@ -40,6 +50,7 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => Ok(signer.sign_message(message).await?),
             Signers::Aws(signer) => Ok(signer.sign_message(message).await?),
+            Signers::Remote(signer) => Ok(signer.sign_message(message).await?),
         }
     }
 
@@ -47,6 +58,7 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => Ok(signer.sign_transaction(message).await?),
             Signers::Aws(signer) => Ok(signer.sign_transaction(message).await?),
+            Signers::Remote(signer) => Ok(signer.sign_transaction(message).await?),
         }
     }
 
@@ -57,6 +69,7 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => Ok(signer.sign_typed_data(payload).await?),
             Signers::Aws(signer) => Ok(signer.sign_typed_data(payload).await?),
+            Signers::Remote(signer) => Ok(signer.sign_typed_data(payload).await?),
         }
     }
 
@@ -64,6 +77,7 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => signer.address(),
             Signers::Aws(signer) => signer.address(),
+            Signers::Remote(signer) => signer.address(),
         }
     }
 
@@ -71,6 +85,7 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => signer.chain_id(),
             Signers::Aws(signer) => signer.chain_id(),
+            Signers::Remote(signer) => signer.chain_id(),
         }
     }
 
@@ -78,6 +93,7 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => signer.with_chain_id(chain_id).into(),
             Signers::Aws(signer) => signer.with_chain_id(chain_id).into(),
+            Signers::Remote(signer) => signer.with_chain_id(chain_id).into(),
         }
     }
 }
@@ -106,6 +122,9 @@ pub enum SignersError {
     /// Wallet Signer Error
     #[error("{0}")]
     WalletError(#[from] WalletError),
+    /// Remote Signer Error
+    #[error("{0}")]
+    RemoteSignerError(#[from] RemoteSignerError),
 }
 
 impl From<std::convert::Infallible> for SignersError {
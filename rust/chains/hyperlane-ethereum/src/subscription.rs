@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use ethers::prelude::{Address, Filter, Middleware, Provider, Ws};
+use ethers_contract::{parse_log, EthEvent, LogMeta as EthersLogMeta};
+use futures_util::{stream, StreamExt};
+use tracing::{debug, instrument, warn};
+use url::Url;
+
+use hyperlane_core::{
+    ContractLocator, HyperlaneLogStore, HyperlaneMessage, InterchainGasPayment, LogMeta,
+    LogSubscriber, H256,
+};
+
+use crate::contracts::i_interchain_gas_paymaster::GasPaymentFilter;
+use crate::contracts::i_mailbox::{DispatchFilter, ProcessIdFilter};
+
+/// Subscribes to the logs of a contract over a websocket, connecting anew for
+/// each subscription so that a dropped connection only ends the current one.
+#[derive(Debug, Clone)]
+pub struct EthereumLogSubscriber {
+    url: Url,
+    address: Address,
+    finality_blocks: u32,
+}
+
+/// What a subscription receives
+enum SubscriptionEvent {
+    Log(ethers::types::Log),
+    Block(Option<u64>),
+}
+
+impl EthereumLogSubscriber {
+    /// Create a subscriber to the logs of the contract at `locator`, which
+    /// are stored once they are `finality_blocks` deep.
+    pub fn new(url: Url, locator: &ContractLocator, finality_blocks: u32) -> Self {
+        Self {
+            url,
+            address: locator.address.into(),
+            finality_blocks,
+        }
+    }
+
+    /// Subscribe to the logs of an event and to new blocks, storing the logs
+    /// once their blocks are final. Logs a reorg removes before then are
+    /// dropped, and those it removes later are removed from the store again.
+    async fn store_subscribed_logs<E, T>(
+        &self,
+        store: &dyn HyperlaneLogStore<T>,
+        to_log: impl Fn(E) -> T + Send + Sync,
+    ) -> eyre::Result<()>
+    where
+        E: EthEvent,
+        T: Send + Sync,
+    {
+        let provider = Provider::new(Ws::connect(&self.url).await?);
+        let filter = Filter::new().address(self.address).topic0(E::signature());
+        let logs = provider.subscribe_logs(&filter).await?;
+        let blocks = provider.subscribe_blocks().await?;
+        debug!(address = ?self.address, event = %E::name(), "Subscribed to logs");
+
+        let mut events = stream::select(
+            logs.map(SubscriptionEvent::Log),
+            blocks.map(|block| SubscriptionEvent::Block(block.number.map(|n| n.as_u64()))),
+        );
+        let mut pending = PendingLogs::new(self.finality_blocks);
+        while let Some(event) = events.next().await {
+            match event {
+                SubscriptionEvent::Log(log) => {
+                    let removed = log.removed.unwrap_or(false);
+                    let meta = LogMeta::from(EthersLogMeta::from(&log));
+                    let log = (to_log(parse_log::<E>(log)?), meta);
+                    if !removed {
+                        pending.insert(log);
+                    } else if !pending.remove(&log.1) {
+                        warn!(log_meta = ?log.1, "Final log was removed from the chain by a reorg");
+                        store.remove_logs(&[log]).await?;
+                    }
+                }
+                SubscriptionEvent::Block(Some(tip)) => pending.set_tip(tip),
+                SubscriptionEvent::Block(None) => {}
+            }
+            let logs = pending.take_final();
+            if !logs.is_empty() {
+                store.store_logs(&logs).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Subscribed logs whose blocks aren't final yet, by block number.
+#[derive(Debug)]
+struct PendingLogs<T> {
+    finality_blocks: u32,
+    /// The latest block, once one was received
+    tip: Option<u64>,
+    logs: BTreeMap<u64, Vec<(T, LogMeta)>>,
+}
+
+impl<T> PendingLogs<T> {
+    fn new(finality_blocks: u32) -> Self {
+        Self {
+            finality_blocks,
+            tip: None,
+            logs: BTreeMap::new(),
+        }
+    }
+
+    fn insert(&mut self, log: (T, LogMeta)) {
+        self.logs.entry(log.1.block_number).or_default().push(log);
+    }
+
+    /// Drop a log that a reorg removed. Returns whether it was pending.
+    fn remove(&mut self, meta: &LogMeta) -> bool {
+        let Some(logs) = self.logs.get_mut(&meta.block_number) else {
+            return false;
+        };
+        let len = logs.len();
+        logs.retain(|(_, pending)| pending != meta);
+        let removed = logs.len() < len;
+        if logs.is_empty() {
+            self.logs.remove(&meta.block_number);
+        }
+        removed
+    }
+
+    fn set_tip(&mut self, tip: u64) {
+        self.tip = self.tip.max(Some(tip));
+    }
+
+    /// Take the logs of the blocks that are final, in block order.
+    fn take_final(&mut self) -> Vec<(T, LogMeta)> {
+        let Some(tip) = self.tip else { return vec![] };
+        let Some(last_final) = tip.checked_sub(self.finality_blocks.into()) else {
+            return vec![];
+        };
+        let not_final = self.logs.split_off(&(last_final + 1));
+        std::mem::replace(&mut self.logs, not_final)
+            .into_values()
+            .flatten()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl LogSubscriber<HyperlaneMessage> for EthereumLogSubscriber {
+    #[instrument(err, skip_all, fields(url = %self.url))]
+    async fn subscribe_logs(
+        &self,
+        store: &dyn HyperlaneLogStore<HyperlaneMessage>,
+    ) -> eyre::Result<()> {
+        self.store_subscribed_logs(store, |event: DispatchFilter| {
+            HyperlaneMessage::from(event.message.to_vec())
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl LogSubscriber<H256> for EthereumLogSubscriber {
+    #[instrument(err, skip_all, fields(url = %self.url))]
+    async fn subscribe_logs(&self, store: &dyn HyperlaneLogStore<H256>) -> eyre::Result<()> {
+        self.store_subscribed_logs(store, |event: ProcessIdFilter| H256::from(event.message_id))
+            .await
+    }
+}
+
+#[async_trait]
+impl LogSubscriber<InterchainGasPayment> for EthereumLogSubscriber {
+    #[instrument(err, skip_all, fields(url = %self.url))]
+    async fn subscribe_logs(
+        &self,
+        store: &dyn HyperlaneLogStore<InterchainGasPayment>,
+    ) -> eyre::Result<()> {
+        self.store_subscribed_logs(store, |event: GasPaymentFilter| InterchainGasPayment {
+            message_id: H256::from(event.message_id),
+            payment: event.payment.into(),
+            gas_amount: event.gas_amount.into(),
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::LogMeta;
+
+    use super::PendingLogs;
+
+    fn log_at(block_number: u64, log_index: u64) -> (u64, LogMeta) {
+        let meta = LogMeta {
+            block_number,
+            log_index: log_index.into(),
+            ..Default::default()
+        };
+        (log_index, meta)
+    }
+
+    fn indices(logs: Vec<(u64, LogMeta)>) -> Vec<u64> {
+        logs.into_iter().map(|(index, _)| index).collect()
+    }
+
+    #[test]
+    fn test_logs_are_taken_once_final() {
+        let mut pending = PendingLogs::new(2);
+        pending.insert(log_at(10, 0));
+        pending.insert(log_at(11, 1));
+        pending.insert(log_at(10, 2));
+        // Nothing is final before a block is received
+        assert!(pending.take_final().is_empty());
+
+        pending.set_tip(11);
+        assert!(pending.take_final().is_empty());
+        pending.set_tip(12);
+        assert_eq!(indices(pending.take_final()), vec![0, 2]);
+        // A block received out of order doesn't move the tip back
+        pending.set_tip(11);
+        pending.set_tip(13);
+        assert_eq!(indices(pending.take_final()), vec![1]);
+        assert!(pending.take_final().is_empty());
+
+        // Logs of blocks that are already final are taken right away
+        pending.insert(log_at(5, 3));
+        assert_eq!(indices(pending.take_final()), vec![3]);
+    }
+
+    #[test]
+    fn test_removed_logs_are_never_taken() {
+        let mut pending = PendingLogs::new(2);
+        pending.insert(log_at(10, 0));
+        pending.insert(log_at(10, 1));
+        assert!(pending.remove(&log_at(10, 0).1));
+        // Logs that aren't pending, e.g. because they are already final
+        assert!(!pending.remove(&log_at(10, 0).1));
+        assert!(!pending.remove(&log_at(9, 0).1));
+
+        pending.set_tip(12);
+        assert_eq!(indices(pending.take_final()), vec![1]);
+        assert!(!pending.remove(&log_at(10, 1).1));
+    }
+}
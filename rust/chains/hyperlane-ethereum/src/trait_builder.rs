@@ -4,8 +4,8 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use ethers::prelude::{
-    Http, JsonRpcClient, Middleware, NonceManagerMiddleware, Provider, Quorum, QuorumProvider,
-    SignerMiddleware, WeightedProvider, Ws, WsClientError,
+    Http, JsonRpcClient, Middleware, Provider, Quorum, QuorumProvider, SignerMiddleware,
+    WeightedProvider, Ws, WsClientError,
 };
 use reqwest::{Client, Url};
 use thiserror::Error;
@@ -19,11 +19,16 @@ use ethers_prometheus::middleware::{
 };
 use hyperlane_core::{ChainCommunicationError, ChainResult, ContractLocator};
 
-use crate::{signers::Signers, ConnectionConf, FallbackProvider, RetryingProvider};
+use crate::{
+    signers::Signers, ConnectionConf, CrossCheckingProvider, FallbackProvider, RetryingProvider,
+    TxNonceManager, TxNonceManagerMiddleware,
+};
 
 // This should be whatever the prometheus scrape interval is
 const METRICS_SCRAPE_INTERVAL: Duration = Duration::from_secs(60);
 const HTTP_CLIENT_TIMEOUT: Duration = Duration::from_secs(60);
+// How often the health of the inner providers of a fallback provider is probed
+const FALLBACK_PROBE_INTERVAL: Duration = Duration::from_secs(30);
 
 /// An error when connecting to an ethereum provider.
 #[derive(Error, Debug)]
@@ -92,6 +97,33 @@ pub trait BuildableWithProvider {
                 self.wrap_with_metrics(quorum_provider, locator, signer, middleware_metrics)
                     .await?
             }
+            ConnectionConf::HttpCrossChecked { urls, quorum } => {
+                let http_client = Client::builder()
+                    .timeout(HTTP_CLIENT_TIMEOUT)
+                    .build()
+                    .map_err(EthereumProviderConnectionError::from)?;
+                let mut providers = Vec::with_capacity(urls.len());
+                for url in urls {
+                    let http_provider = Http::new_with_client(url.clone(), http_client.clone());
+                    let metrics_provider = self.wrap_rpc_with_metrics(
+                        http_provider,
+                        url.clone(),
+                        &rpc_metrics,
+                        &middleware_metrics,
+                    );
+                    // Retry the inner providers for the same reason as with the QuorumProvider.
+                    providers.push(RetryingProvider::new(metrics_provider, Some(5), Some(1000)));
+                }
+                let cross_checking_provider = CrossCheckingProvider::new(
+                    providers,
+                    *quorum,
+                    rpc_metrics
+                        .clone()
+                        .unwrap_or_else(|| JsonRpcClientMetricsBuilder::default().build().unwrap()),
+                );
+                self.wrap_with_metrics(cross_checking_provider, locator, signer, middleware_metrics)
+                    .await?
+            }
             ConnectionConf::HttpFallback { urls } => {
                 let mut builder = FallbackProvider::builder();
                 let http_client = Client::builder()
@@ -108,7 +140,11 @@ pub trait BuildableWithProvider {
                     );
                     builder = builder.add_provider(metrics_provider);
                 }
+                if let Some(rpc_metrics) = &rpc_metrics {
+                    builder = builder.metrics(rpc_metrics.clone());
+                }
                 let fallback_provider = builder.build();
+                fallback_provider.spawn_probing_on_interval(FALLBACK_PROBE_INTERVAL);
                 self.wrap_with_metrics(fallback_provider, locator, signer, middleware_metrics)
                     .await?
             }
@@ -208,10 +244,10 @@ pub trait BuildableWithProvider {
         M: Middleware + 'static,
     {
         Ok(if let Some(signer) = signer {
-            let signing_provider = build_signing_provider(provider, signer)
+            let (signing_provider, nonce_manager) = build_signing_provider(provider, signer)
                 .await
                 .map_err(ChainCommunicationError::from_other)?;
-            self.build_with_provider(signing_provider, locator)
+            self.build_with_signing_provider(signing_provider, nonce_manager, locator)
         } else {
             self.build_with_provider(provider, locator)
         }
@@ -222,18 +258,42 @@ pub trait BuildableWithProvider {
     async fn build_with_provider<M>(&self, provider: M, locator: &ContractLocator) -> Self::Output
     where
         M: Middleware + 'static;
+
+    /// Construct a new instance of the associated trait using a provider that
+    /// signs transactions. `nonce_manager` hands out the signer's nonces, for
+    /// implementations that need to assign them up front.
+    async fn build_with_signing_provider<M>(
+        &self,
+        provider: M,
+        _nonce_manager: Arc<TxNonceManager>,
+        locator: &ContractLocator,
+    ) -> Self::Output
+    where
+        M: Middleware + 'static,
+    {
+        self.build_with_provider(provider, locator).await
+    }
 }
 
 async fn build_signing_provider<M: Middleware>(
     provider: M,
     signer: Signers,
-) -> Result<SignerMiddleware<NonceManagerMiddleware<M>, Signers>, M::Error> {
+) -> Result<
+    (
+        TxNonceManagerMiddleware<SignerMiddleware<M, Signers>>,
+        Arc<TxNonceManager>,
+    ),
+    M::Error,
+> {
     let provider_chain_id = provider.get_chainid().await?;
     let signer = ethers::signers::Signer::with_chain_id(signer, provider_chain_id.as_u64());
 
     let address = ethers::prelude::Signer::address(&signer);
-    let provider = NonceManagerMiddleware::new(provider, address);
-
     let signing_provider = SignerMiddleware::new(provider, signer);
-    Ok(signing_provider)
+
+    // Wraps the signer rather than the other way round so the nonce is set
+    // before the signer fills in the rest of the transaction.
+    let nonce_manager = Arc::new(TxNonceManager::new(address));
+    let signing_provider = TxNonceManagerMiddleware::new(signing_provider, nonce_manager.clone());
+    Ok((signing_provider, nonce_manager))
 }
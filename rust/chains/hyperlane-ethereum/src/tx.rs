@@ -3,18 +3,26 @@ use std::time::Duration;
 
 use ethers::abi::Detokenize;
 use ethers::prelude::{NameOrAddress, TransactionReceipt};
-use ethers::types::Eip1559TransactionRequest;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Eip1559TransactionRequest, TxHash};
 use ethers_contract::builders::ContractCall;
-use tracing::{error, info};
+use tokio::time::{sleep, Instant};
+use tracing::{error, info, warn};
 
 use hyperlane_core::utils::fmt_bytes;
 use hyperlane_core::{ChainCommunicationError, ChainResult, KnownHyperlaneDomain, H256, U256};
 
-use crate::Middleware;
+use crate::{GasEscalatorConf, Middleware};
 
 /// An amount of gas to add to the estimated gas
 const GAS_ESTIMATE_BUFFER: u32 = 50000;
 
+/// How long to wait for a transaction to be included before giving up on it
+const TX_INCLUSION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often to check whether a replaced transaction has been included
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Dispatches a transaction, logs the tx id, and returns the result
 pub(crate) async fn report_tx<M, D>(tx: ContractCall<M, D>) -> ChainResult<TransactionReceipt>
 where
@@ -42,7 +50,7 @@ where
 
     info!(?to, %data, ?tx_hash, "Dispatched tx");
 
-    match tokio::time::timeout(Duration::from_secs(300), dispatched).await {
+    match tokio::time::timeout(TX_INCLUSION_TIMEOUT, dispatched).await {
         // all good
         Ok(Ok(Some(receipt))) => {
             info!(?tx_hash, "confirmed transaction");
@@ -64,6 +72,155 @@ where
     }
 }
 
+/// Dispatches a transaction like `report_tx`, but while it isn't included it is
+/// replaced with copies that pay a higher gas price according to `escalator`.
+/// Returns the receipt of whichever of them was included.
+///
+/// Replacing a transaction requires its nonce to be set, otherwise it's sent
+/// without any replacements.
+pub(crate) async fn report_tx_with_escalator<M, D>(
+    mut tx: ContractCall<M, D>,
+    provider: &M,
+    escalator: &GasEscalatorConf,
+) -> ChainResult<TransactionReceipt>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    let Some(nonce) = tx.tx.nonce().copied().filter(|_| escalator.is_enabled()) else {
+        return report_tx(tx).await;
+    };
+    // Replacements need a gas price to raise
+    if tx.tx.gas_price().is_none() {
+        let gas_price = provider
+            .get_gas_price()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        tx.tx.set_gas_price(gas_price);
+    }
+
+    let to = tx
+        .tx
+        .to()
+        .cloned()
+        .unwrap_or_else(|| NameOrAddress::Address(Default::default()));
+    let data = tx
+        .tx
+        .data()
+        .map(|b| fmt_bytes(b))
+        .unwrap_or_else(|| "None".into());
+
+    info!(?to, %data, ?nonce, "Dispatching transaction");
+    let mut tx_hashes: Vec<TxHash> = vec![*tx.send().await?];
+    info!(?to, ?nonce, tx_hash=?tx_hashes[0], gas_price=?tx.tx.gas_price(), "Dispatched tx");
+
+    for replacement in 1..=escalator.max_bumps {
+        if let Some(receipt) =
+            wait_for_any_receipt(provider, &tx_hashes, escalator.bump_interval).await?
+        {
+            return Ok(receipt);
+        }
+        let Some(bumped) = bump_gas_price(&tx.tx, escalator) else {
+            info!(?to, ?nonce, gas_price=?tx.tx.gas_price(), "Gas price can't be raised any further, waiting for a sent transaction to be included");
+            break;
+        };
+        tx.tx = bumped;
+        match tx.send().await {
+            Ok(pending) => {
+                let tx_hash = *pending;
+                info!(?to, ?nonce, ?tx_hash, replacement, gas_price=?tx.tx.gas_price(), "Dispatched replacement tx");
+                tx_hashes.push(tx_hash);
+            }
+            // The replacement may be rejected because one of the transactions already
+            // sent was just included, so keep waiting for those.
+            Err(error) => {
+                warn!(
+                    ?to,
+                    ?nonce,
+                    replacement,
+                    ?error,
+                    "Failed to dispatch replacement tx"
+                );
+            }
+        }
+    }
+
+    match wait_for_any_receipt(provider, &tx_hashes, TX_INCLUSION_TIMEOUT).await? {
+        Some(receipt) => Ok(receipt),
+        None => {
+            error!(?nonce, ?tx_hashes, "waiting for receipt timed out");
+            Err(ChainCommunicationError::TransactionTimeout())
+        }
+    }
+}
+
+/// Polls for a receipt of any of `tx_hashes` for up to `timeout`. These are
+/// expected to share a nonce so at most one of them can be included.
+///
+/// Errors fetching receipts are assumed to be transient and are retried until
+/// the timeout, since giving up would abandon transactions that may still be
+/// included.
+async fn wait_for_any_receipt<M: Middleware>(
+    provider: &M,
+    tx_hashes: &[TxHash],
+    timeout: Duration,
+) -> ChainResult<Option<TransactionReceipt>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        for (replacement, tx_hash) in tx_hashes.iter().enumerate() {
+            let receipt = match provider.get_transaction_receipt(*tx_hash).await {
+                Ok(receipt) => receipt,
+                Err(error) => {
+                    warn!(
+                        ?tx_hash,
+                        ?error,
+                        "Error fetching transaction receipt, retrying"
+                    );
+                    continue;
+                }
+            };
+            if let Some(receipt) = receipt {
+                info!(?tx_hash, replacement, "confirmed transaction");
+                return Ok(Some(receipt));
+            }
+        }
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        sleep(RECEIPT_POLL_INTERVAL).await;
+    }
+}
+
+/// Returns a copy of the transaction that pays `bump_percent` more for gas, or
+/// `None` if that would exceed the escalator's max gas price.
+fn bump_gas_price(tx: &TypedTransaction, escalator: &GasEscalatorConf) -> Option<TypedTransaction> {
+    let bump = |price: ethers::types::U256| price * (100 + escalator.bump_percent) / 100;
+    let exceeds_max = |price: ethers::types::U256| {
+        escalator
+            .max_gas_price
+            .map_or(false, |max| price > max.into())
+    };
+
+    let mut bumped = tx.clone();
+    if let TypedTransaction::Eip1559(request) = &mut bumped {
+        let max_fee = bump(request.max_fee_per_gas?);
+        if exceeds_max(max_fee) {
+            return None;
+        }
+        request.max_fee_per_gas = Some(max_fee);
+        request.max_priority_fee_per_gas = request
+            .max_priority_fee_per_gas
+            .map(|fee| bump(fee).min(max_fee));
+    } else {
+        let gas_price = bump(tx.gas_price()?);
+        if exceeds_max(gas_price) {
+            return None;
+        }
+        bumped.set_gas_price(gas_price);
+    }
+    Some(bumped)
+}
+
 /// Populates the gas limit and price for a transaction
 pub(crate) async fn fill_tx_gas_params<M, D>(
     tx: ContractCall<M, D>,
@@ -117,3 +274,149 @@ where
     eip_1559_tx.tx = ethers::types::transaction::eip2718::TypedTransaction::Eip1559(request);
     Ok(eip_1559_tx.gas(gas_limit))
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use ethers::prelude::{
+        MockProvider, PendingTransaction, Provider, ProviderError, TransactionReceipt,
+    };
+    use ethers::types::{
+        transaction::eip2718::TypedTransaction, BlockId, Eip1559TransactionRequest,
+        TransactionRequest, TxHash, H160, U256 as EthersU256,
+    };
+
+    use crate::contracts::i_multicall_3::IMulticall3;
+    use crate::{GasEscalatorConf, Middleware};
+
+    use super::{bump_gas_price, report_tx_with_escalator};
+
+    /// Records the transactions sent through it and reports the one with
+    /// `included` as its hash as included. The first `failing_receipts`
+    /// requests for a receipt fail.
+    #[derive(Debug)]
+    struct ScriptedProvider {
+        inner: Provider<MockProvider>,
+        sent: Mutex<Vec<TypedTransaction>>,
+        included: TxHash,
+        failing_receipts: Mutex<usize>,
+    }
+
+    impl ScriptedProvider {
+        fn new(included: TxHash, failing_receipts: usize) -> Self {
+            Self {
+                inner: Provider::mocked().0,
+                sent: Mutex::default(),
+                included,
+                failing_receipts: Mutex::new(failing_receipts),
+            }
+        }
+
+        /// The hash of the `n`th transaction sent, counting from one.
+        fn tx_hash(n: u64) -> TxHash {
+            TxHash::from_low_u64_be(n)
+        }
+    }
+
+    #[async_trait]
+    impl Middleware for ScriptedProvider {
+        type Error = ProviderError;
+        type Provider = MockProvider;
+        type Inner = Provider<MockProvider>;
+
+        fn inner(&self) -> &Self::Inner {
+            &self.inner
+        }
+
+        async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+            &self,
+            tx: T,
+            _block: Option<BlockId>,
+        ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(tx.into());
+            let tx_hash = Self::tx_hash(sent.len() as u64);
+            Ok(PendingTransaction::new(tx_hash, &self.inner))
+        }
+
+        async fn get_transaction_receipt<T: Send + Sync + Into<TxHash>>(
+            &self,
+            transaction_hash: T,
+        ) -> Result<Option<TransactionReceipt>, Self::Error> {
+            {
+                let mut failing_receipts = self.failing_receipts.lock().unwrap();
+                if *failing_receipts > 0 {
+                    *failing_receipts -= 1;
+                    return Err(ProviderError::CustomError("Node unavailable".into()));
+                }
+            }
+            let transaction_hash = transaction_hash.into();
+            Ok(
+                (transaction_hash == self.included).then(|| TransactionReceipt {
+                    transaction_hash,
+                    ..Default::default()
+                }),
+            )
+        }
+    }
+
+    fn escalator(max_gas_price: Option<u64>) -> GasEscalatorConf {
+        GasEscalatorConf {
+            bump_interval: Duration::from_secs(60),
+            bump_percent: 20,
+            max_bumps: 3,
+            max_gas_price: max_gas_price.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn test_bump_gas_price_legacy() {
+        let tx: TypedTransaction = TransactionRequest::new().gas_price(100).into();
+
+        let bumped = bump_gas_price(&tx, &escalator(None)).unwrap();
+        assert_eq!(bumped.gas_price(), Some(EthersU256::from(120)));
+
+        assert!(bump_gas_price(&tx, &escalator(Some(110))).is_none());
+    }
+
+    #[test]
+    fn test_bump_gas_price_eip1559() {
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .max_fee_per_gas(100)
+            .max_priority_fee_per_gas(100)
+            .into();
+
+        let TypedTransaction::Eip1559(bumped) = bump_gas_price(&tx, &escalator(Some(120))).unwrap()
+        else {
+            panic!("Transaction type changed");
+        };
+        assert_eq!(bumped.max_fee_per_gas, Some(EthersU256::from(120)));
+        assert_eq!(bumped.max_priority_fee_per_gas, Some(EthersU256::from(120)));
+
+        assert!(bump_gas_price(&tx, &escalator(Some(119))).is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_escalator_replaces_stuck_tx_despite_receipt_errors() {
+        // Only the first replacement gets included
+        let provider = Arc::new(ScriptedProvider::new(ScriptedProvider::tx_hash(2), 3));
+        let multicall = IMulticall3::new(H160::zero(), provider.clone());
+        let mut tx = multicall.aggregate_3(vec![]);
+        tx.tx.set_nonce(7);
+        tx.tx.set_gas(100_000);
+        tx.tx.set_gas_price(100);
+
+        let receipt = report_tx_with_escalator(tx, provider.as_ref(), &escalator(None))
+            .await
+            .unwrap();
+
+        assert_eq!(receipt.transaction_hash, ScriptedProvider::tx_hash(2));
+        let sent = provider.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[1].nonce(), Some(&EthersU256::from(7)));
+        assert_eq!(sent[1].gas_price(), Some(EthersU256::from(120)));
+    }
+}
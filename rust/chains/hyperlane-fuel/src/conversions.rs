@@ -52,3 +52,38 @@ impl_h256!(
     |v| fuels::prelude::ContractId::new(v.0),
     |v| H256::from(<[u8; 32]>::from(v))
 );
+
+impl_h256!(fuels::tx::Bytes32, |v| fuels::tx::Bytes32::new(v.0), |v| {
+    H256::from(*v)
+});
+
+impl_h256!(
+    fuels::prelude::Address,
+    |v| fuels::prelude::Address::new(v.0),
+    |v| H256::from(*v)
+);
+
+/// Implement conversions between a `HyperlaneMessage` and the `Message` struct
+/// that is generated for each contract ABI which takes a message as an input.
+macro_rules! impl_message_conversion {
+    ($type:ty) => {
+        impl From<&hyperlane_core::HyperlaneMessage> for $type {
+            fn from(m: &hyperlane_core::HyperlaneMessage) -> Self {
+                Self {
+                    version: m.version,
+                    nonce: m.nonce,
+                    origin: m.origin,
+                    sender: fuels::types::Bits256::from_h256(&m.sender),
+                    destination: m.destination,
+                    recipient: fuels::types::Bits256::from_h256(&m.recipient),
+                    body: m.body.clone(),
+                }
+            }
+        }
+    };
+}
+
+impl_message_conversion!(crate::contracts::mailbox::Message);
+impl_message_conversion!(crate::contracts::interchain_security_module::Message);
+impl_message_conversion!(crate::contracts::multisig_ism::Message);
+impl_message_conversion!(crate::contracts::routing_ism::Message);
@@ -1,27 +1,91 @@
+use std::fmt::{Debug, Formatter};
+
 use async_trait::async_trait;
+use fuels::prelude::{Bech32ContractId, ContractId, SettableContract, WalletUnlocked};
+use fuels::tx::Receipt;
+use tracing::instrument;
 
 use hyperlane_core::{
-    ChainResult, HyperlaneChain, HyperlaneContract, IndexRange, Indexer, InterchainGasPaymaster,
+    BlockRange, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, IndexRange, Indexer, InterchainGasPaymaster,
+};
+use hyperlane_core::{
+    HyperlaneDomain, HyperlaneProvider, InterchainGasPayment, LogMeta, SequenceIndexer, H256,
 };
-use hyperlane_core::{HyperlaneDomain, HyperlaneProvider, InterchainGasPayment, LogMeta, H256};
+
+use crate::{
+    contracts::interchain_gas_paymaster::{
+        GasPaymentEvent, InterchainGasPaymaster as FuelInterchainGasPaymasterInner,
+    },
+    conversions::*,
+    ConnectionConf, FuelProvider,
+};
+
+/// The ABI log id of the `GasPaymentEvent` logged by the IGP.
+const GAS_PAYMENT_EVENT_LOG_ID: u64 = 0;
 
 /// A reference to an IGP contract on some Fuel chain
-#[derive(Debug)]
-pub struct FuelInterchainGasPaymaster {}
+pub struct FuelInterchainGasPaymaster {
+    contract: FuelInterchainGasPaymasterInner,
+    provider: FuelProvider,
+    domain: HyperlaneDomain,
+}
+
+impl FuelInterchainGasPaymaster {
+    /// Create a new fuel IGP
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        mut wallet: WalletUnlocked,
+    ) -> ChainResult<Self> {
+        let provider = FuelProvider::connect(conf, locator.domain.clone())?;
+        wallet.set_provider(provider.inner().clone());
+        let address = Bech32ContractId::from_h256(&locator.address);
+
+        Ok(Self {
+            contract: FuelInterchainGasPaymasterInner::new(address, wallet),
+            provider,
+            domain: locator.domain.clone(),
+        })
+    }
+
+    /// Quote the amount of native tokens required to pay for `gas_amount` of
+    /// gas on `destination_domain`.
+    #[instrument(err, ret, skip(self))]
+    pub async fn quote_gas_payment(
+        &self,
+        destination_domain: u32,
+        gas_amount: u64,
+    ) -> ChainResult<u64> {
+        self.contract
+            .methods()
+            .quote_gas_payment(destination_domain, gas_amount)
+            .simulate()
+            .await
+            .map(|r| r.value)
+            .map_err(ChainCommunicationError::from_other)
+    }
+}
 
 impl HyperlaneContract for FuelInterchainGasPaymaster {
     fn address(&self) -> H256 {
-        todo!()
+        self.contract.contract_id().into_h256()
     }
 }
 
 impl HyperlaneChain for FuelInterchainGasPaymaster {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        &self.domain
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.provider.clone())
+    }
+}
+
+impl Debug for FuelInterchainGasPaymaster {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &dyn HyperlaneContract)
     }
 }
 
@@ -29,18 +93,84 @@ impl InterchainGasPaymaster for FuelInterchainGasPaymaster {}
 
 /// Struct that retrieves event data for a Fuel IGP contract
 #[derive(Debug)]
-pub struct FuelInterchainGasPaymasterIndexer {}
+pub struct FuelInterchainGasPaymasterIndexer {
+    igp: FuelInterchainGasPaymaster,
+    finality_blocks: u32,
+}
+
+impl FuelInterchainGasPaymasterIndexer {
+    /// Create a new fuel IGP indexer
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        finality_blocks: u32,
+    ) -> ChainResult<Self> {
+        // The indexer does not send transactions, so it does not need a
+        // funded wallet.
+        let wallet = WalletUnlocked::new_random(None);
+        Ok(Self {
+            igp: FuelInterchainGasPaymaster::new(conf, locator, wallet)?,
+            finality_blocks,
+        })
+    }
+}
 
 #[async_trait]
 impl Indexer<InterchainGasPayment> for FuelInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
     async fn fetch_logs(
         &self,
         range: IndexRange,
     ) -> ChainResult<Vec<(InterchainGasPayment, LogMeta)>> {
-        todo!()
+        let BlockRange(range) = range else {
+            return Err(ChainCommunicationError::from_other_str(
+                "FuelInterchainGasPaymasterIndexer only supports block-based indexing",
+            ))
+        };
+
+        let contract_id = ContractId::from_h256(&self.igp.address());
+        let log_decoder = self.igp.contract.log_decoder();
+        self.igp
+            .provider
+            .contract_receipts_in_range(
+                &contract_id,
+                range,
+                |r| matches!(r, Receipt::LogData { rb, .. } if *rb == GAS_PAYMENT_EVENT_LOG_ID),
+            )
+            .await?
+            .into_iter()
+            .map(|(receipt, meta)| {
+                let event = log_decoder
+                    .get_logs_with_type::<GasPaymentEvent>(&[receipt])
+                    .map_err(ChainCommunicationError::from_other)?
+                    .pop()
+                    .ok_or_else(|| {
+                        ChainCommunicationError::from_other_str("Could not decode gas payment")
+                    })?;
+                Ok((
+                    InterchainGasPayment {
+                        message_id: event.message_id.into_h256(),
+                        payment: event.payment.into(),
+                        gas_amount: event.gas_amount.into(),
+                    },
+                    meta,
+                ))
+            })
+            .collect()
     }
 
+    #[instrument(level = "debug", err, ret, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        todo!()
+        let tip = self.igp.provider.latest_block_height().await?;
+        Ok(tip.saturating_sub(self.finality_blocks))
+    }
+}
+
+#[async_trait]
+impl SequenceIndexer<InterchainGasPayment> for FuelInterchainGasPaymasterIndexer {
+    async fn sequence_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // The IGP does not number gas payments
+        let tip = self.get_finalized_block_number().await?;
+        Ok((None, tip))
     }
 }
@@ -0,0 +1,111 @@
+use std::fmt::{Debug, Formatter};
+
+use async_trait::async_trait;
+use fuels::prelude::{Bech32ContractId, WalletUnlocked};
+use num_traits::cast::FromPrimitive;
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType,
+    H256, U256,
+};
+
+use crate::{
+    contracts::interchain_security_module::InterchainSecurityModule as FuelInterchainSecurityModuleInner,
+    conversions::*, ConnectionConf, FuelProvider,
+};
+
+/// A reference to an ISM contract on some Fuel chain
+pub struct FuelInterchainSecurityModule {
+    contract: FuelInterchainSecurityModuleInner,
+    provider: FuelProvider,
+    domain: HyperlaneDomain,
+}
+
+impl FuelInterchainSecurityModule {
+    /// Create a new fuel ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = FuelProvider::connect(conf, locator.domain.clone())?;
+        // Only simulated calls are made, which do not require a funded wallet.
+        let wallet = WalletUnlocked::new_random(Some(provider.inner().clone()));
+        let address = Bech32ContractId::from_h256(&locator.address);
+
+        Ok(Self {
+            contract: FuelInterchainSecurityModuleInner::new(address, wallet),
+            provider,
+            domain: locator.domain.clone(),
+        })
+    }
+}
+
+impl HyperlaneContract for FuelInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.contract.contract_id().into_h256()
+    }
+}
+
+impl HyperlaneChain for FuelInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+impl Debug for FuelInterchainSecurityModule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &dyn HyperlaneContract)
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for FuelInterchainSecurityModule {
+    #[instrument(err, ret, skip(self))]
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        let module = self
+            .contract
+            .methods()
+            .module_type()
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+        if let Some(module_type) = ModuleType::from_u8(module) {
+            Ok(module_type)
+        } else {
+            warn!(%module, "Unknown module type");
+            Ok(ModuleType::Unused)
+        }
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn dry_run_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        let call = self
+            .contract
+            .methods()
+            .verify(metadata.to_vec(), message.into())
+            .estimate_tx_dependencies(None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let verifies = call
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+        if !verifies {
+            return Ok(None);
+        }
+        let cost = call
+            .estimate_transaction_cost(None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(Some(cost.gas_used.into()))
+    }
+}
@@ -2,24 +2,26 @@
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
-// TODO: Remove once we start filling things in
-#![allow(unused_variables)]
 
 pub use interchain_gas::*;
+pub use interchain_security_module::*;
 pub use mailbox::*;
 pub use multisig_ism::*;
 pub use provider::*;
 pub use routing_ism::*;
 pub use trait_builder::*;
+pub use validator_announce::*;
 
 mod contracts;
 mod conversions;
 mod interchain_gas;
+mod interchain_security_module;
 mod mailbox;
 mod multisig_ism;
 mod provider;
 mod routing_ism;
 mod trait_builder;
+mod validator_announce;
 
 /// Safe default imports of commonly used traits/types.
 pub mod prelude {
@@ -1,27 +1,51 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::{Debug, Formatter};
 use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
 
 use async_trait::async_trait;
-use fuels::prelude::{Bech32ContractId, WalletUnlocked};
+use fuel_abi_types::program_abi::ProgramABI;
+use fuels::core::function_selector::resolve_fn_selector;
+use fuels::prelude::{Bech32ContractId, ContractId, TxParameters, WalletUnlocked};
+use fuels::tx::Receipt;
+use fuels::types::param_types::ParamType;
 use hyperlane_core::accumulator::incremental::IncrementalMerkle;
-use tracing::instrument;
+use tokio::sync::Mutex;
+use tracing::{debug, instrument, warn};
 
 use hyperlane_core::{
-    utils::fmt_bytes, ChainCommunicationError, ChainResult, Checkpoint, ContractLocator,
-    HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProvider, IndexRange, Indexer, LogMeta, Mailbox, TxCostEstimate, TxOutcome, H256,
-    U256,
+    utils::fmt_bytes, BlockRange, ChainCommunicationError, ChainResult, Checkpoint,
+    ContractLocator, Decode, HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, IndexRange, Indexer, LogMeta, Mailbox, MessageIndexer,
+    SequenceIndexer, TxCostEstimate, TxOutcome, H256, U256,
 };
 
 use crate::{
-    contracts::mailbox::Mailbox as FuelMailboxInner, conversions::*, make_provider, ConnectionConf,
+    contracts::mailbox::Mailbox as FuelMailboxInner, conversions::*, ConnectionConf, FuelProvider,
 };
 
+/// The value of the `rb` register of the `LogData` receipt with which the
+/// mailbox logs the encoded bytes of every dispatched message ("hyp" in
+/// ascii).
+const DISPATCHED_MESSAGE_LOG_ID: u64 = 0x687970;
+
+/// The ABI log id of the message id that the mailbox logs when processing a
+/// message.
+const PROCESSED_MESSAGE_ID_LOG_ID: u64 = 6;
+
+/// The number of blocks to scan at once when rebuilding the merkle tree.
+const TREE_REBUILD_CHUNK_SIZE: u32 = 1000;
+
+/// Tolerance applied to the gas estimate of `process` transactions.
+const PROCESS_GAS_ESTIMATE_TOLERANCE: f64 = 0.1;
+
 /// A reference to a Mailbox contract on some Fuel chain
 pub struct FuelMailbox {
     contract: FuelMailboxInner,
+    provider: FuelProvider,
     domain: HyperlaneDomain,
+    tree_cache: Mutex<MerkleTreeCache>,
 }
 
 impl FuelMailbox {
@@ -31,15 +55,114 @@ impl FuelMailbox {
         locator: ContractLocator,
         mut wallet: WalletUnlocked,
     ) -> ChainResult<Self> {
-        let provider = make_provider(conf)?;
-        wallet.set_provider(provider);
+        let provider = FuelProvider::connect(conf, locator.domain.clone())?;
+        wallet.set_provider(provider.inner().clone());
         let address = Bech32ContractId::from_h256(&locator.address);
 
         Ok(FuelMailbox {
             contract: FuelMailboxInner::new(address, wallet),
+            provider,
             domain: locator.domain.clone(),
+            tree_cache: Mutex::default(),
         })
     }
+
+    fn contract_id(&self) -> ContractId {
+        ContractId::from_h256(&self.address())
+    }
+
+    /// Get the messages dispatched within the blocks of `range`.
+    async fn dispatched_messages(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(HyperlaneMessage, LogMeta)>> {
+        self.provider
+            .contract_receipts_in_range(
+                &self.contract_id(),
+                range,
+                |r| matches!(r, Receipt::LogData { rb, .. } if *rb == DISPATCHED_MESSAGE_LOG_ID),
+            )
+            .await?
+            .into_iter()
+            .map(|(receipt, meta)| {
+                let data = receipt.data().unwrap_or_default();
+                Ok((HyperlaneMessage::read_from(&mut &data[..])?, meta))
+            })
+            .collect()
+    }
+
+    /// Get the ids of the messages processed within the blocks of `range`.
+    async fn processed_message_ids(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(H256, LogMeta)>> {
+        self.provider
+            .contract_receipts_in_range(
+                &self.contract_id(),
+                range,
+                |r| matches!(r, Receipt::LogData { rb, .. } if *rb == PROCESSED_MESSAGE_ID_LOG_ID),
+            )
+            .await?
+            .into_iter()
+            .map(|(receipt, meta)| {
+                let data = receipt.data().unwrap_or_default();
+                if data.len() != 32 {
+                    return Err(ChainCommunicationError::from_other_str(
+                        "Processed message id log has an unexpected length",
+                    ));
+                }
+                Ok((H256::from_slice(data), meta))
+            })
+            .collect()
+    }
+}
+
+/// The merkle tree rebuilt from the dispatched message logs, kept between
+/// calls to `tree` so that blocks are only scanned once.
+#[derive(Debug, Default)]
+struct MerkleTreeCache {
+    tree: IncrementalMerkle,
+    /// Ids of the scanned messages that are not in the tree yet, in nonce
+    /// order
+    pending: VecDeque<H256>,
+    /// The first block that has not been scanned yet
+    next_block: u32,
+}
+
+impl MerkleTreeCache {
+    /// Queue the messages dispatched in the blocks up to and including
+    /// `to_block`.
+    fn extend(
+        &mut self,
+        to_block: u32,
+        messages: impl IntoIterator<Item = HyperlaneMessage>,
+    ) -> ChainResult<()> {
+        let mut messages = messages.into_iter().collect::<Vec<_>>();
+        messages.sort_by_key(|message| message.nonce);
+        for message in messages {
+            if message.nonce as usize != self.tree.count() + self.pending.len() {
+                return Err(ChainCommunicationError::from_other_str(
+                    "Gap in dispatched messages while rebuilding the merkle tree",
+                ));
+            }
+            self.pending.push_back(message.id());
+        }
+        self.next_block = to_block + 1;
+        Ok(())
+    }
+
+    /// Ingest the queued messages up to the index of the checkpoint and
+    /// return whether the tree then matches it.
+    fn advance_to(&mut self, checkpoint: &Checkpoint) -> bool {
+        while self.tree.count() <= checkpoint.index as usize && self.tree.root() != checkpoint.root
+        {
+            let Some(id) = self.pending.pop_front() else {
+                break;
+            };
+            self.tree.ingest(id);
+        }
+        self.tree.root() == checkpoint.root
+    }
 }
 
 impl HyperlaneContract for FuelMailbox {
@@ -54,7 +177,7 @@ impl HyperlaneChain for FuelMailbox {
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.provider.clone())
     }
 }
 
@@ -64,14 +187,22 @@ impl Debug for FuelMailbox {
     }
 }
 
+/// Fuel does not support point-in-time queries, so the latest state is read
+/// regardless of the lag.
+fn ignore_lag(lag: Option<NonZeroU64>) {
+    if let Some(lag) = lag {
+        warn!(
+            ?lag,
+            "Fuel does not support querying point-in-time, reading the latest state instead"
+        );
+    }
+}
+
 #[async_trait]
 impl Mailbox for FuelMailbox {
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
-        assert!(
-            lag.is_none(),
-            "Fuel does not support querying point-in-time"
-        );
+        ignore_lag(lag);
         self.contract
             .methods()
             .count()
@@ -81,22 +212,52 @@ impl Mailbox for FuelMailbox {
             .map_err(ChainCommunicationError::from_other)
     }
 
+    /// The fuel mailbox does not expose the branches of its merkle tree, so
+    /// the tree is rebuilt from the dispatched message logs and checked
+    /// against the on-chain root. The rebuilt tree is cached, so only the
+    /// blocks produced since the last call are scanned.
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn tree(&self, lag: Option<NonZeroU64>) -> ChainResult<IncrementalMerkle> {
-        todo!()
+        ignore_lag(lag);
+        let checkpoint = self.latest_checkpoint(None).await?;
+        let mut cache = self.tree_cache.lock().await;
+
+        if !cache.advance_to(&checkpoint) {
+            let tip = self.provider.latest_block_height().await?;
+            let mut from = cache.next_block;
+            while from <= tip {
+                let to = from.saturating_add(TREE_REBUILD_CHUNK_SIZE - 1).min(tip);
+                let messages = self.dispatched_messages(from..=to).await?;
+                if let Err(err) = cache.extend(to, messages.into_iter().map(|(m, _)| m)) {
+                    *cache = MerkleTreeCache::default();
+                    return Err(err);
+                }
+                from = to + 1;
+            }
+            if !cache.advance_to(&checkpoint) {
+                *cache = MerkleTreeCache::default();
+                return Err(ChainCommunicationError::from_other_str(
+                    "Rebuilt merkle tree root does not match the mailbox root",
+                ));
+            }
+        }
+        Ok(cache.tree)
     }
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn delivered(&self, id: H256) -> ChainResult<bool> {
-        todo!()
+        self.contract
+            .methods()
+            .delivered(fuels::types::Bits256::from_h256(&id))
+            .simulate()
+            .await
+            .map(|r| r.value)
+            .map_err(ChainCommunicationError::from_other)
     }
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn latest_checkpoint(&self, lag: Option<NonZeroU64>) -> ChainResult<Checkpoint> {
-        assert!(
-            lag.is_none(),
-            "Fuel does not support querying point-in-time"
-        );
+        ignore_lag(lag);
         let (root, index) = self
             .contract
             .methods()
@@ -116,12 +277,20 @@ impl Mailbox for FuelMailbox {
 
     #[instrument(err, ret, skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
-        todo!()
+        self.contract
+            .methods()
+            .get_default_ism()
+            .simulate()
+            .await
+            .map(|r| r.value.into_h256())
+            .map_err(ChainCommunicationError::from_other)
     }
 
+    /// The fuel mailbox does not support recipient-specified ISMs yet and
+    /// always verifies messages with its default ISM.
     #[instrument(err, ret, skip(self))]
-    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
-        todo!()
+    async fn recipient_ism(&self, _recipient: H256) -> ChainResult<H256> {
+        self.default_ism().await
     }
 
     #[instrument(err, ret, skip(self))]
@@ -131,7 +300,24 @@ impl Mailbox for FuelMailbox {
         metadata: &[u8],
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        todo!()
+        // Adds the recipient and ISM contracts and any variable outputs the
+        // call needs.
+        let mut call = self
+            .contract
+            .methods()
+            .process(metadata.to_vec(), message.into())
+            .estimate_tx_dependencies(None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        if let Some(gas_limit) = tx_gas_limit {
+            call = call.tx_params(TxParameters::default().set_gas_limit(gas_limit.as_u64()));
+        }
+
+        let tx = call
+            .build_tx()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        self.provider.send_transaction(&tx).await
     }
 
     #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%fmt_bytes(metadata)))]
@@ -140,47 +326,237 @@ impl Mailbox for FuelMailbox {
         message: &HyperlaneMessage,
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
-        todo!()
+        let cost = self
+            .contract
+            .methods()
+            .process(metadata.to_vec(), message.into())
+            .estimate_tx_dependencies(None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .estimate_transaction_cost(Some(PROCESS_GAS_ESTIMATE_TOLERANCE))
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        Ok(TxCostEstimate {
+            gas_limit: cost.gas_used.into(),
+            gas_price: cost.gas_price.into(),
+            l2_gas_limit: None,
+        })
     }
 
-    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
-        todo!()
+    async fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
+        let call = self
+            .contract
+            .methods()
+            .process(metadata.to_vec(), message.into())
+            .contract_call;
+        Ok([call.encoded_selector.to_vec(), call.encoded_args.resolve(0)].concat())
     }
 }
 
 /// Struct that retrieves event data for a Fuel Mailbox contract
 #[derive(Debug)]
-pub struct FuelMailboxIndexer {}
+pub struct FuelMailboxIndexer {
+    mailbox: FuelMailbox,
+    finality_blocks: u32,
+}
+
+impl FuelMailboxIndexer {
+    /// Create a new fuel mailbox indexer
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        finality_blocks: u32,
+    ) -> ChainResult<Self> {
+        // The indexer only simulates calls, which does not require a funded
+        // wallet.
+        let wallet = WalletUnlocked::new_random(None);
+        Ok(Self {
+            mailbox: FuelMailbox::new(conf, locator, wallet)?,
+            finality_blocks,
+        })
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        let tip = self.mailbox.provider.latest_block_height().await?;
+        Ok(tip.saturating_sub(self.finality_blocks))
+    }
+}
+
+#[async_trait]
+impl MessageIndexer for FuelMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_count_at_tip(&self) -> ChainResult<(u32, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+        // Fuel does not support point-in-time queries, so the count may be
+        // ahead of the finalized tip.
+        let count = self.mailbox.count(None).await?;
+        Ok((count, tip))
+    }
+}
 
 #[async_trait]
 impl Indexer<HyperlaneMessage> for FuelMailboxIndexer {
+    #[instrument(err, skip(self))]
     async fn fetch_logs(&self, range: IndexRange) -> ChainResult<Vec<(HyperlaneMessage, LogMeta)>> {
-        todo!()
+        let BlockRange(range) = range else {
+            return Err(ChainCommunicationError::from_other_str(
+                "FuelMailboxIndexer only supports block-based indexing",
+            ))
+        };
+        let messages = self.mailbox.dispatched_messages(range).await?;
+        debug!(count = messages.len(), "Fetched dispatched messages");
+        Ok(messages)
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        todo!()
+        self.get_finalized_block_number().await
     }
 }
 
 #[async_trait]
 impl Indexer<H256> for FuelMailboxIndexer {
+    #[instrument(err, skip(self))]
     async fn fetch_logs(&self, range: IndexRange) -> ChainResult<Vec<(H256, LogMeta)>> {
-        todo!()
+        let BlockRange(range) = range else {
+            return Err(ChainCommunicationError::from_other_str(
+                "FuelMailboxIndexer only supports block-based indexing",
+            ))
+        };
+        self.mailbox.processed_message_ids(range).await
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        todo!()
+        self.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceIndexer<H256> for FuelMailboxIndexer {
+    async fn sequence_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // The mailbox does not number processed messages
+        let tip = self.get_finalized_block_number().await?;
+        Ok((None, tip))
     }
 }
 
 struct FuelMailboxAbi;
 
+/// The ABI the mailbox bindings are generated from.
+static MAILBOX_ABI: OnceLock<ProgramABI> = OnceLock::new();
+
 impl HyperlaneAbi for FuelMailboxAbi {
     const SELECTOR_SIZE_BYTES: usize = 8;
 
     fn fn_map() -> HashMap<Vec<u8>, &'static str> {
-        // Can't support this without Fuels exporting it in the generated code
-        todo!()
+        // The generated bindings don't export the function selectors, so they
+        // are derived from the ABI instead
+        let abi = MAILBOX_ABI.get_or_init(|| {
+            serde_json::from_str(include_str!("../abis/Mailbox.abi.json"))
+                .expect("Invalid Fuel mailbox ABI")
+        });
+        let types = abi
+            .types
+            .iter()
+            .map(|t| (t.type_id, t.clone()))
+            .collect::<HashMap<_, _>>();
+        abi.functions
+            .iter()
+            .map(|function| {
+                let inputs = function
+                    .inputs
+                    .iter()
+                    .map(|input| ParamType::try_from_type_application(input, &types))
+                    .collect::<Result<Vec<_>, _>>()
+                    .expect("Invalid Fuel mailbox ABI");
+                let selector = resolve_fn_selector(&function.name, &inputs);
+                (selector.to_vec(), function.name.as_str())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{Checkpoint, HyperlaneAbi, HyperlaneMessage, H256};
+
+    use super::{FuelMailboxAbi, MerkleTreeCache};
+
+    fn message(nonce: u32) -> HyperlaneMessage {
+        HyperlaneMessage {
+            nonce,
+            ..Default::default()
+        }
+    }
+
+    fn checkpoint_after(messages: &[HyperlaneMessage]) -> Checkpoint {
+        let mut tree = super::IncrementalMerkle::default();
+        messages.iter().for_each(|m| tree.ingest(m.id()));
+        Checkpoint {
+            mailbox_address: H256::zero(),
+            mailbox_domain: 0,
+            root: tree.root(),
+            index: tree.count().saturating_sub(1) as u32,
+        }
+    }
+
+    #[test]
+    fn tree_cache_matches_empty_mailbox() {
+        let mut cache = MerkleTreeCache::default();
+        assert!(cache.advance_to(&checkpoint_after(&[])));
+    }
+
+    #[test]
+    fn tree_cache_only_ingests_up_to_checkpoint() {
+        let messages = (0..5).map(message).collect::<Vec<_>>();
+        let mut cache = MerkleTreeCache::default();
+        // Logs may be returned out of order within a range.
+        cache.extend(9, messages.iter().rev().cloned()).unwrap();
+        assert_eq!(cache.next_block, 10);
+
+        assert!(cache.advance_to(&checkpoint_after(&messages[..3])));
+        assert_eq!(cache.tree.count(), 3);
+        assert_eq!(cache.pending.len(), 2);
+
+        assert!(cache.advance_to(&checkpoint_after(&messages)));
+        assert_eq!(cache.tree.count(), 5);
+        assert!(cache.pending.is_empty());
+    }
+
+    #[test]
+    fn tree_cache_needs_newer_blocks() {
+        let messages = (0..3).map(message).collect::<Vec<_>>();
+        let mut cache = MerkleTreeCache::default();
+        cache.extend(9, messages[..2].iter().cloned()).unwrap();
+        assert!(!cache.advance_to(&checkpoint_after(&messages)));
+
+        cache.extend(19, messages[2..].iter().cloned()).unwrap();
+        assert_eq!(cache.next_block, 20);
+        assert!(cache.advance_to(&checkpoint_after(&messages)));
+    }
+
+    #[test]
+    fn tree_cache_rejects_gaps() {
+        let mut cache = MerkleTreeCache::default();
+        cache.extend(9, [message(0)]).unwrap();
+        assert!(cache.extend(19, [message(2)]).is_err());
+    }
+
+    #[test]
+    fn fn_map_resolves_mailbox_selectors() {
+        let fn_map = FuelMailboxAbi::fn_map();
+        assert_eq!(fn_map.len(), 10);
+        assert!(fn_map
+            .keys()
+            .all(|selector| selector.len() == FuelMailboxAbi::SELECTOR_SIZE_BYTES));
+        // The first four bytes of sha256("count()"), left padded
+        assert_eq!(
+            fn_map.get(&vec![0, 0, 0, 0, 0x3c, 0x5b, 0xb3, 0xf2]),
+            Some(&"count")
+        );
     }
 }
@@ -1,37 +1,83 @@
+use std::fmt::{Debug, Formatter};
+
 use async_trait::async_trait;
+use fuels::prelude::{Bech32ContractId, WalletUnlocked};
+use tracing::instrument;
 
 use hyperlane_core::{
-    ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProvider, MultisigIsm, H256,
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, MultisigIsm, H256,
+};
+
+use crate::{
+    contracts::multisig_ism::MultisigIsm as FuelMultisigIsmInner, conversions::*, ConnectionConf,
+    FuelProvider,
 };
 
 /// A reference to a MultisigIsm contract on some Fuel chain
-#[derive(Debug)]
-pub struct FuelMultisigIsm {}
+pub struct FuelMultisigIsm {
+    contract: FuelMultisigIsmInner,
+    provider: FuelProvider,
+    domain: HyperlaneDomain,
+}
+
+impl FuelMultisigIsm {
+    /// Create a new fuel MultisigIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = FuelProvider::connect(conf, locator.domain.clone())?;
+        // Only simulated calls are made, which do not require a funded wallet.
+        let wallet = WalletUnlocked::new_random(Some(provider.inner().clone()));
+        let address = Bech32ContractId::from_h256(&locator.address);
+
+        Ok(Self {
+            contract: FuelMultisigIsmInner::new(address, wallet),
+            provider,
+            domain: locator.domain.clone(),
+        })
+    }
+}
 
 impl HyperlaneContract for FuelMultisigIsm {
     fn address(&self) -> H256 {
-        todo!()
+        self.contract.contract_id().into_h256()
     }
 }
 
 impl HyperlaneChain for FuelMultisigIsm {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        &self.domain
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.provider.clone())
+    }
+}
+
+impl Debug for FuelMultisigIsm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &dyn HyperlaneContract)
     }
 }
 
 #[async_trait]
 impl MultisigIsm for FuelMultisigIsm {
     /// Returns the validator and threshold needed to verify message
+    #[instrument(err, ret, skip(self))]
     async fn validators_and_threshold(
         &self,
         message: &HyperlaneMessage,
     ) -> ChainResult<(Vec<H256>, u8)> {
-        todo!()
+        let (validators, threshold) = self
+            .contract
+            .methods()
+            .validators_and_threshold(message.into())
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+        Ok((
+            validators.into_iter().map(|v| v.into_h256()).collect(),
+            threshold,
+        ))
     }
 }
@@ -1,34 +1,274 @@
+use std::ops::RangeInclusive;
+
 use async_trait::async_trait;
+use fuels::client::{PageDirection, PaginationRequest};
+use fuels::prelude::{ContractId, Error as FuelsError, Provider, ScriptTransaction};
+use fuels::tx::{
+    field::{GasLimit, GasPrice, Inputs},
+    Receipt, ScriptExecutionResult, Transaction,
+};
+use fuels::types::{block::Block, transaction::Transaction as _};
+use futures_util::future::try_join_all;
+use tracing::instrument;
 
 use hyperlane_core::{
-    BlockInfo, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider, TxnInfo, H256,
+    BlockInfo, ChainCommunicationError, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, HyperlaneProviderError, LogMeta, TxOutcome, TxnInfo, TxnReceiptInfo, H256,
+    H512, U256,
 };
 
+use crate::{conversions::*, make_provider, ConnectionConf};
+
 /// A wrapper around a fuel provider to get generic blockchain information.
-#[derive(Debug)]
-pub struct FuelProvider {}
+#[derive(Debug, Clone)]
+pub struct FuelProvider {
+    domain: HyperlaneDomain,
+    provider: Provider,
+}
+
+impl FuelProvider {
+    /// Create a new fuel provider.
+    pub fn new(domain: HyperlaneDomain, provider: Provider) -> Self {
+        Self { domain, provider }
+    }
+
+    /// Connect to the fuel node described by the connection configuration.
+    pub fn connect(conf: &ConnectionConf, domain: HyperlaneDomain) -> ChainResult<Self> {
+        Ok(Self::new(domain, make_provider(conf)?))
+    }
+
+    /// Get the underlying fuels provider.
+    pub fn inner(&self) -> &Provider {
+        &self.provider
+    }
+
+    /// Get the height of the latest block.
+    pub async fn latest_block_height(&self) -> ChainResult<u32> {
+        self.provider
+            .latest_block_height()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// Get the blocks within `range`, ordered by height.
+    async fn blocks_in_range(&self, range: &RangeInclusive<u32>) -> ChainResult<Vec<Block>> {
+        let count = (range.end() - range.start() + 1) as usize;
+        // The cursor is exclusive, so we start at the block before the range.
+        let cursor = range.start().checked_sub(1).map(|h| h.to_string());
+        let blocks = self
+            .provider
+            .get_blocks(PaginationRequest {
+                cursor,
+                results: count,
+                direction: PageDirection::Forward,
+            })
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .results;
+        Ok(blocks
+            .into_iter()
+            .filter(|b| range.contains(&(b.header.height as u32)))
+            .collect())
+    }
+
+    /// Submit a signed script transaction and wait for it to be executed.
+    /// A reverted transaction is reported as not executed rather than as an
+    /// error.
+    #[instrument(err, ret, skip(self, tx))]
+    pub(crate) async fn send_transaction(&self, tx: &ScriptTransaction) -> ChainResult<TxOutcome> {
+        let tx_id = tx.id().into_h256();
+        let gas_price = tx.gas_price();
+
+        let receipts = match self.provider.send_transaction(tx).await {
+            Ok(receipts) => receipts,
+            Err(FuelsError::RevertTransactionError { receipts, .. }) => receipts,
+            Err(err) => return Err(ChainCommunicationError::from_other(err)),
+        };
+
+        Ok(TxOutcome {
+            transaction_id: tx_id.into(),
+            executed: receipts_succeeded(&receipts),
+            gas_used: receipts_gas_used(&receipts).into(),
+            gas_price: gas_price.into(),
+        })
+    }
+
+    async fn receipts(&self, tx_id: &H256) -> ChainResult<Vec<Receipt>> {
+        self.provider
+            .client
+            .receipts(&format!("{tx_id:x}"))
+            .await
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// Find all receipts emitted by `contract` within the blocks of `range`
+    /// that are accepted by `filter`, along with the metadata of the log.
+    ///
+    /// Fuel has no log filtering api, so this walks every transaction in the
+    /// range and should only be used with small ranges.
+    #[instrument(level = "debug", err, skip(self, filter))]
+    pub(crate) async fn contract_receipts_in_range<F>(
+        &self,
+        contract: &ContractId,
+        range: RangeInclusive<u32>,
+        filter: F,
+    ) -> ChainResult<Vec<(Receipt, LogMeta)>>
+    where
+        F: Fn(&Receipt) -> bool,
+    {
+        let address = contract.into_h256();
+        let mut logs = vec![];
+        for block in self.blocks_in_range(&range).await? {
+            // There is no query for the receipts of a whole block, so the
+            // receipts of its transactions are fetched concurrently.
+            let tx_ids = block
+                .transactions
+                .iter()
+                .map(|tx_id| tx_id.into_h256())
+                .collect::<Vec<_>>();
+            let block_receipts =
+                try_join_all(tx_ids.iter().map(|tx_id| self.receipts(tx_id))).await?;
+            for (tx_index, (tx_id, receipts)) in tx_ids.into_iter().zip(block_receipts).enumerate()
+            {
+                // Receipts of reverted transactions must not be indexed.
+                if !receipts_succeeded(&receipts) {
+                    continue;
+                }
+                for (log_index, receipt) in receipts.into_iter().enumerate() {
+                    if receipt.contract_id() != Some(contract) || !filter(&receipt) {
+                        continue;
+                    }
+                    logs.push((
+                        receipt,
+                        LogMeta {
+                            address,
+                            block_number: block.header.height,
+                            block_hash: block.id.into_h256(),
+                            transaction_id: tx_id.into(),
+                            transaction_index: tx_index as u64,
+                            log_index: U256::from(log_index as u64),
+                        },
+                    ));
+                }
+            }
+        }
+        Ok(logs)
+    }
+}
+
+/// Whether the script of the transaction which produced the receipts
+/// succeeded.
+fn receipts_succeeded(receipts: &[Receipt]) -> bool {
+    receipts.iter().any(|r| {
+        matches!(
+            r,
+            Receipt::ScriptResult {
+                result: ScriptExecutionResult::Success,
+                ..
+            }
+        )
+    })
+}
+
+/// The gas used by the script of the transaction which produced the receipts.
+fn receipts_gas_used(receipts: &[Receipt]) -> u64 {
+    receipts
+        .iter()
+        .find_map(|r| match r {
+            Receipt::ScriptResult { gas_used, .. } => Some(*gas_used),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
 
 impl HyperlaneChain for FuelProvider {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        &self.domain
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.clone())
     }
 }
 
 #[async_trait]
 impl HyperlaneProvider for FuelProvider {
-    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
-        todo!()
+    #[instrument(err, skip(self))]
+    async fn get_block(&self, hash: &H256, _number: u64) -> ChainResult<BlockInfo> {
+        let block = self
+            .provider
+            .block(&format!("{hash:x}"))
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .ok_or(HyperlaneProviderError::CouldNotFindObjectByHash(*hash))?;
+
+        Ok(BlockInfo {
+            hash: *hash,
+            timestamp: block
+                .header
+                .time
+                .map(|t| t.timestamp() as u64)
+                .unwrap_or_default(),
+            number: block.header.height,
+        })
     }
 
-    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
-        todo!()
+    #[instrument(err, skip(self))]
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        let txn_hash: H256 = (*hash).into();
+        let response = self
+            .provider
+            .get_transaction_by_id(&format!("{txn_hash:x}"))
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .ok_or(HyperlaneProviderError::CouldNotFindObjectByHash(txn_hash))?;
+
+        let Transaction::Script(tx) = response.transaction else {
+            return Err(ChainCommunicationError::from_other_str(
+                "Only script transactions are supported",
+            ));
+        };
+
+        let sender = tx
+            .inputs()
+            .iter()
+            .find_map(|input| input.input_owner())
+            .map(|owner| owner.into_h256())
+            .unwrap_or_default();
+        let receipts = self.receipts(&txn_hash).await?;
+        let receipt = (!receipts.is_empty()).then(|| {
+            let gas_used = U256::from(receipts_gas_used(&receipts));
+            TxnReceiptInfo {
+                gas_used,
+                cumulative_gas_used: gas_used,
+                effective_gas_price: Some((*tx.gas_price()).into()),
+            }
+        });
+
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit: (*tx.gas_limit()).into(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price: Some((*tx.gas_price()).into()),
+            // Fuel is UTXO based and has no account nonces
+            nonce: 0,
+            sender,
+            recipient: None,
+            receipt,
+        })
     }
 
+    #[instrument(err, skip(self))]
     async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
-        todo!()
+        let contract = self
+            .provider
+            .client
+            .contract(&format!("{address:x}"))
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(contract.is_some())
     }
 }
@@ -1,34 +1,75 @@
+use std::fmt::{Debug, Formatter};
+
 use async_trait::async_trait;
+use fuels::prelude::{Bech32ContractId, WalletUnlocked};
+use tracing::instrument;
 
 use hyperlane_core::{
-    ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProvider, RoutingIsm, H256,
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RoutingIsm, H256,
+};
+
+use crate::{
+    contracts::routing_ism::RoutingIsm as FuelRoutingIsmInner, conversions::*, ConnectionConf,
+    FuelProvider,
 };
 
 /// A reference to a RoutingIsm contract on some Fuel chain
-#[derive(Debug)]
-pub struct FuelRoutingIsm {}
+pub struct FuelRoutingIsm {
+    contract: FuelRoutingIsmInner,
+    provider: FuelProvider,
+    domain: HyperlaneDomain,
+}
+
+impl FuelRoutingIsm {
+    /// Create a new fuel RoutingIsm
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = FuelProvider::connect(conf, locator.domain.clone())?;
+        // Only simulated calls are made, which do not require a funded wallet.
+        let wallet = WalletUnlocked::new_random(Some(provider.inner().clone()));
+        let address = Bech32ContractId::from_h256(&locator.address);
+
+        Ok(Self {
+            contract: FuelRoutingIsmInner::new(address, wallet),
+            provider,
+            domain: locator.domain.clone(),
+        })
+    }
+}
 
 impl HyperlaneContract for FuelRoutingIsm {
     fn address(&self) -> H256 {
-        todo!()
+        self.contract.contract_id().into_h256()
     }
 }
 
 impl HyperlaneChain for FuelRoutingIsm {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        &self.domain
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.provider.clone())
+    }
+}
+
+impl Debug for FuelRoutingIsm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &dyn HyperlaneContract)
     }
 }
 
 #[async_trait]
 impl RoutingIsm for FuelRoutingIsm {
     /// Returns the ism needed to verify message
+    #[instrument(err, ret, skip(self))]
     async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
-        todo!()
+        self.contract
+            .methods()
+            .route(message.into())
+            .simulate()
+            .await
+            .map(|r| r.value.into_h256())
+            .map_err(ChainCommunicationError::from_other)
     }
 }
@@ -1,22 +1,108 @@
+use std::fmt::{Debug, Formatter};
+
 use async_trait::async_trait;
+use fuels::prelude::{Bech32ContractId, TxParameters, WalletUnlocked, BASE_ASSET_ID};
+use fuels::types::{Bits256, B512};
+use tracing::{instrument, trace};
 
 use hyperlane_core::{
-    ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain, ValidatorAnnounce, H256,
+    Announcement, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Signature, SignedType, TxOutcome,
+    ValidatorAnnounce, H256, U256,
+};
+
+use crate::{
+    contracts::validator_announce::ValidatorAnnounce as FuelValidatorAnnounceInner, conversions::*,
+    ConnectionConf, FuelProvider,
 };
 
 /// A reference to a ValidatorAnnounce contract on some Fuel chain
-#[derive(Debug)]
-pub struct FuelValidatorAnnounce {}
+pub struct FuelValidatorAnnounce {
+    contract: FuelValidatorAnnounceInner,
+    provider: FuelProvider,
+    domain: HyperlaneDomain,
+}
+
+impl FuelValidatorAnnounce {
+    /// Create a new fuel ValidatorAnnounce
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        mut wallet: WalletUnlocked,
+    ) -> ChainResult<Self> {
+        let provider = FuelProvider::connect(conf, locator.domain.clone())?;
+        wallet.set_provider(provider.inner().clone());
+        let address = Bech32ContractId::from_h256(&locator.address);
+
+        Ok(Self {
+            contract: FuelValidatorAnnounceInner::new(address, wallet),
+            provider,
+            domain: locator.domain.clone(),
+        })
+    }
+
+    async fn announced_storage_locations(&self, validator: &H256) -> ChainResult<Vec<String>> {
+        let validator = Bits256::from_h256(validator);
+        let count = self
+            .contract
+            .methods()
+            .get_announced_storage_location_count(validator)
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+
+        let mut locations = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let location = self
+                .contract
+                .methods()
+                .get_announced_storage_location(validator, index)
+                .simulate()
+                .await
+                .map_err(ChainCommunicationError::from_other)?
+                .value;
+            locations
+                .push(String::from_utf8(location).map_err(ChainCommunicationError::from_other)?);
+        }
+        Ok(locations)
+    }
+}
+
+/// Encode an ECDSA signature in the 64 byte compact form used by Fuel, which
+/// stores the recovery id in the most significant bit of `s`.
+fn signature_to_b512(signature: &Signature) -> B512 {
+    let bytes = <[u8; 65]>::from(signature);
+    let mut r = [0u8; 32];
+    let mut s = [0u8; 32];
+    r.copy_from_slice(&bytes[..32]);
+    s.copy_from_slice(&bytes[32..64]);
+    // `v` is either 27 or 28
+    if bytes[64] % 2 == 0 {
+        s[0] |= 0x80;
+    }
+    B512::from((Bits256(r), Bits256(s)))
+}
 
 impl HyperlaneContract for FuelValidatorAnnounce {
     fn address(&self) -> H256 {
-        todo!()
+        self.contract.contract_id().into_h256()
     }
 }
 
 impl HyperlaneChain for FuelValidatorAnnounce {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+impl Debug for FuelValidatorAnnounce {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &dyn HyperlaneContract)
     }
 }
 
@@ -26,6 +112,62 @@ impl ValidatorAnnounce for FuelValidatorAnnounce {
         &self,
         validators: &[H256],
     ) -> ChainResult<Vec<Vec<String>>> {
-        todo!()
+        let mut storage_locations = Vec::with_capacity(validators.len());
+        for validator in validators {
+            storage_locations.push(self.announced_storage_locations(validator).await?);
+        }
+        Ok(storage_locations)
+    }
+
+    #[instrument(ret, skip(self))]
+    async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
+        let Ok(cost) = self
+            .contract
+            .methods()
+            .announce(
+                Bits256::from_h256(&announcement.value.validator.into()),
+                announcement.value.storage_location.into_bytes(),
+                signature_to_b512(&announcement.signature),
+            )
+            .estimate_transaction_cost(None)
+            .await
+        else {
+            trace!("Unable to estimate announce cost");
+            return None;
+        };
+
+        let Ok(balance) = self
+            .contract
+            .wallet()
+            .get_asset_balance(&BASE_ASSET_ID)
+            .await
+        else {
+            trace!("Unable to query balance");
+            return None;
+        };
+
+        Some(cost.total_fee.saturating_sub(balance).into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn announce(
+        &self,
+        announcement: SignedType<Announcement>,
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let mut call = self.contract.methods().announce(
+            Bits256::from_h256(&announcement.value.validator.into()),
+            announcement.value.storage_location.into_bytes(),
+            signature_to_b512(&announcement.signature),
+        );
+        if let Some(gas_limit) = tx_gas_limit {
+            call = call.tx_params(TxParameters::default().set_gas_limit(gas_limit.as_u64()));
+        }
+
+        let tx = call
+            .build_tx()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        self.provider.send_transaction(&tx).await
     }
 }
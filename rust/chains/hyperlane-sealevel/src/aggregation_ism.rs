@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+
+use hyperlane_core::{
+    AggregationIsm, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RawHyperlaneMessage,
+    H256,
+};
+use hyperlane_sealevel_aggregation_ism::instruction::{
+    modules_and_threshold_instruction, ModulesAndThreshold,
+};
+use serializable_account_meta::SimulationReturnData;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+
+use crate::{utils::simulate_instruction, ConnectionConf, RpcClientWithDebug, SealevelProvider};
+
+/// A reference to an AggregationIsm contract on some Sealevel chain
+#[derive(Debug)]
+pub struct SealevelAggregationIsm {
+    rpc_client: RpcClientWithDebug,
+    payer: Option<Keypair>,
+    program_id: Pubkey,
+    domain: HyperlaneDomain,
+    provider: SealevelProvider,
+}
+
+impl SealevelAggregationIsm {
+    /// Create a new Sealevel AggregationIsm.
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, payer: Option<Keypair>) -> Self {
+        let rpc_client = RpcClientWithDebug::new(conf.url.to_string());
+        let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
+
+        Self {
+            rpc_client,
+            payer,
+            program_id,
+            domain: locator.domain.clone(),
+            provider: SealevelProvider::new(locator.domain.clone(), conf),
+        }
+    }
+}
+
+impl HyperlaneContract for SealevelAggregationIsm {
+    fn address(&self) -> H256 {
+        self.program_id.to_bytes().into()
+    }
+}
+
+impl HyperlaneChain for SealevelAggregationIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl AggregationIsm for SealevelAggregationIsm {
+    /// Returns the `m` ISMs and `n` threshold needed to n-of-m verify the message
+    async fn modules_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let instruction = modules_and_threshold_instruction(
+            self.program_id,
+            RawHyperlaneMessage::from(message).to_vec(),
+        )
+        .map_err(ChainCommunicationError::from_other)?;
+
+        let modules_and_threshold =
+            simulate_instruction::<SimulationReturnData<ModulesAndThreshold>>(
+                &self.rpc_client,
+                self.payer
+                    .as_ref()
+                    .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?,
+                instruction,
+            )
+            .await?
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "No return data was returned from the aggregation ism",
+                )
+            })?
+            .return_data;
+
+        let modules = modules_and_threshold
+            .modules
+            .into_iter()
+            .map(|module| module.to_bytes().into())
+            .collect();
+
+        Ok((modules, modules_and_threshold.threshold))
+    }
+}
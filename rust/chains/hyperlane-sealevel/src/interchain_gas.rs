@@ -1,30 +1,122 @@
 use async_trait::async_trait;
+use base64::Engine;
 use hyperlane_core::{
-    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
-    HyperlaneProvider, IndexRange, Indexer, InterchainGasPaymaster, InterchainGasPayment, LogMeta,
-    H256,
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneProvider, IndexRange, Indexer, InterchainGasPaymaster,
+    InterchainGasPayment, LogMeta, SequenceIndexer, SequenceRange, H256, H512, U256,
+};
+use hyperlane_sealevel_igp::{
+    accounts::{
+        GasPaymentAccount, InterchainGasPaymasterType, OverheadIgpAccount, ProgramDataAccount,
+        GAS_PAYMENT_DISCRIMINATOR,
+    },
+    igp_gas_payment_pda_seeds, igp_program_data_pda_seeds,
+    instruction::{Instruction as IgpInstruction, QuoteGasPayment},
+};
+use serializable_account_meta::SimulationReturnData;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    system_program,
 };
 use tracing::{info, instrument};
 
-use crate::{ConnectionConf, SealevelProvider};
-use solana_sdk::pubkey::Pubkey;
+use crate::{utils::simulate_instruction, ConnectionConf, RpcClientWithDebug, SealevelProvider};
+
+/// The offset of the `unique_gas_payment_pubkey` field in a gas payment
+/// account: the `initialized` flag, the discriminator, then `sequence_number`,
+/// `igp`, `destination_domain`, `message_id` and `gas_amount`. This is the
+/// same for every version of the account data.
+const UNIQUE_GAS_PAYMENT_PUBKEY_OFFSET: usize = 1 + 8 + 8 + 32 + 4 + 32 + 8;
 
 /// A reference to an IGP contract on some Sealevel chain
 #[derive(Debug)]
 pub struct SealevelInterchainGasPaymaster {
+    rpc_client: RpcClientWithDebug,
+    payer: Option<Keypair>,
     program_id: Pubkey,
     domain: HyperlaneDomain,
+    provider: SealevelProvider,
 }
 
 impl SealevelInterchainGasPaymaster {
     /// Create a new Sealevel IGP.
-    pub fn new(_conf: &ConnectionConf, locator: ContractLocator) -> Self {
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, payer: Option<Keypair>) -> Self {
+        let rpc_client = RpcClientWithDebug::new(conf.url.to_string());
         let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
         Self {
+            rpc_client,
+            payer,
             program_id,
             domain: locator.domain.clone(),
+            provider: SealevelProvider::new(locator.domain.clone(), conf),
         }
     }
+
+    /// Quote the amount of lamports required to pay for `gas_amount` of gas
+    /// on `destination_domain` using the given IGP account.
+    #[instrument(err, ret, skip(self))]
+    pub async fn quote_gas_payment(
+        &self,
+        igp: &InterchainGasPaymasterType,
+        destination_domain: u32,
+        gas_amount: u64,
+    ) -> ChainResult<U256> {
+        let mut accounts = vec![AccountMeta::new_readonly(system_program::id(), false)];
+        match igp {
+            InterchainGasPaymasterType::Igp(igp_account) => {
+                accounts.push(AccountMeta::new_readonly(*igp_account, false));
+            }
+            InterchainGasPaymasterType::OverheadIgp(overhead_igp_account) => {
+                let overhead_igp = OverheadIgpAccount::fetch(
+                    &mut &self.fetch_account(overhead_igp_account).await?.data[..],
+                )
+                .map_err(ChainCommunicationError::from_other)?
+                .into_inner();
+                accounts.push(AccountMeta::new_readonly(overhead_igp.inner, false));
+                accounts.push(AccountMeta::new_readonly(*overhead_igp_account, false));
+            }
+        }
+
+        let instruction = Instruction::new_with_borsh(
+            self.program_id,
+            &IgpInstruction::QuoteGasPayment(QuoteGasPayment {
+                destination_domain,
+                gas_amount,
+            }),
+            accounts,
+        );
+        let quote = simulate_instruction::<SimulationReturnData<u64>>(
+            &self.rpc_client,
+            self.payer
+                .as_ref()
+                .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?,
+            instruction,
+        )
+        .await?
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str("No return data was returned from the IGP")
+        })?
+        .return_data;
+        Ok(quote.into())
+    }
+
+    async fn fetch_account(&self, pubkey: &Pubkey) -> ChainResult<Account> {
+        self.rpc_client
+            .get_account_with_commitment(pubkey, CommitmentConfig::finalized())
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value
+            .ok_or_else(|| ChainCommunicationError::from_other_str("Could not find account data"))
+    }
 }
 
 impl HyperlaneContract for SealevelInterchainGasPaymaster {
@@ -39,20 +131,155 @@ impl HyperlaneChain for SealevelInterchainGasPaymaster {
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        Box::new(SealevelProvider::new(self.domain.clone()))
+        Box::new(self.provider.clone())
     }
 }
 
 impl InterchainGasPaymaster for SealevelInterchainGasPaymaster {}
 
-/// Struct that retrieves event data for a Sealevel IGP contract
+/// Struct that retrieves event data for a Sealevel IGP contract. Payments
+/// made through any IGP account owned by the program are indexed.
 #[derive(Debug)]
-pub struct SealevelInterchainGasPaymasterIndexer {}
+pub struct SealevelInterchainGasPaymasterIndexer {
+    igp: SealevelInterchainGasPaymaster,
+}
 
 impl SealevelInterchainGasPaymasterIndexer {
     /// Create a new Sealevel IGP indexer.
-    pub fn new(_conf: &ConnectionConf, _locator: ContractLocator) -> Self {
-        Self {}
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        Self {
+            igp: SealevelInterchainGasPaymaster::new(conf, locator, None),
+        }
+    }
+
+    /// The latest finalized slot, since gas payments are positioned by the
+    /// slot they were made in.
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        let slot = self
+            .igp
+            .rpc_client
+            .get_slot_with_commitment(CommitmentConfig::finalized())
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        slot.try_into().map_err(ChainCommunicationError::from_other)
+    }
+
+    /// The number of gas payments made through the program, along with the
+    /// slot at which it was read.
+    async fn payment_count(&self) -> ChainResult<(u32, u64)> {
+        let (program_data_key, _bump) =
+            Pubkey::find_program_address(igp_program_data_pda_seeds!(), &self.igp.program_id);
+        let response = self
+            .igp
+            .rpc_client
+            .get_account_with_commitment(&program_data_key, CommitmentConfig::finalized())
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let account = response.value.ok_or_else(|| {
+            ChainCommunicationError::from_other_str("Could not find account data")
+        })?;
+        let program_data = ProgramDataAccount::fetch(&mut &account.data[..])
+            .map_err(ChainCommunicationError::from_other)?
+            .into_inner();
+        let count = program_data
+            .payment_count
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok((count, response.context.slot))
+    }
+
+    async fn get_payment_with_sequence(
+        &self,
+        sequence: u32,
+    ) -> ChainResult<(InterchainGasPayment, LogMeta)> {
+        let target_payment_account_bytes = &[
+            &GAS_PAYMENT_DISCRIMINATOR[..],
+            &u64::from(sequence).to_le_bytes()[..],
+        ]
+        .concat();
+        let target_payment_account_bytes =
+            base64::engine::general_purpose::STANDARD.encode(target_payment_account_bytes);
+
+        // First, find all accounts with the matching account data.
+        // To keep responses small in case there is ever more than 1
+        // match, we don't request the full account data, and just request
+        // the `unique_gas_payment_pubkey` field.
+        #[allow(deprecated)]
+        let memcmp = RpcFilterType::Memcmp(Memcmp {
+            // Ignore the first byte, which is the `initialized` bool flag.
+            offset: 1,
+            bytes: MemcmpEncodedBytes::Base64(target_payment_account_bytes),
+            encoding: None,
+        });
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![memcmp]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig {
+                    offset: UNIQUE_GAS_PAYMENT_PUBKEY_OFFSET,
+                    length: 32,
+                }),
+                commitment: Some(CommitmentConfig::finalized()),
+                min_context_slot: None,
+            },
+            with_context: Some(false),
+        };
+        let accounts = self
+            .igp
+            .rpc_client
+            .get_program_accounts_with_config(&self.igp.program_id, config)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        // Now loop through matching accounts and find the one with a valid account pubkey
+        // that proves it's an actual gas payment PDA.
+        let valid_payment_pda_pubkey = accounts
+            .iter()
+            .find(|(pubkey, account)| {
+                let unique_gas_payment_pubkey = Pubkey::new(&account.data);
+                Pubkey::try_find_program_address(
+                    igp_gas_payment_pda_seeds!(unique_gas_payment_pubkey),
+                    &self.igp.program_id,
+                )
+                .map(|(expected_pubkey, _bump)| expected_pubkey == *pubkey)
+                .unwrap_or(false)
+            })
+            .map(|(pubkey, _)| *pubkey)
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find valid gas payment PDA pubkey",
+                )
+            })?;
+
+        // Now that we have the valid gas payment PDA pubkey, we can get the full account data.
+        let gas_payment = GasPaymentAccount::fetch(
+            &mut &self
+                .igp
+                .fetch_account(&valid_payment_pda_pubkey)
+                .await?
+                .data[..],
+        )
+        .map_err(ChainCommunicationError::from_other)?
+        .into_inner();
+
+        Ok((
+            InterchainGasPayment {
+                message_id: gas_payment.message_id,
+                payment: gas_payment.payment.into(),
+                gas_amount: gas_payment.gas_amount.into(),
+            },
+            LogMeta {
+                address: self.igp.program_id.to_bytes().into(),
+                block_number: gas_payment.slot,
+                // TODO: get these when building out scraper support.
+                block_hash: H256::zero(),
+                transaction_id: H512::zero(),
+                transaction_index: 0,
+                // Gas payments are deduplicated by their transaction id and
+                // log index, so use the sequence to keep them unique.
+                log_index: gas_payment.sequence_number.into(),
+            },
+        ))
     }
 }
 
@@ -61,16 +288,42 @@ impl Indexer<InterchainGasPayment> for SealevelInterchainGasPaymasterIndexer {
     #[instrument(err, skip(self))]
     async fn fetch_logs(
         &self,
-        _range: IndexRange,
+        range: IndexRange,
     ) -> ChainResult<Vec<(InterchainGasPayment, LogMeta)>> {
-        info!("Gas payment indexing not implemented for Sealevel");
-        Ok(vec![])
+        let SequenceRange(range) = range else {
+            return Err(ChainCommunicationError::from_other_str(
+                "SealevelInterchainGasPaymasterIndexer only supports sequence-based indexing",
+            ))
+        };
+
+        info!(
+            ?range,
+            "Fetching SealevelInterchainGasPaymasterIndexer InterchainGasPayment logs"
+        );
+
+        let mut payments = Vec::with_capacity(range.clone().count());
+        for sequence in range {
+            payments.push(self.get_payment_with_sequence(sequence).await?);
+        }
+        Ok(payments)
     }
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        // As a workaround to avoid gas payment indexing on Sealevel,
-        // we pretend the block number is 1.
-        Ok(1)
+        self.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceIndexer<InterchainGasPayment> for SealevelInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
+    async fn sequence_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // The count and the tip are read from the same response so that they
+        // describe the same state of the chain.
+        let (count, slot) = self.payment_count().await?;
+        let tip = slot
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok((Some(count), tip))
     }
 }
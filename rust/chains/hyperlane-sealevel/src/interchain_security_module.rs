@@ -10,7 +10,7 @@ use hyperlane_core::{
 use hyperlane_sealevel_interchain_security_module_interface::InterchainSecurityModuleInstruction;
 use serializable_account_meta::SimulationReturnData;
 
-use crate::{utils::simulate_instruction, ConnectionConf, RpcClientWithDebug};
+use crate::{utils::simulate_instruction, ConnectionConf, RpcClientWithDebug, SealevelProvider};
 
 /// A reference to an InterchainSecurityModule contract on some Sealevel chain
 #[derive(Debug)]
@@ -19,6 +19,7 @@ pub struct SealevelInterchainSecurityModule {
     payer: Option<Keypair>,
     program_id: Pubkey,
     domain: HyperlaneDomain,
+    provider: SealevelProvider,
 }
 
 impl SealevelInterchainSecurityModule {
@@ -31,6 +32,7 @@ impl SealevelInterchainSecurityModule {
             payer,
             program_id,
             domain: locator.domain.clone(),
+            provider: SealevelProvider::new(locator.domain.clone(), conf),
         }
     }
 }
@@ -47,7 +49,7 @@ impl HyperlaneChain for SealevelInterchainSecurityModule {
     }
 
     fn provider(&self) -> Box<dyn hyperlane_core::HyperlaneProvider> {
-        Box::new(crate::SealevelProvider::new(self.domain.clone()))
+        Box::new(self.provider.clone())
     }
 }
 
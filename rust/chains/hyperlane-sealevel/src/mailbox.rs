@@ -1,23 +1,35 @@
 #![allow(warnings)] // FIXME remove
 
-use std::{collections::HashMap, num::NonZeroU64, str::FromStr as _};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU64,
+    str::FromStr as _,
+};
 
 use async_trait::async_trait;
 use borsh::{BorshDeserialize, BorshSerialize};
-use jsonrpc_core::futures_util::TryFutureExt;
+use jsonrpc_core::futures_util::{future::BoxFuture, TryFutureExt};
+use num_traits::FromPrimitive;
 use tracing::{debug, info, instrument, warn};
 
 use hyperlane_core::{
     accumulator::incremental::IncrementalMerkle, ChainCommunicationError, ChainResult, Checkpoint,
     ContractLocator, Decode as _, Encode as _, HyperlaneAbi, HyperlaneChain, HyperlaneContract,
     HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, IndexRange, Indexer, LogMeta, Mailbox,
-    MessageIndexer, SequenceRange, TxCostEstimate, TxOutcome, H256, H512, U256,
+    MessageIndexer, ModuleType, SequenceIndexer, SequenceRange, TxCostEstimate, TxOutcome, H256,
+    H512, U256,
+};
+use hyperlane_sealevel_aggregation_ism::{
+    instruction::{modules_and_threshold_instruction, ModulesAndThreshold},
+    metadata::AggregationIsmMetadata,
 };
 use hyperlane_sealevel_interchain_security_module_interface::{
     InterchainSecurityModuleInstruction, VerifyInstruction,
 };
 use hyperlane_sealevel_mailbox::{
-    accounts::{DispatchedMessageAccount, InboxAccount, OutboxAccount},
+    accounts::{
+        DispatchedMessageAccount, InboxAccount, OutboxAccount, PROCESSED_MESSAGE_DISCRIMINATOR,
+    },
     instruction::InboxProcess,
     mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds,
     mailbox_process_authority_pda_seeds, mailbox_processed_message_pda_seeds,
@@ -25,11 +37,16 @@ use hyperlane_sealevel_mailbox::{
 use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
 };
+use hyperlane_sealevel_routing_ism::instruction::route_instruction;
 use serializable_account_meta::SimulationReturnData;
 use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
 use solana_client::{
     nonblocking::rpc_client::RpcClient,
-    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig},
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::{
+        RpcAccountInfoConfig, RpcBlockConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+        RpcSimulateTransactionConfig,
+    },
     rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
 };
 use solana_sdk::{
@@ -47,8 +64,8 @@ use solana_sdk::{
 };
 use solana_transaction_status::{
     EncodedConfirmedBlock, EncodedTransaction, EncodedTransactionWithStatusMeta,
-    UiInnerInstructions, UiInstruction, UiMessage, UiParsedInstruction, UiReturnDataEncoding,
-    UiTransaction, UiTransactionReturnData, UiTransactionStatusMeta,
+    TransactionDetails, UiInnerInstructions, UiInstruction, UiMessage, UiParsedInstruction,
+    UiReturnDataEncoding, UiTransaction, UiTransactionReturnData, UiTransactionStatusMeta,
 };
 
 use crate::RpcClientWithDebug;
@@ -61,8 +78,14 @@ const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
 const SPL_NOOP: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
 
 // The max amount of compute units for a transaction.
-// TODO: consider a more sane value and/or use IGP gas payments instead.
 const PROCESS_COMPUTE_UNITS: u32 = 1_400_000;
+/// Compute units used by a process transaction can vary slightly between
+/// simulation and execution, so the estimate is padded by this percentage.
+const PROCESS_COMPUTE_UNITS_BUFFER_PERCENT: u64 = 10;
+
+/// The maximum depth of nested routing and aggregation ISMs that account
+/// metas are resolved for.
+const MAX_ISM_DEPTH: usize = 8;
 
 /// A reference to a Mailbox contract on some Sealevel chain
 pub struct SealevelMailbox {
@@ -72,6 +95,7 @@ pub struct SealevelMailbox {
     rpc_client: RpcClient,
     domain: HyperlaneDomain,
     payer: Option<Keypair>,
+    provider: SealevelProvider,
 }
 
 impl SealevelMailbox {
@@ -102,6 +126,7 @@ impl SealevelMailbox {
             rpc_client,
             domain: locator.domain.clone(),
             payer,
+            provider: SealevelProvider::new(locator.domain.clone(), conf),
         })
     }
 
@@ -194,25 +219,156 @@ impl SealevelMailbox {
     }
 
     /// Gets the account metas required for the ISM's `Verify` instruction.
+    ///
+    /// Routing and aggregation ISMs only return the account metas they require
+    /// themselves, so the program IDs and account metas of the ISMs they
+    /// invoke are resolved and appended here.
     pub async fn get_ism_verify_account_metas(
         &self,
         ism: Pubkey,
         metadata: Vec<u8>,
         message: Vec<u8>,
     ) -> ChainResult<Vec<AccountMeta>> {
-        let instruction =
-            InterchainSecurityModuleInstruction::VerifyAccountMetas(VerifyInstruction {
-                metadata,
-                message,
-            });
-        self.get_account_metas_with_instruction_bytes(
+        self.get_nested_ism_verify_account_metas(ism, metadata, message, HashSet::new())
+            .await
+    }
+
+    /// Recursive part of `get_ism_verify_account_metas`. `ancestors` are the
+    /// ISMs that led to `ism`, used to refuse ISM configurations that nest too
+    /// deeply or route back to themselves.
+    fn get_nested_ism_verify_account_metas(
+        &self,
+        ism: Pubkey,
+        metadata: Vec<u8>,
+        message: Vec<u8>,
+        mut ancestors: HashSet<Pubkey>,
+    ) -> BoxFuture<'_, ChainResult<Vec<AccountMeta>>> {
+        Box::pin(async move {
+            if ancestors.len() >= MAX_ISM_DEPTH {
+                warn!(?ism, depth = ancestors.len(), "ISM is nested too deeply");
+                return Err(ChainCommunicationError::from_other_str(
+                    "ISM nesting exceeds the maximum depth",
+                ));
+            }
+            if !ancestors.insert(ism) {
+                warn!(?ism, "ISM is configured as one of its own modules");
+                return Err(ChainCommunicationError::from_other_str(
+                    "ISM is configured as one of its own modules",
+                ));
+            }
+
+            let instruction =
+                InterchainSecurityModuleInstruction::VerifyAccountMetas(VerifyInstruction {
+                    metadata: metadata.clone(),
+                    message: message.clone(),
+                });
+            let mut account_metas = self
+                .get_account_metas_with_instruction_bytes(
+                    ism,
+                    &instruction
+                        .encode()
+                        .map_err(ChainCommunicationError::from_other)?,
+                    hyperlane_sealevel_interchain_security_module_interface::VERIFY_ACCOUNT_METAS_PDA_SEEDS,
+                )
+                .await?;
+
+            match self.get_module_type(ism).await? {
+                ModuleType::Routing => {
+                    // The routed ISM and the accounts it requires.
+                    let routed_ism = self.get_routed_ism(ism, &message).await?;
+                    account_metas.push(AccountMeta::new_readonly(routed_ism, false));
+                    account_metas.extend(
+                        self.get_nested_ism_verify_account_metas(
+                            routed_ism, metadata, message, ancestors,
+                        )
+                        .await?,
+                    );
+                }
+                ModuleType::Aggregation => {
+                    // The program and accounts of each module that metadata
+                    // was provided for, in the order the modules are configured.
+                    let modules = self.get_aggregated_modules(ism, &message).await?;
+                    let metadata = AggregationIsmMetadata::decode(&metadata, modules.len())
+                        .map_err(ChainCommunicationError::from_other)?;
+                    for (module, sub_metadata) in modules.into_iter().zip(metadata.sub_metadata) {
+                        if let Some(sub_metadata) = sub_metadata {
+                            account_metas.push(AccountMeta::new_readonly(module, false));
+                            account_metas.extend(
+                                self.get_nested_ism_verify_account_metas(
+                                    module,
+                                    sub_metadata,
+                                    message.clone(),
+                                    ancestors.clone(),
+                                )
+                                .await?,
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            Ok(account_metas)
+        })
+    }
+
+    /// Gets the module type of the ISM.
+    async fn get_module_type(&self, ism: Pubkey) -> ChainResult<ModuleType> {
+        let instruction = Instruction::new_with_bytes(
             ism,
-            &instruction
+            &InterchainSecurityModuleInstruction::Type
                 .encode()
                 .map_err(ChainCommunicationError::from_other)?,
-            hyperlane_sealevel_interchain_security_module_interface::VERIFY_ACCOUNT_METAS_PDA_SEEDS,
-        )
-        .await
+            vec![],
+        );
+        let module_type = self
+            .simulate_instruction::<SimulationReturnData<u32>>(instruction)
+            .await?
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str("No return data was returned from the ISM")
+            })?
+            .return_data;
+
+        Ok(ModuleType::from_u32(module_type).unwrap_or(ModuleType::Unused))
+    }
+
+    /// Gets the ISM that a routing ISM routes the message to.
+    async fn get_routed_ism(&self, routing_ism: Pubkey, message: &[u8]) -> ChainResult<Pubkey> {
+        let origin = HyperlaneMessage::read_from(&mut &message[..])
+            .map_err(ChainCommunicationError::from_other)?
+            .origin;
+        let instruction = route_instruction(routing_ism, origin, message.to_vec())
+            .map_err(ChainCommunicationError::from_other)?;
+        let ism = self
+            .simulate_instruction::<SimulationReturnData<Pubkey>>(instruction)
+            .await?
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "No return data was returned from the routing ism",
+                )
+            })?
+            .return_data;
+        Ok(ism)
+    }
+
+    /// Gets the modules of an aggregation ISM.
+    async fn get_aggregated_modules(
+        &self,
+        aggregation_ism: Pubkey,
+        message: &[u8],
+    ) -> ChainResult<Vec<Pubkey>> {
+        let instruction = modules_and_threshold_instruction(aggregation_ism, message.to_vec())
+            .map_err(ChainCommunicationError::from_other)?;
+        let modules_and_threshold = self
+            .simulate_instruction::<SimulationReturnData<ModulesAndThreshold>>(instruction)
+            .await?
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "No return data was returned from the aggregation ism",
+                )
+            })?
+            .return_data;
+        Ok(modules_and_threshold.modules)
     }
 
     /// Gets the account metas required for the recipient's `MessageRecipientInstruction::Handle` instruction.
@@ -253,6 +409,132 @@ impl SealevelMailbox {
 
         self.get_account_metas(instruction).await
     }
+
+    fn process_instruction_data(
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
+        let mut encoded_message = vec![];
+        message.write_to(&mut encoded_message).unwrap();
+
+        hyperlane_sealevel_mailbox::instruction::Instruction::InboxProcess(InboxProcess {
+            metadata: metadata.to_vec(),
+            message: encoded_message,
+        })
+        .into_instruction_data()
+        .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// Builds the `InboxProcess` instruction for a message, including the
+    /// accounts required by the recipient and its ISM.
+    async fn get_process_instruction(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        payer: &Pubkey,
+    ) -> ChainResult<Instruction> {
+        let recipient: Pubkey = message.recipient.0.into();
+        let mut encoded_message = vec![];
+        message.write_to(&mut encoded_message).unwrap();
+
+        let (process_authority_key, _process_authority_bump) = Pubkey::try_find_program_address(
+            mailbox_process_authority_pda_seeds!(&recipient),
+            &self.program_id,
+        )
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str(
+                "Could not find program address for process authority",
+            )
+        })?;
+        let (processed_message_account_key, _processed_message_account_bump) =
+            Pubkey::try_find_program_address(
+                mailbox_processed_message_pda_seeds!(message.id()),
+                &self.program_id,
+            )
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find program address for processed message account",
+                )
+            })?;
+
+        // Get the account metas required for the recipient.InterchainSecurityModule instruction.
+        let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
+
+        // Get the recipient ISM.
+        let ism = self
+            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
+            .await?;
+
+        let ixn_data = Self::process_instruction_data(message, metadata)?;
+
+        // Craft the accounts for the transaction.
+        let mut accounts: Vec<AccountMeta> = vec![
+            AccountMeta::new_readonly(*payer, true),
+            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM).unwrap(), false),
+            AccountMeta::new(self.inbox.0, false),
+            AccountMeta::new_readonly(process_authority_key, false),
+            AccountMeta::new(processed_message_account_key, false),
+        ];
+        accounts.extend(ism_getter_account_metas);
+        accounts.extend([
+            AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
+            AccountMeta::new_readonly(ism, false),
+        ]);
+
+        // Get the account metas required for the ISM.Verify instruction.
+        let ism_verify_account_metas = self
+            .get_ism_verify_account_metas(ism, metadata.into(), encoded_message)
+            .await?;
+        accounts.extend(ism_verify_account_metas);
+
+        // The recipient.
+        accounts.extend([AccountMeta::new_readonly(recipient, false)]);
+
+        // Get account metas required for the Handle instruction
+        let handle_account_metas = self.get_handle_account_metas(message).await?;
+        accounts.extend(handle_account_metas);
+
+        Ok(Instruction {
+            program_id: self.program_id,
+            data: ixn_data,
+            accounts,
+        })
+    }
+
+    /// Prepends the compute budget instructions to the `InboxProcess` instruction.
+    fn process_instructions(
+        inbox_instruction: Instruction,
+        compute_unit_limit: u32,
+        priority_fee: u64,
+    ) -> Vec<Instruction> {
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+            inbox_instruction,
+        ]
+    }
+
+    /// Gets the median priority fee, in micro-lamports per compute unit, paid
+    /// in recent blocks by transactions writing to the instruction's writable
+    /// accounts.
+    async fn get_priority_fee(&self, instruction: &Instruction) -> ChainResult<u64> {
+        let writable_accounts: Vec<Pubkey> = instruction
+            .accounts
+            .iter()
+            .filter(|account| account.is_writable)
+            .map(|account| account.pubkey)
+            .collect();
+        let mut fees: Vec<u64> = self
+            .rpc_client
+            .get_recent_prioritization_fees(&writable_accounts)
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+        fees.sort_unstable();
+        Ok(fees.get(fees.len() / 2).copied().unwrap_or_default())
+    }
 }
 
 impl HyperlaneContract for SealevelMailbox {
@@ -267,7 +549,7 @@ impl HyperlaneChain for SealevelMailbox {
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        Box::new(SealevelProvider::new(self.domain.clone()))
+        Box::new(self.provider.clone())
     }
 }
 
@@ -397,100 +679,32 @@ impl Mailbox for SealevelMailbox {
         &self,
         message: &HyperlaneMessage,
         metadata: &[u8],
-        _tx_gas_limit: Option<U256>,
+        tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        let recipient: Pubkey = message.recipient.0.into();
-        let mut encoded_message = vec![];
-        message.write_to(&mut encoded_message).unwrap();
-
         let payer = self
             .payer
             .as_ref()
             .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
 
-        let mut instructions = Vec::with_capacity(2);
-        // Set the compute unit limit.
-        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
-            PROCESS_COMPUTE_UNITS,
-        ));
-
         // "processed" level commitment does not guarantee finality.
         // roughly 5% of blocks end up on a dropped fork.
         // However we don't want this function to be a bottleneck and there already
         // is retry logic in the agents.
         let commitment = CommitmentConfig::processed();
 
-        let (process_authority_key, _process_authority_bump) = Pubkey::try_find_program_address(
-            mailbox_process_authority_pda_seeds!(&recipient),
-            &self.program_id,
-        )
-        .ok_or_else(|| {
-            ChainCommunicationError::from_other_str(
-                "Could not find program address for process authority",
-            )
-        })?;
-        let (processed_message_account_key, _processed_message_account_bump) =
-            Pubkey::try_find_program_address(
-                mailbox_processed_message_pda_seeds!(message.id()),
-                &self.program_id,
-            )
-            .ok_or_else(|| {
-                ChainCommunicationError::from_other_str(
-                    "Could not find program address for processed message account",
-                )
-            })?;
-
-        // Get the account metas required for the recipient.InterchainSecurityModule instruction.
-        let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
-
-        // Get the recipient ISM.
-        let ism = self
-            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
+        let inbox_instruction = self
+            .get_process_instruction(message, metadata, &payer.pubkey())
             .await?;
-
-        let ixn =
-            hyperlane_sealevel_mailbox::instruction::Instruction::InboxProcess(InboxProcess {
-                metadata: metadata.to_vec(),
-                message: encoded_message.clone(),
-            });
-        let ixn_data = ixn
-            .into_instruction_data()
-            .map_err(ChainCommunicationError::from_other)?;
-
-        // Craft the accounts for the transaction.
-        let mut accounts: Vec<AccountMeta> = vec![
-            AccountMeta::new_readonly(payer.pubkey(), true),
-            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM).unwrap(), false),
-            AccountMeta::new(self.inbox.0, false),
-            AccountMeta::new_readonly(process_authority_key, false),
-            AccountMeta::new(processed_message_account_key, false),
-        ];
-        accounts.extend(ism_getter_account_metas);
-        accounts.extend([
-            AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
-            AccountMeta::new_readonly(ism, false),
-        ]);
-
-        // Get the account metas required for the ISM.Verify instruction.
-        let ism_verify_account_metas = self
-            .get_ism_verify_account_metas(ism, metadata.into(), encoded_message)
-            .await?;
-        accounts.extend(ism_verify_account_metas);
-
-        // The recipient.
-        accounts.extend([AccountMeta::new_readonly(recipient, false)]);
-
-        // Get account metas required for the Handle instruction
-        let handle_account_metas = self.get_handle_account_metas(message).await?;
-        accounts.extend(handle_account_metas);
-
-        let inbox_instruction = Instruction {
-            program_id: self.program_id,
-            data: ixn_data,
-            accounts,
-        };
         tracing::info!("accounts={:#?}", inbox_instruction.accounts);
-        instructions.push(inbox_instruction);
+        let priority_fee = self.get_priority_fee(&inbox_instruction).await?;
+        // Use the estimated compute units if we have them, but never more than
+        // a transaction is allowed.
+        let compute_unit_limit = tx_gas_limit
+            .map(|limit| limit.min(PROCESS_COMPUTE_UNITS.into()).as_u32())
+            .unwrap_or(PROCESS_COMPUTE_UNITS);
+        let instructions =
+            Self::process_instructions(inbox_instruction, compute_unit_limit, priority_fee);
+
         let (recent_blockhash, _) = self
             .rpc_client
             .get_latest_blockhash_with_commitment(commitment)
@@ -528,25 +742,86 @@ impl Mailbox for SealevelMailbox {
         })
     }
 
+    /// Simulates the process transaction. The gas limit is the number of
+    /// compute units consumed plus a buffer, and the gas price is the priority
+    /// fee in micro-lamports per compute unit.
     #[instrument(err, ret, skip(self))]
     async fn process_estimate_costs(
         &self,
-        _message: &HyperlaneMessage,
-        _metadata: &[u8],
+        message: &HyperlaneMessage,
+        metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
-        // TODO use correct data upon integrating IGP support
+        let payer = self
+            .payer
+            .as_ref()
+            .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
+
+        let inbox_instruction = self
+            .get_process_instruction(message, metadata, &payer.pubkey())
+            .await?;
+        let priority_fee = self.get_priority_fee(&inbox_instruction).await?;
+        let instructions =
+            Self::process_instructions(inbox_instruction, PROCESS_COMPUTE_UNITS, priority_fee);
+
+        let simulation = self
+            .rpc_client
+            .simulate_transaction_with_config(
+                &Transaction::new_unsigned(Message::new(&instructions, Some(&payer.pubkey()))),
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    // The message has no blockhash, so let the node fill one in.
+                    replace_recent_blockhash: true,
+                    commitment: Some(CommitmentConfig::processed()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+        if let Some(err) = simulation.err {
+            warn!(logs = ?simulation.logs, "Process simulation failed");
+            return Err(ChainCommunicationError::from_other(err));
+        }
+        let units_consumed = simulation.units_consumed.ok_or_else(|| {
+            ChainCommunicationError::from_other_str(
+                "Process simulation did not report compute units consumed",
+            )
+        })?;
+        let gas_limit = (units_consumed * (100 + PROCESS_COMPUTE_UNITS_BUFFER_PERCENT) / 100)
+            .min(PROCESS_COMPUTE_UNITS.into());
+
         Ok(TxCostEstimate {
-            gas_limit: U256::zero(),
-            gas_price: U256::zero(),
+            gas_limit: gas_limit.into(),
+            gas_price: priority_fee.into(),
             l2_gas_limit: None,
         })
     }
 
-    fn process_calldata(&self, _message: &HyperlaneMessage, _metadata: &[u8]) -> Vec<u8> {
-        todo!()
+    /// Returns the full `InboxProcess` instruction, i.e. the program id, the
+    /// accounts required by the recipient and its ISM, and the instruction
+    /// data, serialized with bincode.
+    async fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
+        let payer = self
+            .payer
+            .as_ref()
+            .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
+        let instruction = self
+            .get_process_instruction(message, metadata, &payer.pubkey())
+            .await?;
+        encode_instruction(&instruction)
     }
 }
 
+/// Serializes an instruction, including its program id and accounts, so that
+/// it can be handed around as calldata.
+fn encode_instruction(instruction: &Instruction) -> ChainResult<Vec<u8>> {
+    bincode::serialize(instruction).map_err(ChainCommunicationError::from_other)
+}
+
 /// Struct that retrieves event data for a Sealevel Mailbox contract
 #[derive(Debug)]
 pub struct SealevelMailboxIndexer {
@@ -679,6 +954,184 @@ impl SealevelMailboxIndexer {
             },
         ))
     }
+
+    /// The number of messages processed by the mailbox.
+    async fn processed_count(&self) -> ChainResult<u32> {
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(&self.mailbox.inbox.0, CommitmentConfig::finalized())
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str("Could not find account data")
+            })?;
+        let inbox = InboxAccount::fetch(&mut account.data.as_ref())
+            .map_err(ChainCommunicationError::from_other)?
+            .into_inner();
+        inbox
+            .processed_count
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    async fn get_delivery_with_sequence(&self, sequence: u32) -> ChainResult<(H256, LogMeta)> {
+        let target_processed_message_account_bytes = &[
+            &PROCESSED_MESSAGE_DISCRIMINATOR[..],
+            &u64::from(sequence).to_le_bytes()[..],
+        ]
+        .concat();
+        let target_processed_message_account_bytes =
+            base64::encode(target_processed_message_account_bytes);
+
+        // First, find all accounts with the matching account data.
+        // To keep responses small in case there is ever more than 1
+        // match, we don't request the full account data, and just request
+        // the `message_id` and `slot` fields.
+        let memcmp = RpcFilterType::Memcmp(Memcmp {
+            // Ignore the first byte, which is the `initialized` bool flag.
+            offset: 1,
+            bytes: MemcmpEncodedBytes::Base64(target_processed_message_account_bytes),
+            encoding: None,
+        });
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![memcmp]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig {
+                    offset: 1 + 8 + 8, // the offset to get the `message_id` field
+                    length: 32 + 8,    // the length of the `message_id` and `slot` fields
+                }),
+                commitment: Some(CommitmentConfig::finalized()),
+                min_context_slot: None,
+            },
+            with_context: Some(false),
+        };
+        let accounts = self
+            .rpc_client
+            .get_program_accounts_with_config(&self.mailbox.program_id, config)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        // Now loop through matching accounts and find the one with a valid account pubkey
+        // that proves it's an actual processed message PDA.
+        let mut valid_processed_message = Option::<(Pubkey, H256, u64)>::None;
+
+        for (pubkey, account) in accounts.iter() {
+            if account.data.len() != 32 + 8 {
+                continue;
+            }
+            let message_id = H256::from_slice(&account.data[..32]);
+            let (expected_pubkey, _bump) = Pubkey::try_find_program_address(
+                mailbox_processed_message_pda_seeds!(message_id),
+                &self.mailbox.program_id,
+            )
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find program address for message_id",
+                )
+            })?;
+            if expected_pubkey == *pubkey {
+                let mut slot = [0u8; 8];
+                slot.copy_from_slice(&account.data[32..]);
+                valid_processed_message = Some((*pubkey, message_id, u64::from_le_bytes(slot)));
+                break;
+            }
+        }
+
+        let (processed_message_pubkey, message_id, slot) =
+            valid_processed_message.ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find valid processed message PDA pubkey",
+                )
+            })?;
+
+        let (transaction_id, block_hash, transaction_index) = self
+            .get_processed_message_transaction(&processed_message_pubkey, slot)
+            .await?;
+
+        Ok((
+            message_id,
+            LogMeta {
+                address: self.mailbox.program_id.to_bytes().into(),
+                block_number: slot,
+                block_hash,
+                transaction_id,
+                transaction_index,
+                log_index: U256::zero(),
+            },
+        ))
+    }
+
+    /// Finds the transaction that created a processed message PDA, returning
+    /// its signature, block hash and index within the block.
+    async fn get_processed_message_transaction(
+        &self,
+        processed_message_pubkey: &Pubkey,
+        slot: u64,
+    ) -> ChainResult<(H512, H256, u64)> {
+        // The PDA is created by the process transaction and never written to
+        // by the mailbox again, so the oldest successful transaction in the
+        // processed slot is the one that created it. Signatures are returned
+        // newest first.
+        let signature = self
+            .rpc_client
+            .get_signatures_for_address_with_config(
+                processed_message_pubkey,
+                GetConfirmedSignaturesForAddress2Config {
+                    commitment: Some(CommitmentConfig::finalized()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .into_iter()
+            .rev()
+            .find(|status| status.slot == slot && status.err.is_none())
+            .map(|status| status.signature)
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find the transaction that processed the message",
+                )
+            })?;
+
+        let block = self
+            .rpc_client
+            .get_block_with_config(
+                slot,
+                RpcBlockConfig {
+                    encoding: None,
+                    transaction_details: Some(TransactionDetails::Signatures),
+                    rewards: Some(false),
+                    commitment: Some(CommitmentConfig::finalized()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let transaction_index = block
+            .signatures
+            .unwrap_or_default()
+            .iter()
+            .position(|block_signature| *block_signature == signature)
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find the transaction in its block",
+                )
+            })?;
+        let block_hash = Hash::from_str(&block.blockhash)
+            .map_err(ChainCommunicationError::from_other)?
+            .to_bytes()
+            .into();
+        let signature =
+            Signature::from_str(&signature).map_err(ChainCommunicationError::from_other)?;
+
+        Ok((
+            H512::from_slice(signature.as_ref()),
+            block_hash,
+            transaction_index as u64,
+        ))
+    }
 }
 
 #[async_trait]
@@ -720,8 +1173,21 @@ impl Indexer<HyperlaneMessage> for SealevelMailboxIndexer {
 
 #[async_trait]
 impl Indexer<H256> for SealevelMailboxIndexer {
-    async fn fetch_logs(&self, _range: IndexRange) -> ChainResult<Vec<(H256, LogMeta)>> {
-        todo!()
+    #[instrument(err, skip(self))]
+    async fn fetch_logs(&self, range: IndexRange) -> ChainResult<Vec<(H256, LogMeta)>> {
+        let SequenceRange(range) = range else {
+            return Err(ChainCommunicationError::from_other_str(
+                "SealevelMailboxIndexer only supports sequence-based indexing",
+            ))
+        };
+
+        info!(?range, "Fetching SealevelMailboxIndexer delivery logs");
+
+        let mut deliveries = Vec::with_capacity(range.clone().count());
+        for sequence in range {
+            deliveries.push(self.get_delivery_with_sequence(sequence).await?);
+        }
+        Ok(deliveries)
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
@@ -729,6 +1195,17 @@ impl Indexer<H256> for SealevelMailboxIndexer {
     }
 }
 
+#[async_trait]
+impl SequenceIndexer<H256> for SealevelMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn sequence_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+        // TODO: need to make sure the call and tip are at the same height?
+        let count = self.processed_count().await?;
+        Ok((Some(count), tip))
+    }
+}
+
 struct SealevelMailboxAbi;
 
 // TODO figure out how this is used and if we can support it for sealevel.
@@ -739,3 +1216,32 @@ impl HyperlaneAbi for SealevelMailboxAbi {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encoded_instruction_includes_program_id_and_accounts() {
+        let message = HyperlaneMessage {
+            recipient: H256::repeat_byte(0x22),
+            body: vec![1, 2, 3],
+            ..Default::default()
+        };
+        let metadata = vec![4, 5, 6];
+        let instruction = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                AccountMeta::new_readonly(Pubkey::new_unique(), true),
+                AccountMeta::new(Pubkey::new_unique(), false),
+            ],
+            data: SealevelMailbox::process_instruction_data(&message, &metadata).unwrap(),
+        };
+
+        let calldata = encode_instruction(&instruction).unwrap();
+        let decoded: Instruction = bincode::deserialize(&calldata).unwrap();
+
+        assert_eq!(decoded, instruction);
+        assert_ne!(calldata, instruction.data);
+    }
+}
@@ -28,6 +28,7 @@ pub struct SealevelMultisigIsm {
     payer: Option<Keypair>,
     program_id: Pubkey,
     domain: HyperlaneDomain,
+    provider: SealevelProvider,
 }
 
 impl SealevelMultisigIsm {
@@ -41,6 +42,7 @@ impl SealevelMultisigIsm {
             payer,
             program_id,
             domain: locator.domain.clone(),
+            provider: SealevelProvider::new(locator.domain.clone(), conf),
         }
     }
 }
@@ -57,7 +59,7 @@ impl HyperlaneChain for SealevelMultisigIsm {
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        Box::new(SealevelProvider::new(self.domain.clone()))
+        Box::new(self.provider.clone())
     }
 }
 
@@ -1,19 +1,32 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use solana_client::rpc_config::{RpcBlockConfig, RpcTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, hash::Hash, pubkey::Pubkey, signature::Signature,
+};
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+use tracing::instrument;
 
 use hyperlane_core::{
-    BlockInfo, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider, TxnInfo, H256,
+    BlockInfo, ChainCommunicationError, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, HyperlaneProviderError, TxnInfo, TxnReceiptInfo, H256, H512, U256,
 };
 
+use crate::{ConnectionConf, RpcClientWithDebug};
+
 /// A wrapper around a Sealevel provider to get generic blockchain information.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SealevelProvider {
     domain: HyperlaneDomain,
+    rpc_client: Arc<RpcClientWithDebug>,
 }
 
 impl SealevelProvider {
     /// Create a new Sealevel provider.
-    pub fn new(domain: HyperlaneDomain) -> Self {
-        SealevelProvider { domain }
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf) -> Self {
+        let rpc_client = Arc::new(RpcClientWithDebug::new(conf.url.to_string()));
+        SealevelProvider { domain, rpc_client }
     }
 }
 
@@ -23,24 +36,114 @@ impl HyperlaneChain for SealevelProvider {
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        Box::new(SealevelProvider {
-            domain: self.domain.clone(),
-        })
+        Box::new(self.clone())
     }
 }
 
 #[async_trait]
 impl HyperlaneProvider for SealevelProvider {
-    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
-        todo!() // FIXME
+    /// Solana has no RPC to look up a block by its hash, so it's looked up by
+    /// its slot instead.
+    #[instrument(err, skip(self))]
+    async fn get_block(&self, hash: &H256, slot: u64) -> ChainResult<BlockInfo> {
+        let block = self
+            .rpc_client
+            .get_block_with_config(
+                slot,
+                RpcBlockConfig {
+                    encoding: None,
+                    transaction_details: Some(TransactionDetails::None),
+                    rewards: Some(false),
+                    commitment: Some(CommitmentConfig::finalized()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        // The slot may have been skipped on a fork, so make sure it holds the
+        // block that was asked for.
+        if block.blockhash != Hash::new_from_array(hash.0).to_string() {
+            return Err(HyperlaneProviderError::CouldNotFindObjectByHash(*hash).into());
+        }
+        Ok(BlockInfo {
+            hash: *hash,
+            timestamp: block.block_time.unwrap_or_default() as u64,
+            number: slot,
+        })
     }
 
-    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
-        todo!() // FIXME
+    #[instrument(err, skip(self))]
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        let signature = Signature::new(hash.as_bytes());
+        let txn = self
+            .rpc_client
+            .get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::finalized()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        let message = txn
+            .transaction
+            .transaction
+            .decode()
+            .ok_or_else(|| ChainCommunicationError::from_other_str("Could not decode transaction"))?
+            .message;
+        let account_keys = message.static_account_keys();
+        // The fee payer is always the first account of a transaction.
+        let sender = account_keys
+            .first()
+            .map(|key| H256::from(key.to_bytes()))
+            .unwrap_or_default();
+        // A transaction may invoke several programs; report the last one.
+        let recipient = message
+            .instructions()
+            .last()
+            .and_then(|ix| account_keys.get(ix.program_id_index as usize))
+            .map(|key| H256::from(key.to_bytes()));
+
+        let receipt = txn.transaction.meta.map(|meta| {
+            let compute_units: Option<u64> = meta.compute_units_consumed.into();
+            let gas_used = U256::from(compute_units.unwrap_or_default());
+            TxnReceiptInfo {
+                gas_used,
+                cumulative_gas_used: gas_used,
+                // Sealevel charges a flat fee per signature rather than a
+                // price per compute unit.
+                effective_gas_price: None,
+            }
+        });
+
+        Ok(TxnInfo {
+            hash: *hash,
+            // Compute units are the closest analogue of gas. Transactions
+            // don't carry an explicit limit, so report what was consumed.
+            gas_limit: receipt.as_ref().map(|r| r.gas_used).unwrap_or_default(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price: None,
+            // Sealevel uses recent blockhashes instead of account nonces.
+            nonce: 0,
+            sender,
+            recipient,
+            receipt,
+        })
     }
 
-    async fn is_contract(&self, _address: &H256) -> ChainResult<bool> {
-        // FIXME
-        Ok(true)
+    #[instrument(err, skip(self))]
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        let pubkey = Pubkey::from(<[u8; 32]>::from(*address));
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(&pubkey, CommitmentConfig::finalized())
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+        Ok(account.map(|a| a.executable).unwrap_or(false))
     }
 }
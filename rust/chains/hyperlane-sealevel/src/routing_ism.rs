@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RawHyperlaneMessage, RoutingIsm, H256,
+};
+use hyperlane_sealevel_routing_ism::instruction::route_instruction;
+use serializable_account_meta::SimulationReturnData;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+
+use crate::{utils::simulate_instruction, ConnectionConf, RpcClientWithDebug, SealevelProvider};
+
+/// A reference to a RoutingIsm contract on some Sealevel chain
+#[derive(Debug)]
+pub struct SealevelRoutingIsm {
+    rpc_client: RpcClientWithDebug,
+    payer: Option<Keypair>,
+    program_id: Pubkey,
+    domain: HyperlaneDomain,
+    provider: SealevelProvider,
+}
+
+impl SealevelRoutingIsm {
+    /// Create a new Sealevel RoutingIsm.
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator, payer: Option<Keypair>) -> Self {
+        let rpc_client = RpcClientWithDebug::new(conf.url.to_string());
+        let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
+
+        Self {
+            rpc_client,
+            payer,
+            program_id,
+            domain: locator.domain.clone(),
+            provider: SealevelProvider::new(locator.domain.clone(), conf),
+        }
+    }
+}
+
+impl HyperlaneContract for SealevelRoutingIsm {
+    fn address(&self) -> H256 {
+        self.program_id.to_bytes().into()
+    }
+}
+
+impl HyperlaneChain for SealevelRoutingIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl RoutingIsm for SealevelRoutingIsm {
+    /// Returns the ISM needed to verify message
+    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
+        let instruction = route_instruction(
+            self.program_id,
+            message.origin,
+            RawHyperlaneMessage::from(message).to_vec(),
+        )
+        .map_err(ChainCommunicationError::from_other)?;
+
+        let ism = simulate_instruction::<SimulationReturnData<Pubkey>>(
+            &self.rpc_client,
+            self.payer
+                .as_ref()
+                .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?,
+            instruction,
+        )
+        .await?
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str(
+                "No return data was returned from the routing ism",
+            )
+        })?
+        .return_data;
+
+        Ok(ism.to_bytes().into())
+    }
+}
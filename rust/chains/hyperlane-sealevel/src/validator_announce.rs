@@ -8,7 +8,7 @@ use hyperlane_core::{
 };
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
 
-use crate::{ConnectionConf, RpcClientWithDebug};
+use crate::{ConnectionConf, RpcClientWithDebug, SealevelProvider};
 use hyperlane_sealevel_validator_announce::{
     accounts::ValidatorStorageLocationsAccount, validator_storage_locations_pda_seeds,
 };
@@ -19,6 +19,7 @@ pub struct SealevelValidatorAnnounce {
     program_id: Pubkey,
     rpc_client: RpcClientWithDebug,
     domain: HyperlaneDomain,
+    provider: SealevelProvider,
 }
 
 impl SealevelValidatorAnnounce {
@@ -30,6 +31,7 @@ impl SealevelValidatorAnnounce {
             program_id,
             rpc_client,
             domain: locator.domain.clone(),
+            provider: SealevelProvider::new(locator.domain.clone(), conf),
         }
     }
 }
@@ -46,7 +48,7 @@ impl HyperlaneChain for SealevelValidatorAnnounce {
     }
 
     fn provider(&self) -> Box<dyn hyperlane_core::HyperlaneProvider> {
-        Box::new(crate::SealevelProvider::new(self.domain.clone()))
+        Box::new(self.provider.clone())
     }
 }
 
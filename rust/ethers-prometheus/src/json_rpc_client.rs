@@ -9,7 +9,7 @@ use derive_builder::Builder;
 use derive_new::new;
 use ethers::prelude::JsonRpcClient;
 use maplit::hashmap;
-use prometheus::{CounterVec, IntCounterVec};
+use prometheus::{CounterVec, GaugeVec, IntCounterVec, IntGaugeVec};
 use serde::{de::DeserializeOwned, Serialize};
 
 pub use crate::ChainInfo;
@@ -48,6 +48,63 @@ pub struct JsonRpcClientMetrics {
     ///   might still be an "error" but not one with the transport layer.
     #[builder(setter(into, strip_option), default)]
     request_duration_seconds: Option<CounterVec>,
+
+    /// Total number of responses from this client that disagreed with the
+    /// response a quorum of other clients agreed on.
+    /// - `provider_node`: node this is connecting to, e.g. `alchemy.com`,
+    ///   `quicknode.pro`, or `localhost:8545`.
+    /// - `chain`: chain name (or chain id if the name is unknown) of the chain
+    ///   the request was made on.
+    /// - `method`: request method string.
+    #[builder(setter(into, strip_option), default)]
+    disagreement_count: Option<IntCounterVec>,
+
+    /// Whether this client was demoted for repeatedly disagreeing with the
+    /// other clients, `1` if it was and `0` otherwise.
+    /// - `provider_node`: node this is connecting to, e.g. `alchemy.com`,
+    ///   `quicknode.pro`, or `localhost:8545`.
+    /// - `chain`: chain name (or chain id if the name is unknown) of the chain
+    ///   the client is connected to.
+    #[builder(setter(into, strip_option), default)]
+    demoted: Option<IntGaugeVec>,
+
+    /// Health score of this client between `0` and `1`, from its latency,
+    /// error rate and how far it lags behind the highest block seen.
+    /// - `provider_node`: node this is connecting to, e.g. `alchemy.com`,
+    ///   `quicknode.pro`, or `localhost:8545`.
+    /// - `chain`: chain name (or chain id if the name is unknown) of the chain
+    ///   the client is connected to.
+    #[builder(setter(into, strip_option), default)]
+    health_score: Option<GaugeVec>,
+}
+
+impl JsonRpcClientMetrics {
+    /// Record that a response from a node disagreed with the response a quorum
+    /// of other nodes agreed on.
+    pub fn increment_disagreements(&self, provider_node: &str, chain: &str, method: &str) {
+        if let Some(counter) = &self.disagreement_count {
+            counter
+                .with_label_values(&[provider_node, chain, method])
+                .inc()
+        }
+    }
+
+    /// Record whether a node is demoted for repeatedly disagreeing with the
+    /// other nodes.
+    pub fn set_demoted(&self, provider_node: &str, chain: &str, demoted: bool) {
+        if let Some(gauge) = &self.demoted {
+            gauge
+                .with_label_values(&[provider_node, chain])
+                .set(demoted as i64)
+        }
+    }
+
+    /// Record the health score of a node.
+    pub fn set_health_score(&self, provider_node: &str, chain: &str, score: f64) {
+        if let Some(gauge) = &self.health_score {
+            gauge.with_label_values(&[provider_node, chain]).set(score)
+        }
+    }
 }
 
 /// Expected label names for the metric.
@@ -61,6 +118,24 @@ pub const REQUEST_DURATION_SECONDS_LABELS: &[&str] =
 /// Help string for the metric.
 pub const REQUEST_DURATION_SECONDS_HELP: &str = "Total number of seconds spent making requests";
 
+/// Expected label names for the metric.
+pub const DISAGREEMENT_COUNT_LABELS: &[&str] = &["provider_node", "chain", "method"];
+/// Help string for the metric.
+pub const DISAGREEMENT_COUNT_HELP: &str =
+    "Total number of responses from this client that disagreed with a quorum of other clients";
+
+/// Expected label names for the metric.
+pub const DEMOTED_LABELS: &[&str] = &["provider_node", "chain"];
+/// Help string for the metric.
+pub const DEMOTED_HELP: &str =
+    "Whether this client was demoted for repeatedly disagreeing with other clients";
+
+/// Expected label names for the metric.
+pub const HEALTH_SCORE_LABELS: &[&str] = &["provider_node", "chain"];
+/// Help string for the metric.
+pub const HEALTH_SCORE_HELP: &str =
+    "Health score of this client from its latency, error rate and block height lag";
+
 /// Configuration for the prometheus JsonRpcClioent. This can be loaded via
 /// serde.
 #[derive(Default, Clone, Debug)]
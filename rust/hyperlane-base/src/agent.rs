@@ -6,7 +6,10 @@ use futures_util::future::select_all;
 use tokio::task::JoinHandle;
 use tracing::{debug_span, instrument::Instrumented, Instrument};
 
-use crate::{metrics::CoreMetrics, settings::Settings};
+use crate::{
+    metrics::{CoreMetrics, HttpRoutes},
+    settings::Settings,
+};
 
 /// Properties shared across all hyperlane agents
 #[derive(Debug)]
@@ -39,6 +42,11 @@ pub trait BaseAgent: Send + Sync + Debug {
     where
         Self: Sized;
 
+    /// Routes to serve on the agent's HTTP server alongside the metrics.
+    fn http_routes(&self) -> Option<HttpRoutes> {
+        None
+    }
+
     /// Start running this agent.
     #[allow(clippy::async_yields_async)]
     async fn run(self) -> Instrumented<JoinHandle<Result<()>>>;
@@ -68,7 +76,7 @@ pub async fn agent_main<A: BaseAgent>() -> Result<()> {
     let metrics = settings.as_ref().metrics(A::AGENT_NAME)?;
     core_settings.tracing.start_tracing(&metrics)?;
     let agent = A::from_settings(settings, metrics.clone()).await?;
-    metrics.run_http_server();
+    metrics.run_http_server(agent.http_routes());
 
     agent.run().await.await?
 }
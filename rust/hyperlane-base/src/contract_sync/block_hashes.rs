@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tracing::warn;
+
+use hyperlane_core::{ChainResult, Indexer, LogMeta, H256};
+
+/// Maximum number of indexed blocks whose hashes are remembered. Reorgs
+/// deeper than the oldest remembered block are rolled back to that block.
+const MAX_INDEXED_BLOCKS: usize = 256;
+
+/// How often the remembered hashes are compared with the chain. Cursors index
+/// blocks that are already final, so reorgs of them are rare enough that
+/// checking every time a cursor moves isn't worth an RPC call.
+const REORG_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The hashes of recently indexed blocks, which a cursor compares with the
+/// chain to detect that logs it indexed were removed by a reorg.
+#[derive(Debug, Default)]
+pub(crate) struct IndexedBlockHashes {
+    hashes: BTreeMap<u32, H256>,
+    /// The latest indexed block whose hash is looked up at the next check
+    unhashed_block: Option<u32>,
+    last_checked: Option<Instant>,
+}
+
+impl IndexedBlockHashes {
+    /// Remember the hashes of the blocks logs were found in.
+    pub fn record_logs<T>(&mut self, logs: &[(T, LogMeta)]) {
+        for (_, meta) in logs {
+            if let Ok(block_number) = u32::try_from(meta.block_number) {
+                self.insert(block_number, meta.block_hash);
+            }
+        }
+    }
+
+    /// Remember a block that was indexed, e.g. the last block of an indexed
+    /// range, so that reorgs which add logs to blocks that had none are
+    /// detected too. Its hash is looked up at the next check, so a reorg of
+    /// such a block before then goes undetected.
+    pub fn record_block(&mut self, block_number: u32) {
+        self.unhashed_block = self.unhashed_block.max(Some(block_number));
+    }
+
+    /// Look up and remember the hash of the latest block that was recorded
+    /// without one. Failing to get the hash only means reorgs of it may go
+    /// undetected, so it is not treated as an error.
+    async fn hash_recorded_block<T, I>(&mut self, indexer: &I)
+    where
+        I: Indexer<T> + ?Sized,
+    {
+        let Some(block_number) = self.unhashed_block.take() else { return };
+        match indexer.fetch_block_hash(block_number).await {
+            Ok(Some(hash)) => self.insert(block_number, hash),
+            Ok(None) => {}
+            Err(err) => warn!(block_number, ?err, "Failed to get hash of indexed block"),
+        }
+    }
+
+    fn insert(&mut self, block_number: u32, hash: H256) {
+        self.hashes.insert(block_number, hash);
+        while self.hashes.len() > MAX_INDEXED_BLOCKS {
+            self.hashes.pop_first();
+        }
+    }
+
+    /// Compare the remembered hashes with the chain's, starting from the most
+    /// recently indexed block, and return the first block whose logs have to
+    /// be rolled back and re-indexed, if any. Forgets the hashes of the blocks
+    /// that are rolled back. This only checks the chain once every
+    /// `REORG_CHECK_INTERVAL` and returns `None` in between.
+    pub async fn find_reorg<T, I>(&mut self, indexer: &I) -> ChainResult<Option<u32>>
+    where
+        I: Indexer<T> + ?Sized,
+    {
+        let now = Instant::now();
+        if matches!(self.last_checked, Some(last) if now < last + REORG_CHECK_INTERVAL) {
+            return Ok(None);
+        }
+        let reorged = self.compare_with_chain(indexer).await?;
+        self.last_checked = Some(now);
+        if reorged.is_none() {
+            self.hash_recorded_block(indexer).await;
+        }
+        Ok(reorged)
+    }
+
+    async fn compare_with_chain<T, I>(&mut self, indexer: &I) -> ChainResult<Option<u32>>
+    where
+        I: Indexer<T> + ?Sized,
+    {
+        let mut reorged = None;
+        for (&block_number, &hash) in self.hashes.iter().rev() {
+            match indexer.fetch_block_hash(block_number).await? {
+                // The indexer can't look up block hashes, so reorgs can't be detected
+                None => return Ok(None),
+                // Blocks before one that is still on the chain are too
+                Some(chain_hash) if chain_hash == hash => {
+                    reorged = reorged.map(|_| block_number + 1);
+                    break;
+                }
+                Some(chain_hash) => {
+                    warn!(
+                        block_number,
+                        indexed_hash = ?hash,
+                        ?chain_hash,
+                        "Indexed block is no longer part of the chain"
+                    );
+                    reorged = Some(block_number);
+                }
+            }
+        }
+        if let Some(from) = reorged {
+            if self.hashes.keys().next() == Some(&from) {
+                warn!(
+                    from,
+                    "Reorg may be deeper than the oldest indexed block that is remembered"
+                );
+            }
+            self.hashes.retain(|&block_number, _| block_number < from);
+            self.unhashed_block = self
+                .unhashed_block
+                .filter(|&block_number| block_number < from);
+        }
+        Ok(reorged)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use hyperlane_core::{ChainResult, IndexRange, H512, U256};
+
+    use super::*;
+
+    /// An indexer of a chain whose block hashes can be changed
+    #[derive(Debug, Default)]
+    struct MockChain {
+        hashes: Mutex<HashMap<u32, H256>>,
+        hash_lookups: AtomicUsize,
+    }
+
+    impl MockChain {
+        fn set_hash(&self, block_number: u32, hash: u64) {
+            self.hashes
+                .lock()
+                .unwrap()
+                .insert(block_number, H256::from_low_u64_be(hash));
+        }
+    }
+
+    #[async_trait]
+    impl Indexer<()> for MockChain {
+        async fn fetch_logs(&self, _: IndexRange) -> ChainResult<Vec<((), LogMeta)>> {
+            Ok(vec![])
+        }
+
+        async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+            Ok(0)
+        }
+
+        async fn fetch_block_hash(&self, block_number: u32) -> ChainResult<Option<H256>> {
+            self.hash_lookups.fetch_add(1, Ordering::SeqCst);
+            Ok(self.hashes.lock().unwrap().get(&block_number).copied())
+        }
+    }
+
+    fn log_at(block_number: u32, hash: u64) -> ((), LogMeta) {
+        let meta = LogMeta {
+            address: H256::zero(),
+            block_number: block_number.into(),
+            block_hash: H256::from_low_u64_be(hash),
+            transaction_id: H512::zero(),
+            transaction_index: 0,
+            log_index: U256::zero(),
+        };
+        ((), meta)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_finds_first_reorged_block() {
+        let chain = MockChain::default();
+        for block_number in 0..=30 {
+            chain.set_hash(block_number, block_number.into());
+        }
+        let mut hashes = IndexedBlockHashes::default();
+        hashes.record_logs(&[log_at(5, 5)]);
+        hashes.record_block(10);
+        assert_eq!(hashes.find_reorg(&chain).await.unwrap(), None);
+        hashes.record_logs(&[log_at(15, 15)]);
+        hashes.record_block(20);
+        tokio::time::advance(REORG_CHECK_INTERVAL).await;
+        assert_eq!(hashes.find_reorg(&chain).await.unwrap(), None);
+        assert_eq!(
+            hashes.hashes.keys().copied().collect::<Vec<_>>(),
+            vec![5, 10, 15, 20]
+        );
+
+        // Blocks from 16 onwards were replaced
+        for block_number in 16..=30 {
+            chain.set_hash(block_number, 100 + u64::from(block_number));
+        }
+        tokio::time::advance(REORG_CHECK_INTERVAL).await;
+        assert_eq!(hashes.find_reorg(&chain).await.unwrap(), Some(16));
+        tokio::time::advance(REORG_CHECK_INTERVAL).await;
+        assert_eq!(hashes.find_reorg(&chain).await.unwrap(), None);
+
+        // Blocks from 5 onwards were replaced, so everything remembered is rolled back
+        for block_number in 5..=30 {
+            chain.set_hash(block_number, 200 + u64::from(block_number));
+        }
+        tokio::time::advance(REORG_CHECK_INTERVAL).await;
+        assert_eq!(hashes.find_reorg(&chain).await.unwrap(), Some(5));
+        assert!(hashes.hashes.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_checks_the_chain_once_per_interval() {
+        let chain = MockChain::default();
+        for block_number in 0..=30 {
+            chain.set_hash(block_number, block_number.into());
+        }
+        let mut hashes = IndexedBlockHashes::default();
+        hashes.record_logs(&[log_at(5, 5)]);
+        for block_number in 10..=20 {
+            hashes.record_block(block_number);
+            assert_eq!(hashes.find_reorg(&chain).await.unwrap(), None);
+        }
+        // The remembered block was compared and the latest recorded one hashed
+        assert_eq!(chain.hash_lookups.load(Ordering::SeqCst), 2);
+
+        tokio::time::advance(REORG_CHECK_INTERVAL).await;
+        assert_eq!(hashes.find_reorg(&chain).await.unwrap(), None);
+        assert_eq!(chain.hash_lookups.load(Ordering::SeqCst), 4);
+        assert_eq!(
+            hashes.hashes.keys().copied().collect::<Vec<_>>(),
+            vec![5, 10, 20]
+        );
+    }
+
+    #[test]
+    fn test_remembers_a_bounded_number_of_blocks() {
+        let mut hashes = IndexedBlockHashes::default();
+        let logs: Vec<_> = (0..MAX_INDEXED_BLOCKS as u32 + 10)
+            .map(|block_number| log_at(block_number, block_number.into()))
+            .collect();
+        hashes.record_logs(&logs);
+        assert_eq!(hashes.hashes.len(), MAX_INDEXED_BLOCKS);
+        assert_eq!(hashes.hashes.keys().next(), Some(&10));
+    }
+}
@@ -12,12 +12,12 @@ use tokio::time::sleep;
 use tracing::{debug, warn};
 
 use hyperlane_core::{
-    BlockRange, ChainResult, ContractSyncCursor, CursorAction, HyperlaneMessage,
-    HyperlaneMessageStore, HyperlaneWatermarkedLogStore, IndexMode, IndexRange, Indexer, LogMeta,
-    MessageIndexer, SequenceRange,
+    BlockRange, ChainCommunicationError, ChainResult, ContractSyncCursor, CursorAction,
+    HyperlaneMessage, HyperlaneMessageStore, HyperlaneWatermarkedLogStore, IndexMode, IndexRange,
+    Indexer, LogMeta, MessageIndexer, SequenceIndexer, SequenceRange,
 };
 
-use crate::contract_sync::eta_calculator::SyncerEtaCalculator;
+use crate::contract_sync::{block_hashes::IndexedBlockHashes, eta_calculator::SyncerEtaCalculator};
 
 /// Time window for the moving average used in the eta calculator in seconds.
 const ETA_TIME_WINDOW: f64 = 2. * 60.;
@@ -78,6 +78,26 @@ impl MessageSyncCursor {
             Ok(())
         }
     }
+
+    /// Rewind to re-index the messages dispatched from a block onwards, which
+    /// were removed by a reorg.
+    async fn rewind(&mut self, from_block: u32) {
+        self.next_block = u32::min(self.next_block, from_block);
+        while self.next_nonce > 0 {
+            match self
+                .retrieve_dispatched_block_number(self.next_nonce - 1)
+                .await
+            {
+                Some(block_number) if block_number >= from_block => self.next_nonce -= 1,
+                _ => break,
+            }
+        }
+        warn!(
+            next_block = self.next_block,
+            next_nonce = self.next_nonce,
+            "Rewound to before reorg"
+        );
+    }
 }
 
 /// A MessageSyncCursor that syncs forwards in perpetuity.
@@ -85,10 +105,58 @@ impl MessageSyncCursor {
 pub(crate) struct ForwardMessageSyncCursor {
     cursor: MessageSyncCursor,
     mode: IndexMode,
+    /// Hashes of the blocks that were indexed, to detect reorgs.
+    #[new(default)]
+    indexed_blocks: IndexedBlockHashes,
+    /// The first nonce the cursor fast forwarded over.
+    #[new(default)]
+    first_synced_nonce: Option<u32>,
 }
 
 impl ForwardMessageSyncCursor {
+    /// If blocks that were indexed are no longer part of the chain, rewinds
+    /// to re-index them and returns the first block to roll back.
+    async fn check_for_reorg(&mut self) -> ChainResult<Option<u32>> {
+        let Some(from) = self.indexed_blocks.find_reorg(&*self.cursor.indexer).await? else {
+            return Ok(None);
+        };
+        self.cursor.rewind(from).await;
+        Ok(Some(from))
+    }
+
+    /// Rewind to messages that were fast forwarded over but have since been
+    /// removed from the DB, e.g. by a subscription that saw a reorg remove
+    /// them, so that they are indexed again.
+    async fn rewind_to_removed_messages(&mut self) {
+        let Some(first_synced_nonce) = self.first_synced_nonce else { return };
+        let next_nonce = self.cursor.next_nonce;
+        while self.cursor.next_nonce > first_synced_nonce
+            && self
+                .cursor
+                .retrieve_message_by_nonce(self.cursor.next_nonce - 1)
+                .await
+                .is_none()
+        {
+            self.cursor.next_nonce -= 1;
+        }
+        if self.cursor.next_nonce < next_nonce {
+            // Rewind to the block of the last message that is still stored
+            let prev_nonce = self.cursor.next_nonce.saturating_sub(1);
+            self.cursor.next_block = self
+                .cursor
+                .retrieve_dispatched_block_number(prev_nonce)
+                .await
+                .unwrap_or(self.cursor.start_block);
+            warn!(
+                next_nonce = self.cursor.next_nonce,
+                next_block = self.cursor.next_block,
+                "Rewound to messages removed from the db"
+            );
+        }
+    }
+
     async fn get_next_range(&mut self) -> ChainResult<Option<IndexRange>> {
+        self.rewind_to_removed_messages().await;
         // Check if any new messages have been inserted into the DB,
         // and update the cursor accordingly.
         while self
@@ -97,6 +165,8 @@ impl ForwardMessageSyncCursor {
             .await
             .is_some()
         {
+            self.first_synced_nonce
+                .get_or_insert(self.cursor.next_nonce);
             if let Some(block_number) = self
                 .cursor
                 .retrieve_dispatched_block_number(self.cursor.next_nonce)
@@ -131,7 +201,7 @@ impl ForwardMessageSyncCursor {
                 self.cursor.next_block = to + 1;
 
                 let range = match self.mode {
-                    IndexMode::Block => BlockRange(from..=to),
+                    IndexMode::Block | IndexMode::Subscription => BlockRange(from..=to),
                     IndexMode::Sequence => SequenceRange(
                         cursor_count
                             ..=u32::min(
@@ -158,6 +228,9 @@ impl ContractSyncCursor<HyperlaneMessage> for ForwardMessageSyncCursor {
     async fn next_action(&mut self) -> ChainResult<(CursorAction, Duration)> {
         // TODO: Fix ETA calculation
         let eta = Duration::from_secs(0);
+        if let Some(from) = self.check_for_reorg().await? {
+            return Ok((CursorAction::Rollback(from), eta));
+        }
         if let Some(range) = self.get_next_range().await? {
             Ok((CursorAction::Query(range), eta))
         } else {
@@ -174,6 +247,12 @@ impl ContractSyncCursor<HyperlaneMessage> for ForwardMessageSyncCursor {
     /// at which it was dispatched.
     /// Otherwise, rewind all the way back to the start block.
     async fn update(&mut self, logs: Vec<(HyperlaneMessage, LogMeta)>) -> Result<()> {
+        self.indexed_blocks.record_logs(&logs);
+        if !matches!(self.mode, IndexMode::Sequence) {
+            // The range that was queried ends right before the next block
+            let range_end = self.cursor.next_block.saturating_sub(1);
+            self.indexed_blocks.record_block(range_end);
+        }
         let prev_nonce = self.cursor.next_nonce.saturating_sub(1);
         // We may wind up having re-indexed messages that are previous to the nonce that we are looking for.
         // We should not consider these messages when checking for continuity errors.
@@ -235,7 +314,7 @@ impl BackwardMessageSyncCursor {
         let next_nonce = self.cursor.next_nonce;
 
         let range = match self.mode {
-            IndexMode::Block => BlockRange(from..=to),
+            IndexMode::Block | IndexMode::Subscription => BlockRange(from..=to),
             IndexMode::Sequence => {
                 SequenceRange(next_nonce.saturating_sub(MAX_SEQUENCE_RANGE)..=next_nonce)
             }
@@ -310,6 +389,10 @@ impl ContractSyncCursor<HyperlaneMessage> for ForwardBackwardMessageSyncCursor {
     async fn next_action(&mut self) -> ChainResult<(CursorAction, Duration)> {
         // TODO: Proper ETA for backwards sync
         let eta = Duration::from_secs(0);
+        if let Some(from) = self.forward.check_for_reorg().await? {
+            self.direction = SyncDirection::Forward;
+            return Ok((CursorAction::Rollback(from), eta));
+        }
         // Prioritize forward syncing over backward syncing.
         if let Some(forward_range) = self.forward.get_next_range().await? {
             self.direction = SyncDirection::Forward;
@@ -348,6 +431,8 @@ pub(crate) struct RateLimitedContractSyncCursor<T> {
     from: u32,
     eta_calculator: SyncerEtaCalculator,
     initial_height: u32,
+    /// Hashes of the blocks that were indexed, to detect reorgs.
+    indexed_blocks: IndexedBlockHashes,
 }
 
 impl<T> RateLimitedContractSyncCursor<T> {
@@ -368,6 +453,7 @@ impl<T> RateLimitedContractSyncCursor<T> {
             from: initial_height,
             initial_height,
             eta_calculator: SyncerEtaCalculator::new(initial_height, tip, ETA_TIME_WINDOW),
+            indexed_blocks: IndexedBlockHashes::default(),
         })
     }
 
@@ -409,6 +495,12 @@ where
     T: Send + Debug + 'static,
 {
     async fn next_action(&mut self) -> ChainResult<(CursorAction, Duration)> {
+        if let Some(from) = self.indexed_blocks.find_reorg(&*self.indexer).await? {
+            // Re-index from the first block that is no longer part of the chain
+            self.from = u32::min(self.from, from);
+            warn!(from = self.from, "Rewound to before reorg");
+            return Ok((CursorAction::Rollback(from), Duration::from_secs(0)));
+        }
         let to = u32::min(self.tip, self.from + self.chunk_size);
         let from = to.saturating_sub(self.chunk_size);
         let eta = if to < self.tip {
@@ -422,10 +514,7 @@ where
             CursorAction::Sleep(rate_limit)
         } else {
             self.from = to + 1;
-            // TODO: note at the moment IndexModes are not considered here, and
-            // block-based indexing is always used.
-            // This should be changed when Sealevel IGP indexing is implemented,
-            // along with a refactor to better accommodate indexing modes.
+            // Sequence-based indexing is handled by `SequenceSyncCursor`.
             CursorAction::Query(BlockRange(from..=to))
         };
         Ok((action, eta))
@@ -435,7 +524,11 @@ where
         self.from.saturating_sub(1)
     }
 
-    async fn update(&mut self, _: Vec<(T, LogMeta)>) -> Result<()> {
+    async fn update(&mut self, logs: Vec<(T, LogMeta)>) -> Result<()> {
+        self.indexed_blocks.record_logs(&logs);
+        // The range that was queried ends right before the next block
+        self.indexed_blocks
+            .record_block(self.from.saturating_sub(1));
         // Store a relatively conservative view of the high watermark, which should allow a single watermark to be
         // safely shared across multiple cursors, so long as they are running sufficiently in sync
         self.db
@@ -447,3 +540,83 @@ where
         Ok(())
     }
 }
+
+/// A cursor that syncs sequenced events (e.g. gas payments on Sealevel)
+/// forwards in perpetuity. It stores the next sequence to index as the
+/// sequence watermark.
+pub(crate) struct SequenceSyncCursor<T> {
+    indexer: Arc<dyn SequenceIndexer<T>>,
+    db: Arc<dyn HyperlaneWatermarkedLogStore<T>>,
+    /// The next sequence that should be indexed.
+    next_sequence: u32,
+    /// The tip reported alongside the latest sequence count.
+    tip: u32,
+    /// How long to wait before checking for new sequences once synced.
+    poll_interval: Duration,
+    eta_calculator: SyncerEtaCalculator,
+}
+
+impl<T> SequenceSyncCursor<T> {
+    /// Construct a new sequence sync cursor.
+    pub async fn new(
+        indexer: Arc<dyn SequenceIndexer<T>>,
+        db: Arc<dyn HyperlaneWatermarkedLogStore<T>>,
+        next_sequence: u32,
+        poll_interval: Duration,
+    ) -> Result<Self> {
+        let (count, tip) = indexer.sequence_and_tip().await?;
+        let Some(count) = count else {
+            eyre::bail!("Sequence-based indexing is not supported by this indexer");
+        };
+        Ok(Self {
+            indexer,
+            db,
+            next_sequence,
+            tip,
+            poll_interval,
+            // The calculator works on any monotonic position, so it is fed
+            // sequences instead of blocks.
+            eta_calculator: SyncerEtaCalculator::new(next_sequence, count, ETA_TIME_WINDOW),
+        })
+    }
+}
+
+#[async_trait]
+impl<T> ContractSyncCursor<T> for SequenceSyncCursor<T>
+where
+    T: Send + Debug + 'static,
+{
+    async fn next_action(&mut self) -> ChainResult<(CursorAction, Duration)> {
+        let (count, tip) = self.indexer.sequence_and_tip().await?;
+        let count = count.ok_or_else(|| {
+            ChainCommunicationError::from_other_str("Indexer did not return a sequence count")
+        })?;
+        self.tip = tip;
+
+        if self.next_sequence >= count {
+            return Ok((
+                CursorAction::Sleep(self.poll_interval),
+                Duration::from_secs(0),
+            ));
+        }
+
+        let from = self.next_sequence;
+        let to = u32::min(count - 1, from + MAX_SEQUENCE_RANGE);
+        let eta = if to + 1 < count {
+            self.eta_calculator.calculate(from, count)
+        } else {
+            Duration::from_secs(0)
+        };
+        self.next_sequence = to + 1;
+        Ok((CursorAction::Query(SequenceRange(from..=to)), eta))
+    }
+
+    fn latest_block(&self) -> u32 {
+        self.tip
+    }
+
+    async fn update(&mut self, _: Vec<(T, LogMeta)>) -> Result<()> {
+        self.db.store_sequence_watermark(self.next_sequence).await?;
+        Ok(())
+    }
+}
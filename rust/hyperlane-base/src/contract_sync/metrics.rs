@@ -18,6 +18,14 @@ pub struct ContractSyncMetrics {
     /// - `chain`: Chain the indexer is collecting data from.
     pub stored_events: IntCounterVec,
 
+    /// Reorgs that removed indexed blocks from the chain, after which the
+    /// logs stored from those blocks were rolled back and re-indexed.
+    ///
+    /// Labels:
+    /// - `data_type`: the data the indexer is recording. E.g. `messages` or `gas_payments`.
+    /// - `chain`: Chain the indexer is collecting data from.
+    pub reorgs: IntCounterVec,
+
     /// See `last_known_message_nonce` in CoreMetrics.
     pub message_nonce: IntGaugeVec,
 }
@@ -41,11 +49,20 @@ impl ContractSyncMetrics {
             )
             .expect("failed to register stored_events metric");
 
+        let reorgs = metrics
+            .new_int_counter(
+                "contract_sync_reorgs",
+                "Number of reorgs of indexed blocks that were rolled back",
+                &["data_type", "chain"],
+            )
+            .expect("failed to register reorgs metric");
+
         let message_nonce = metrics.last_known_message_nonce();
 
         ContractSyncMetrics {
             indexed_height,
             stored_events,
+            reorgs,
             message_nonce,
         }
     }
@@ -1,21 +1,33 @@
-use std::{fmt::Debug, marker::PhantomData, sync::Arc};
+use std::{fmt::Debug, marker::PhantomData, sync::Arc, time::Duration};
 
+use async_trait::async_trait;
 use cursor::*;
 use derive_new::new;
 use hyperlane_core::{
     utils::fmt_sync_time, ContractSyncCursor, CursorAction, HyperlaneDomain, HyperlaneLogStore,
-    HyperlaneMessage, HyperlaneMessageStore, HyperlaneWatermarkedLogStore, Indexer, MessageIndexer,
+    HyperlaneMessage, HyperlaneMessageStore, HyperlaneWatermarkedLogStore, IndexMode, Indexer,
+    LogMeta, LogSubscriber, MessageIndexer, SequenceIndexer,
 };
 pub use metrics::ContractSyncMetrics;
+use prometheus::IntCounter;
 use tokio::time::sleep;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::settings::IndexSettings;
 
+mod block_hashes;
 mod cursor;
 mod eta_calculator;
 mod metrics;
 
+/// How long to wait before subscribing to logs again after a subscription
+/// ended.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// How long to wait before asking a cursor for its next action again after it
+/// failed, e.g. because the RPC to check for reorgs failed.
+const NEXT_ACTION_RETRY_DELAY: Duration = Duration::from_secs(5);
+
 /// Entity that drives the syncing of an agent's db with on-chain data.
 /// Extracts chain-specific data (emitted checkpoints, messages, etc) from an
 /// `indexer` and fills the agent's db with this data.
@@ -55,10 +67,18 @@ where
             .metrics
             .stored_events
             .with_label_values(&[label, chain_name]);
+        let reorgs = self.metrics.reorgs.with_label_values(&[label, chain_name]);
 
         loop {
             indexed_height.set(cursor.latest_block() as i64);
-            let Ok((action, eta)) = cursor.next_action().await else { continue };
+            let (action, eta) = match cursor.next_action().await {
+                Ok(next_action) => next_action,
+                Err(err) => {
+                    warn!(?err, "Failed to get the next cursor action, retrying");
+                    sleep(NEXT_ACTION_RETRY_DELAY).await;
+                    continue;
+                }
+            };
             match action {
                 CursorAction::Query(range) => {
                     debug!(?range, "Looking for for events in index range");
@@ -81,14 +101,77 @@ where
                 CursorAction::Sleep(duration) => {
                     sleep(duration).await;
                 }
+                CursorAction::Rollback(from_block) => {
+                    // Remove what was stored from blocks a reorg removed
+                    let removed = self.db.rollback_logs(from_block).await?;
+                    warn!(from_block, removed, "Rolled back logs after reorg");
+                    reorgs.inc();
+                    // Let the cursor store how far it rewound
+                    cursor.update(vec![]).await?;
+                }
             }
         }
     }
+
+    /// Store logs as soon as they are final using a subscription, which is
+    /// subscribed to again whenever it ends. Meant to run alongside `sync`,
+    /// whose cursor fills any gaps the subscription leaves.
+    #[tracing::instrument(name = "ContractSubscription", fields(domain=self.domain().name()), skip(self, subscriber))]
+    pub async fn subscribe(
+        &self,
+        label: &'static str,
+        subscriber: Arc<dyn LogSubscriber<T>>,
+    ) -> eyre::Result<()> {
+        let store = MeteredLogStore {
+            db: &self.db,
+            stored_logs: self
+                .metrics
+                .stored_events
+                .with_label_values(&[label, self.domain.as_ref()]),
+        };
+        loop {
+            match subscriber.subscribe_logs(&store).await {
+                Ok(()) => warn!("Log subscription ended, subscribing again"),
+                Err(err) => warn!(?err, "Log subscription failed, subscribing again"),
+            }
+            sleep(RESUBSCRIBE_DELAY).await;
+        }
+    }
+}
+
+/// Stores logs in the db of a ContractSync, counting them like
+/// `ContractSync::sync` does.
+#[derive(Debug)]
+struct MeteredLogStore<'a, D> {
+    db: &'a D,
+    stored_logs: IntCounter,
 }
 
-/// A ContractSync for syncing events using a RateLimitedContractSyncCursor
+#[async_trait]
+impl<T, D> HyperlaneLogStore<T> for MeteredLogStore<'_, D>
+where
+    T: Send + Sync + 'static,
+    D: HyperlaneLogStore<T>,
+{
+    async fn store_logs(&self, logs: &[(T, LogMeta)]) -> eyre::Result<u32> {
+        let stored = self.db.store_logs(logs).await?;
+        self.stored_logs.inc_by(stored as u64);
+        Ok(stored)
+    }
+
+    async fn rollback_logs(&self, from_block: u32) -> eyre::Result<u32> {
+        self.db.rollback_logs(from_block).await
+    }
+
+    async fn remove_logs(&self, logs: &[(T, LogMeta)]) -> eyre::Result<u32> {
+        self.db.remove_logs(logs).await
+    }
+}
+
+/// A ContractSync for syncing events using a RateLimitedContractSyncCursor,
+/// or a SequenceSyncCursor for sequence-based indexing
 pub type WatermarkContractSync<T> =
-    ContractSync<T, Arc<dyn HyperlaneWatermarkedLogStore<T>>, Arc<dyn Indexer<T>>>;
+    ContractSync<T, Arc<dyn HyperlaneWatermarkedLogStore<T>>, Arc<dyn SequenceIndexer<T>>>;
 impl<T> WatermarkContractSync<T>
 where
     T: Debug + Send + Sync + Clone + 'static,
@@ -97,23 +180,49 @@ where
     pub async fn rate_limited_cursor(
         &self,
         index_settings: IndexSettings,
-    ) -> Box<dyn ContractSyncCursor<T>> {
-        let watermark = self.db.retrieve_high_watermark().await.unwrap();
+    ) -> eyre::Result<Box<dyn ContractSyncCursor<T>>> {
+        if let IndexMode::Sequence = index_settings.mode {
+            let next_sequence = self.db.retrieve_sequence_watermark().await?;
+            return self
+                .sequence_cursor(
+                    next_sequence.unwrap_or_default(),
+                    index_settings.poll_interval,
+                )
+                .await;
+        }
+        let watermark = self.db.retrieve_high_watermark().await?;
         let index_settings = IndexSettings {
             from: watermark.unwrap_or(index_settings.from),
-            chunk_size: index_settings.chunk_size,
-            mode: index_settings.mode,
+            ..index_settings
         };
-        Box::new(
+        Ok(Box::new(
             RateLimitedContractSyncCursor::new(
                 Arc::new(self.indexer.clone()),
                 self.db.clone(),
                 index_settings.chunk_size,
                 index_settings.from,
             )
-            .await
-            .unwrap(),
-        )
+            .await?,
+        ))
+    }
+
+    /// Returns a new cursor to be used for syncing events from the indexer by
+    /// sequence, starting at `next_sequence` and checking for new events every
+    /// `poll_interval` once caught up
+    pub async fn sequence_cursor(
+        &self,
+        next_sequence: u32,
+        poll_interval: Duration,
+    ) -> eyre::Result<Box<dyn ContractSyncCursor<T>>> {
+        Ok(Box::new(
+            SequenceSyncCursor::new(
+                self.indexer.clone(),
+                self.db.clone(),
+                next_sequence,
+                poll_interval,
+            )
+            .await?,
+        ))
     }
 }
 
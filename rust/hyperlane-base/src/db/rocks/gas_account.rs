@@ -0,0 +1,85 @@
+use std::io::{Read, Write};
+
+use hyperlane_core::{Decode, Encode, HyperlaneProtocolError, H256, U256};
+
+/// Identifies the messages of one sender to one destination, which gas
+/// accounts are kept for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GasAccountKey {
+    /// Destination domain of the messages
+    pub destination: u32,
+    /// Sender of the messages
+    pub sender: H256,
+}
+
+/// Running totals of what a sender paid for the delivery of their messages and
+/// what delivering them cost, both in the origin chain's native token.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct GasAccount {
+    /// Total payments for delivered messages
+    pub payment: U256,
+    /// Total cost of the transactions that delivered or attempted to deliver
+    /// the messages
+    pub cost: U256,
+    /// Number of messages delivered
+    pub delivered: u32,
+}
+
+impl GasAccount {
+    /// How much more the sender has paid than delivery cost
+    pub fn surplus(&self) -> U256 {
+        self.payment.saturating_sub(self.cost)
+    }
+
+    /// How much more delivery has cost than the sender paid
+    pub fn deficit(&self) -> U256 {
+        self.cost.saturating_sub(self.payment)
+    }
+}
+
+impl Encode for GasAccountKey {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        Ok(self.destination.write_to(writer)? + self.sender.write_to(writer)?)
+    }
+}
+
+impl Decode for GasAccountKey {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            destination: u32::read_from(reader)?,
+            sender: H256::read_from(reader)?,
+        })
+    }
+}
+
+impl Encode for GasAccount {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        Ok(self.payment.write_to(writer)?
+            + self.cost.write_to(writer)?
+            + self.delivered.write_to(writer)?)
+    }
+}
+
+impl Decode for GasAccount {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            payment: U256::read_from(reader)?,
+            cost: U256::read_from(reader)?,
+            delivered: u32::read_from(reader)?,
+        })
+    }
+}
@@ -1,21 +1,29 @@
+use std::collections::BTreeMap;
 use std::future::Future;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_trait::async_trait;
 use eyre::Result;
 use paste::paste;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tokio::time::sleep;
 use tracing::{debug, instrument, trace};
 
 use hyperlane_core::{
-    HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, HyperlaneMessageStore,
+    Decode, Encode, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, HyperlaneMessageStore,
     HyperlaneWatermarkedLogStore, InterchainGasExpenditure, InterchainGasPayment,
-    InterchainGasPaymentMeta, LogMeta, H256,
+    InterchainGasPaymentMeta, LogMeta, H256, U256,
 };
 
 use super::{
-    storage_types::{InterchainGasExpenditureData, InterchainGasPaymentData},
-    DbError, TypedDB, DB,
+    message_status::unix_timestamp,
+    storage_types::{
+        BlockKey, InterchainGasExpenditureData, InterchainGasPaymentData,
+        InterchainGasPaymentRecord,
+    },
+    DbError, GasAccount, GasAccountKey, MessageStatus, MessageStatusRecord, SigningJournalEntry,
+    TypedDB, DB,
 };
 
 // these keys MUST not be given multiple uses in case multiple agents are
@@ -23,20 +31,54 @@ use super::{
 
 const MESSAGE_ID: &str = "message_id_";
 const MESSAGE_DISPATCHED_BLOCK_NUMBER: &str = "message_dispatched_block_number_";
+const MESSAGE_DISPATCHED_BLOCK_HASH: &str = "message_dispatched_block_hash_";
+const MESSAGE_INDEXED_AT: &str = "message_indexed_at_";
+const MESSAGE_DISPATCHED_AT: &str = "message_dispatched_at_";
+const MESSAGE_NONCE_FOR_BLOCK: &str = "message_nonce_for_block_";
 const MESSAGE: &str = "message_";
 const NONCE_PROCESSED: &str = "nonce_processed_";
 const GAS_PAYMENT_FOR_MESSAGE_ID: &str = "gas_payment_for_message_id_v2_";
 const GAS_PAYMENT_META_PROCESSED: &str = "gas_payment_meta_processed_v3_";
+const GAS_PAYMENT_RECORD_FOR_BLOCK: &str = "gas_payment_record_for_block_";
 const GAS_EXPENDITURE_FOR_MESSAGE_ID: &str = "gas_expenditure_for_message_id_v2_";
 const PENDING_MESSAGE_RETRY_COUNT_FOR_MESSAGE_ID: &str =
     "pending_message_retry_count_for_message_id_";
+const MESSAGE_STATUS_FOR_MESSAGE_ID: &str = "message_status_for_message_id_";
+const DEAD_LETTER_MESSAGE_ID: &str = "dead_letter_message_id_";
+const GAS_ACCOUNT_FOR_SENDER: &str = "gas_account_for_sender_";
 const LATEST_INDEXED_GAS_PAYMENT_BLOCK: &str = "latest_indexed_gas_payment_block";
+const NEXT_GAS_PAYMENT_SEQUENCE: &str = "next_gas_payment_sequence";
+const SIGNED_CHECKPOINT_FOR_INDEX: &str = "signed_checkpoint_for_index_";
+const LEGACY_SIGNED_ROOT_FOR_INDEX: &str = "legacy_signed_root_for_index_";
+const KEY_ROTATION_STARTED_AT_FOR_VALIDATOR: &str = "key_rotation_started_at_for_validator_";
+
+/// Logs can be rolled back for up to this many blocks before the latest block
+/// logs were stored for. What is kept to roll back the logs of older blocks is
+/// removed, so deeper reorgs aren't rolled back completely.
+const MAX_ROLLBACK_DEPTH: u64 = 100_000;
 
 type DbResult<T> = std::result::Result<T, DbError>;
 
 /// DB handle for storing data tied to a specific Mailbox.
 #[derive(Debug, Clone)]
-pub struct HyperlaneRocksDB(HyperlaneDomain, TypedDB);
+pub struct HyperlaneRocksDB(HyperlaneDomain, TypedDB, MessageRollbackSubscribers);
+
+type MessageRollbackSubscribers = Arc<Mutex<Vec<UnboundedSender<MessageRollback>>>>;
+
+/// Messages that were removed from the db because a reorg removed them from
+/// the chain, which consumers that already read them have to forget.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageRollback {
+    /// The ids of the removed messages by nonce
+    pub removed: BTreeMap<u32, H256>,
+}
+
+impl MessageRollback {
+    /// The lowest nonce that was removed
+    pub fn from_nonce(&self) -> Option<u32> {
+        self.removed.keys().next().copied()
+    }
+}
 
 impl std::ops::Deref for HyperlaneRocksDB {
     type Target = TypedDB;
@@ -61,7 +103,7 @@ impl AsRef<DB> for HyperlaneRocksDB {
 impl HyperlaneRocksDB {
     /// Instantiated new `HyperlaneRocksDB`
     pub fn new(domain: &HyperlaneDomain, db: DB) -> Self {
-        Self(domain.clone(), TypedDB::new(domain, db))
+        Self(domain.clone(), TypedDB::new(domain, db), Default::default())
     }
 
     /// Get the domain this database is scoped to
@@ -69,12 +111,35 @@ impl HyperlaneRocksDB {
         &self.0
     }
 
+    /// Subscribe to the messages that are removed from the db from now on
+    /// because a reorg removed them from the chain.
+    pub fn subscribe_message_rollbacks(&self) -> UnboundedReceiver<MessageRollback> {
+        let (sender, receiver) = unbounded_channel();
+        self.2
+            .lock()
+            .expect("rollback subscribers poisoned")
+            .push(sender);
+        receiver
+    }
+
+    fn notify_message_rollback(&self, removed: BTreeMap<u32, H256>) {
+        if removed.is_empty() {
+            return;
+        }
+        let rollback = MessageRollback { removed };
+        self.2
+            .lock()
+            .expect("rollback subscribers poisoned")
+            .retain(|subscriber| subscriber.send(rollback.clone()).is_ok());
+    }
+
     /// Store a raw committed message
     ///
     /// Keys --> Values:
     /// - `nonce` --> `id`
     /// - `id` --> `message`
     /// - `nonce` --> `dispatched block number`
+    /// - `dispatched block number, nonce` --> `true`
     pub fn store_message(
         &self,
         message: &HyperlaneMessage,
@@ -94,9 +159,111 @@ impl HyperlaneRocksDB {
         self.store_message_id_by_nonce(&message.nonce, &id)?;
         // - `nonce` --> `dispatched block number`
         self.store_dispatched_block_number_by_nonce(&message.nonce, &dispatched_block_number)?;
+        // - `dispatched block number, nonce` --> `true`
+        self.store_keyed_encodable(
+            MESSAGE_NONCE_FOR_BLOCK,
+            &BlockKey {
+                block_number: dispatched_block_number,
+                key: message.nonce,
+            },
+            &true,
+        )?;
         Ok(true)
     }
 
+    /// Remove the messages dispatched from a block onwards, along with what is
+    /// stored about them by nonce and id, and notify the subscribers to
+    /// message rollbacks.
+    /// Returns the number of messages that were removed.
+    pub fn remove_messages_from_block(&self, from_block: u64) -> DbResult<u32> {
+        let keys = self
+            .iter_keyed_decodable_from::<BlockKey<u32>, bool>(
+                MESSAGE_NONCE_FOR_BLOCK,
+                &from_block.to_vec(),
+            )
+            .map(|entry| entry.map(|(key, _)| key))
+            .collect::<DbResult<Vec<_>>>()?;
+        let mut removed = BTreeMap::new();
+        for BlockKey {
+            block_number,
+            key: nonce,
+        } in keys
+        {
+            debug!(nonce, block_number, "Removing reorged message from db");
+            if let Some(id) = self.remove_message(nonce)? {
+                removed.insert(nonce, id);
+            }
+        }
+        let count = removed.len() as u32;
+        self.notify_message_rollback(removed);
+        Ok(count)
+    }
+
+    /// Remove a message that a reorg removed from the chain, if it is the
+    /// message stored for its nonce, and notify the subscribers to message
+    /// rollbacks.
+    /// Returns whether the message was removed.
+    pub fn remove_reorged_message(&self, message: &HyperlaneMessage) -> DbResult<bool> {
+        let id = message.id();
+        if self.retrieve_message_id_by_nonce(&message.nonce)? != Some(id) {
+            return Ok(false);
+        }
+        debug!(msg=?message, "Removing reorged message from db");
+        self.remove_message(message.nonce)?;
+        self.notify_message_rollback(BTreeMap::from([(message.nonce, id)]));
+        Ok(true)
+    }
+
+    /// Remove the message stored for a nonce along with what is stored about
+    /// it by nonce and id.
+    /// Returns the id of the removed message, if there was one.
+    fn remove_message(&self, nonce: u32) -> DbResult<Option<H256>> {
+        let id = self.retrieve_message_id_by_nonce(&nonce)?;
+        if let Some(id) = id {
+            self.delete_keyed(MESSAGE, &id)?;
+            self.delete_keyed(MESSAGE_DISPATCHED_BLOCK_HASH, &id)?;
+            self.delete_keyed(MESSAGE_INDEXED_AT, &id)?;
+            self.delete_keyed(MESSAGE_DISPATCHED_AT, &id)?;
+        }
+        if let Some(block_number) = self.retrieve_dispatched_block_number_by_nonce(&nonce)? {
+            self.delete_keyed(
+                MESSAGE_NONCE_FOR_BLOCK,
+                &BlockKey {
+                    block_number,
+                    key: nonce,
+                },
+            )?;
+        }
+        self.delete_keyed(MESSAGE_ID, &nonce)?;
+        self.delete_keyed(MESSAGE_DISPATCHED_BLOCK_NUMBER, &nonce)?;
+        self.delete_keyed(NONCE_PROCESSED, &nonce)?;
+        Ok(id)
+    }
+
+    /// Remove the rows of a block index for the blocks more than
+    /// `MAX_ROLLBACK_DEPTH` before `latest_block`, whose logs aren't rolled
+    /// back anymore.
+    fn prune_block_index<K: Encode + Decode, V: Decode>(
+        &self,
+        prefix: &str,
+        latest_block: u64,
+    ) -> DbResult<()> {
+        let before = latest_block.saturating_sub(MAX_ROLLBACK_DEPTH);
+        let pruned = self
+            .iter_keyed_decodable_from::<BlockKey<K>, V>(prefix, &[])
+            .take_while(|entry| {
+                entry
+                    .as_ref()
+                    .map_or(true, |(key, _)| key.block_number < before)
+            })
+            .map(|entry| entry.map(|(key, _)| key))
+            .collect::<DbResult<Vec<_>>>()?;
+        for key in pruned {
+            self.delete_keyed(prefix, &key)?;
+        }
+        Ok(())
+    }
+
     /// Retrieve a message by its nonce
     pub fn retrieve_message_by_nonce(&self, nonce: u32) -> DbResult<Option<HyperlaneMessage>> {
         let id = self.retrieve_message_id_by_nonce(&nonce)?;
@@ -144,6 +311,14 @@ impl HyperlaneRocksDB {
         }
         // Set the gas payment as processed
         self.store_processed_by_gas_payment_meta(&payment_meta, &true)?;
+        // Keep the payment so it can be removed from the total again
+        self.store_gas_payment_record_by_block(
+            &BlockKey {
+                block_number: log_meta.block_number,
+                key: payment_meta,
+            },
+            &InterchainGasPaymentRecord { payment },
+        )?;
 
         // Update the total gas payment for the message to include the payment
         self.update_gas_payment_by_message_id(payment)?;
@@ -152,6 +327,63 @@ impl HyperlaneRocksDB {
         Ok(true)
     }
 
+    /// Remove the gas payments emitted from a block onwards from the total
+    /// payments for their messages. Payments that were processed before their
+    /// blocks were recorded can't be removed.
+    /// Returns the number of gas payments that were removed.
+    pub fn remove_gas_payments_from_block(&self, from_block: u64) -> DbResult<u32> {
+        let records = self
+            .iter_keyed_decodable_from::<BlockKey<_>, InterchainGasPaymentRecord>(
+                GAS_PAYMENT_RECORD_FOR_BLOCK,
+                &from_block.to_vec(),
+            )
+            .collect::<DbResult<Vec<_>>>()?;
+        let removed = records.len() as u32;
+        for (key, record) in records {
+            self.remove_gas_payment(&key, record)?;
+        }
+        Ok(removed)
+    }
+
+    /// Remove a gas payment that a reorg removed from the chain from the total
+    /// payment for its message, if it was processed.
+    /// Returns whether the gas payment was removed.
+    pub fn remove_reorged_gas_payment(&self, log_meta: &LogMeta) -> DbResult<bool> {
+        let key = BlockKey {
+            block_number: log_meta.block_number,
+            key: log_meta.into(),
+        };
+        let Some(record) = self.retrieve_gas_payment_record_by_block(&key)? else {
+            return Ok(false);
+        };
+        self.remove_gas_payment(&key, record)?;
+        Ok(true)
+    }
+
+    /// Subtract a processed gas payment from the total payment for its message
+    /// and forget that it was processed.
+    fn remove_gas_payment(
+        &self,
+        key: &BlockKey<InterchainGasPaymentMeta>,
+        record: InterchainGasPaymentRecord,
+    ) -> DbResult<()> {
+        let payment = record.payment;
+        let total = self.retrieve_gas_payment_by_message_id(payment.message_id)?;
+        let total = InterchainGasPaymentData {
+            payment: total.payment.saturating_sub(payment.payment),
+            gas_amount: total.gas_amount.saturating_sub(payment.gas_amount),
+        };
+        debug!(
+            ?payment,
+            payment_meta=?key.key,
+            new_total_gas_payment=?total,
+            "Removing reorged gas payment from db"
+        );
+        self.store_interchain_gas_payment_data_by_message_id(&payment.message_id, &total)?;
+        self.delete_keyed(GAS_PAYMENT_META_PROCESSED, &key.key)?;
+        self.delete_keyed(GAS_PAYMENT_RECORD_FOR_BLOCK, key)
+    }
+
     /// Processes the gas expenditure and store the total expenditure for the
     /// message.
     pub fn process_gas_expenditure(&self, expenditure: InterchainGasExpenditure) -> DbResult<()> {
@@ -200,6 +432,123 @@ impl HyperlaneRocksDB {
             .complete(message_id))
     }
 
+    /// Record the latest status of a message being relayed.
+    ///
+    /// Messages that are dropped are also added to the dead-letter bucket,
+    /// which they leave again if they are later given another status, e.g.
+    /// because they were queued again after a restart.
+    pub fn update_message_status(&self, message_id: &H256, status: MessageStatus) -> DbResult<()> {
+        let previous = self.retrieve_message_status_by_message_id(message_id)?;
+        let was_dropped = matches!(
+            previous,
+            Some(MessageStatusRecord {
+                status: MessageStatus::Dropped(_),
+                ..
+            })
+        );
+        let is_dropped = matches!(status, MessageStatus::Dropped(_));
+        let record = MessageStatusRecord::new(status, previous.as_ref());
+        trace!(?message_id, ?record, "Storing message status");
+        self.store_message_status_by_message_id(message_id, &record)?;
+
+        if is_dropped {
+            // - `id` --> `time dropped`
+            self.store_keyed_encodable(DEAD_LETTER_MESSAGE_ID, message_id, &unix_timestamp())?;
+        } else if was_dropped {
+            self.delete_keyed(DEAD_LETTER_MESSAGE_ID, message_id)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieve the latest status of a message
+    pub fn retrieve_message_status(
+        &self,
+        message_id: &H256,
+    ) -> DbResult<Option<MessageStatusRecord>> {
+        self.retrieve_message_status_by_message_id(message_id)
+    }
+
+    /// Retrieve the ids and status records of all messages in the dead-letter
+    /// bucket.
+    pub fn retrieve_dead_letters(&self) -> DbResult<Vec<(H256, MessageStatusRecord)>> {
+        let ids = self.retrieve_all_keyed_decodable::<H256, u64>(DEAD_LETTER_MESSAGE_ID)?;
+        ids.into_iter()
+            .filter_map(|(id, _)| {
+                self.retrieve_message_status_by_message_id(&id)
+                    .transpose()
+                    .map(|record| record.map(|record| (id, record)))
+            })
+            .collect()
+    }
+
+    /// Add a payment and the cost of a delivery attempt to the gas account of
+    /// a sender, counting a delivered message if the payment is for one.
+    /// Returns the updated account.
+    pub fn update_gas_account(
+        &self,
+        key: &GasAccountKey,
+        payment: Option<U256>,
+        cost: U256,
+    ) -> DbResult<GasAccount> {
+        let mut account = self.retrieve_gas_account(key)?;
+        account.cost = account.cost.saturating_add(cost);
+        if let Some(payment) = payment {
+            account.payment = account.payment.saturating_add(payment);
+            account.delivered += 1;
+        }
+        debug!(?key, ?account, "Storing gas account");
+        self.store_gas_account_by_sender(key, &account)?;
+        Ok(account)
+    }
+
+    /// Retrieve the gas account of a sender
+    pub fn retrieve_gas_account(&self, key: &GasAccountKey) -> DbResult<GasAccount> {
+        Ok(self
+            .retrieve_gas_account_by_sender(key)?
+            .unwrap_or_default())
+    }
+
+    /// Retrieve the gas accounts of all senders
+    pub fn retrieve_gas_accounts(&self) -> DbResult<Vec<(GasAccountKey, GasAccount)>> {
+        self.retrieve_all_keyed_decodable(GAS_ACCOUNT_FOR_SENDER)
+    }
+
+    /// Journal that the checkpoint `entry` is about to be signed for `index`.
+    /// Returns the previously journaled entry without journaling `entry` if
+    /// it conflicts with it, in which case `entry` must not be signed.
+    ///
+    /// Callers must not journal the same index concurrently.
+    pub fn journal_signing(
+        &self,
+        index: u32,
+        entry: SigningJournalEntry,
+    ) -> DbResult<Option<SigningJournalEntry>> {
+        match self.retrieve_signed_checkpoint_by_index(&index)? {
+            Some(journaled) if journaled != entry => Ok(Some(journaled)),
+            Some(_) => Ok(None),
+            None => {
+                self.store_signed_checkpoint_by_index(&index, &entry)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Journal that a legacy checkpoint with `root` is about to be signed for
+    /// `index`. Returns the previously journaled root without journaling
+    /// `root` if it differs from it, in which case `root` must not be signed.
+    ///
+    /// Callers must not journal the same index concurrently.
+    pub fn journal_legacy_signing(&self, index: u32, root: H256) -> DbResult<Option<H256>> {
+        match self.retrieve_legacy_signed_root_by_index(&index)? {
+            Some(journaled) if journaled != root => Ok(Some(journaled)),
+            Some(_) => Ok(None),
+            None => {
+                self.store_legacy_signed_root_by_index(&index, &root)?;
+                Ok(None)
+            }
+        }
+    }
+
     /// Retrieve the total gas payment for a message
     pub fn retrieve_gas_expenditure_by_message_id(
         &self,
@@ -221,14 +570,44 @@ impl HyperlaneLogStore<HyperlaneMessage> for HyperlaneRocksDB {
         for (message, meta) in messages {
             let stored_message = self.store_message(message, meta.block_number)?;
             if stored_message {
+                // Kept so that the latency of delivering the message can be
+                // measured from when it was dispatched and indexed
+                let id = message.id();
+                self.store_dispatched_block_hash_by_message_id(&id, &meta.block_hash)?;
+                self.store_indexed_at_by_message_id(&id, &unix_timestamp())?;
                 stored += 1;
             }
         }
+        if let Some(latest_block) = messages.iter().map(|(_, meta)| meta.block_number).max() {
+            self.prune_block_index::<u32, bool>(MESSAGE_NONCE_FOR_BLOCK, latest_block)?;
+        }
         if stored > 0 {
             debug!(messages = stored, "Wrote new messages to database");
         }
         Ok(stored)
     }
+
+    /// Remove the messages dispatched from a block onwards.
+    #[instrument(skip(self))]
+    async fn rollback_logs(&self, from_block: u32) -> Result<u32> {
+        let removed = self.remove_messages_from_block(from_block.into())?;
+        if removed > 0 {
+            debug!(messages = removed, "Removed reorged messages from database");
+        }
+        Ok(removed)
+    }
+
+    /// Remove dispatched messages that a reorg removed from the chain.
+    #[instrument(skip_all)]
+    async fn remove_logs(&self, messages: &[(HyperlaneMessage, LogMeta)]) -> Result<u32> {
+        let mut removed = 0;
+        for (message, _) in messages {
+            if self.remove_reorged_message(message)? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
 }
 
 #[async_trait]
@@ -242,11 +621,42 @@ impl HyperlaneLogStore<InterchainGasPayment> for HyperlaneRocksDB {
                 new += 1;
             }
         }
+        if let Some(latest_block) = payments.iter().map(|(_, meta)| meta.block_number).max() {
+            self.prune_block_index::<InterchainGasPaymentMeta, InterchainGasPaymentRecord>(
+                GAS_PAYMENT_RECORD_FOR_BLOCK,
+                latest_block,
+            )?;
+        }
         if new > 0 {
             debug!(payments = new, "Wrote new gas payments to database");
         }
         Ok(new)
     }
+
+    /// Remove the interchain gas payments emitted from a block onwards.
+    #[instrument(skip(self))]
+    async fn rollback_logs(&self, from_block: u32) -> Result<u32> {
+        let removed = self.remove_gas_payments_from_block(from_block.into())?;
+        if removed > 0 {
+            debug!(
+                payments = removed,
+                "Removed reorged gas payments from database"
+            );
+        }
+        Ok(removed)
+    }
+
+    /// Remove interchain gas payments that a reorg removed from the chain.
+    #[instrument(skip_all)]
+    async fn remove_logs(&self, payments: &[(InterchainGasPayment, LogMeta)]) -> Result<u32> {
+        let mut removed = 0;
+        for (_, meta) in payments {
+            if self.remove_reorged_gas_payment(meta)? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
 }
 
 #[async_trait]
@@ -281,6 +691,16 @@ where
         let result = self.store_encodable("", LATEST_INDEXED_GAS_PAYMENT_BLOCK, &block_number)?;
         Ok(result)
     }
+    /// Gets the next sequence to index
+    async fn retrieve_sequence_watermark(&self) -> Result<Option<u32>> {
+        let watermark = self.retrieve_decodable("", NEXT_GAS_PAYMENT_SEQUENCE)?;
+        Ok(watermark)
+    }
+    /// Stores the next sequence to index
+    async fn store_sequence_watermark(&self, next_sequence: u32) -> Result<()> {
+        let result = self.store_encodable("", NEXT_GAS_PAYMENT_SEQUENCE, &next_sequence)?;
+        Ok(result)
+    }
 }
 
 /// Generate a call to ChainSetup for the given builder
@@ -312,8 +732,24 @@ macro_rules! make_store_and_retrieve {
 make_store_and_retrieve!(pub, message_id_by_nonce, MESSAGE_ID, u32, H256);
 make_store_and_retrieve!(pub(self), message_by_id, MESSAGE, H256, HyperlaneMessage);
 make_store_and_retrieve!(pub(self), dispatched_block_number_by_nonce, MESSAGE_DISPATCHED_BLOCK_NUMBER, u32, u64);
+make_store_and_retrieve!(
+    pub,
+    dispatched_block_hash_by_message_id,
+    MESSAGE_DISPATCHED_BLOCK_HASH,
+    H256,
+    H256
+);
+make_store_and_retrieve!(pub, indexed_at_by_message_id, MESSAGE_INDEXED_AT, H256, u64);
+make_store_and_retrieve!(
+    pub,
+    dispatched_at_by_message_id,
+    MESSAGE_DISPATCHED_AT,
+    H256,
+    u64
+);
 make_store_and_retrieve!(pub, processed_by_nonce, NONCE_PROCESSED, u32, bool);
 make_store_and_retrieve!(pub(self), processed_by_gas_payment_meta, GAS_PAYMENT_META_PROCESSED, InterchainGasPaymentMeta, bool);
+make_store_and_retrieve!(pub(self), gas_payment_record_by_block, GAS_PAYMENT_RECORD_FOR_BLOCK, BlockKey<InterchainGasPaymentMeta>, InterchainGasPaymentRecord);
 make_store_and_retrieve!(pub(self), interchain_gas_expenditure_data_by_message_id, GAS_EXPENDITURE_FOR_MESSAGE_ID, H256, InterchainGasExpenditureData);
 make_store_and_retrieve!(pub(self), interchain_gas_payment_data_by_message_id, GAS_PAYMENT_FOR_MESSAGE_ID, H256, InterchainGasPaymentData);
 make_store_and_retrieve!(
@@ -323,3 +759,14 @@ make_store_and_retrieve!(
     H256,
     u32
 );
+make_store_and_retrieve!(pub(self), message_status_by_message_id, MESSAGE_STATUS_FOR_MESSAGE_ID, H256, MessageStatusRecord);
+make_store_and_retrieve!(pub(self), gas_account_by_sender, GAS_ACCOUNT_FOR_SENDER, GasAccountKey, GasAccount);
+make_store_and_retrieve!(pub(self), signed_checkpoint_by_index, SIGNED_CHECKPOINT_FOR_INDEX, u32, SigningJournalEntry);
+make_store_and_retrieve!(pub(self), legacy_signed_root_by_index, LEGACY_SIGNED_ROOT_FOR_INDEX, u32, H256);
+make_store_and_retrieve!(
+    pub,
+    key_rotation_started_at_by_validator,
+    KEY_ROTATION_STARTED_AT_FOR_VALIDATOR,
+    H256,
+    u64
+);
@@ -0,0 +1,134 @@
+use std::io::{Error, ErrorKind, Read, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use hyperlane_core::{Decode, Encode, HyperlaneProtocolError};
+
+/// What last happened to a message the relayer is delivering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "reason", rename_all = "camelCase")]
+pub enum MessageStatus {
+    /// Waiting to be prepared for delivery.
+    Queued,
+    /// Metadata for the recipient's ISM could not be built yet.
+    MetadataUnavailable,
+    /// The gas paid for the message does not meet the gas payment policy yet.
+    GasUnderpaid,
+    /// Delivering the message needs more gas than the transaction gas limit.
+    GasLimitExceeded,
+    /// Submitted in a transaction that has yet to be confirmed.
+    Submitted,
+    /// The transaction delivering the message reverted and it will be retried.
+    Reverted,
+    /// Delivery is confirmed and the message will not be looked at again.
+    Confirmed,
+    /// The relayer gave up on delivering the message.
+    Dropped(String),
+}
+
+impl MessageStatus {
+    fn tag(&self) -> u8 {
+        match self {
+            Self::Queued => 0,
+            Self::MetadataUnavailable => 1,
+            Self::GasUnderpaid => 2,
+            Self::GasLimitExceeded => 3,
+            Self::Submitted => 4,
+            Self::Reverted => 5,
+            Self::Confirmed => 6,
+            Self::Dropped(_) => 7,
+        }
+    }
+}
+
+/// A message's latest status along with when it changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageStatusRecord {
+    /// The latest status.
+    #[serde(flatten)]
+    pub status: MessageStatus,
+    /// Unix timestamp in seconds of when the message was first given a status.
+    pub first_seen_at: u64,
+    /// Unix timestamp in seconds of when the status last changed.
+    pub updated_at: u64,
+}
+
+impl MessageStatusRecord {
+    /// Create the record for a status change happening now, keeping when the
+    /// message was first seen from the previous record if there is one.
+    pub fn new(status: MessageStatus, previous: Option<&MessageStatusRecord>) -> Self {
+        let now = unix_timestamp();
+        Self {
+            status,
+            first_seen_at: previous.map_or(now, |p| p.first_seen_at),
+            updated_at: now,
+        }
+    }
+}
+
+/// Seconds since the unix epoch, as timestamps are stored in the DB.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl Encode for MessageStatusRecord {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        writer.write_all(&[self.status.tag()])?;
+        let mut written = 1;
+        if let MessageStatus::Dropped(reason) = &self.status {
+            written += (reason.len() as u32).write_to(writer)?;
+            writer.write_all(reason.as_bytes())?;
+            written += reason.len();
+        }
+        written += self.first_seen_at.write_to(writer)?;
+        written += self.updated_at.write_to(writer)?;
+        Ok(written)
+    }
+}
+
+impl Decode for MessageStatusRecord {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        let mut tag = [0; 1];
+        reader.read_exact(&mut tag)?;
+        let status = match tag[0] {
+            0 => MessageStatus::Queued,
+            1 => MessageStatus::MetadataUnavailable,
+            2 => MessageStatus::GasUnderpaid,
+            3 => MessageStatus::GasLimitExceeded,
+            4 => MessageStatus::Submitted,
+            5 => MessageStatus::Reverted,
+            6 => MessageStatus::Confirmed,
+            7 => {
+                let len = u32::read_from(reader)?;
+                let mut reason = vec![0; len as usize];
+                reader.read_exact(&mut reason)?;
+                MessageStatus::Dropped(String::from_utf8(reason).map_err(|e| {
+                    HyperlaneProtocolError::IoError(Error::new(ErrorKind::InvalidData, e))
+                })?)
+            }
+            _ => {
+                return Err(HyperlaneProtocolError::IoError(Error::new(
+                    ErrorKind::InvalidData,
+                    "decoded message status invalid",
+                )))
+            }
+        };
+        Ok(Self {
+            status,
+            first_seen_at: u64::read_from(reader)?,
+            updated_at: u64::read_from(reader)?,
+        })
+    }
+}
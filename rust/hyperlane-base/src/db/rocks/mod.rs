@@ -2,10 +2,13 @@ use std::path::PathBuf;
 use std::{io, path::Path, sync::Arc};
 
 use hyperlane_core::HyperlaneProtocolError;
-use rocksdb::{Options, DB as Rocks};
+use rocksdb::{Direction, IteratorMode, Options, DB as Rocks};
 use tracing::info;
 
+pub use gas_account::*;
 pub use hyperlane_db::*;
+pub use message_status::*;
+pub use signing_journal::*;
 pub use typed_db::*;
 
 /// Shared functionality surrounding use of rocksdb
@@ -16,6 +19,15 @@ mod hyperlane_db;
 /// Type-specific db operations
 mod typed_db;
 
+/// Status records of messages being relayed
+mod message_status;
+
+/// Gas accounting for message senders
+mod gas_account;
+
+/// Journal of the checkpoints a validator signed
+mod signing_journal;
+
 /// Internal-use storage types.
 mod storage_types;
 
@@ -103,4 +115,36 @@ impl DB {
     pub fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
         Ok(self.0.get(key)?)
     }
+
+    /// Delete a value from the DB
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        Ok(self.0.delete(key)?)
+    }
+
+    /// Iterate over the keys and values of every entry whose key starts with
+    /// `prefix`, in key order
+    pub fn prefix_iter<'a>(
+        &'a self,
+        prefix: &'a [u8],
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + 'a {
+        self.prefix_iter_from(prefix.to_vec(), prefix)
+    }
+
+    /// Iterate over the keys and values of the entries whose key starts with
+    /// `prefix`, in key order, starting at the first key that is at least
+    /// `from`
+    pub fn prefix_iter_from(
+        &self,
+        prefix: Vec<u8>,
+        from: &[u8],
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + '_ {
+        self.0
+            .iterator(IteratorMode::From(from, Direction::Forward))
+            .take_while(move |entry| {
+                entry
+                    .as_ref()
+                    .map_or(true, |(key, _)| key.starts_with(&prefix))
+            })
+            .map(|entry| entry.map_err(Into::into))
+    }
 }
@@ -0,0 +1,35 @@
+use std::io::{Read, Write};
+
+use hyperlane_core::{Decode, Encode, HyperlaneProtocolError, H256};
+
+/// What a validator signed for an index of the merkle tree, journaled before
+/// signing so that it never signs a conflicting checkpoint for the index.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SigningJournalEntry {
+    /// Merkle root of the signed checkpoint
+    pub root: H256,
+    /// Id of the message inserted at the index
+    pub message_id: H256,
+}
+
+impl Encode for SigningJournalEntry {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        Ok(self.root.write_to(writer)? + self.message_id.write_to(writer)?)
+    }
+}
+
+impl Decode for SigningJournalEntry {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            root: H256::read_from(reader)?,
+            message_id: H256::read_from(reader)?,
+        })
+    }
+}
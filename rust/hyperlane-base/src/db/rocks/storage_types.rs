@@ -120,3 +120,69 @@ impl Decode for InterchainGasExpenditureData {
         })
     }
 }
+
+/// A single processed gas payment, kept so that it can be subtracted from the
+/// total payment for its message again if a reorg removes the block it was
+/// emitted in.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct InterchainGasPaymentRecord {
+    pub payment: InterchainGasPayment,
+}
+
+impl Encode for InterchainGasPaymentRecord {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        Ok(self.payment.message_id.write_to(writer)?
+            + self.payment.payment.write_to(writer)?
+            + self.payment.gas_amount.write_to(writer)?)
+    }
+}
+
+impl Decode for InterchainGasPaymentRecord {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            payment: InterchainGasPayment {
+                message_id: H256::read_from(reader)?,
+                payment: U256::read_from(reader)?,
+                gas_amount: U256::read_from(reader)?,
+            },
+        })
+    }
+}
+
+/// The key of something stored for the block it was found in. The block
+/// number is encoded first and big-endian, so keys are ordered by block and
+/// what was stored from a block onwards can be found without a full scan.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct BlockKey<K> {
+    pub block_number: u64,
+    pub key: K,
+}
+
+impl<K: Encode> Encode for BlockKey<K> {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        Ok(self.block_number.write_to(writer)? + self.key.write_to(writer)?)
+    }
+}
+
+impl<K: Decode> Decode for BlockKey<K> {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            block_number: u64::read_from(reader)?,
+            key: K::read_from(reader)?,
+        })
+    }
+}
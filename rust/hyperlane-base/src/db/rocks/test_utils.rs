@@ -30,12 +30,14 @@ where
 
 #[cfg(test)]
 mod test {
+    use std::collections::BTreeMap;
+
     use hyperlane_core::{
-        HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, LogMeta, RawHyperlaneMessage, H256,
-        H512, U256,
+        HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, InterchainGasPayment, LogMeta,
+        RawHyperlaneMessage, H256, H512, U256,
     };
 
-    use crate::db::HyperlaneRocksDB;
+    use crate::db::{GasAccountKey, HyperlaneRocksDB, MessageStatus, SigningJournalEntry};
 
     use super::*;
 
@@ -72,6 +74,271 @@ mod test {
                 RawHyperlaneMessage::from(&by_nonce),
                 RawHyperlaneMessage::from(&m)
             );
+            assert_eq!(
+                db.retrieve_dispatched_block_hash_by_message_id(&m.id())
+                    .unwrap(),
+                Some(H256::from_low_u64_be(1))
+            );
+            assert!(db
+                .retrieve_indexed_at_by_message_id(&m.id())
+                .unwrap()
+                .is_some());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_tracks_message_status_and_dead_letters() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_tracks_message_status_and_dead_letters"),
+                db,
+            );
+            let dropped = H256::from_low_u64_be(1);
+            let requeued = H256::from_low_u64_be(2);
+            let confirmed = H256::from_low_u64_be(3);
+            let reason = "Recipient is not a contract".to_owned();
+
+            for id in [dropped, requeued, confirmed] {
+                db.update_message_status(&id, MessageStatus::Queued)
+                    .unwrap();
+            }
+            db.update_message_status(&dropped, MessageStatus::Dropped(reason.clone()))
+                .unwrap();
+            db.update_message_status(&requeued, MessageStatus::Dropped(reason.clone()))
+                .unwrap();
+            db.update_message_status(&requeued, MessageStatus::Queued)
+                .unwrap();
+            db.update_message_status(&confirmed, MessageStatus::Confirmed)
+                .unwrap();
+
+            let record = db.retrieve_message_status(&confirmed).unwrap().unwrap();
+            assert_eq!(record.status, MessageStatus::Confirmed);
+            assert!(record.first_seen_at <= record.updated_at);
+            assert_eq!(db.retrieve_message_status(&H256::zero()).unwrap(), None);
+
+            let dead_letters = db.retrieve_dead_letters().unwrap();
+            assert_eq!(dead_letters.len(), 1);
+            assert_eq!(dead_letters[0].0, dropped);
+            assert_eq!(dead_letters[0].1.status, MessageStatus::Dropped(reason));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_accumulates_gas_accounts() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_accumulates_gas_accounts"),
+                db,
+            );
+            let key = GasAccountKey {
+                destination: 12,
+                sender: H256::from_low_u64_be(4),
+            };
+            let other = GasAccountKey {
+                destination: 13,
+                ..key
+            };
+
+            db.update_gas_account(&key, None, U256::from(40)).unwrap();
+            let account = db
+                .update_gas_account(&key, Some(U256::from(100)), U256::from(50))
+                .unwrap();
+            assert_eq!(account.payment, U256::from(100));
+            assert_eq!(account.cost, U256::from(90));
+            assert_eq!(account.delivered, 1);
+            assert_eq!(account.surplus(), U256::from(10));
+            assert_eq!(account.deficit(), U256::zero());
+
+            db.update_gas_account(&other, Some(U256::from(1)), U256::from(2))
+                .unwrap();
+            assert_eq!(
+                db.retrieve_gas_account(&other).unwrap().deficit(),
+                U256::one()
+            );
+            let accounts = db.retrieve_gas_accounts().unwrap();
+            assert_eq!(accounts.len(), 2);
+            assert!(accounts.contains(&(key, account)));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_rolls_back_reorged_logs() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_rolls_back_reorged_logs"),
+                db,
+            );
+            let meta = |block_number: u64| LogMeta {
+                address: H256::from_low_u64_be(1),
+                block_number,
+                block_hash: H256::from_low_u64_be(block_number),
+                transaction_id: H512::from_low_u64_be(block_number),
+                transaction_index: 0,
+                log_index: U256::from(0),
+            };
+            let messages: Vec<_> = (0..3)
+                .map(|nonce| {
+                    let message = HyperlaneMessage {
+                        nonce,
+                        ..HyperlaneMessage::default()
+                    };
+                    (message, meta(10 + nonce as u64))
+                })
+                .collect();
+            db.store_logs(&messages).await.unwrap();
+
+            let message_id = messages[0].0.id();
+            let payment = |payment: u64| InterchainGasPayment {
+                message_id,
+                payment: U256::from(payment),
+                gas_amount: U256::from(payment * 10),
+            };
+            db.store_logs(&[(payment(1), meta(10)), (payment(2), meta(12))])
+                .await
+                .unwrap();
+
+            let mut rollbacks = db.subscribe_message_rollbacks();
+            assert_eq!(
+                HyperlaneLogStore::<HyperlaneMessage>::rollback_logs(&db, 11)
+                    .await
+                    .unwrap(),
+                2
+            );
+            assert!(db.retrieve_message_by_nonce(0).unwrap().is_some());
+            assert!(db.retrieve_message_by_nonce(1).unwrap().is_none());
+            assert!(db.retrieve_message_by_nonce(2).unwrap().is_none());
+            assert_eq!(
+                db.retrieve_dispatched_block_hash_by_message_id(&messages[1].0.id())
+                    .unwrap(),
+                None
+            );
+            let rollback = rollbacks.try_recv().unwrap();
+            assert_eq!(rollback.from_nonce(), Some(1));
+            assert_eq!(
+                rollback.removed,
+                BTreeMap::from([(1, messages[1].0.id()), (2, messages[2].0.id())])
+            );
+
+            assert_eq!(
+                HyperlaneLogStore::<InterchainGasPayment>::rollback_logs(&db, 11)
+                    .await
+                    .unwrap(),
+                1
+            );
+            let total = db.retrieve_gas_payment_by_message_id(message_id).unwrap();
+            assert_eq!(total.payment, U256::from(1));
+            assert_eq!(total.gas_amount, U256::from(10));
+
+            // The removed payment is processed again when re-indexed
+            db.store_logs(&[(payment(2), meta(12))]).await.unwrap();
+            let total = db.retrieve_gas_payment_by_message_id(message_id).unwrap();
+            assert_eq!(total.payment, U256::from(3));
+
+            // Logs reported as removed are only removed if they were stored
+            assert_eq!(db.remove_logs(&[(payment(4), meta(13))]).await.unwrap(), 0);
+            assert_eq!(db.remove_logs(&[(payment(1), meta(10))]).await.unwrap(), 1);
+            let total = db.retrieve_gas_payment_by_message_id(message_id).unwrap();
+            assert_eq!(total.payment, U256::from(2));
+
+            let other_message = HyperlaneMessage {
+                body: vec![1],
+                ..messages[0].0.clone()
+            };
+            assert_eq!(
+                db.remove_logs(&[(other_message, meta(10))]).await.unwrap(),
+                0
+            );
+            assert_eq!(db.remove_logs(&messages[..1]).await.unwrap(), 1);
+            assert!(db.retrieve_message_by_nonce(0).unwrap().is_none());
+            assert_eq!(rollbacks.try_recv().unwrap().from_nonce(), Some(0));
+            assert!(rollbacks.try_recv().is_err());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_only_rolls_back_recent_blocks() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_only_rolls_back_recent_blocks"),
+                db,
+            );
+            let meta = |block_number: u64| LogMeta {
+                address: H256::from_low_u64_be(1),
+                block_number,
+                block_hash: H256::from_low_u64_be(block_number),
+                transaction_id: H512::from_low_u64_be(block_number),
+                transaction_index: 0,
+                log_index: U256::from(0),
+            };
+            let message = |nonce| HyperlaneMessage {
+                nonce,
+                ..HyperlaneMessage::default()
+            };
+            let payment = InterchainGasPayment {
+                message_id: message(0).id(),
+                payment: U256::from(1),
+                gas_amount: U256::from(1),
+            };
+            db.store_logs(&[(message(0), meta(10))]).await.unwrap();
+            db.store_logs(&[(payment, meta(10))]).await.unwrap();
+            // Blocks long after the first ones are indexed
+            db.store_logs(&[(message(1), meta(1_000_000))])
+                .await
+                .unwrap();
+            db.store_logs(&[(payment, meta(1_000_000))]).await.unwrap();
+
+            assert_eq!(
+                HyperlaneLogStore::<HyperlaneMessage>::rollback_logs(&db, 0)
+                    .await
+                    .unwrap(),
+                1
+            );
+            assert!(db.retrieve_message_by_nonce(0).unwrap().is_some());
+            assert!(db.retrieve_message_by_nonce(1).unwrap().is_none());
+            assert_eq!(
+                HyperlaneLogStore::<InterchainGasPayment>::rollback_logs(&db, 0)
+                    .await
+                    .unwrap(),
+                1
+            );
+            let total = db.retrieve_gas_payment_by_message_id(payment.message_id);
+            assert_eq!(total.unwrap().payment, U256::from(1));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_refuses_conflicting_signings() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_refuses_conflicting_signings"),
+                db,
+            );
+            let entry = SigningJournalEntry {
+                root: H256::from_low_u64_be(1),
+                message_id: H256::from_low_u64_be(2),
+            };
+            let conflicting = SigningJournalEntry {
+                root: H256::from_low_u64_be(3),
+                ..entry
+            };
+            assert_eq!(db.journal_signing(5, entry).unwrap(), None);
+            // Signing the same checkpoint again is fine
+            assert_eq!(db.journal_signing(5, entry).unwrap(), None);
+            assert_eq!(db.journal_signing(5, conflicting).unwrap(), Some(entry));
+            // The conflicting checkpoint isn't journaled
+            assert_eq!(db.journal_signing(5, conflicting).unwrap(), Some(entry));
+            assert_eq!(db.journal_signing(6, conflicting).unwrap(), None);
+
+            assert_eq!(db.journal_legacy_signing(5, entry.root).unwrap(), None);
+            assert_eq!(
+                db.journal_legacy_signing(5, conflicting.root).unwrap(),
+                Some(entry.root)
+            );
         })
         .await;
     }
@@ -85,4 +85,37 @@ impl TypedDB {
     ) -> Result<Option<V>> {
         self.retrieve_decodable(prefix, key.to_vec())
     }
+
+    /// Delete the value stored for an encodable key
+    pub fn delete_keyed<K: Encode>(&self, prefix: impl AsRef<[u8]>, key: &K) -> Result<()> {
+        self.db
+            .delete(&self.prefixed_key(prefix.as_ref(), &key.to_vec()))
+    }
+
+    /// Retrieve every decodable kv pair stored under a prefix
+    pub fn retrieve_all_keyed_decodable<K: Decode, V: Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+    ) -> Result<Vec<(K, V)>> {
+        self.iter_keyed_decodable_from(prefix, &[]).collect()
+    }
+
+    /// Iterate over the decodable kv pairs stored under a prefix in key order,
+    /// starting at the first pair whose encoded key is at least `from`
+    pub fn iter_keyed_decodable_from<K: Decode, V: Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+        from: &[u8],
+    ) -> impl Iterator<Item = Result<(K, V)>> + '_ {
+        let prefix = self.prefixed_key(prefix.as_ref(), &[]);
+        let from = [prefix.as_slice(), from].concat();
+        let key_start = prefix.len();
+        self.db.prefix_iter_from(prefix, &from).map(move |entry| {
+            let (key, value) = entry?;
+            Ok((
+                K::read_from(&mut &key[key_start..])?,
+                V::read_from(&mut &value[..])?,
+            ))
+        })
+    }
 }
@@ -11,6 +11,7 @@ use prometheus::{
 };
 use tokio::task::JoinHandle;
 use tracing::warn;
+use warp::filters::BoxedFilter;
 
 use ethers_prometheus::{json_rpc_client::JsonRpcClientMetrics, middleware::MiddlewareMetrics};
 
@@ -25,6 +26,9 @@ macro_rules! namespaced {
     };
 }
 
+/// Additional routes an agent serves on the metrics HTTP server.
+pub type HttpRoutes = BoxedFilter<(Box<dyn warp::Reply>,)>;
+
 /// Metrics for a particular domain
 pub struct CoreMetrics {
     /// Metrics registry for adding new metrics and gathering reports
@@ -42,6 +46,8 @@ pub struct CoreMetrics {
 
     operations_processed_count: IntCounterVec,
     messages_processed_count: IntCounterVec,
+    message_latency: HistogramVec,
+    oldest_undelivered_message_age: IntGaugeVec,
 
     latest_checkpoint: IntGaugeVec,
 
@@ -135,7 +141,7 @@ impl CoreMetrics {
                 "Mailbox latest checkpoint",
                 const_labels_ref
             ),
-            &["phase", "chain"],
+            &["phase", "chain", "validator"],
             registry
         )?;
 
@@ -159,6 +165,30 @@ impl CoreMetrics {
             registry
         )?;
 
+        let message_latency = register_histogram_vec_with_registry!(
+            histogram_opts!(
+                namespaced!("message_latency_seconds"),
+                "Seconds a message took to go from one stage of delivery to the next",
+                vec![
+                    1., 5., 15., 30., 60., 120., 300., 600., 1200., 1800., 3600., 7200., 21600.,
+                    86400.
+                ],
+                const_labels.clone()
+            ),
+            &["stage", "origin", "remote"],
+            registry
+        )?;
+
+        let oldest_undelivered_message_age = register_int_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("oldest_undelivered_message_age_seconds"),
+                "Age of the oldest message waiting to be delivered",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
         Ok(Self {
             agent_name: for_agent.into(),
             registry,
@@ -175,6 +205,8 @@ impl CoreMetrics {
 
             operations_processed_count,
             messages_processed_count,
+            message_latency,
+            oldest_undelivered_message_age,
 
             latest_checkpoint,
 
@@ -312,6 +344,11 @@ impl CoreMetrics {
     /// - `validator_observed`: When the validator has observed the checkpoint
     ///   on the mailbox contract.
     /// - `validator_processed`: When the validator has written this checkpoint.
+    ///
+    /// Labels:
+    /// - `chain`: Origin chain
+    /// - `validator`: Address of the validator key, as a validator rotating
+    ///   keys signs with two
     pub fn latest_checkpoint(&self) -> IntGaugeVec {
         self.latest_checkpoint.clone()
     }
@@ -369,6 +406,37 @@ impl CoreMetrics {
         self.messages_processed_count.clone()
     }
 
+    /// Seconds messages took to go from one stage of delivery to the next.
+    ///
+    /// Labels:
+    /// - `stage`: The stages the latency was measured between, see below.
+    /// - `origin`: Chain the message came from.
+    /// - `remote`: Chain the message is delivered to.
+    ///
+    /// The following stages are implemented:
+    /// - `dispatch_to_index`: From the timestamp of the block the message was
+    ///   dispatched in to when it was indexed.
+    /// - `index_to_prepare`: From when the message was indexed to when it was
+    ///   first ready to be submitted.
+    /// - `prepare_to_submit`: From when the message was last prepared to when
+    ///   a transaction delivering it was included.
+    /// - `submit_to_confirm`: From when the transaction delivering the message
+    ///   was included to when the delivery was confirmed.
+    pub fn message_latency(&self) -> HistogramVec {
+        self.message_latency.clone()
+    }
+
+    /// Seconds since the oldest message that is waiting to be delivered was
+    /// dispatched, or 0 if there are none. Messages that were dropped are not
+    /// waited on.
+    ///
+    /// Labels:
+    /// - `origin`: Chain the message came from.
+    /// - `remote`: Chain the message is delivered to.
+    pub fn oldest_undelivered_message_age(&self) -> IntGaugeVec {
+        self.oldest_undelivered_message_age.clone()
+    }
+
     /// Measure of span durations provided by tracing.
     ///
     /// Labels:
@@ -411,14 +479,20 @@ impl CoreMetrics {
         Ok(out_buf)
     }
 
-    /// Run an HTTP server serving OpenMetrics format reports on `/metrics`
+    /// Run an HTTP server serving OpenMetrics format reports on `/metrics`,
+    /// along with any additional `routes` the agent provides.
     ///
     /// This is compatible with Prometheus, which ought to be configured to
     /// scrape me!
-    pub fn run_http_server(self: Arc<Self>) -> JoinHandle<()> {
+    pub fn run_http_server(self: Arc<Self>, routes: Option<HttpRoutes>) -> JoinHandle<()> {
         use warp::Filter;
         let port = self.listen_port;
         tracing::info!(port, "starting prometheus server on 0.0.0.0");
+        let routes = routes.unwrap_or_else(|| {
+            warp::any()
+                .and_then(|| async { Err::<Box<dyn warp::Reply>, _>(warp::reject::not_found()) })
+                .boxed()
+        });
         tokio::spawn(async move {
             warp::serve(
                 warp::path!("metrics")
@@ -433,6 +507,7 @@ impl CoreMetrics {
                             "text/plain; charset=utf-8",
                         )
                     })
+                    .or(routes)
                     .or(warp::any().map(|| {
                         warp::reply::with_status(
                             "go look at /metrics",
@@ -17,5 +17,16 @@ pub(crate) fn create_json_rpc_client_metrics(
             REQUEST_DURATION_SECONDS_HELP,
             REQUEST_DURATION_SECONDS_LABELS,
         )?)
+        .disagreement_count(metrics.new_int_counter(
+            "request_disagreement_count",
+            DISAGREEMENT_COUNT_HELP,
+            DISAGREEMENT_COUNT_LABELS,
+        )?)
+        .demoted(metrics.new_int_gauge("provider_demoted", DEMOTED_HELP, DEMOTED_LABELS)?)
+        .health_score(metrics.new_gauge(
+            "provider_health_score",
+            HEALTH_SCORE_HELP,
+            HEALTH_SCORE_LABELS,
+        )?)
         .build()?)
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use ethers::prelude::Selector;
 use ethers_prometheus::middleware::{
@@ -7,9 +7,9 @@ use ethers_prometheus::middleware::{
 use eyre::{eyre, Context, Result};
 use hyperlane_core::{
     AggregationIsm, CcipReadIsm, ContractLocator, HyperlaneAbi, HyperlaneDomain,
-    HyperlaneDomainProtocol, HyperlaneProvider, HyperlaneSigner, IndexMode, Indexer,
-    InterchainGasPaymaster, InterchainGasPayment, InterchainSecurityModule, Mailbox,
-    MessageIndexer, MultisigIsm, RoutingIsm, ValidatorAnnounce, H256,
+    HyperlaneDomainProtocol, HyperlaneMessage, HyperlaneProvider, HyperlaneSigner, IndexMode,
+    InterchainGasPaymaster, InterchainGasPayment, InterchainSecurityModule, LogSubscriber, Mailbox,
+    MessageIndexer, MultisigIsm, RoutingIsm, SequenceIndexer, ValidatorAnnounce, H256,
 };
 use hyperlane_ethereum::{
     self as h_eth, BuildableWithProvider, EthereumInterchainGasPaymasterAbi, EthereumMailboxAbi,
@@ -43,6 +43,8 @@ pub struct ChainConf {
     pub metrics_conf: PrometheusMiddlewareConf,
     /// Settings for event indexing
     pub index: IndexSettings,
+    /// Settings for replacing stuck transactions. Only used by Ethereum chains.
+    pub gas_escalator: h_eth::GasEscalatorConf,
 }
 
 /// A connection to _some_ blockchain.
@@ -87,6 +89,9 @@ pub struct IndexSettings {
     pub chunk_size: u32,
     /// The indexing mode.
     pub mode: IndexMode,
+    /// How long to wait before checking for new events again once indexing
+    /// has caught up with the chain. Only used by sequence-based indexing.
+    pub poll_interval: Duration,
 }
 
 impl ChainConf {
@@ -102,8 +107,14 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::HyperlaneProviderBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
-            ChainConnectionConf::Sealevel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let provider = h_fuel::FuelProvider::connect(conf, self.domain.clone())?;
+                Ok(Box::new(provider) as Box<dyn HyperlaneProvider>)
+            }
+            ChainConnectionConf::Sealevel(conf) => {
+                let provider = h_sealevel::SealevelProvider::new(self.domain.clone(), conf);
+                Ok(Box::new(provider) as Box<dyn HyperlaneProvider>)
+            }
         }
         .context(ctx)
     }
@@ -115,8 +126,15 @@ impl ChainConf {
 
         match &self.connection {
             ChainConnectionConf::Ethereum(conf) => {
-                self.build_ethereum(conf, &locator, metrics, h_eth::MailboxBuilder {})
-                    .await
+                self.build_ethereum(
+                    conf,
+                    &locator,
+                    metrics,
+                    h_eth::MailboxBuilder {
+                        gas_escalator: self.gas_escalator.clone(),
+                    },
+                )
+                .await
             }
 
             ChainConnectionConf::Fuel(conf) => {
@@ -156,7 +174,14 @@ impl ChainConf {
                 .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let indexer = Box::new(h_fuel::FuelMailboxIndexer::new(
+                    conf,
+                    locator,
+                    self.finality_blocks,
+                )?);
+                Ok(indexer as Box<dyn MessageIndexer>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn MessageIndexer>)
@@ -169,7 +194,7 @@ impl ChainConf {
     pub async fn build_delivery_indexer(
         &self,
         metrics: &CoreMetrics,
-    ) -> Result<Box<dyn Indexer<H256>>> {
+    ) -> Result<Box<dyn SequenceIndexer<H256>>> {
         let ctx = "Building delivery indexer";
         let locator = self.locator(self.addresses.mailbox);
 
@@ -186,10 +211,17 @@ impl ChainConf {
                 .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let indexer = Box::new(h_fuel::FuelMailboxIndexer::new(
+                    conf,
+                    locator,
+                    self.finality_blocks,
+                )?);
+                Ok(indexer as Box<dyn SequenceIndexer<H256>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
-                Ok(indexer as Box<dyn Indexer<H256>>)
+                Ok(indexer as Box<dyn SequenceIndexer<H256>>)
             }
         }
         .context(ctx)
@@ -215,10 +247,17 @@ impl ChainConf {
                 .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.fuel_signer_or_random().await.context(ctx)?;
+                let paymaster = Box::new(h_fuel::FuelInterchainGasPaymaster::new(
+                    conf, locator, wallet,
+                )?);
+                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
+                let keypair = self.sealevel_signer().await.context(ctx)?;
                 let paymaster = Box::new(h_sealevel::SealevelInterchainGasPaymaster::new(
-                    conf, locator,
+                    conf, locator, keypair,
                 ));
                 Ok(paymaster as Box<dyn InterchainGasPaymaster>)
             }
@@ -230,7 +269,7 @@ impl ChainConf {
     pub async fn build_interchain_gas_payment_indexer(
         &self,
         metrics: &CoreMetrics,
-    ) -> Result<Box<dyn Indexer<InterchainGasPayment>>> {
+    ) -> Result<Box<dyn SequenceIndexer<InterchainGasPayment>>> {
         let ctx = "Building IGP indexer";
         let locator = self.locator(self.addresses.interchain_gas_paymaster);
 
@@ -248,17 +287,42 @@ impl ChainConf {
                 .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let indexer = Box::new(h_fuel::FuelInterchainGasPaymasterIndexer::new(
+                    conf,
+                    locator,
+                    self.finality_blocks,
+                )?);
+                Ok(indexer as Box<dyn SequenceIndexer<InterchainGasPayment>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelInterchainGasPaymasterIndexer::new(
                     conf, locator,
                 ));
-                Ok(indexer as Box<dyn Indexer<InterchainGasPayment>>)
+                Ok(indexer as Box<dyn SequenceIndexer<InterchainGasPayment>>)
             }
         }
         .context(ctx)
     }
 
+    /// Try to convert the chain settings into a subscriber to dispatched
+    /// messages, if the chain is indexed using subscriptions
+    pub fn build_message_subscriber(
+        &self,
+    ) -> Result<Option<Arc<dyn LogSubscriber<HyperlaneMessage>>>> {
+        self.build_log_subscriber(self.addresses.mailbox)
+            .context("Building message subscriber")
+    }
+
+    /// Try to convert the chain settings into a subscriber to gas payments,
+    /// if the chain is indexed using subscriptions
+    pub fn build_interchain_gas_payment_subscriber(
+        &self,
+    ) -> Result<Option<Arc<dyn LogSubscriber<InterchainGasPayment>>>> {
+        self.build_log_subscriber(self.addresses.interchain_gas_paymaster)
+            .context("Building IGP subscriber")
+    }
+
     /// Try to convert the chain settings into a ValidatorAnnounce
     pub async fn build_validator_announce(
         &self,
@@ -271,7 +335,11 @@ impl ChainConf {
                     .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.fuel_signer_or_random().await?;
+                let va = Box::new(h_fuel::FuelValidatorAnnounce::new(conf, locator, wallet)?);
+                Ok(va as Box<dyn ValidatorAnnounce>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let va = Box::new(h_sealevel::SealevelValidatorAnnounce::new(conf, locator));
                 Ok(va as Box<dyn ValidatorAnnounce>)
@@ -301,7 +369,10 @@ impl ChainConf {
                 .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let ism = Box::new(h_fuel::FuelInterchainSecurityModule::new(conf, locator)?);
+                Ok(ism as Box<dyn InterchainSecurityModule>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let keypair = self.sealevel_signer().await.context(ctx)?;
                 let ism = Box::new(h_sealevel::SealevelInterchainSecurityModule::new(
@@ -328,7 +399,10 @@ impl ChainConf {
                     .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let ism = Box::new(h_fuel::FuelMultisigIsm::new(conf, locator)?);
+                Ok(ism as Box<dyn MultisigIsm>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let keypair = self.sealevel_signer().await.context(ctx)?;
                 let ism = Box::new(h_sealevel::SealevelMultisigIsm::new(conf, locator, keypair));
@@ -356,9 +430,14 @@ impl ChainConf {
                     .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
-            ChainConnectionConf::Sealevel(_) => {
-                Err(eyre!("Sealevel does not support routing ISM yet")).context(ctx)
+            ChainConnectionConf::Fuel(conf) => {
+                let ism = Box::new(h_fuel::FuelRoutingIsm::new(conf, locator)?);
+                Ok(ism as Box<dyn RoutingIsm>)
+            }
+            ChainConnectionConf::Sealevel(conf) => {
+                let keypair = self.sealevel_signer().await.context(ctx)?;
+                let ism = Box::new(h_sealevel::SealevelRoutingIsm::new(conf, locator, keypair));
+                Ok(ism as Box<dyn RoutingIsm>)
             }
         }
         .context(ctx)
@@ -382,9 +461,15 @@ impl ChainConf {
                     .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
-            ChainConnectionConf::Sealevel(_) => {
-                Err(eyre!("Sealevel does not support aggregation ISM yet")).context(ctx)
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support aggregation ISM yet")).context(ctx)
+            }
+            ChainConnectionConf::Sealevel(conf) => {
+                let keypair = self.sealevel_signer().await.context(ctx)?;
+                let ism = Box::new(h_sealevel::SealevelAggregationIsm::new(
+                    conf, locator, keypair,
+                ));
+                Ok(ism as Box<dyn AggregationIsm>)
             }
         }
         .context(ctx)
@@ -408,7 +493,11 @@ impl ChainConf {
                     .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support CCIP read ISM yet")).context(ctx)
+            }
+            // CCIP read relies on the EVM `OffchainLookup` revert, which has no
+            // Sealevel equivalent.
             ChainConnectionConf::Sealevel(_) => {
                 Err(eyre!("Sealevel does not support CCIP read ISM yet")).context(ctx)
             }
@@ -434,6 +523,16 @@ impl ChainConf {
         })
     }
 
+    /// A fuel wallet for contracts which only need to sign transactions when
+    /// a signer is configured. Falls back to a random unfunded wallet, which is
+    /// sufficient for simulated calls.
+    async fn fuel_signer_or_random(&self) -> Result<fuels::prelude::WalletUnlocked> {
+        Ok(self
+            .signer()
+            .await?
+            .unwrap_or_else(|| fuels::prelude::WalletUnlocked::new_random(None)))
+    }
+
     async fn sealevel_signer(&self) -> Result<Option<h_sealevel::Keypair>> {
         self.signer().await
     }
@@ -492,6 +591,24 @@ impl ChainConf {
         cfg
     }
 
+    fn build_log_subscriber<T>(&self, address: H256) -> Result<Option<Arc<dyn LogSubscriber<T>>>>
+    where
+        h_eth::EthereumLogSubscriber: LogSubscriber<T>,
+    {
+        if !matches!(self.index.mode, IndexMode::Subscription) {
+            return Ok(None);
+        }
+        let locator = self.locator(address);
+        match &self.connection {
+            ChainConnectionConf::Ethereum(h_eth::ConnectionConf::Ws { url }) => Ok(Some(Arc::new(
+                h_eth::EthereumLogSubscriber::new(url.clone(), &locator, self.finality_blocks),
+            ))),
+            _ => Err(eyre!(
+                "Subscription indexing requires an Ethereum websocket connection"
+            )),
+        }
+    }
+
     fn locator(&self, address: H256) -> ContractLocator {
         ContractLocator {
             domain: &self.domain,
@@ -4,9 +4,12 @@ use std::{collections::HashMap, path::PathBuf};
 use eyre::{eyre, Context, Report, Result};
 use hyperlane_core::H160;
 use prometheus::{IntGauge, IntGaugeVec};
+use reqwest::Url;
 use rusoto_core::Region;
 
-use crate::{CheckpointSyncer, LocalStorage, MultisigCheckpointSyncer, S3Storage};
+use crate::{
+    CheckpointSyncer, GcsStorage, HttpStorage, LocalStorage, MultisigCheckpointSyncer, S3Storage,
+};
 
 /// Checkpoint Syncer types
 #[derive(Debug, Clone)]
@@ -23,6 +26,18 @@ pub enum CheckpointSyncerConf {
         /// S3 Region
         region: Region,
     },
+    /// A checkpoint syncer on GCS
+    Gcs {
+        /// Bucket name
+        bucket: String,
+        /// Folder in the bucket checkpoints are stored in
+        folder: Option<String>,
+    },
+    /// A read-only checkpoint syncer for checkpoints published over HTTPS
+    Http {
+        /// Url checkpoints are published under
+        url: Url,
+    },
 }
 
 impl FromStr for CheckpointSyncerConf {
@@ -48,6 +63,26 @@ impl FromStr for CheckpointSyncerConf {
                         .context("Invalid region when parsing storage location")?,
                 })
             }
+            "gs" => {
+                let (bucket, folder) = match suffix.split_once('/') {
+                    Some((bucket, folder)) => (bucket, Some(folder.trim_end_matches('/'))),
+                    None => (suffix, None),
+                };
+                if bucket.is_empty() {
+                    return Err(eyre!(
+                        "Error parsing storage location; missing bucket ({suffix})"
+                    ));
+                }
+                Ok(CheckpointSyncerConf::Gcs {
+                    bucket: bucket.into(),
+                    folder: folder.filter(|f| !f.is_empty()).map(Into::into),
+                })
+            }
+            "https" => Ok(CheckpointSyncerConf::Http {
+                url: s
+                    .parse()
+                    .context("Invalid url when parsing storage location")?,
+            }),
             "file" => Ok(CheckpointSyncerConf::LocalStorage {
                 path: suffix.into(),
             }),
@@ -71,6 +106,14 @@ impl CheckpointSyncerConf {
                 region.clone(),
                 latest_index_gauge,
             )),
+            CheckpointSyncerConf::Gcs { bucket, folder } => Box::new(GcsStorage::new(
+                bucket.clone(),
+                folder.clone(),
+                latest_index_gauge,
+            )?),
+            CheckpointSyncerConf::Http { url } => {
+                Box::new(HttpStorage::new(url.clone(), latest_index_gauge)?)
+            }
         })
     }
 }
@@ -102,3 +145,29 @@ impl MultisigCheckpointSyncerConf {
         Ok(MultisigCheckpointSyncer::new(checkpoint_syncers))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parses_storage_locations() {
+        assert!(matches!(
+            "gs://bucket".parse(),
+            Ok(CheckpointSyncerConf::Gcs { bucket, folder: None }) if bucket == "bucket"
+        ));
+        assert!(matches!(
+            "gs://bucket/validator/1/".parse(),
+            Ok(CheckpointSyncerConf::Gcs { bucket, folder: Some(folder) })
+                if bucket == "bucket" && folder == "validator/1"
+        ));
+        assert!("gs://".parse::<CheckpointSyncerConf>().is_err());
+        assert!(matches!(
+            "https://example.com/checkpoints".parse(),
+            Ok(CheckpointSyncerConf::Http { url }) if url.as_str() == "https://example.com/checkpoints"
+        ));
+        assert!("http://example.com/checkpoints"
+            .parse::<CheckpointSyncerConf>()
+            .is_err());
+    }
+}
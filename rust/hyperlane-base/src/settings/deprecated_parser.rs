@@ -3,6 +3,7 @@
 // TODO: Remove this module once we have finished migrating to the new format.
 
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use ethers_prometheus::middleware::PrometheusMiddlewareConf;
 use eyre::{eyre, Context};
@@ -158,6 +159,7 @@ struct DeprecatedRawIndexSettings {
     from: Option<StrOrInt>,
     chunk: Option<StrOrInt>,
     mode: Option<String>,
+    poll_interval: Option<StrOrInt>,
 }
 
 impl FromRawConf<DeprecatedRawIndexSettings> for IndexSettings {
@@ -188,10 +190,17 @@ impl FromRawConf<DeprecatedRawIndexSettings> for IndexSettings {
             })
             .unwrap_or_default();
 
+        let poll_interval = raw
+            .poll_interval
+            .and_then(|v| v.try_into().take_err(&mut err, || cwp + "poll_interval"))
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(5));
+
         err.into_result(Self {
             from,
             chunk_size,
             mode,
+            poll_interval,
         })
     }
 }
@@ -213,6 +222,8 @@ pub struct DeprecatedRawChainConf {
     metrics_conf: Option<PrometheusMiddlewareConf>,
     #[serde(default)]
     index: Option<DeprecatedRawIndexSettings>,
+    #[serde(default)]
+    gas_escalator: Option<h_eth::RawGasEscalatorConf>,
 }
 
 impl FromRawConf<DeprecatedRawChainConf> for ChainConf {
@@ -279,6 +290,14 @@ impl FromRawConf<DeprecatedRawChainConf> for ChainConf {
 
         let metrics_conf = raw.metrics_conf.unwrap_or_default();
 
+        let gas_escalator = raw
+            .gas_escalator
+            .and_then(|v| {
+                v.parse_config(&cwp.join("gas_escalator"))
+                    .take_config_err(&mut err)
+            })
+            .unwrap_or_default();
+
         cfg_unwrap_all!(cwp, err: [connection, domain, addresses]);
 
         err.into_result(Self {
@@ -289,6 +308,7 @@ impl FromRawConf<DeprecatedRawChainConf> for ChainConf {
             finality_blocks,
             index,
             metrics_conf,
+            gas_escalator,
         })
     }
 }
@@ -10,6 +10,7 @@ use std::{
     cmp::Reverse,
     collections::{HashMap, HashSet},
     path::PathBuf,
+    time::Duration,
 };
 
 use eyre::{eyre, Context};
@@ -45,9 +46,11 @@ struct RawAgentChainMetadataConf {
     #[serde(default)]
     custom_rpc_urls: HashMap<String, RawRpcUrlConf>,
     rpc_consensus_type: Option<RawRpcConsensusType>,
+    rpc_quorum: Option<StrOrInt>,
     signer: Option<RawSignerConf>,
     #[serde(default)]
     index: RawAgentChainMetadataIndexConf,
+    gas_escalator: Option<h_eth::RawGasEscalatorConf>,
 
     // -- ChainMetadata --
     protocol: Option<String>,
@@ -85,6 +88,7 @@ struct RawAgentChainMetadataIndexConf {
     from: Option<StrOrInt>,
     chunk: Option<StrOrInt>,
     mode: Option<String>,
+    poll_interval: Option<StrOrInt>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -151,6 +155,11 @@ pub struct RawSignerConf {
     key: Option<String>,
     id: Option<String>,
     region: Option<String>,
+    url: Option<String>,
+    address: Option<String>,
+    client_cert: Option<String>,
+    client_key: Option<String>,
+    ca_cert: Option<String>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -165,6 +174,7 @@ struct RawAgentLogConf {
 enum RawRpcConsensusType {
     Fallback,
     Quorum,
+    CrossChecked,
     #[serde(other)]
     Unknown,
 }
@@ -185,6 +195,13 @@ pub enum RawCheckpointSyncerConf {
         /// S3 Region
         region: Option<String>,
     },
+    /// A checkpoint syncer on GCS
+    Gcs {
+        /// Bucket name
+        bucket: Option<String>,
+        /// Folder in the bucket
+        folder: Option<String>,
+    },
     /// Unknown checkpoint syncer type was specified
     #[serde(other)]
     Unknown,
@@ -342,6 +359,14 @@ impl FromRawConf<RawAgentChainMetadataConf> for ChainConf {
             );
         }
 
+        let gas_escalator = raw
+            .gas_escalator
+            .and_then(|v| {
+                v.parse_config(&cwp.join("gas_escalator"))
+                    .take_config_err(&mut err)
+            })
+            .unwrap_or_default();
+
         cfg_unwrap_all!(cwp, err: [index, finality_blocks, domain]);
 
         let connection: Option<ChainConnectionConf> = match domain.domain_protocol() {
@@ -378,6 +403,14 @@ impl FromRawConf<RawAgentChainMetadataConf> for ChainConf {
                         Some(RawRpcConsensusType::Quorum) => {
                             Some(h_eth::ConnectionConf::HttpQuorum { urls })
                         }
+                        Some(RawRpcConsensusType::CrossChecked) => {
+                            let quorum = raw
+                                .rpc_quorum
+                                .and_then(|v| v.try_into().take_err(&mut err, || cwp + "rpc_quorum"))
+                                .map(|v: u32| v as usize);
+                            h_eth::ConnectionConf::http_cross_checked(urls, quorum)
+                                .take_err(&mut err, || cwp + "rpc_quorum")
+                        }
                         Some(RawRpcConsensusType::Unknown) => {
                             err.push(cwp + "rpc_consensus_type", eyre!("unknown rpc consensus type"));
                             None
@@ -413,6 +446,7 @@ impl FromRawConf<RawAgentChainMetadataConf> for ChainConf {
             connection,
             metrics_conf: Default::default(),
             index,
+            gas_escalator,
         })
     }
 }
@@ -553,10 +587,17 @@ impl FromRawConf<RawAgentChainMetadataIndexConf, Option<&HyperlaneDomain>> for I
             })
             .unwrap_or_default();
 
+        let poll_interval = raw
+            .poll_interval
+            .and_then(|v| v.try_into().take_err(&mut err, || cwp + "poll_interval"))
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(5));
+
         err.into_result(Self {
             from,
             chunk_size,
             mode,
+            poll_interval,
         })
     }
 }
@@ -591,6 +632,41 @@ impl FromRawConf<RawSignerConf> for SignerConf {
                     .parse()
                     .into_config_result(region_path)?,
             }),
+            Some("remote") => {
+                let url_path = || cwp + "url";
+                let address_path = || cwp + "address";
+                let tls = match (raw.client_cert, raw.client_key) {
+                    (Some(client_cert), Some(client_key)) => Some(h_eth::RemoteSignerTlsConf {
+                        client_cert: client_cert.into(),
+                        client_key: client_key.into(),
+                        ca_cert: raw.ca_cert.map(Into::into),
+                    }),
+                    (None, None) => None,
+                    (Some(_), None) => {
+                        return Err(eyre!("Missing `clientKey` for Remote signer"))
+                            .into_config_result(|| cwp + "client_key")
+                    }
+                    (None, Some(_)) => {
+                        return Err(eyre!("Missing `clientCert` for Remote signer"))
+                            .into_config_result(|| cwp + "client_cert")
+                    }
+                };
+                Ok(Self::Remote(h_eth::RemoteSignerConf {
+                    url: raw
+                        .url
+                        .ok_or_else(|| eyre!("Missing `url` for Remote signer"))
+                        .into_config_result(url_path)?
+                        .parse()
+                        .into_config_result(url_path)?,
+                    address: raw
+                        .address
+                        .ok_or_else(|| eyre!("Missing `address` for Remote signer"))
+                        .into_config_result(address_path)?
+                        .parse()
+                        .into_config_result(address_path)?,
+                    tls,
+                }))
+            }
             Some(t) => Err(eyre!("Unknown signer type `{t}`")).into_config_result(|| cwp + "type"),
             None if raw.key.is_some() => Ok(Self::HexKey {
                 key: raw.key.unwrap().parse().into_config_result(key_path)?,
@@ -652,6 +728,12 @@ impl FromRawConf<RawCheckpointSyncerConf> for CheckpointSyncerConf {
                     .parse()
                     .into_config_result(|| cwp + "region")?,
             }),
+            RawCheckpointSyncerConf::Gcs { bucket, folder } => Ok(Self::Gcs {
+                bucket: bucket
+                    .ok_or_else(|| eyre!("Missing `bucket` for GCS checkpoint syncer"))
+                    .into_config_result(|| cwp + "bucket")?,
+                folder,
+            }),
             RawCheckpointSyncerConf::Unknown => Err(eyre!("Missing `type` for checkpoint syncer"))
                 .into_config_result(|| cwp + "type"),
         }
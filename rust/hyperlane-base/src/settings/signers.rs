@@ -28,6 +28,9 @@ pub enum SignerConf {
         /// The AWS region
         region: Region,
     },
+    /// A remote signer, such as Web3Signer, which holds the key and signs
+    /// messages over JSON-RPC. It can't sign transactions.
+    Remote(hyperlane_ethereum::RemoteSignerConf),
     /// Assume the local node will sign on RPC calls automatically
     #[default]
     Node,
@@ -73,6 +76,9 @@ impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
                 let signer = AwsSigner::new(client, id, 0).await?;
                 hyperlane_ethereum::Signers::Aws(signer)
             }
+            SignerConf::Remote(conf) => hyperlane_ethereum::Signers::Remote(
+                hyperlane_ethereum::RemoteSigner::connect(conf)?,
+            ),
             SignerConf::Node => bail!("Node signer"),
         })
     }
@@ -88,6 +94,7 @@ impl BuildableWithSignerConf for fuels::prelude::WalletUnlocked {
                 fuels::prelude::WalletUnlocked::new_from_private_key(key, None)
             }
             SignerConf::Aws { .. } => bail!("Aws signer is not supported by fuel"),
+            SignerConf::Remote(_) => bail!("Remote signer is not supported by fuel"),
             SignerConf::Node => bail!("Node signer is not supported by fuel"),
         })
     }
@@ -104,6 +111,7 @@ impl BuildableWithSignerConf for Keypair {
                     .context("Unable to create Keypair")?
             }
             SignerConf::Aws { .. } => bail!("Aws signer is not supported by fuel"),
+            SignerConf::Remote(_) => bail!("Remote signer is not supported by sealevel"),
             SignerConf::Node => bail!("Node signer is not supported by fuel"),
         })
     }
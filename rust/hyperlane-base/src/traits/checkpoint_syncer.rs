@@ -1,10 +1,14 @@
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::ops::RangeInclusive;
 
 use async_trait::async_trait;
 use eyre::Result;
 
 use hyperlane_core::{SignedAnnouncement, SignedCheckpoint, SignedCheckpointWithMessageId};
 
+use crate::{CheckpointBundle, CheckpointBundleManifest};
+
 /// A generic trait to read/write Checkpoints offchain
 #[async_trait]
 pub trait CheckpointSyncer: Debug + Send + Sync {
@@ -14,6 +18,43 @@ pub trait CheckpointSyncer: Debug + Send + Sync {
     async fn legacy_fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpoint>>;
     /// Attempt to fetch the signed (checkpoint, messageId) tuple at this index
     async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>>;
+    /// Attempt to fetch the signed (checkpoint, messageId) tuples in this
+    /// range, ordered by index. Reads the bundles that cover the range and
+    /// falls back to fetching the checkpoints of the remaining indices one by
+    /// one.
+    async fn fetch_checkpoints(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> Result<Vec<SignedCheckpointWithMessageId>> {
+        let mut checkpoints = BTreeMap::new();
+        if let Some(manifest) = self.fetch_bundle_manifest().await? {
+            for bundle_range in manifest.overlapping(&range) {
+                let Some(bundle) = self.fetch_bundle(bundle_range.clone()).await? else {
+                    continue
+                };
+                checkpoints.extend(
+                    bundle
+                        .checkpoints
+                        .into_iter()
+                        .filter(|checkpoint| range.contains(&checkpoint.value.index))
+                        .map(|checkpoint| (checkpoint.value.index, checkpoint)),
+                );
+            }
+        }
+        for index in range {
+            if checkpoints.contains_key(&index) {
+                continue;
+            }
+            if let Some(checkpoint) = self.fetch_checkpoint(index).await? {
+                checkpoints.insert(index, checkpoint);
+            }
+        }
+        Ok(checkpoints.into_values().collect())
+    }
+    /// Attempt to fetch the manifest of the bundles of this syncer
+    async fn fetch_bundle_manifest(&self) -> Result<Option<CheckpointBundleManifest>>;
+    /// Attempt to fetch the bundle of checkpoints covering this range
+    async fn fetch_bundle(&self, range: RangeInclusive<u32>) -> Result<Option<CheckpointBundle>>;
     /// Write the signed checkpoint to this syncer
     async fn legacy_write_checkpoint(&self, signed_checkpoint: &SignedCheckpoint) -> Result<()>;
     /// Write the signed (checkpoint, messageId) tuple to this syncer
@@ -21,6 +62,12 @@ pub trait CheckpointSyncer: Debug + Send + Sync {
         &self,
         signed_checkpoint: &SignedCheckpointWithMessageId,
     ) -> Result<()>;
+    /// Write a bundle of signed (checkpoint, messageId) tuples to this syncer.
+    /// It's only fetched once it's added to the manifest.
+    async fn write_bundle(&self, bundle: &CheckpointBundle) -> Result<()>;
+    /// Write the manifest of the bundles of this syncer, replacing the
+    /// previous one. There must be only one writer, which keeps the manifest.
+    async fn write_bundle_manifest(&self, manifest: &CheckpointBundleManifest) -> Result<()>;
     /// Write the signed announcement to this syncer
     async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()>;
     /// Return the announcement storage location for this syncer
@@ -0,0 +1,106 @@
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use hyperlane_core::SignedCheckpointWithMessageId;
+
+/// The number of checkpoints validators bundle together. Bundles cover
+/// ranges of indices aligned to multiples of this.
+pub const CHECKPOINT_BUNDLE_SIZE: u32 = 1000;
+
+/// The signed checkpoints of a range of indices, published in addition to
+/// the checkpoint of each index so that they can be fetched at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointBundle {
+    /// The indices the bundle covers
+    pub range: RangeInclusive<u32>,
+    /// The signed checkpoints in the range, ordered by index
+    pub checkpoints: Vec<SignedCheckpointWithMessageId>,
+}
+
+/// Lists the bundles of checkpoints a checkpoint syncer has published.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckpointBundleManifest {
+    /// The ranges of the published bundles, ordered by their first index
+    pub bundles: Vec<RangeInclusive<u32>>,
+}
+
+impl CheckpointBundle {
+    /// Bundle the checkpoints in `range`, ignoring any outside of it.
+    pub fn new(
+        range: RangeInclusive<u32>,
+        checkpoints: impl IntoIterator<Item = SignedCheckpointWithMessageId>,
+    ) -> Self {
+        let mut checkpoints: Vec<_> = checkpoints
+            .into_iter()
+            .filter(|checkpoint| range.contains(&checkpoint.value.index))
+            .collect();
+        checkpoints.sort_by_key(|checkpoint| checkpoint.value.index);
+        checkpoints.dedup_by_key(|checkpoint| checkpoint.value.index);
+        Self { range, checkpoints }
+    }
+
+    /// The range of the bundle that covers `index`.
+    pub fn range_containing(index: u32) -> RangeInclusive<u32> {
+        let start = index - index % CHECKPOINT_BUNDLE_SIZE;
+        start..=start.saturating_add(CHECKPOINT_BUNDLE_SIZE - 1)
+    }
+}
+
+impl CheckpointBundleManifest {
+    /// Add a published bundle to the manifest.
+    pub fn add(&mut self, range: RangeInclusive<u32>) {
+        if !self.bundles.contains(&range) {
+            self.bundles.push(range);
+            self.bundles.sort_by_key(|range| *range.start());
+        }
+    }
+
+    /// Whether the bundle covering `range` was published.
+    pub fn contains(&self, range: &RangeInclusive<u32>) -> bool {
+        self.bundles.contains(range)
+    }
+
+    /// The bundles that cover any of the indices in `range`.
+    pub fn overlapping<'a>(
+        &'a self,
+        range: &'a RangeInclusive<u32>,
+    ) -> impl Iterator<Item = &'a RangeInclusive<u32>> {
+        self.bundles
+            .iter()
+            .filter(|bundle| bundle.start() <= range.end() && range.start() <= bundle.end())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_manifest_tracks_bundled_ranges() {
+        let mut manifest = CheckpointBundleManifest::default();
+        assert!(!manifest.contains(&(0..=999)));
+
+        manifest.add(1000..=1999);
+        manifest.add(0..=999);
+        manifest.add(1000..=1999);
+        assert_eq!(manifest.bundles, vec![0..=999, 1000..=1999]);
+        assert!(manifest.contains(&(0..=999)));
+        assert!(!manifest.contains(&(2000..=2999)));
+
+        let range = 990..=1000;
+        assert_eq!(
+            manifest.overlapping(&range).collect::<Vec<_>>(),
+            vec![&(0..=999), &(1000..=1999)]
+        );
+        let range = 2000..=2100;
+        assert_eq!(manifest.overlapping(&range).count(), 0);
+    }
+
+    #[test]
+    fn test_bundles_are_aligned() {
+        assert_eq!(CheckpointBundle::range_containing(0), 0..=999);
+        assert_eq!(CheckpointBundle::range_containing(1999), 1000..=1999);
+        assert_eq!(CheckpointBundle::range_containing(2000), 2000..=2999);
+    }
+}
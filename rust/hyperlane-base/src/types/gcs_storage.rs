@@ -0,0 +1,431 @@
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+use std::{env, fmt};
+
+use async_trait::async_trait;
+use eyre::{Context, Result};
+use prometheus::IntGauge;
+use reqwest::{Client, StatusCode, Url};
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use hyperlane_core::{SignedAnnouncement, SignedCheckpoint, SignedCheckpointWithMessageId};
+
+use crate::{CheckpointBundle, CheckpointBundleManifest, CheckpointSyncer};
+
+/// The timeout for GCS requests.
+const GCS_REQUEST_TIMEOUT_SECONDS: u64 = 30;
+
+/// The GCS API endpoint.
+const GCS_API_URL: &str = "https://storage.googleapis.com";
+
+/// The environment variable pointing to a GCS emulator, e.g.
+/// fake-gcs-server, which Google's client libraries use too. Requests to an
+/// emulator are not authenticated.
+const STORAGE_EMULATOR_HOST: &str = "STORAGE_EMULATOR_HOST";
+
+/// Where the access token of the instance's service account is fetched from
+/// when running on GCP.
+const GCP_METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// How long before it expires an access token is refreshed.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Type for reading/writing to GCS. Reads are anonymous, so the bucket must be
+/// publicly readable. Writes are authenticated as the service account of the
+/// GCP instance the agent runs on.
+pub struct GcsStorage {
+    /// The name of the bucket.
+    bucket: String,
+    /// The folder in the bucket checkpoints are stored in, if any.
+    folder: Option<String>,
+    /// The GCS API endpoint, or that of an emulator.
+    api_url: Url,
+    /// Whether requests to write are authenticated.
+    authenticated: bool,
+    client: Client,
+    /// The cached access token and when it expires.
+    access_token: Mutex<Option<(String, Instant)>>,
+    /// The latest seen signed checkpoint index.
+    latest_index: Option<IntGauge>,
+}
+
+impl fmt::Debug for GcsStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GcsStorage")
+            .field("bucket", &self.bucket)
+            .field("folder", &self.folder)
+            .field("api_url", &self.api_url.as_str())
+            .finish()
+    }
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl GcsStorage {
+    /// Create a new GCS checkpoint syncer, which uses the emulator at
+    /// `STORAGE_EMULATOR_HOST` if it is set.
+    pub fn new(
+        bucket: String,
+        folder: Option<String>,
+        latest_index: Option<IntGauge>,
+    ) -> Result<Self> {
+        match env::var(STORAGE_EMULATOR_HOST) {
+            Ok(host) if host.contains("://") => {
+                Self::with_api_url(bucket, folder, host.parse()?, false, latest_index)
+            }
+            Ok(host) => Self::with_api_url(
+                bucket,
+                folder,
+                format!("http://{host}").parse()?,
+                false,
+                latest_index,
+            ),
+            Err(_) => Self::with_api_url(bucket, folder, GCS_API_URL.parse()?, true, latest_index),
+        }
+    }
+
+    fn with_api_url(
+        bucket: String,
+        folder: Option<String>,
+        api_url: Url,
+        authenticated: bool,
+        latest_index: Option<IntGauge>,
+    ) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(GCS_REQUEST_TIMEOUT_SECONDS))
+            .build()?;
+        Ok(Self {
+            bucket,
+            folder,
+            api_url,
+            authenticated,
+            client,
+            access_token: Mutex::new(None),
+            latest_index,
+        })
+    }
+
+    fn object_name(&self, key: String) -> String {
+        match &self.folder {
+            Some(folder) => format!("{folder}/{key}"),
+            None => key,
+        }
+    }
+
+    /// The url to download an object with the JSON API.
+    fn download_url(&self, key: String) -> Url {
+        let mut url = self.api_url.clone();
+        url.path_segments_mut()
+            .expect("GCS API url can't be a base")
+            .pop_if_empty()
+            .extend([
+                "storage",
+                "v1",
+                "b",
+                self.bucket.as_str(),
+                "o",
+                self.object_name(key).as_str(),
+            ]);
+        url.query_pairs_mut().append_pair("alt", "media");
+        url
+    }
+
+    /// The url to upload an object with the JSON API.
+    fn upload_url(&self, key: String) -> Url {
+        let mut url = self.api_url.clone();
+        url.path_segments_mut()
+            .expect("GCS API url can't be a base")
+            .pop_if_empty()
+            .extend(["upload", "storage", "v1", "b", self.bucket.as_str(), "o"]);
+        url.query_pairs_mut()
+            .append_pair("uploadType", "media")
+            .append_pair("name", &self.object_name(key));
+        url
+    }
+
+    /// Gets an access token of the instance's service account, fetching a new
+    /// one from the metadata server if the cached one is about to expire.
+    async fn access_token(&self) -> Result<String> {
+        let mut cached = self.access_token.lock().await;
+        if let Some((token, expiry)) = cached.as_ref() {
+            if Instant::now() + TOKEN_REFRESH_MARGIN < *expiry {
+                return Ok(token.clone());
+            }
+        }
+        let token: AccessToken = serde_json::from_slice(
+            &self
+                .client
+                .get(GCP_METADATA_TOKEN_URL)
+                .header("Metadata-Flavor", "Google")
+                .send()
+                .await?
+                .error_for_status()
+                .context("Failed to get access token from the GCP metadata server")?
+                .bytes()
+                .await?,
+        )?;
+        let expiry = Instant::now() + Duration::from_secs(token.expires_in);
+        *cached = Some((token.access_token.clone(), expiry));
+        Ok(token.access_token)
+    }
+
+    async fn write_to_bucket(&self, key: String, body: &str) -> Result<()> {
+        let mut req = self
+            .client
+            .post(self.upload_url(key))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_owned());
+        if self.authenticated {
+            req = req.bearer_auth(self.access_token().await?);
+        }
+        req.send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    /// Reads anonymously, so the bucket must be publicly readable.
+    async fn anonymously_read_from_bucket(&self, key: String) -> Result<Option<Vec<u8>>> {
+        let res = self.client.get(self.download_url(key)).send().await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(res.error_for_status()?.bytes().await?.to_vec()))
+    }
+
+    fn legacy_checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}.json")
+    }
+
+    fn checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}_with_id.json")
+    }
+
+    fn index_key() -> String {
+        "checkpoint_latest_index.json".to_owned()
+    }
+
+    fn bundle_key(range: &RangeInclusive<u32>) -> String {
+        format!("checkpoint_bundle_{}_{}.json", range.start(), range.end())
+    }
+
+    fn bundle_manifest_key() -> String {
+        "checkpoint_bundle_manifest.json".to_owned()
+    }
+
+    fn announcement_key() -> String {
+        "announcement.json".to_owned()
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for GcsStorage {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let ret = self
+            .anonymously_read_from_bucket(GcsStorage::index_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into);
+
+        if let Ok(Some(latest_index)) = ret {
+            if let Some(gauge) = &self.latest_index {
+                gauge.set(latest_index as i64);
+            }
+        }
+
+        ret
+    }
+
+    async fn legacy_fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpoint>> {
+        self.anonymously_read_from_bucket(GcsStorage::legacy_checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.anonymously_read_from_bucket(GcsStorage::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn fetch_bundle_manifest(&self) -> Result<Option<CheckpointBundleManifest>> {
+        self.anonymously_read_from_bucket(GcsStorage::bundle_manifest_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn fetch_bundle(&self, range: RangeInclusive<u32>) -> Result<Option<CheckpointBundle>> {
+        self.anonymously_read_from_bucket(GcsStorage::bundle_key(&range))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn legacy_write_checkpoint(&self, signed_checkpoint: &SignedCheckpoint) -> Result<()> {
+        let serialized_checkpoint = serde_json::to_string_pretty(signed_checkpoint)?;
+        self.write_to_bucket(
+            GcsStorage::legacy_checkpoint_key(signed_checkpoint.value.index),
+            &serialized_checkpoint,
+        )
+        .await?;
+
+        self.write_to_bucket(
+            GcsStorage::index_key(),
+            &signed_checkpoint.value.index.to_string(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let serialized_checkpoint = serde_json::to_string_pretty(signed_checkpoint)?;
+        self.write_to_bucket(
+            GcsStorage::checkpoint_key(signed_checkpoint.value.index),
+            &serialized_checkpoint,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn write_bundle(&self, bundle: &CheckpointBundle) -> Result<()> {
+        let serialized_bundle = serde_json::to_string(bundle)?;
+        self.write_to_bucket(GcsStorage::bundle_key(&bundle.range), &serialized_bundle)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn write_bundle_manifest(&self, manifest: &CheckpointBundleManifest) -> Result<()> {
+        let serialized_manifest = serde_json::to_string_pretty(manifest)?;
+        self.write_to_bucket(GcsStorage::bundle_manifest_key(), &serialized_manifest)
+            .await?;
+        Ok(())
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        let serialized_announcement = serde_json::to_string_pretty(signed_announcement)?;
+        self.write_to_bucket(GcsStorage::announcement_key(), &serialized_announcement)
+            .await?;
+        Ok(())
+    }
+
+    fn announcement_location(&self) -> String {
+        match &self.folder {
+            Some(folder) => format!("gs://{}/{folder}", self.bucket),
+            None => format!("gs://{}", self.bucket),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    use ethers::signers::LocalWallet;
+    use warp::{hyper::body::Bytes, Filter};
+
+    use hyperlane_core::{CheckpointWithMessageId, HyperlaneSignerExt, H256};
+
+    use super::*;
+
+    type Objects = Arc<StdMutex<HashMap<String, Vec<u8>>>>;
+
+    /// Serve the parts of the GCS JSON API used by `GcsStorage`, like
+    /// fake-gcs-server would, returning its url.
+    fn serve_emulator(objects: Objects) -> Url {
+        let download = {
+            let objects = objects.clone();
+            warp::get()
+                .and(warp::path!("storage" / "v1" / "b" / String / "o" / String))
+                .map(move |bucket: String, name: String| {
+                    let key = format!("{bucket}/{}", name.replace("%2F", "/"));
+                    match objects.lock().unwrap().get(&key) {
+                        Some(data) => warp::http::Response::new(data.clone()),
+                        None => warp::http::Response::builder()
+                            .status(StatusCode::NOT_FOUND)
+                            .body(vec![])
+                            .unwrap(),
+                    }
+                })
+        };
+        let upload = warp::post()
+            .and(warp::path!(
+                "upload" / "storage" / "v1" / "b" / String / "o"
+            ))
+            .and(warp::query::<HashMap<String, String>>())
+            .and(warp::body::bytes())
+            .map(
+                move |bucket: String, query: HashMap<String, String>, body: Bytes| {
+                    assert_eq!(query["uploadType"], "media");
+                    let key = format!("{bucket}/{}", query["name"]);
+                    objects.lock().unwrap().insert(key, body.to_vec());
+                    "{}"
+                },
+            );
+        let (addr, server) = warp::serve(download.or(upload)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{addr}").parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_writes_and_fetches_checkpoints() {
+        let objects = Objects::default();
+        let storage = GcsStorage::with_api_url(
+            "bucket".to_owned(),
+            Some("validator/1".to_owned()),
+            serve_emulator(objects.clone()),
+            false,
+            None,
+        )
+        .unwrap();
+        assert_eq!(storage.announcement_location(), "gs://bucket/validator/1");
+        assert_eq!(storage.latest_index().await.unwrap(), None);
+        assert!(storage.fetch_checkpoint(0).await.unwrap().is_none());
+
+        let signer: LocalWallet =
+            "1111111111111111111111111111111111111111111111111111111111111111"
+                .parse()
+                .unwrap();
+        let signer = hyperlane_ethereum::Signers::from(signer);
+        let checkpoint = CheckpointWithMessageId {
+            checkpoint: hyperlane_core::Checkpoint {
+                mailbox_address: H256::repeat_byte(1),
+                mailbox_domain: 1,
+                root: H256::repeat_byte(2),
+                index: 3,
+            },
+            message_id: H256::repeat_byte(3),
+        };
+        let signed = signer.sign(checkpoint).await.unwrap();
+        storage.write_checkpoint(&signed).await.unwrap();
+        storage
+            .legacy_write_checkpoint(&signer.sign(checkpoint.checkpoint).await.unwrap())
+            .await
+            .unwrap();
+
+        assert!(objects
+            .lock()
+            .unwrap()
+            .contains_key("bucket/validator/1/checkpoint_3_with_id.json"));
+        assert_eq!(storage.latest_index().await.unwrap(), Some(3));
+        let fetched = storage.fetch_checkpoint(3).await.unwrap().unwrap();
+        assert_eq!(fetched.value, signed.value);
+        assert_eq!(fetched.signature, signed.signature);
+    }
+}
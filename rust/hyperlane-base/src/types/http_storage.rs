@@ -0,0 +1,207 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use eyre::{bail, Result};
+use prometheus::IntGauge;
+use reqwest::{Client, StatusCode, Url};
+
+use hyperlane_core::{SignedAnnouncement, SignedCheckpoint, SignedCheckpointWithMessageId};
+
+use crate::{CheckpointBundle, CheckpointBundleManifest, CheckpointSyncer};
+
+/// The timeout for HTTP requests.
+const HTTP_REQUEST_TIMEOUT_SECONDS: u64 = 30;
+
+/// Type for reading checkpoints a validator publishes over HTTP(S), laid out
+/// the same way as in an S3 bucket. It is read-only, so it can't be used by
+/// validators to publish checkpoints.
+#[derive(Debug, Clone)]
+pub struct HttpStorage {
+    /// The url checkpoints are published under.
+    url: Url,
+    client: Client,
+    /// The latest seen signed checkpoint index.
+    latest_index: Option<IntGauge>,
+}
+
+impl HttpStorage {
+    /// Create a new HTTP checkpoint syncer reading from under `url`.
+    pub fn new(url: Url, latest_index: Option<IntGauge>) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(HTTP_REQUEST_TIMEOUT_SECONDS))
+            .build()?;
+        Ok(Self {
+            url,
+            client,
+            latest_index,
+        })
+    }
+
+    /// The url of an object, relative to the url checkpoints are published
+    /// under, whether or not it ends with a `/`.
+    fn object_url(&self, key: String) -> Url {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .expect("HTTP checkpoint syncer url can't be a base")
+            .pop_if_empty()
+            .push(&key);
+        url
+    }
+
+    async fn read(&self, key: String) -> Result<Option<Vec<u8>>> {
+        let res = self.client.get(self.object_url(key)).send().await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(res.error_for_status()?.bytes().await?.to_vec()))
+    }
+
+    fn legacy_checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}.json")
+    }
+
+    fn checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}_with_id.json")
+    }
+
+    fn index_key() -> String {
+        "checkpoint_latest_index.json".to_owned()
+    }
+
+    fn bundle_key(range: &RangeInclusive<u32>) -> String {
+        format!("checkpoint_bundle_{}_{}.json", range.start(), range.end())
+    }
+
+    fn bundle_manifest_key() -> String {
+        "checkpoint_bundle_manifest.json".to_owned()
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for HttpStorage {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let ret = self
+            .read(HttpStorage::index_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into);
+
+        if let Ok(Some(latest_index)) = ret {
+            if let Some(gauge) = &self.latest_index {
+                gauge.set(latest_index as i64);
+            }
+        }
+
+        ret
+    }
+
+    async fn legacy_fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpoint>> {
+        self.read(HttpStorage::legacy_checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.read(HttpStorage::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn fetch_bundle_manifest(&self) -> Result<Option<CheckpointBundleManifest>> {
+        self.read(HttpStorage::bundle_manifest_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn fetch_bundle(&self, range: RangeInclusive<u32>) -> Result<Option<CheckpointBundle>> {
+        self.read(HttpStorage::bundle_key(&range))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn legacy_write_checkpoint(&self, _: &SignedCheckpoint) -> Result<()> {
+        bail!("HTTP checkpoint syncer at {} is read-only", self.url)
+    }
+
+    async fn write_checkpoint(&self, _: &SignedCheckpointWithMessageId) -> Result<()> {
+        bail!("HTTP checkpoint syncer at {} is read-only", self.url)
+    }
+
+    async fn write_bundle(&self, _: &CheckpointBundle) -> Result<()> {
+        bail!("HTTP checkpoint syncer at {} is read-only", self.url)
+    }
+
+    async fn write_bundle_manifest(&self, _: &CheckpointBundleManifest) -> Result<()> {
+        bail!("HTTP checkpoint syncer at {} is read-only", self.url)
+    }
+
+    async fn write_announcement(&self, _: &SignedAnnouncement) -> Result<()> {
+        bail!("HTTP checkpoint syncer at {} is read-only", self.url)
+    }
+
+    fn announcement_location(&self) -> String {
+        self.url.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::signers::LocalWallet;
+    use warp::Filter;
+
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, HyperlaneSignerExt, H256};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetches_published_checkpoints() {
+        // Publish checkpoints laid out like in S3 from a directory
+        let dir = tempfile::tempdir().unwrap();
+        let (addr, server) =
+            warp::serve(warp::path("checkpoints").and(warp::fs::dir(dir.path().to_owned())))
+                .bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        let storage =
+            HttpStorage::new(format!("http://{addr}/checkpoints").parse().unwrap(), None).unwrap();
+        assert_eq!(storage.latest_index().await.unwrap(), None);
+        assert!(storage.fetch_checkpoint(3).await.unwrap().is_none());
+
+        let signer: LocalWallet =
+            "1111111111111111111111111111111111111111111111111111111111111111"
+                .parse()
+                .unwrap();
+        let signer = hyperlane_ethereum::Signers::from(signer);
+        let checkpoint = CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                mailbox_address: H256::repeat_byte(1),
+                mailbox_domain: 1,
+                root: H256::repeat_byte(2),
+                index: 3,
+            },
+            message_id: H256::repeat_byte(3),
+        };
+        let signed = signer.sign(checkpoint).await.unwrap();
+        std::fs::write(
+            dir.path().join("checkpoint_3_with_id.json"),
+            serde_json::to_string_pretty(&signed).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("checkpoint_latest_index.json"), "3").unwrap();
+
+        assert_eq!(storage.latest_index().await.unwrap(), Some(3));
+        let fetched = storage.fetch_checkpoint(3).await.unwrap().unwrap();
+        assert_eq!(fetched.value, signed.value);
+        assert_eq!(fetched.signature, signed.signature);
+        assert!(storage.write_checkpoint(&signed).await.is_err());
+    }
+}
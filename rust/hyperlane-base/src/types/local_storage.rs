@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
@@ -7,6 +8,7 @@ use prometheus::IntGauge;
 use hyperlane_core::{SignedAnnouncement, SignedCheckpoint, SignedCheckpointWithMessageId};
 
 use crate::traits::CheckpointSyncer;
+use crate::{CheckpointBundle, CheckpointBundleManifest};
 
 #[derive(Debug, Clone)]
 /// Type for reading/write to LocalStorage
@@ -38,6 +40,15 @@ impl LocalStorage {
         self.path.join(format!("{}_with_id.json", index))
     }
 
+    fn bundle_file_path(&self, range: &RangeInclusive<u32>) -> PathBuf {
+        self.path
+            .join(format!("bundle_{}_{}.json", range.start(), range.end()))
+    }
+
+    fn bundle_manifest_file_path(&self) -> PathBuf {
+        self.path.join("bundle_manifest.json")
+    }
+
     fn latest_index_file_path(&self) -> PathBuf {
         self.path.join("index.json")
     }
@@ -93,6 +104,22 @@ impl CheckpointSyncer for LocalStorage {
         Ok(Some(checkpoint))
     }
 
+    async fn fetch_bundle_manifest(&self) -> Result<Option<CheckpointBundleManifest>> {
+        let Ok(data) = tokio::fs::read(self.bundle_manifest_file_path()).await else {
+            return Ok(None)
+        };
+        let manifest = serde_json::from_slice(&data)?;
+        Ok(Some(manifest))
+    }
+
+    async fn fetch_bundle(&self, range: RangeInclusive<u32>) -> Result<Option<CheckpointBundle>> {
+        let Ok(data) = tokio::fs::read(self.bundle_file_path(&range)).await else {
+            return Ok(None)
+        };
+        let bundle = serde_json::from_slice(&data)?;
+        Ok(Some(bundle))
+    }
+
     async fn legacy_write_checkpoint(&self, signed_checkpoint: &SignedCheckpoint) -> Result<()> {
         let serialized_checkpoint = serde_json::to_string_pretty(signed_checkpoint)?;
         let path = self.legacy_checkpoint_file_path(signed_checkpoint.value.index);
@@ -125,6 +152,24 @@ impl CheckpointSyncer for LocalStorage {
         Ok(())
     }
 
+    async fn write_bundle(&self, bundle: &CheckpointBundle) -> Result<()> {
+        let serialized_bundle = serde_json::to_string(bundle)?;
+        let path = self.bundle_file_path(&bundle.range);
+        tokio::fs::write(&path, &serialized_bundle)
+            .await
+            .with_context(|| format!("Writing checkpoint bundle to {path:?}"))?;
+        Ok(())
+    }
+
+    async fn write_bundle_manifest(&self, manifest: &CheckpointBundleManifest) -> Result<()> {
+        let serialized_manifest = serde_json::to_string_pretty(manifest)?;
+        let path = self.bundle_manifest_file_path();
+        tokio::fs::write(&path, &serialized_manifest)
+            .await
+            .with_context(|| format!("Writing checkpoint bundle manifest to {path:?}"))?;
+        Ok(())
+    }
+
     async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
         let serialized_announcement = serde_json::to_string_pretty(signed_announcement)?;
         let path = self.announcement_file_path();
@@ -138,3 +183,66 @@ impl CheckpointSyncer for LocalStorage {
         format!("file://{}", self.path.to_str().unwrap())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Signature, H256, U256};
+
+    use super::*;
+
+    fn signed_checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+        SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    mailbox_address: H256::repeat_byte(1),
+                    mailbox_domain: 1,
+                    root: H256::from_low_u64_be(index.into()),
+                    index,
+                },
+                message_id: H256::from_low_u64_be(index.into()),
+            },
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetches_checkpoints_from_bundles() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path().to_owned(), None).unwrap();
+        for index in 0..25 {
+            storage
+                .write_checkpoint(&signed_checkpoint(index))
+                .await
+                .unwrap();
+        }
+        let mut manifest = CheckpointBundleManifest::default();
+        for range in [0..=9, 10..=19] {
+            let checkpoints = storage.fetch_checkpoints(range.clone()).await.unwrap();
+            storage
+                .write_bundle(&CheckpointBundle::new(range.clone(), checkpoints))
+                .await
+                .unwrap();
+            manifest.add(range);
+        }
+        storage.write_bundle_manifest(&manifest).await.unwrap();
+        let manifest = storage.fetch_bundle_manifest().await.unwrap().unwrap();
+        assert_eq!(manifest.bundles, vec![0..=9, 10..=19]);
+
+        // Bundled checkpoints are read from bundles, the rest one by one
+        for index in 0..20 {
+            std::fs::remove_file(storage.checkpoint_file_path(index)).unwrap();
+        }
+        let checkpoints = storage.fetch_checkpoints(5..=30).await.unwrap();
+        assert_eq!(
+            checkpoints
+                .iter()
+                .map(|checkpoint| checkpoint.value.index)
+                .collect::<Vec<_>>(),
+            (5..25).collect::<Vec<_>>()
+        );
+    }
+}
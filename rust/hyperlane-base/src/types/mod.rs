@@ -1,7 +1,13 @@
+mod checkpoint_bundle;
+mod gcs_storage;
+mod http_storage;
 mod local_storage;
 mod multisig;
 mod s3_storage;
 
+pub use checkpoint_bundle::*;
+pub use gcs_storage::*;
+pub use http_storage::*;
 pub use local_storage::*;
 pub use multisig::*;
 pub use s3_storage::*;
@@ -1,4 +1,5 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use derive_new::new;
@@ -198,7 +199,8 @@ impl MultisigCheckpointSyncer {
     ///
     /// Attempts to find a quorum of signed checkpoints from that index,
     /// iterating backwards if unsuccessful, until the (optional) index is
-    /// reached.
+    /// reached. Indices that validators publish bundles for are fetched at
+    /// once instead, looking for the highest index with a quorum among them.
     ///
     /// Note it's possible to not find a quorum.
     #[instrument(err, skip(self))]
@@ -248,13 +250,156 @@ impl MultisigCheckpointSyncer {
                 debug!(%start_index, %highest_quorum_index, "Highest quorum index is below the minimum index");
                 return Ok(None);
             }
-            for index in (minimum_index..=start_index).rev() {
+            if let Ok(Some(checkpoint)) = self
+                .fetch_checkpoint(validators, threshold, start_index)
+                .await
+            {
+                return Ok(Some(checkpoint));
+            }
+            if minimum_index == start_index {
+                debug!("No checkpoint found in range");
+                return Ok(None);
+            }
+            // Keep iterating backwards, stopping at the first quorum, down to
+            // the indices that any validator publishes bundles for. Those are
+            // fetched at once, which validators serve in a few requests.
+            let bundled_end = self
+                .highest_bundled_index(validators, &(minimum_index..=start_index - 1))
+                .await;
+            let scan_end = bundled_end.map_or(minimum_index, |end| end + 1);
+            for index in (scan_end..start_index).rev() {
                 if let Ok(Some(checkpoint)) =
                     self.fetch_checkpoint(validators, threshold, index).await
                 {
                     return Ok(Some(checkpoint));
                 }
             }
+            if let Some(bundled_end) = bundled_end {
+                return self
+                    .fetch_checkpoints_in_range(validators, threshold, minimum_index..=bundled_end)
+                    .await;
+            }
+        }
+        debug!("No checkpoint found in range");
+        Ok(None)
+    }
+
+    /// The highest index in `range` that is covered by a bundle published by
+    /// any of the validators, if any.
+    async fn highest_bundled_index(
+        &self,
+        validators: &[H256],
+        range: &RangeInclusive<u32>,
+    ) -> Option<u32> {
+        let mut highest_bundled_index = None;
+        for validator in validators {
+            let Some(checkpoint_syncer) = self.checkpoint_syncers.get(&H160::from(*validator))
+            else {
+                continue;
+            };
+            let manifest = match checkpoint_syncer.fetch_bundle_manifest().await {
+                Ok(Some(manifest)) => manifest,
+                Ok(None) => continue,
+                Err(err) => {
+                    debug!(
+                        validator = format!("{validator:#x}"),
+                        ?err,
+                        "Unable to fetch bundle manifest"
+                    );
+                    continue;
+                }
+            };
+            let bundled_end = manifest
+                .overlapping(range)
+                .map(|bundle| *bundle.end().min(range.end()))
+                .max();
+            highest_bundled_index = highest_bundled_index.max(bundled_end);
+        }
+        highest_bundled_index
+    }
+
+    /// Fetches the signed checkpoints in `range` of each validator at once and
+    /// returns the one with the highest index that has a quorum of
+    /// signatures, if any.
+    #[instrument(err, skip(self))]
+    pub async fn fetch_checkpoints_in_range(
+        &self,
+        validators: &[H256],
+        threshold: usize,
+        range: RangeInclusive<u32>,
+    ) -> Result<Option<MultisigSignedCheckpoint<CheckpointWithMessageId>>> {
+        // The signed checkpoints of each index, grouped by root like in
+        // `fetch_checkpoint`, in the order of the validators
+        let mut signed_checkpoints_per_index: BTreeMap<
+            u32,
+            HashMap<H256, Vec<SignedCheckpointWithSigner<CheckpointWithMessageId>>>,
+        > = BTreeMap::new();
+
+        for validator in validators {
+            let addr = H160::from(*validator);
+            let Some(checkpoint_syncer) = self.checkpoint_syncers.get(&addr) else {
+                debug!(%validator, "Unable to find checkpoint syncer");
+                continue;
+            };
+            let signed_checkpoints = match checkpoint_syncer.fetch_checkpoints(range.clone()).await
+            {
+                Ok(signed_checkpoints) => signed_checkpoints,
+                Err(err) => {
+                    debug!(
+                        validator = format!("{validator:#x}"),
+                        ?err,
+                        "Unable to fetch signed checkpoints"
+                    );
+                    continue;
+                }
+            };
+            debug!(
+                validator = format!("{validator:#x}"),
+                count = signed_checkpoints.len(),
+                "Fetched signed checkpoints"
+            );
+            for signed_checkpoint in signed_checkpoints {
+                let index = signed_checkpoint.value.index;
+                if !range.contains(&index) {
+                    continue;
+                }
+                // Ensure that the signature is actually by the validator. A
+                // bad signature only rules out this checkpoint, not the rest.
+                let signer = match signed_checkpoint.recover() {
+                    Ok(signer) if H256::from(signer) == *validator => signer,
+                    result => {
+                        debug!(
+                            validator = format!("{validator:#x}"),
+                            index,
+                            ?result,
+                            "Checkpoint signature mismatch"
+                        );
+                        continue;
+                    }
+                };
+                let root = signed_checkpoint.value.root;
+                signed_checkpoints_per_index
+                    .entry(index)
+                    .or_default()
+                    .entry(root)
+                    .or_default()
+                    .push(SignedCheckpointWithSigner {
+                        signer,
+                        signed_checkpoint,
+                    });
+            }
+        }
+
+        for (index, signed_checkpoints_per_root) in signed_checkpoints_per_index.iter().rev() {
+            for signed_checkpoints in signed_checkpoints_per_root.values() {
+                if signed_checkpoints.len() >= threshold {
+                    let checkpoint = MultisigSignedCheckpoint::<CheckpointWithMessageId>::try_from(
+                        signed_checkpoints,
+                    )?;
+                    debug!(index, ?checkpoint, "Fetched multisig checkpoint");
+                    return Ok(Some(checkpoint));
+                }
+            }
         }
         debug!("No checkpoint found in range");
         Ok(None)
@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::sync::OnceLock;
 use std::{fmt, time::Duration};
 
@@ -16,7 +17,7 @@ use tokio::time::timeout;
 use hyperlane_core::{SignedAnnouncement, SignedCheckpoint, SignedCheckpointWithMessageId};
 
 use crate::settings::aws_credentials::AwsChainCredentialsProvider;
-use crate::CheckpointSyncer;
+use crate::{CheckpointBundle, CheckpointBundleManifest, CheckpointSyncer};
 
 /// The timeout for S3 requests. Rusoto doesn't offer timeout configuration
 /// out of the box, so S3 requests must be wrapped with a timeout.
@@ -132,6 +133,14 @@ impl S3Storage {
         "checkpoint_latest_index.json".to_owned()
     }
 
+    fn bundle_key(range: &RangeInclusive<u32>) -> String {
+        format!("checkpoint_bundle_{}_{}.json", range.start(), range.end())
+    }
+
+    fn bundle_manifest_key() -> String {
+        "checkpoint_bundle_manifest.json".to_owned()
+    }
+
     fn announcement_key() -> String {
         "announcement.json".to_owned()
     }
@@ -172,6 +181,22 @@ impl CheckpointSyncer for S3Storage {
             .map_err(Into::into)
     }
 
+    async fn fetch_bundle_manifest(&self) -> Result<Option<CheckpointBundleManifest>> {
+        self.anonymously_read_from_bucket(S3Storage::bundle_manifest_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn fetch_bundle(&self, range: RangeInclusive<u32>) -> Result<Option<CheckpointBundle>> {
+        self.anonymously_read_from_bucket(S3Storage::bundle_key(&range))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
     async fn legacy_write_checkpoint(&self, signed_checkpoint: &SignedCheckpoint) -> Result<()> {
         let serialized_checkpoint = serde_json::to_string_pretty(signed_checkpoint)?;
         self.write_to_bucket(
@@ -201,6 +226,21 @@ impl CheckpointSyncer for S3Storage {
         Ok(())
     }
 
+    async fn write_bundle(&self, bundle: &CheckpointBundle) -> Result<()> {
+        let serialized_bundle = serde_json::to_string(bundle)?;
+        self.write_to_bucket(S3Storage::bundle_key(&bundle.range), &serialized_bundle)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn write_bundle_manifest(&self, manifest: &CheckpointBundleManifest) -> Result<()> {
+        let serialized_manifest = serde_json::to_string_pretty(manifest)?;
+        self.write_to_bucket(S3Storage::bundle_manifest_key(), &serialized_manifest)
+            .await?;
+        Ok(())
+    }
+
     async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
         let serialized_announcement = serde_json::to_string_pretty(signed_announcement)?;
         self.write_to_bucket(S3Storage::announcement_key(), &serialized_announcement)
@@ -74,6 +74,9 @@ pub enum ChainCommunicationError {
     /// No signer is available and was required for the operation
     #[error("Signer unavailable")]
     SignerUnavailable,
+    /// Processing several messages in one transaction isn't supported
+    #[error("Batching messages is not supported")]
+    BatchingNotSupported,
 }
 
 impl ChainCommunicationError {
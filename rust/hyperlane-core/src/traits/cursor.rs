@@ -26,4 +26,8 @@ pub enum CursorAction {
     Query(IndexRange),
     /// Direct the contract_sync task to sleep for a duration
     Sleep(Duration),
+    /// Direct the contract_sync task to remove the logs it stored from a block
+    /// onwards, which a reorg removed from the chain. The cursor has already
+    /// rewound to re-index them.
+    Rollback(u32),
 }
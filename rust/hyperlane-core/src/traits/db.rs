@@ -13,6 +13,22 @@ pub trait HyperlaneLogStore<T>: Send + Sync + Debug {
     /// Store a list of logs and their associated metadata
     /// Returns the number of elements that were stored.
     async fn store_logs(&self, logs: &[(T, LogMeta)]) -> Result<u32>;
+
+    /// Remove the logs that were stored from a block onwards, e.g. because a
+    /// reorg removed that block from the chain.
+    /// Returns the number of elements that were removed. Stores that can't
+    /// remove logs keep them and return 0.
+    async fn rollback_logs(&self, _from_block: u32) -> Result<u32> {
+        Ok(0)
+    }
+
+    /// Remove logs that were stored but have since been removed from the
+    /// chain by a reorg, e.g. as reported by a subscription to the logs.
+    /// Returns the number of elements that were removed. Stores that can't
+    /// remove logs keep them and return 0.
+    async fn remove_logs(&self, _logs: &[(T, LogMeta)]) -> Result<u32> {
+        Ok(0)
+    }
 }
 
 /// Extension of HyperlaneLogStore trait that supports getting the block number at which a known message was dispatched.
@@ -33,4 +49,16 @@ pub trait HyperlaneWatermarkedLogStore<T>: HyperlaneLogStore<T> {
     async fn retrieve_high_watermark(&self) -> Result<Option<u32>>;
     /// Stores the block number high watermark
     async fn store_high_watermark(&self, block_number: u32) -> Result<()>;
+
+    /// Gets the next sequence to index, when indexing by sequence. Stores
+    /// that derive it from the events they stored return `None`.
+    async fn retrieve_sequence_watermark(&self) -> Result<Option<u32>> {
+        Ok(None)
+    }
+
+    /// Stores the next sequence to index, when indexing by sequence. Stores
+    /// that derive it from the events they stored don't keep it.
+    async fn store_sequence_watermark(&self, _next_sequence: u32) -> Result<()> {
+        Ok(())
+    }
 }
@@ -11,7 +11,7 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 use serde::Deserialize;
 
-use crate::{ChainResult, HyperlaneMessage, LogMeta};
+use crate::{ChainResult, HyperlaneLogStore, HyperlaneMessage, LogMeta, H256};
 
 /// Indexing mode.
 #[derive(Copy, Debug, Default, Deserialize, Clone)]
@@ -22,6 +22,10 @@ pub enum IndexMode {
     Block,
     /// Sequence based indexing.
     Sequence,
+    /// Block based indexing, with logs also stored as soon as their blocks
+    /// are final by subscribing to them. The block based cursor keeps running
+    /// to fill any gaps the subscription leaves.
+    Subscription,
 }
 
 /// An indexing range.
@@ -44,6 +48,14 @@ pub trait Indexer<T: Sized>: Send + Sync + Debug {
 
     /// Get the chain's latest block number that has reached finality
     async fn get_finalized_block_number(&self) -> ChainResult<u32>;
+
+    /// Get the hash of the block at a height of the chain, which cursors
+    /// compare with the hashes of the blocks they indexed to detect reorgs.
+    /// Returns None if the indexer can't look up block hashes, in which case
+    /// reorgs are not detected.
+    async fn fetch_block_hash(&self, _block_number: u32) -> ChainResult<Option<H256>> {
+        Ok(None)
+    }
 }
 
 /// Interface for Mailbox contract indexer. Interface for allowing other
@@ -54,3 +66,26 @@ pub trait MessageIndexer: Indexer<HyperlaneMessage> + 'static {
     /// Return the latest finalized mailbox count and block number
     async fn fetch_count_at_tip(&self) -> ChainResult<(u32, u32)>;
 }
+
+/// Interface for indexers of events that may be emitted with a sequence
+/// number, e.g. gas payments on Sealevel. Used by sequence-based cursors to
+/// know how far to index.
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait SequenceIndexer<T>: Indexer<T> + 'static {
+    /// Return the latest finalized sequence count, if the contract has one,
+    /// and the block number it was read at.
+    async fn sequence_and_tip(&self) -> ChainResult<(Option<u32>, u32)>;
+}
+
+/// Interface for indexers that can subscribe to logs as they are emitted, so
+/// they can be stored as soon as they are final without waiting for a cursor
+/// to query for them.
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait LogSubscriber<T>: Send + Sync + Debug {
+    /// Subscribe to logs and store them once their blocks are final, removing
+    /// those that a reorg removes from the chain anyway. Returns when the
+    /// subscription ends, or with the error that ended it.
+    async fn subscribe_logs(&self, store: &dyn HyperlaneLogStore<T>) -> eyre::Result<()>;
+}
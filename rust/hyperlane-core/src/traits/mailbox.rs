@@ -6,7 +6,8 @@ use auto_impl::auto_impl;
 
 use crate::{
     accumulator::incremental::IncrementalMerkle, traits::TxOutcome, utils::domain_hash,
-    ChainResult, Checkpoint, HyperlaneContract, HyperlaneMessage, TxCostEstimate, H256, U256,
+    ChainCommunicationError, ChainResult, Checkpoint, HyperlaneContract, HyperlaneMessage,
+    TxCostEstimate, H256, U256,
 };
 
 /// Interface for the Mailbox chain contract. Allows abstraction over different
@@ -54,6 +55,25 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome>;
 
+    /// Process several messages in a single transaction.
+    ///
+    /// Returns an outcome for each of the `items`, in the same order. These all
+    /// share the batch's transaction id and gas price, but are only `executed`
+    /// if that particular message was delivered, and are attributed a share of
+    /// the batch's gas usage in proportion to their `gas_limit`. If the batch
+    /// transaction reverts as a whole, none of them are `executed`.
+    ///
+    /// Mailboxes that can't batch messages return
+    /// `ChainCommunicationError::BatchingNotSupported`.
+    async fn process_batch(
+        &self,
+        items: &[BatchItem],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<Vec<TxOutcome>> {
+        let _ = (items, tx_gas_limit);
+        Err(ChainCommunicationError::BatchingNotSupported)
+    }
+
     /// Estimate transaction costs to process a message.
     async fn process_estimate_costs(
         &self,
@@ -62,6 +82,22 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
     ) -> ChainResult<TxCostEstimate>;
 
     /// Get the calldata for a transaction to process a message with a proof
-    /// against the provided signed checkpoint
-    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8>;
+    /// against the provided signed checkpoint. On chains where a call also
+    /// needs its accounts, these are looked up and included.
+    async fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>>;
+}
+
+/// A message to process as part of a batch, see `Mailbox::process_batch`.
+#[derive(Debug, Clone)]
+pub struct BatchItem {
+    /// The message to process
+    pub message: HyperlaneMessage,
+    /// The metadata to process the message with
+    pub metadata: Vec<u8>,
+    /// The gas limit estimated for processing the message on its own
+    pub gas_limit: U256,
 }
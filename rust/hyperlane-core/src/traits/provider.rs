@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 use thiserror::Error;
 
-use crate::{BlockInfo, ChainResult, HyperlaneChain, TxnInfo, H256};
+use crate::{BlockInfo, ChainResult, HyperlaneChain, TxnInfo, H256, H512};
 
 /// Interface for a provider. Allows abstraction over different provider types
 /// for different chains.
@@ -16,11 +16,14 @@ use crate::{BlockInfo, ChainResult, HyperlaneChain, TxnInfo, H256};
 #[async_trait]
 #[auto_impl(&, Box, Arc)]
 pub trait HyperlaneProvider: HyperlaneChain + Send + Sync + Debug {
-    /// Get block info for a given block hash
-    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo>;
+    /// Get block info for a block whose hash and number are both known, e.g.
+    /// from the metadata of a log. Chains that can't look blocks up by hash
+    /// use the number instead.
+    async fn get_block(&self, hash: &H256, number: u64) -> ChainResult<BlockInfo>;
 
-    /// Get txn info for a given txn hash
-    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo>;
+    /// Get txn info for a given txn hash. Chains with 256-bit transaction
+    /// hashes expect them left-padded with zeros.
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo>;
 
     /// Returns whether a contract exists at the provided address
     async fn is_contract(&self, address: &H256) -> ChainResult<bool>;
@@ -38,4 +41,7 @@ pub enum HyperlaneProviderError {
     /// Could not find a transaction, block, or other object
     #[error("Could not find object from provider with hash {0:?}")]
     CouldNotFindObjectByHash(H256),
+    /// Could not find a block at a height of the chain
+    #[error("Could not find block from provider at height {0}")]
+    CouldNotFindBlockByNumber(u64),
 }
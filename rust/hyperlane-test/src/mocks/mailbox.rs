@@ -46,6 +46,12 @@ mock! {
             tx_gas_limit: Option<U256>,
         ) -> ChainResult<TxOutcome> {}
 
+        pub fn process_batch(
+            &self,
+            items: &[BatchItem],
+            tx_gas_limit: Option<U256>,
+        ) -> ChainResult<Vec<TxOutcome>> {}
+
         pub fn process_estimate_costs(
             &self,
             message: &HyperlaneMessage,
@@ -56,7 +62,7 @@ mock! {
             &self,
             message: &HyperlaneMessage,
             metadata: &[u8],
-        ) -> Vec<u8> {}
+        ) -> ChainResult<Vec<u8>> {}
     }
 }
 
@@ -101,6 +107,14 @@ impl Mailbox for MockMailboxContract {
         self.process(message, metadata, tx_gas_limit)
     }
 
+    async fn process_batch(
+        &self,
+        items: &[BatchItem],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<Vec<TxOutcome>> {
+        self.process_batch(items, tx_gas_limit)
+    }
+
     async fn process_estimate_costs(
         &self,
         message: &HyperlaneMessage,
@@ -109,7 +123,11 @@ impl Mailbox for MockMailboxContract {
         self.process_estimate_costs(message, metadata)
     }
 
-    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+    async fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
         self.process_calldata(message, metadata)
     }
 }
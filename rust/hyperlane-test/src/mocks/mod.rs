@@ -1,6 +1,9 @@
 /// Mock mailbox contract
 pub mod mailbox;
+/// Mock provider
+pub mod provider;
 pub mod validator_announce;
 
 pub use mailbox::MockMailboxContract;
+pub use provider::MockHyperlaneProvider;
 pub use validator_announce::MockValidatorAnnounceContract;
@@ -0,0 +1,47 @@
+#![allow(non_snake_case)]
+use core::fmt::Debug;
+use mockall::*;
+
+use async_trait::async_trait;
+use hyperlane_core::*;
+
+mock! {
+    pub HyperlaneProvider {
+        fn _domain(&self) -> &HyperlaneDomain;
+        fn _provider(&self) -> Box<dyn HyperlaneProvider>;
+        fn _get_block(&self, hash: &H256, number: u64) -> ChainResult<BlockInfo>;
+        fn _get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo>;
+        fn _is_contract(&self, address: &H256) -> ChainResult<bool>;
+    }
+}
+
+impl HyperlaneChain for MockHyperlaneProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        self._domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self._provider()
+    }
+}
+
+impl Debug for MockHyperlaneProvider {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for MockHyperlaneProvider {
+    async fn get_block(&self, hash: &H256, number: u64) -> ChainResult<BlockInfo> {
+        self._get_block(hash, number)
+    }
+
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        self._get_txn_by_hash(hash)
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        self._is_contract(address)
+    }
+}
@@ -1,6 +1,9 @@
+pub mod accounts;
 pub mod error;
+pub mod instruction;
 pub mod interface;
 pub mod multisig;
+pub mod processor;
 #[cfg(feature = "test-data")]
 pub mod test_data;
 
@@ -2,7 +2,10 @@
 //! Useful for use in unit & integration tests, which can't import from
 //! each other.
 
-use hyperlane_core::{Checkpoint, CheckpointWithMessageId, HyperlaneMessage, H160, H256};
+use hyperlane_core::{
+    accumulator::{merkle::MerkleTree, TREE_DEPTH},
+    Checkpoint, CheckpointWithMessageId, HyperlaneMessage, H160, H256,
+};
 use std::str::FromStr;
 
 pub struct MultisigIsmTestData {
@@ -12,11 +15,21 @@ pub struct MultisigIsmTestData {
     pub signatures: Vec<Vec<u8>>,
 }
 
+pub struct MerkleRootMultisigIsmTestData {
+    pub message: HyperlaneMessage,
+    /// The signed checkpoint, which is for a later message than `message`.
+    pub checkpoint: CheckpointWithMessageId,
+    /// A proof of `message`'s inclusion in the tree with the checkpoint's root.
+    pub proof: [H256; TREE_DEPTH],
+    pub validators: Vec<H160>,
+    pub signatures: Vec<Vec<u8>>,
+}
+
 const ORIGIN_DOMAIN: u32 = 1234u32;
 const DESTINATION_DOMAIN: u32 = 4321u32;
 
-pub fn get_multisig_ism_test_data() -> MultisigIsmTestData {
-    let message = HyperlaneMessage {
+fn test_message() -> HyperlaneMessage {
+    HyperlaneMessage {
         version: 0,
         nonce: 69,
         origin: ORIGIN_DOMAIN,
@@ -30,7 +43,11 @@ pub fn get_multisig_ism_test_data() -> MultisigIsmTestData {
         )
         .unwrap(),
         body: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
-    };
+    }
+}
+
+pub fn get_multisig_ism_test_data() -> MultisigIsmTestData {
+    let message = test_message();
 
     let checkpoint = CheckpointWithMessageId {
         checkpoint: Checkpoint {
@@ -82,3 +99,52 @@ pub fn get_multisig_ism_test_data() -> MultisigIsmTestData {
         signatures: vec![signature_0, signature_1, signature_2],
     }
 }
+
+/// The validators are the same as those in `get_multisig_ism_test_data`.
+pub fn get_merkle_root_multisig_ism_test_data() -> MerkleRootMultisigIsmTestData {
+    let message = test_message();
+
+    // A tree of 101 leaves, where the leaf at the message's nonce (69) is the
+    // message ID and every other leaf `i` is `H256::from_low_u64_be(i)`.
+    let leaves: Vec<H256> = (0..=100u64)
+        .map(|i| {
+            if i == message.nonce as u64 {
+                message.id()
+            } else {
+                H256::from_low_u64_be(i)
+            }
+        })
+        .collect();
+    let tree = MerkleTree::create(&leaves, TREE_DEPTH);
+    let (_, proof) = tree.generate_proof(message.nonce as usize, TREE_DEPTH);
+
+    let checkpoint = CheckpointWithMessageId {
+        checkpoint: Checkpoint {
+            mailbox_address: H256::from_str(
+                "0xabababababababababababababababababababababababababababababababab",
+            )
+            .unwrap(),
+            mailbox_domain: ORIGIN_DOMAIN,
+            // 0xac65e8cc1d9e2ad4e70d20e0d0534aa928dad71e9daa60c77ff7b59e407b12ff
+            root: tree.hash(),
+            index: 100,
+        },
+        message_id: leaves[100],
+    };
+
+    // checkpoint.eth_signed_message_hash() is equal to:
+    // 0x7c5eab673127877b9538a2e1e97fb9e9dd1365add2d8567c5e1e5667feb667a1
+    // and each signature below is over it by the validator of the same index
+    // in `get_multisig_ism_test_data`.
+    let signature_0 = hex::decode("b59607a9983a6960709aa9d0ef2ba25fcd6023463d551811fe153b4ea89a196c2bdb08eef3525f9eecc4a7211157a7533040e92440f564c42b2241c2d5403bda1c").unwrap();
+    let signature_1 = hex::decode("bf0f1582b40aae6400c42c50919a826101f7eb8cf8b776586b0a026d342210062233a655a6d09c8439944b817cd7a4ddb5a2b07ac0d97f77ba52a287812874831c").unwrap();
+    let signature_2 = hex::decode("23ab8352ca2110ada9671c61aaa265b76a139dccabdd02c88f6614cebcd4595f2187e93ddb7a5f79ec0f82ace2b7b93866806d2abf68ed12fe566c4a8385d84c1b").unwrap();
+
+    MerkleRootMultisigIsmTestData {
+        message,
+        checkpoint,
+        proof: proof.try_into().unwrap(),
+        validators: get_multisig_ism_test_data().validators,
+        signatures: vec![signature_0, signature_1, signature_2],
+    }
+}
@@ -258,8 +258,15 @@ impl DiscriminatorData for GasPaymentData {
     const DISCRIMINATOR: [u8; 8] = *GAS_PAYMENT_DISCRIMINATOR;
 }
 
+/// The version of the gas payment account data layout.
+///
+/// Gas payment accounts created before the layout was versioned end after
+/// `slot` and have no `payment`. Later layouts append a version byte and the
+/// fields added in that version, so older accounts can still be read.
+pub const GAS_PAYMENT_DATA_VERSION: u8 = 1;
+
 /// Gas payment account data.
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Default)]
+#[derive(Debug, PartialEq, Default)]
 pub struct GasPaymentData {
     /// The sequence number of the gas payment.
     pub sequence_number: u64,
@@ -275,6 +282,52 @@ pub struct GasPaymentData {
     pub unique_gas_payment_pubkey: Pubkey,
     /// The slot of the gas payment.
     pub slot: Slot,
+    /// The amount of lamports paid. Zero for payments made before the
+    /// payment was recorded.
+    pub payment: u64,
+}
+
+impl BorshSerialize for GasPaymentData {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.sequence_number.serialize(writer)?;
+        self.igp.serialize(writer)?;
+        self.destination_domain.serialize(writer)?;
+        self.message_id.serialize(writer)?;
+        self.gas_amount.serialize(writer)?;
+        self.unique_gas_payment_pubkey.serialize(writer)?;
+        self.slot.serialize(writer)?;
+        GAS_PAYMENT_DATA_VERSION.serialize(writer)?;
+        self.payment.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for GasPaymentData {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let mut data = Self {
+            sequence_number: u64::deserialize(buf)?,
+            igp: Pubkey::deserialize(buf)?,
+            destination_domain: u32::deserialize(buf)?,
+            message_id: H256::deserialize(buf)?,
+            gas_amount: u64::deserialize(buf)?,
+            unique_gas_payment_pubkey: Pubkey::deserialize(buf)?,
+            slot: Slot::deserialize(buf)?,
+            payment: 0,
+        };
+        // Unversioned accounts end here.
+        if buf.is_empty() {
+            return Ok(data);
+        }
+        match u8::deserialize(buf)? {
+            1 => data.payment = u64::deserialize(buf)?,
+            version => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Unsupported gas payment data version {version}"),
+                ))
+            }
+        }
+        Ok(data)
+    }
 }
 
 impl SizedData for GasPaymentData {
@@ -286,7 +339,9 @@ impl SizedData for GasPaymentData {
         // 8 for gas_amount
         // 32 for unique_gas_payment_pubkey
         // 8 for slot
-        8 + 32 + 4 + 32 + 8 + 32 + 8
+        // 1 for the version
+        // 8 for payment
+        8 + 32 + 4 + 32 + 8 + 32 + 8 + 1 + 8
     }
 }
 
@@ -303,6 +358,47 @@ fn convert_decimals(num: U256, from_decimals: u8, to_decimals: u8) -> U256 {
 mod test {
     use super::*;
 
+    fn gas_payment_data() -> GasPaymentData {
+        GasPaymentData {
+            sequence_number: 7,
+            igp: Pubkey::new_unique(),
+            destination_domain: 1234,
+            message_id: H256::repeat_byte(0x11),
+            gas_amount: 100_000,
+            unique_gas_payment_pubkey: Pubkey::new_unique(),
+            slot: 42,
+            payment: 1_000_000,
+        }
+    }
+
+    #[test]
+    fn test_gas_payment_data_roundtrip() {
+        let data = gas_payment_data();
+        let bytes = data.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), data.size());
+        assert_eq!(GasPaymentData::try_from_slice(&bytes).unwrap(), data);
+    }
+
+    #[test]
+    fn test_gas_payment_data_reads_unversioned_accounts() {
+        let data = gas_payment_data();
+        let bytes = data.try_to_vec().unwrap();
+        // Strip the version byte and the payment.
+        let unversioned = &bytes[..bytes.len() - 1 - 8];
+        assert_eq!(
+            GasPaymentData::try_from_slice(unversioned).unwrap(),
+            GasPaymentData { payment: 0, ..data }
+        );
+    }
+
+    #[test]
+    fn test_gas_payment_data_rejects_unknown_versions() {
+        let mut bytes = gas_payment_data().try_to_vec().unwrap();
+        let version_index = bytes.len() - 1 - 8;
+        bytes[version_index] = GAS_PAYMENT_DATA_VERSION + 1;
+        assert!(GasPaymentData::try_from_slice(&bytes).is_err());
+    }
+
     #[test]
     fn test_convert_decimals() {
         let num = U256::from(1000000u128);
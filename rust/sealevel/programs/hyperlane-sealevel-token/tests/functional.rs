@@ -822,6 +822,8 @@ async fn test_transfer_remote() {
             destination_domain: REMOTE_DOMAIN,
             message_id: message.id(),
             gas_amount: REMOTE_GAS_AMOUNT,
+            // The test IGP's gas oracle makes one unit of gas cost one lamport.
+            payment: REMOTE_GAS_AMOUNT,
             unique_gas_payment_pubkey: unique_message_account_keypair.pubkey(),
             slot: transfer_remote_tx_status.slot,
         }
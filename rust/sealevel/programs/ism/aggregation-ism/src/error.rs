@@ -0,0 +1,32 @@
+//! Hyperlane Sealevel aggregation ISM specific errors.
+
+use solana_program::program_error::ProgramError;
+
+#[derive(Copy, Clone, Debug, Eq, thiserror::Error, num_derive::FromPrimitive, PartialEq)]
+#[repr(u32)]
+pub enum Error {
+    #[error("Account not found in the correct order")]
+    AccountOutOfOrder = 1,
+    #[error("Account is not owner")]
+    AccountNotOwner = 2,
+    #[error("Program ID is not owner")]
+    ProgramIdNotOwner = 3,
+    #[error("Account not initialized")]
+    AccountNotInitialized = 4,
+    #[error("Already initialized")]
+    AlreadyInitialized = 5,
+    #[error("Invalid modules and threshold")]
+    InvalidModulesAndThreshold = 6,
+    #[error("Invalid metadata")]
+    InvalidMetadata = 7,
+    #[error("Threshold not met")]
+    ThresholdNotMet = 8,
+    #[error("Module program is not executable")]
+    ModuleNotExecutable = 9,
+}
+
+impl From<Error> for ProgramError {
+    fn from(err: Error) -> Self {
+        ProgramError::Custom(err as u32)
+    }
+}
@@ -0,0 +1,139 @@
+use hyperlane_core::Encode;
+
+use crate::error::Error;
+
+/// Bytes used to store one member of the (start, end) range tuple.
+const METADATA_RANGE_SIZE: usize = 4;
+
+#[derive(Debug, PartialEq)]
+pub struct AggregationIsmMetadata {
+    /// The metadata for each module in the aggregation ISM, in the same
+    /// order as the modules. `None` if no metadata was provided for a module.
+    pub sub_metadata: Vec<Option<Vec<u8>>>,
+}
+
+/// Format of metadata, matching `AggregationIsmMetadata.sol`:
+/// [????:????] Metadata start/end uint32 ranges, packed as uint64
+/// [????:????] ISM metadata, packed encoding
+/// A module without metadata has a start (and end) of 0.
+impl AggregationIsmMetadata {
+    /// Decodes the metadata for an aggregation ISM with `module_count` modules.
+    pub fn decode(bytes: &[u8], module_count: usize) -> Result<Self, Error> {
+        let ranges_len = module_count * METADATA_RANGE_SIZE * 2;
+        if bytes.len() < ranges_len {
+            return Err(Error::InvalidMetadata);
+        }
+
+        let read_index = |offset: usize| -> usize {
+            let mut index = [0u8; METADATA_RANGE_SIZE];
+            index.copy_from_slice(&bytes[offset..offset + METADATA_RANGE_SIZE]);
+            u32::from_be_bytes(index) as usize
+        };
+
+        let sub_metadata = (0..module_count)
+            .map(|i| {
+                let range_offset = i * METADATA_RANGE_SIZE * 2;
+                let start = read_index(range_offset);
+                let end = read_index(range_offset + METADATA_RANGE_SIZE);
+                if start == 0 {
+                    return Ok(None);
+                }
+                // The metadata must not overlap the ranges, and must fit in the bytes.
+                if start < ranges_len || start > end || end > bytes.len() {
+                    return Err(Error::InvalidMetadata);
+                }
+                Ok(Some(bytes[start..end].to_vec()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { sub_metadata })
+    }
+}
+
+impl Encode for AggregationIsmMetadata {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let ranges_len = self.sub_metadata.len() * METADATA_RANGE_SIZE * 2;
+
+        let mut ranges = Vec::with_capacity(ranges_len);
+        let mut offset = ranges_len;
+        for sub_metadata in &self.sub_metadata {
+            let (start, end) = match sub_metadata {
+                Some(sub_metadata) => (offset, offset + sub_metadata.len()),
+                None => (0, 0),
+            };
+            ranges.extend_from_slice(&(start as u32).to_be_bytes());
+            ranges.extend_from_slice(&(end as u32).to_be_bytes());
+            offset = end.max(offset);
+        }
+
+        let mut bytes_written = writer.write(&ranges)?;
+        for sub_metadata in self.sub_metadata.iter().flatten() {
+            bytes_written += writer.write(sub_metadata)?;
+        }
+        Ok(bytes_written)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_correctly_formatted_metadata() {
+        // Produced by the relayer's `AggregationIsmMetadataBuilder` for three
+        // modules, with metadata provided for the first and last.
+        let metadata_bytes = vec![
+            0, 0, 0, 24, 0, 0, 0, 27, // module 0: [24, 27)
+            0, 0, 0, 0, 0, 0, 0, 0, // module 1: no metadata
+            0, 0, 0, 27, 0, 0, 0, 29, // module 2: [27, 29)
+            1, 2, 3, 4, 5,
+        ];
+
+        let metadata = AggregationIsmMetadata::decode(&metadata_bytes, 3).unwrap();
+        assert_eq!(
+            metadata.sub_metadata,
+            vec![Some(vec![1, 2, 3]), None, Some(vec![4, 5])],
+        );
+        assert_eq!(metadata.to_vec(), metadata_bytes);
+    }
+
+    #[test]
+    fn test_decode_empty_sub_metadata() {
+        let metadata = AggregationIsmMetadata {
+            sub_metadata: vec![Some(vec![]), Some(vec![1])],
+        };
+        let metadata_bytes = metadata.to_vec();
+
+        assert_eq!(
+            AggregationIsmMetadata::decode(&metadata_bytes, 2).unwrap(),
+            metadata
+        );
+    }
+
+    #[test]
+    fn test_decode_too_short_is_err() {
+        // Not enough bytes for the ranges of two modules.
+        let metadata_bytes = vec![0u8; 12];
+
+        let result = AggregationIsmMetadata::decode(&metadata_bytes, 2);
+        assert!(result.unwrap_err() == Error::InvalidMetadata);
+    }
+
+    #[test]
+    fn test_decode_out_of_bounds_range_is_err() {
+        // The range ends past the end of the metadata.
+        let metadata_bytes = vec![0, 0, 0, 8, 0, 0, 0, 12, 1, 2, 3];
+
+        let result = AggregationIsmMetadata::decode(&metadata_bytes, 1);
+        assert!(result.unwrap_err() == Error::InvalidMetadata);
+
+        // The range overlaps the ranges.
+        let metadata_bytes = vec![0, 0, 0, 4, 0, 0, 0, 9, 1];
+
+        let result = AggregationIsmMetadata::decode(&metadata_bytes, 1);
+        assert!(result.unwrap_err() == Error::InvalidMetadata);
+    }
+}
@@ -0,0 +1,531 @@
+use hyperlane_core::{Decode, HyperlaneMessage, ModuleType};
+
+use access_control::AccessControl;
+use account_utils::{create_pda_account, DiscriminatorDecode, SizedData};
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction,
+};
+use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction as SolanaInstruction},
+    program::{invoke, set_return_data},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    accounts::{
+        AccessControlAccount, AccessControlData, ModulesAndThresholdAccount,
+        ModulesAndThresholdData,
+    },
+    error::Error,
+    instruction::{Instruction, ModulesAndThreshold},
+    metadata::AggregationIsmMetadata,
+};
+
+use borsh::BorshSerialize;
+
+const ISM_TYPE: ModuleType = ModuleType::Aggregation;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// PDA seeds relating to the access control PDA account.
+#[macro_export]
+macro_rules! access_control_pda_seeds {
+    () => {{
+        &[b"aggregation_ism", b"-", b"access_control"]
+    }};
+
+    ($bump_seed:expr) => {{
+        &[b"aggregation_ism", b"-", b"access_control", &[$bump_seed]]
+    }};
+}
+
+/// PDA seeds relating to the modules and threshold PDA account.
+#[macro_export]
+macro_rules! modules_and_threshold_pda_seeds {
+    () => {{
+        &[b"aggregation_ism", b"-", b"modules_and_threshold"]
+    }};
+
+    ($bump_seed:expr) => {{
+        &[
+            b"aggregation_ism",
+            b"-",
+            b"modules_and_threshold",
+            &[$bump_seed],
+        ]
+    }};
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // First, try to decode the instruction as an interchain security module
+    // interface supported function based off the discriminator.
+    if let Ok(ism_instruction) = InterchainSecurityModuleInstruction::decode(instruction_data) {
+        return match ism_instruction {
+            InterchainSecurityModuleInstruction::Type => {
+                set_return_data(
+                    &SimulationReturnData::new(ISM_TYPE as u32)
+                        .try_to_vec()
+                        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?[..],
+                );
+                return Ok(());
+            }
+            InterchainSecurityModuleInstruction::Verify(verify_data) => verify(
+                program_id,
+                accounts,
+                verify_data.metadata,
+                verify_data.message,
+            ),
+            InterchainSecurityModuleInstruction::VerifyAccountMetas(verify_data) => {
+                let account_metas = verify_account_metas(
+                    program_id,
+                    accounts,
+                    verify_data.metadata,
+                    verify_data.message,
+                )?;
+                // Wrap it in the SimulationReturnData because serialized account_metas
+                // may end with zero byte(s), which are incorrectly truncated as
+                // simulated transaction return data.
+                // See `SimulationReturnData` for details.
+                let bytes = SimulationReturnData::new(account_metas)
+                    .try_to_vec()
+                    .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+                set_return_data(&bytes[..]);
+                Ok(())
+            }
+        };
+    }
+
+    match Instruction::decode(instruction_data)? {
+        // Initializes the program.
+        Instruction::Initialize => initialize(program_id, accounts),
+        // Sets the modules and threshold.
+        Instruction::SetModulesAndThreshold(config) => {
+            set_modules_and_threshold(program_id, accounts, config)
+        }
+        // Gets the modules and threshold required to verify a message.
+        // The same modules and threshold are used for all messages.
+        Instruction::ModulesAndThreshold(_message_bytes) => {
+            get_modules_and_threshold(program_id, accounts)
+        }
+        // Gets the owner of this program from the access control account.
+        Instruction::GetOwner => get_owner(program_id, accounts),
+        // Sets the owner of this program in the access control account.
+        Instruction::TransferOwnership(new_owner) => {
+            transfer_ownership(program_id, accounts, new_owner)
+        }
+    }
+}
+
+/// Initializes the program, creating the access control PDA account.
+///
+/// Accounts:
+/// 0. `[signer]` The new owner and payer of the access control PDA.
+/// 1. `[writable]` The access control PDA account.
+/// 2. `[executable]` The system program account.
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The new owner of this program and payer of the access control PDA.
+    let owner_account = next_account_info(accounts_iter)?;
+    if !owner_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Account 1: The access control PDA account.
+    let access_control_pda_account = next_account_info(accounts_iter)?;
+    let (access_control_pda_key, access_control_pda_bump_seed) =
+        Pubkey::find_program_address(access_control_pda_seeds!(), program_id);
+    if *access_control_pda_account.key != access_control_pda_key {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    // Ensure the access control PDA account isn't already initialized.
+    if let Ok(Some(_)) =
+        AccessControlAccount::fetch_data(&mut &access_control_pda_account.data.borrow()[..])
+    {
+        return Err(Error::AlreadyInitialized.into());
+    }
+
+    // Account 2: The system program account.
+    let system_program_account = next_account_info(accounts_iter)?;
+    if !solana_program::system_program::check_id(system_program_account.key) {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    // Create the access control PDA account.
+    let access_control_account = AccessControlAccount::from(AccessControlData {
+        bump_seed: access_control_pda_bump_seed,
+        owner: Some(*owner_account.key),
+    });
+    let access_control_account_data_size = access_control_account.size();
+    create_pda_account(
+        owner_account,
+        &Rent::get()?,
+        access_control_account_data_size,
+        program_id,
+        system_program_account,
+        access_control_pda_account,
+        access_control_pda_seeds!(access_control_pda_bump_seed),
+    )?;
+
+    // Store the access control data.
+    access_control_account.store(access_control_pda_account, false)?;
+
+    Ok(())
+}
+
+/// Verifies a message by invoking the `Verify` instruction of each module
+/// that metadata was provided for. Each of these modules must successfully
+/// verify the message, and there must be at least `threshold` of them.
+///
+/// Accounts:
+/// 0. `[]` The modules and threshold PDA account.
+/// 1. `[executable]` The program of the first module with metadata.
+/// 2. `[??]` The accounts required by that module's `Verify` instruction,
+///    followed by the program and accounts of each subsequent module with
+///    metadata, in the order the modules are configured.
+fn verify(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    metadata_bytes: Vec<u8>,
+    message_bytes: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter().peekable();
+
+    // Account 0: The modules and threshold PDA account.
+    let modules_and_threshold_pda_account = next_account_info(accounts_iter)?;
+    let modules_and_threshold =
+        modules_and_threshold(program_id, modules_and_threshold_pda_account)?;
+
+    let metadata =
+        AggregationIsmMetadata::decode(&metadata_bytes, modules_and_threshold.modules.len())?;
+
+    // The modules that metadata was provided for, paired with that metadata.
+    let modules_with_metadata: Vec<(&Pubkey, Vec<u8>)> = modules_and_threshold
+        .modules
+        .iter()
+        .zip(metadata.sub_metadata)
+        .filter_map(|(module, sub_metadata)| sub_metadata.map(|m| (module, m)))
+        .collect();
+
+    if modules_with_metadata.len() < modules_and_threshold.threshold as usize {
+        return Err(Error::ThresholdNotMet.into());
+    }
+
+    for (i, (module, sub_metadata)) in modules_with_metadata.iter().enumerate() {
+        // The module program.
+        let module_account = next_account_info(accounts_iter)?;
+        if module_account.key != *module {
+            return Err(Error::AccountOutOfOrder.into());
+        }
+        if !module_account.executable {
+            return Err(Error::ModuleNotExecutable.into());
+        }
+
+        // The accounts required by the module's `Verify` instruction.
+        // The next module with metadata's program ID is used as a marker
+        // for the end of these accounts. For the last module, all remaining
+        // accounts are used.
+        let next_module = modules_with_metadata.get(i + 1).map(|(module, _)| *module);
+        let mut module_verify_infos = vec![];
+        let mut module_verify_account_metas = vec![];
+        while let Some(next_info) = accounts_iter.peek() {
+            if Some(next_info.key) == next_module {
+                break;
+            }
+
+            let account_info = next_account_info(accounts_iter)?;
+            module_verify_infos.push(account_info.clone());
+            module_verify_account_metas.push(AccountMeta {
+                pubkey: *account_info.key,
+                is_signer: account_info.is_signer,
+                is_writable: account_info.is_writable,
+            });
+        }
+
+        // Call into the module to verify the message.
+        // If the module fails to verify the message, the whole transaction fails.
+        let verify_instruction = InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+            metadata: sub_metadata.clone(),
+            message: message_bytes.clone(),
+        });
+        let verify = SolanaInstruction::new_with_bytes(
+            **module,
+            &verify_instruction.encode()?,
+            module_verify_account_metas,
+        );
+        invoke(&verify, &module_verify_infos)?;
+    }
+
+    Ok(())
+}
+
+/// Gets the list of AccountMetas required by the `Verify` instruction.
+/// Only the modules and threshold PDA is returned - the program and the accounts
+/// required by each module that metadata is provided for must be appended by
+/// the caller, who is expected to look up the modules using the
+/// `ModulesAndThreshold` instruction.
+///
+/// Accounts:
+/// 0. `[]` This program's PDA relating to the seeds VERIFY_ACCOUNT_METAS_PDA_SEEDS.
+///         Note this is not actually used / required in this implementation.
+fn verify_account_metas(
+    program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    _metadata_bytes: Vec<u8>,
+    message_bytes: Vec<u8>,
+) -> Result<Vec<SerializableAccountMeta>, ProgramError> {
+    // Ensure the message is valid, for parity with other ISMs.
+    HyperlaneMessage::read_from(&mut &message_bytes[..])
+        .map_err(|_| ProgramError::InvalidArgument)?;
+    let (modules_and_threshold_pda_key, _) =
+        Pubkey::find_program_address(modules_and_threshold_pda_seeds!(), program_id);
+
+    Ok(vec![AccountMeta::new_readonly(
+        modules_and_threshold_pda_key,
+        false,
+    )
+    .into()])
+}
+
+/// Gets the modules and threshold, and returns it as return data.
+///
+/// Accounts:
+/// 0. `[]` The modules and threshold PDA account.
+fn get_modules_and_threshold(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The modules and threshold PDA account.
+    let modules_and_threshold_pda_account = next_account_info(accounts_iter)?;
+    let modules_and_threshold =
+        modules_and_threshold(program_id, modules_and_threshold_pda_account)?;
+
+    // Wrap it in the SimulationReturnData because serialized modules_and_threshold
+    // may end with zero byte(s), which are incorrectly truncated as
+    // simulated transaction return data.
+    // See `SimulationReturnData` for details.
+    let bytes = SimulationReturnData::new(modules_and_threshold)
+        .try_to_vec()
+        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+    set_return_data(&bytes[..]);
+    Ok(())
+}
+
+/// Gets the modules and threshold.
+/// Returns an Err if the provided account isn't the modules and threshold PDA.
+fn modules_and_threshold(
+    program_id: &Pubkey,
+    modules_and_threshold_pda_account: &AccountInfo,
+) -> Result<ModulesAndThreshold, ProgramError> {
+    if modules_and_threshold_pda_account.owner != program_id {
+        return Err(Error::ProgramIdNotOwner.into());
+    }
+
+    let modules_and_threshold_data = ModulesAndThresholdAccount::fetch_data(
+        &mut &modules_and_threshold_pda_account.data.borrow()[..],
+    )?
+    .ok_or(Error::AccountNotInitialized)?;
+
+    let modules_and_threshold_pda_key = Pubkey::create_program_address(
+        modules_and_threshold_pda_seeds!(modules_and_threshold_data.bump_seed),
+        program_id,
+    )?;
+    // This check validates that the provided modules_and_threshold_pda_account is valid
+    if *modules_and_threshold_pda_account.key != modules_and_threshold_pda_key {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    Ok(modules_and_threshold_data.modules_and_threshold)
+}
+
+/// Sets the modules and threshold.
+///
+/// Accounts:
+/// 0. `[signer]` The access control owner and payer of the modules and threshold PDA.
+/// 1. `[]` The access control PDA account.
+/// 2. `[writable]` The modules and threshold PDA account.
+/// 3. `[executable]` OPTIONAL - The system program account. Required if creating the
+///    modules and threshold PDA.
+fn set_modules_and_threshold(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    config: ModulesAndThreshold,
+) -> ProgramResult {
+    // Validate the provided modules and threshold.
+    config.validate()?;
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The owner of this program.
+    // This is verified as correct further below.
+    let owner_account = next_account_info(accounts_iter)?;
+
+    // Account 1: The access control PDA account.
+    let access_control_pda_account = next_account_info(accounts_iter)?;
+    let access_control_data = access_control_data(program_id, access_control_pda_account)?;
+    // Ensure the owner account is the owner of this program.
+    access_control_data.ensure_owner_signer(owner_account)?;
+
+    // Account 2: The modules and threshold PDA account.
+    let modules_and_threshold_pda_account = next_account_info(accounts_iter)?;
+
+    let modules_and_threshold_data = ModulesAndThresholdAccount::fetch_data(
+        &mut &modules_and_threshold_pda_account.data.borrow()[..],
+    );
+
+    let bump_seed = match modules_and_threshold_data {
+        Ok(Some(modules_and_threshold_data)) => {
+            // The PDA account exists already, we need to confirm the key of the
+            // modules_and_threshold_pda_account is the PDA with the stored bump seed.
+            let modules_and_threshold_pda_key = Pubkey::create_program_address(
+                modules_and_threshold_pda_seeds!(modules_and_threshold_data.bump_seed),
+                program_id,
+            )?;
+            // This check validates that the provided modules_and_threshold_pda_account is valid
+            if *modules_and_threshold_pda_account.key != modules_and_threshold_pda_key {
+                return Err(Error::AccountOutOfOrder.into());
+            }
+            // Extra sanity check that the owner of the PDA account is this program
+            if modules_and_threshold_pda_account.owner != program_id {
+                return Err(Error::ProgramIdNotOwner.into());
+            }
+
+            modules_and_threshold_data.bump_seed
+        }
+        Ok(None) | Err(_) => {
+            // Create the modules and threshold PDA account if it doesn't exist.
+
+            // This is the initial size - because reallocations are allowed
+            // in the `store` call further below, it's possible that the
+            // size will be increased.
+            let modules_and_threshold_pda_size: usize = 1024;
+
+            // First find the key and bump seed for the modules and threshold PDA,
+            // and ensure it matches the provided account.
+            let (modules_and_threshold_pda_key, modules_and_threshold_pda_bump) =
+                Pubkey::find_program_address(modules_and_threshold_pda_seeds!(), program_id);
+            if *modules_and_threshold_pda_account.key != modules_and_threshold_pda_key {
+                return Err(Error::AccountOutOfOrder.into());
+            }
+
+            // Account 3: The system program account.
+            let system_program_account = next_account_info(accounts_iter)?;
+            if !solana_program::system_program::check_id(system_program_account.key) {
+                return Err(Error::AccountOutOfOrder.into());
+            }
+
+            // Create the modules and threshold PDA account.
+            create_pda_account(
+                owner_account,
+                &Rent::get()?,
+                modules_and_threshold_pda_size,
+                program_id,
+                system_program_account,
+                modules_and_threshold_pda_account,
+                modules_and_threshold_pda_seeds!(modules_and_threshold_pda_bump),
+            )?;
+
+            modules_and_threshold_pda_bump
+        }
+    };
+
+    // Now store the new modules and threshold according to the config:
+    ModulesAndThresholdAccount::from(ModulesAndThresholdData {
+        bump_seed,
+        modules_and_threshold: config,
+    })
+    .store(modules_and_threshold_pda_account, true)?;
+
+    Ok(())
+}
+
+/// Gets the owner of this program from the access control account, and returns it as return data.
+/// Intended to be used by instructions querying the owner.
+///
+/// Accounts:
+/// 0. `[]` The access control PDA account.
+fn get_owner(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The access control PDA account.
+    let access_control_pda_account = next_account_info(accounts_iter)?;
+
+    let access_control_data = access_control_data(program_id, access_control_pda_account)?;
+
+    // Wrap it in the SimulationReturnData because serialized `access_control_data.owner`
+    // may end with zero byte(s), which are incorrectly truncated as
+    // simulated transaction return data.
+    // See `SimulationReturnData` for details.
+    let bytes = SimulationReturnData::new(access_control_data.owner)
+        .try_to_vec()
+        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+    set_return_data(&bytes[..]);
+    Ok(())
+}
+
+/// Gets the access control data of this program.
+/// Returns an Err if the provided account isn't the access control PDA.
+fn access_control_data(
+    program_id: &Pubkey,
+    access_control_pda_account: &AccountInfo,
+) -> Result<AccessControlData, ProgramError> {
+    let access_control_data =
+        AccessControlAccount::fetch_data(&mut &access_control_pda_account.data.borrow()[..])?
+            .ok_or(Error::AccountNotInitialized)?;
+    // Confirm the key of the access_control_pda_account is the correct PDA
+    // using the stored bump seed.
+    let access_control_pda_key = Pubkey::create_program_address(
+        access_control_pda_seeds!(access_control_data.bump_seed),
+        program_id,
+    )?;
+    // This check validates that the provided access_control_pda_account is valid
+    if *access_control_pda_account.key != access_control_pda_key {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+    // Extra sanity check that the owner of the PDA account is this program
+    if access_control_pda_account.owner != program_id {
+        return Err(Error::ProgramIdNotOwner.into());
+    }
+
+    Ok(*access_control_data)
+}
+
+/// Transfers ownership to a new access control owner.
+///
+/// Accounts:
+/// 0. `[signer]` The current access control owner.
+/// 1. `[]` The access control PDA account.
+fn transfer_ownership(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_owner: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The current access control owner.
+    // This is verified as correct further below.
+    let owner_account = next_account_info(accounts_iter)?;
+
+    // Account 1: The access control PDA account.
+    let access_control_pda_account = next_account_info(accounts_iter)?;
+    let mut access_control_data = access_control_data(program_id, access_control_pda_account)?;
+
+    // Transfer ownership. This errors if `owner_account` is not a signer or the owner.
+    access_control_data.transfer_ownership(owner_account, new_owner)?;
+
+    // Store the new access control owner.
+    AccessControlAccount::from(access_control_data).store(access_control_pda_account, false)?;
+
+    Ok(())
+}
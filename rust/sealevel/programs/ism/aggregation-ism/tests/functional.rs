@@ -0,0 +1,589 @@
+//! Contains functional tests for things that cannot be done
+//! strictly in unit tests. This includes CPIs, like creating
+//! new PDA accounts and invoking the aggregated ISMs.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    system_program,
+};
+
+use hyperlane_core::{Encode, HyperlaneMessage, ModuleType, H256};
+use hyperlane_sealevel_aggregation_ism::{
+    access_control_pda_seeds,
+    accounts::{
+        AccessControlAccount, AccessControlData, ModulesAndThresholdAccount,
+        ModulesAndThresholdData,
+    },
+    error::Error as AggregationIsmError,
+    instruction::{
+        init_instruction, modules_and_threshold_instruction, set_modules_and_threshold_instruction,
+        ModulesAndThreshold,
+    },
+    metadata::AggregationIsmMetadata,
+    modules_and_threshold_pda_seeds,
+    processor::process_instruction,
+};
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction,
+};
+use hyperlane_sealevel_test_ism::{
+    program::{TestIsmError, TestIsmInstruction},
+    test_ism_storage_pda_seeds,
+};
+use hyperlane_test_utils::{
+    assert_transaction_error, get_ism_verify_account_metas, new_funded_keypair,
+    process_instruction as process_test_instruction, simulate_instruction,
+};
+use serializable_account_meta::SimulationReturnData;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+    transaction::TransactionError,
+};
+
+fn aggregation_ism_id() -> Pubkey {
+    pubkey!("4PZ5DxLkLFCrJiQJGQgGPbDGbXCWZBW8GiHn2fGHBXKH")
+}
+
+fn test_ism_ids() -> [Pubkey; 3] {
+    [
+        pubkey!("5NpmSEpvc8BwjkbwfuJE6kVebk8Gz2HViyjLYQsSnaSf"),
+        pubkey!("6E1N5dmWvRgACZJtnF7HaXsH7Tpq6Bqdum1cdJVtJgaJ"),
+        pubkey!("7CQ2rd2WPJV1ATKnHWUqY4D2YYqxe4AG3xQ7ETXKEY1x"),
+    ]
+}
+
+async fn setup_client() -> (BanksClient, Keypair) {
+    let mut program_test = ProgramTest::new(
+        "hyperlane_sealevel_aggregation_ism",
+        aggregation_ism_id(),
+        processor!(process_instruction),
+    );
+    for test_ism_id in test_ism_ids() {
+        program_test.add_program(
+            "hyperlane_sealevel_test_ism",
+            test_ism_id,
+            processor!(hyperlane_sealevel_test_ism::program::process_instruction),
+        );
+    }
+
+    let (banks_client, payer, _recent_blockhash) = program_test.start().await;
+
+    (banks_client, payer)
+}
+
+async fn initialize(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+) -> Result<(Pubkey, u8), BanksClientError> {
+    let program_id = aggregation_ism_id();
+
+    process_test_instruction(
+        banks_client,
+        init_instruction(program_id, payer.pubkey()).unwrap(),
+        payer,
+        &[payer],
+    )
+    .await?;
+
+    Ok(Pubkey::find_program_address(
+        access_control_pda_seeds!(),
+        &program_id,
+    ))
+}
+
+async fn set_modules_and_threshold(
+    banks_client: &mut BanksClient,
+    owner: &Keypair,
+    modules_and_threshold: ModulesAndThreshold,
+) -> Result<(Pubkey, u8), BanksClientError> {
+    let program_id = aggregation_ism_id();
+
+    process_test_instruction(
+        banks_client,
+        set_modules_and_threshold_instruction(program_id, owner.pubkey(), modules_and_threshold)
+            .unwrap(),
+        owner,
+        &[owner],
+    )
+    .await?;
+
+    Ok(Pubkey::find_program_address(
+        modules_and_threshold_pda_seeds!(),
+        &program_id,
+    ))
+}
+
+/// Initializes each of the test ISMs, and configures the aggregation ISM
+/// to use them with the provided threshold.
+async fn setup_modules(banks_client: &mut BanksClient, payer: &Keypair, threshold: u8) {
+    initialize(banks_client, payer).await.unwrap();
+
+    for test_ism_id in test_ism_ids() {
+        let (storage_pda_key, _) =
+            Pubkey::find_program_address(test_ism_storage_pda_seeds!(), &test_ism_id);
+
+        process_test_instruction(
+            banks_client,
+            Instruction::new_with_bytes(
+                test_ism_id,
+                &TestIsmInstruction::Init.try_to_vec().unwrap(),
+                vec![
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new(storage_pda_key, false),
+                ],
+            ),
+            payer,
+            &[payer],
+        )
+        .await
+        .unwrap();
+    }
+
+    set_modules_and_threshold(
+        banks_client,
+        payer,
+        ModulesAndThreshold {
+            modules: test_ism_ids().to_vec(),
+            threshold,
+        },
+    )
+    .await
+    .unwrap();
+}
+
+async fn set_test_ism_accept(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    test_ism_id: Pubkey,
+    accept: bool,
+) {
+    let (storage_pda_key, _) =
+        Pubkey::find_program_address(test_ism_storage_pda_seeds!(), &test_ism_id);
+
+    process_test_instruction(
+        banks_client,
+        Instruction::new_with_bytes(
+            test_ism_id,
+            &TestIsmInstruction::SetAccept(accept).try_to_vec().unwrap(),
+            vec![AccountMeta::new(storage_pda_key, false)],
+        ),
+        payer,
+        &[payer],
+    )
+    .await
+    .unwrap();
+}
+
+fn test_message() -> HyperlaneMessage {
+    HyperlaneMessage {
+        version: 0,
+        nonce: 0,
+        origin: 1234,
+        sender: H256::random(),
+        destination: 4321,
+        recipient: H256::random(),
+        body: vec![1, 2, 3, 4, 5],
+    }
+}
+
+async fn get_modules_and_threshold(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    message: &HyperlaneMessage,
+) -> Result<ModulesAndThreshold, BanksClientError> {
+    Ok(
+        simulate_instruction::<SimulationReturnData<ModulesAndThreshold>>(
+            banks_client,
+            payer,
+            modules_and_threshold_instruction(aggregation_ism_id(), message.to_vec()).unwrap(),
+        )
+        .await?
+        .unwrap()
+        .return_data,
+    )
+}
+
+/// Gets the accounts required to verify the message with the aggregation ISM,
+/// the way a relayer is expected to: the aggregation ISM's own account metas,
+/// followed by the program and account metas of each module with metadata.
+async fn verify_account_metas(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    message: &HyperlaneMessage,
+    metadata: &AggregationIsmMetadata,
+) -> Vec<AccountMeta> {
+    let mut account_metas = get_ism_verify_account_metas(
+        banks_client,
+        payer,
+        aggregation_ism_id(),
+        metadata.to_vec(),
+        message.to_vec(),
+    )
+    .await
+    .unwrap();
+
+    let modules_and_threshold = get_modules_and_threshold(banks_client, payer, message)
+        .await
+        .unwrap();
+    for (module, sub_metadata) in modules_and_threshold
+        .modules
+        .into_iter()
+        .zip(metadata.sub_metadata.iter())
+    {
+        if let Some(sub_metadata) = sub_metadata {
+            account_metas.push(AccountMeta::new_readonly(module, false));
+            account_metas.extend(
+                get_ism_verify_account_metas(
+                    banks_client,
+                    payer,
+                    module,
+                    sub_metadata.clone(),
+                    message.to_vec(),
+                )
+                .await
+                .unwrap(),
+            );
+        }
+    }
+
+    account_metas
+}
+
+async fn verify(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    message: &HyperlaneMessage,
+    metadata: &AggregationIsmMetadata,
+    account_metas: Vec<AccountMeta>,
+) -> Result<(), BanksClientError> {
+    process_test_instruction(
+        banks_client,
+        Instruction::new_with_bytes(
+            aggregation_ism_id(),
+            &InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+                metadata: metadata.to_vec(),
+                message: message.to_vec(),
+            })
+            .encode()
+            .unwrap(),
+            account_metas,
+        ),
+        payer,
+        &[payer],
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_initialize() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (access_control_pda_key, access_control_pda_bump_seed) =
+        initialize(&mut banks_client, &payer).await.unwrap();
+
+    let access_control_account_data = banks_client
+        .get_account(access_control_pda_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let access_control = AccessControlAccount::fetch_data(&mut &access_control_account_data[..])
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        access_control,
+        Box::new(AccessControlData {
+            bump_seed: access_control_pda_bump_seed,
+            owner: Some(payer.pubkey()),
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_errors_if_called_twice() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+
+    // Create a new payer as a hack to get a new tx ID, because the
+    // instruction data is the same and the recent blockhash is the same
+    let new_payer = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = initialize(&mut banks_client, &new_payer).await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(AggregationIsmError::AlreadyInitialized as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_set_modules_and_threshold() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+
+    let modules_and_threshold = ModulesAndThreshold {
+        modules: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        threshold: 2,
+    };
+    let (modules_and_threshold_pda_key, modules_and_threshold_pda_bump_seed) =
+        set_modules_and_threshold(&mut banks_client, &payer, modules_and_threshold.clone())
+            .await
+            .unwrap();
+
+    let account_data = banks_client
+        .get_account(modules_and_threshold_pda_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let modules_and_threshold_data = ModulesAndThresholdAccount::fetch_data(&mut &account_data[..])
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        modules_and_threshold_data,
+        Box::new(ModulesAndThresholdData {
+            bump_seed: modules_and_threshold_pda_bump_seed,
+            modules_and_threshold,
+        }),
+    );
+
+    // Change it up after the PDA has been created.
+    let modules_and_threshold = ModulesAndThreshold {
+        modules: vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ],
+        threshold: 1,
+    };
+    set_modules_and_threshold(&mut banks_client, &payer, modules_and_threshold.clone())
+        .await
+        .unwrap();
+
+    // And query it with the `ModulesAndThreshold` instruction.
+    assert_eq!(
+        get_modules_and_threshold(&mut banks_client, &payer, &test_message())
+            .await
+            .unwrap(),
+        modules_and_threshold,
+    );
+}
+
+#[tokio::test]
+async fn test_set_modules_and_threshold_errors() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+
+    // Threshold exceeds the number of modules
+    let result = set_modules_and_threshold(
+        &mut banks_client,
+        &payer,
+        ModulesAndThreshold {
+            modules: vec![Pubkey::new_unique()],
+            threshold: 2,
+        },
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(AggregationIsmError::InvalidModulesAndThreshold as u32),
+        ),
+    );
+
+    // Not the owner
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = set_modules_and_threshold(
+        &mut banks_client,
+        &non_owner,
+        ModulesAndThreshold {
+            modules: vec![Pubkey::new_unique()],
+            threshold: 1,
+        },
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[tokio::test]
+async fn test_verify() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    setup_modules(&mut banks_client, &payer, 2).await;
+
+    // Metadata for the first and last modules, meeting the threshold.
+    let message = test_message();
+    let metadata = AggregationIsmMetadata {
+        sub_metadata: vec![Some(vec![1, 2, 3]), None, Some(vec![4, 5])],
+    };
+    let account_metas = verify_account_metas(&mut banks_client, &payer, &message, &metadata).await;
+    verify(
+        &mut banks_client,
+        &payer,
+        &message,
+        &metadata,
+        account_metas,
+    )
+    .await
+    .unwrap();
+
+    // Metadata for all modules, exceeding the threshold.
+    let message = test_message();
+    let metadata = AggregationIsmMetadata {
+        sub_metadata: vec![Some(vec![1]), Some(vec![2]), Some(vec![])],
+    };
+    let account_metas = verify_account_metas(&mut banks_client, &payer, &message, &metadata).await;
+    verify(
+        &mut banks_client,
+        &payer,
+        &message,
+        &metadata,
+        account_metas,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_threshold_not_met() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    setup_modules(&mut banks_client, &payer, 2).await;
+
+    // Metadata for only one module.
+    let message = test_message();
+    let metadata = AggregationIsmMetadata {
+        sub_metadata: vec![None, Some(vec![1, 2, 3]), None],
+    };
+    let account_metas = verify_account_metas(&mut banks_client, &payer, &message, &metadata).await;
+    let result = verify(
+        &mut banks_client,
+        &payer,
+        &message,
+        &metadata,
+        account_metas,
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(AggregationIsmError::ThresholdNotMet as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_module_rejects() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    setup_modules(&mut banks_client, &payer, 1).await;
+    set_test_ism_accept(&mut banks_client, &payer, test_ism_ids()[1], false).await;
+
+    // Metadata for a module that rejects the message fails verification,
+    // even though the threshold would otherwise be met.
+    let message = test_message();
+    let metadata = AggregationIsmMetadata {
+        sub_metadata: vec![Some(vec![1]), Some(vec![2]), None],
+    };
+    let account_metas = verify_account_metas(&mut banks_client, &payer, &message, &metadata).await;
+    let result = verify(
+        &mut banks_client,
+        &payer,
+        &message,
+        &metadata,
+        account_metas,
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TestIsmError::VerifyNotAccepted as u32),
+        ),
+    );
+
+    // Omitting the metadata for the rejecting module succeeds.
+    let metadata = AggregationIsmMetadata {
+        sub_metadata: vec![Some(vec![1]), None, None],
+    };
+    let account_metas = verify_account_metas(&mut banks_client, &payer, &message, &metadata).await;
+    verify(
+        &mut banks_client,
+        &payer,
+        &message,
+        &metadata,
+        account_metas,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_modules_out_of_order() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    setup_modules(&mut banks_client, &payer, 2).await;
+
+    let message = test_message();
+    let metadata = AggregationIsmMetadata {
+        sub_metadata: vec![Some(vec![1]), Some(vec![2]), None],
+    };
+    let mut account_metas =
+        verify_account_metas(&mut banks_client, &payer, &message, &metadata).await;
+    // Swap the first module's program and accounts with the second's.
+    // [modules and threshold PDA, module 0, module 0 storage, module 1, module 1 storage]
+    account_metas[1..].rotate_left(2);
+
+    let result = verify(
+        &mut banks_client,
+        &payer,
+        &message,
+        &metadata,
+        account_metas,
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(AggregationIsmError::AccountOutOfOrder as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_ism_type() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    let module_type = simulate_instruction::<SimulationReturnData<u32>>(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            aggregation_ism_id(),
+            &InterchainSecurityModuleInstruction::Type.encode().unwrap(),
+            vec![],
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+    assert_eq!(module_type, ModuleType::Aggregation as u32);
+}
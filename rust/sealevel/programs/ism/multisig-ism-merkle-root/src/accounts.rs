@@ -0,0 +1,3 @@
+pub use multisig_ism::accounts::{
+    AccessControlAccount, AccessControlData, DomainData, DomainDataAccount,
+};
@@ -0,0 +1,3 @@
+//! Errors of the multisig ISM, shared with the other multisig ISMs.
+
+pub use multisig_ism::error::Error;
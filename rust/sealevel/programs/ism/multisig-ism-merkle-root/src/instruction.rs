@@ -0,0 +1,14 @@
+use solana_program::{
+    instruction::Instruction as SolanaInstruction, program_error::ProgramError, pubkey::Pubkey,
+};
+
+pub use multisig_ism::instruction::{Domained, Instruction, ValidatorsAndThreshold};
+
+use crate::processor::PDA_SEED_PREFIX;
+
+pub fn init_instruction(
+    program_id: Pubkey,
+    payer: Pubkey,
+) -> Result<SolanaInstruction, ProgramError> {
+    multisig_ism::instruction::init_instruction(program_id, payer, PDA_SEED_PREFIX)
+}
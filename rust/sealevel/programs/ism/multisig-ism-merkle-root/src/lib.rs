@@ -0,0 +1,18 @@
+//! A multisig Interchain Security Module that accepts signatures over
+//! a checkpoint and a merkle proof of the message's inclusion in the
+//! checkpoint's root. The checkpoint may be for the message itself or for
+//! any later message, so a single signed checkpoint can be used to verify
+//! every message before it.
+
+#![deny(warnings)]
+#![deny(unsafe_code)]
+
+pub mod accounts;
+pub mod error;
+pub mod instruction;
+pub mod metadata;
+pub mod processor;
+
+// Used by the PDA seed macros.
+#[doc(hidden)]
+pub use multisig_ism;
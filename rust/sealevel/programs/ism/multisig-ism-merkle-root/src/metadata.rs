@@ -0,0 +1,183 @@
+use ecdsa_signature::EcdsaSignature;
+use hyperlane_core::{accumulator::TREE_DEPTH, Encode, H256};
+
+use crate::error::Error;
+
+#[derive(Debug)]
+pub struct MultisigIsmMerkleRootMetadata {
+    pub origin_mailbox: H256,
+    pub checkpoint_index: u32,
+    pub checkpoint_message_id: H256,
+    pub proof: [H256; TREE_DEPTH],
+    pub validator_signatures: Vec<EcdsaSignature>,
+}
+
+const ORIGIN_MAILBOX_OFFSET: usize = 0;
+const CHECKPOINT_INDEX_OFFSET: usize = 32;
+const CHECKPOINT_MESSAGE_ID_OFFSET: usize = 36;
+const MERKLE_PROOF_OFFSET: usize = 68;
+const MERKLE_PROOF_LENGTH: usize = 32 * TREE_DEPTH;
+const SIGNATURES_OFFSET: usize = MERKLE_PROOF_OFFSET + MERKLE_PROOF_LENGTH;
+const SIGNATURE_LENGTH: usize = 65;
+
+/// Format of metadata:
+/// [   0:  32] Origin mailbox address
+/// [  32:  36] Signed checkpoint index
+/// [  36:  68] Signed checkpoint message ID
+/// [  68:1092] Merkle proof
+/// [1092:????] Validator signatures (length := threshold)
+/// Note that the validator signatures being the length of the threshold is
+/// not enforced here and should be enforced by the caller.
+impl TryFrom<Vec<u8>> for MultisigIsmMerkleRootMetadata {
+    type Error = Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        let bytes_len = bytes.len();
+        // Require the bytes to be at least big enough to include a single signature.
+        if bytes_len < SIGNATURES_OFFSET + SIGNATURE_LENGTH {
+            return Err(Error::InvalidMetadata);
+        }
+
+        let origin_mailbox =
+            H256::from_slice(&bytes[ORIGIN_MAILBOX_OFFSET..CHECKPOINT_INDEX_OFFSET]);
+        let mut checkpoint_index = [0u8; 4];
+        checkpoint_index
+            .copy_from_slice(&bytes[CHECKPOINT_INDEX_OFFSET..CHECKPOINT_MESSAGE_ID_OFFSET]);
+        let checkpoint_index = u32::from_be_bytes(checkpoint_index);
+        let checkpoint_message_id =
+            H256::from_slice(&bytes[CHECKPOINT_MESSAGE_ID_OFFSET..MERKLE_PROOF_OFFSET]);
+
+        let mut proof = [H256::zero(); TREE_DEPTH];
+        for (i, node) in proof.iter_mut().enumerate() {
+            let node_offset = MERKLE_PROOF_OFFSET + (i * 32);
+            *node = H256::from_slice(&bytes[node_offset..node_offset + 32]);
+        }
+
+        let signature_bytes_len = bytes_len - SIGNATURES_OFFSET;
+        // Require the signature bytes to be a multiple of the signature length.
+        // We don't need to check if signature_bytes_len is 0 because this is checked
+        // above.
+        if signature_bytes_len % SIGNATURE_LENGTH != 0 {
+            return Err(Error::InvalidMetadata);
+        }
+        let signature_count = signature_bytes_len / SIGNATURE_LENGTH;
+        let mut validator_signatures = Vec::with_capacity(signature_count);
+        for i in 0..signature_count {
+            let signature_offset = SIGNATURES_OFFSET + (i * SIGNATURE_LENGTH);
+            let signature = EcdsaSignature::from_bytes(
+                &bytes[signature_offset..signature_offset + SIGNATURE_LENGTH],
+            )
+            .map_err(|_| Error::InvalidMetadata)?;
+            validator_signatures.push(signature);
+        }
+
+        Ok(Self {
+            origin_mailbox,
+            checkpoint_index,
+            checkpoint_message_id,
+            proof,
+            validator_signatures,
+        })
+    }
+}
+
+impl Encode for MultisigIsmMerkleRootMetadata {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let mut bytes_written = 0;
+        bytes_written += writer.write(self.origin_mailbox.as_ref())?;
+        bytes_written += writer.write(&self.checkpoint_index.to_be_bytes())?;
+        bytes_written += writer.write(self.checkpoint_message_id.as_ref())?;
+        for node in &self.proof {
+            bytes_written += writer.write(node.as_ref())?;
+        }
+        for signature in &self.validator_signatures {
+            bytes_written += writer.write(&signature.as_fixed_bytes()[..])?;
+        }
+        Ok(bytes_written)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn random_proof() -> [H256; TREE_DEPTH] {
+        let mut proof = [H256::zero(); TREE_DEPTH];
+        for node in proof.iter_mut() {
+            *node = H256::random();
+        }
+        proof
+    }
+
+    #[test]
+    fn test_decode_correctly_formatted_metadata() {
+        let origin_mailbox = H256::random();
+        let checkpoint_index: u32 = 420;
+        let checkpoint_message_id = H256::random();
+        let proof = random_proof();
+        let validator_signatures = vec![
+            EcdsaSignature {
+                serialized_rs: [11u8; 64],
+                recovery_id: 0,
+            },
+            EcdsaSignature {
+                serialized_rs: [12u8; 64],
+                recovery_id: 1,
+            },
+            EcdsaSignature {
+                serialized_rs: [13u8; 64],
+                recovery_id: 0,
+            },
+        ];
+        let mut metadata_bytes = origin_mailbox.as_bytes().to_vec();
+        metadata_bytes.extend_from_slice(&checkpoint_index.to_be_bytes());
+        metadata_bytes.extend_from_slice(checkpoint_message_id.as_bytes());
+        for node in &proof {
+            metadata_bytes.extend_from_slice(node.as_bytes());
+        }
+        for signature in &validator_signatures {
+            metadata_bytes.extend_from_slice(&signature.as_fixed_bytes()[..]);
+        }
+
+        let metadata = MultisigIsmMerkleRootMetadata::try_from(metadata_bytes.clone()).unwrap();
+        assert_eq!(metadata.origin_mailbox, origin_mailbox);
+        assert_eq!(metadata.checkpoint_index, checkpoint_index);
+        assert_eq!(metadata.checkpoint_message_id, checkpoint_message_id);
+        assert_eq!(metadata.proof, proof);
+        assert_eq!(metadata.validator_signatures, validator_signatures);
+
+        // Encoding round trips
+        assert_eq!(metadata.to_vec(), metadata_bytes);
+    }
+
+    #[test]
+    fn test_decode_no_signatures_is_err() {
+        let mut metadata_bytes = H256::random().as_bytes().to_vec();
+        metadata_bytes.extend_from_slice(&69u32.to_be_bytes());
+        metadata_bytes.extend_from_slice(H256::random().as_bytes());
+        for node in &random_proof() {
+            metadata_bytes.extend_from_slice(node.as_bytes());
+        }
+
+        let result = MultisigIsmMerkleRootMetadata::try_from(metadata_bytes);
+        assert!(result.unwrap_err() == Error::InvalidMetadata);
+    }
+
+    #[test]
+    fn test_decode_incorrect_signature_length_is_err() {
+        let mut metadata_bytes = H256::random().as_bytes().to_vec();
+        metadata_bytes.extend_from_slice(&69u32.to_be_bytes());
+        metadata_bytes.extend_from_slice(H256::random().as_bytes());
+        for node in &random_proof() {
+            metadata_bytes.extend_from_slice(node.as_bytes());
+        }
+        // 64 byte signature instead of 65.
+        metadata_bytes.extend_from_slice(&[1u8; 64]);
+
+        let result = MultisigIsmMerkleRootMetadata::try_from(metadata_bytes);
+        assert!(result.unwrap_err() == Error::InvalidMetadata);
+    }
+}
@@ -0,0 +1,307 @@
+use ecdsa_signature::EcdsaSignature;
+use hyperlane_core::{
+    accumulator::{merkle::merkle_root_from_branch, TREE_DEPTH},
+    Checkpoint, CheckpointWithMessageId, HyperlaneMessage, ModuleType,
+};
+use multisig_ism::processor::{self, MultisigIsmProgram};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::metadata::MultisigIsmMerkleRootMetadata;
+
+/// The first seed of each of this program's PDAs.
+pub const PDA_SEED_PREFIX: &[u8] = b"multisig_ism_merkle_root";
+
+/// A multisig ISM whose validators sign a checkpoint that the message is
+/// proven to be included in. The checkpoint may be for any later message.
+pub struct MerkleRootMultisigIsm;
+
+impl MultisigIsmProgram for MerkleRootMultisigIsm {
+    const MODULE_TYPE: ModuleType = ModuleType::MerkleRootMultisig;
+    const PDA_SEED_PREFIX: &'static [u8] = PDA_SEED_PREFIX;
+
+    fn signed_checkpoint(
+        metadata: Vec<u8>,
+        message: &HyperlaneMessage,
+    ) -> Result<(CheckpointWithMessageId, Vec<EcdsaSignature>), ProgramError> {
+        let metadata = MultisigIsmMerkleRootMetadata::try_from(metadata)?;
+        // The root the message is proven to be included in. If the proof is incorrect,
+        // this won't match the signed root and signature verification will fail.
+        let root = merkle_root_from_branch(
+            message.id(),
+            &metadata.proof,
+            TREE_DEPTH,
+            message.nonce as usize,
+        );
+        let checkpoint = CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                mailbox_address: metadata.origin_mailbox,
+                mailbox_domain: message.origin,
+                root,
+                index: metadata.checkpoint_index,
+            },
+            message_id: metadata.checkpoint_message_id,
+        };
+        Ok((checkpoint, metadata.validator_signatures))
+    }
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// PDA seeds relating to the access control PDA account.
+#[macro_export]
+macro_rules! access_control_pda_seeds {
+    () => {{
+        $crate::multisig_ism::access_control_pda_seeds!($crate::processor::PDA_SEED_PREFIX)
+    }};
+
+    ($bump_seed:expr) => {{
+        $crate::multisig_ism::access_control_pda_seeds!(
+            $crate::processor::PDA_SEED_PREFIX,
+            $bump_seed
+        )
+    }};
+}
+
+/// PDA seeds relating to a domain data PDA account.
+/// A distinct account exists for each domain.
+#[macro_export]
+macro_rules! domain_data_pda_seeds {
+    ($domain:expr) => {{
+        $crate::multisig_ism::domain_data_pda_seeds!($crate::processor::PDA_SEED_PREFIX, $domain)
+    }};
+
+    ($domain:expr, $bump_seed:expr) => {{
+        $crate::multisig_ism::domain_data_pda_seeds!(
+            $crate::processor::PDA_SEED_PREFIX,
+            $domain,
+            $bump_seed
+        )
+    }};
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    processor::process_instruction::<MerkleRootMultisigIsm>(program_id, accounts, instruction_data)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    use crate::{
+        accounts::{DomainData, DomainDataAccount},
+        error::Error,
+        instruction::ValidatorsAndThreshold,
+    };
+
+    use ecdsa_signature::EcdsaSignature;
+    use hyperlane_core::{Encode, HyperlaneMessage, H256};
+    use hyperlane_sealevel_interchain_security_module_interface::{
+        InterchainSecurityModuleInstruction, VerifyInstruction,
+    };
+    use multisig_ism::test_data::{
+        get_merkle_root_multisig_ism_test_data, MerkleRootMultisigIsmTestData,
+    };
+    use solana_program::stake_history::Epoch;
+    use std::str::FromStr;
+
+    const ORIGIN_DOMAIN: u32 = 1234u32;
+
+    fn id() -> Pubkey {
+        Pubkey::from_str("8zfw7NpyeqP5jKXqqDfk9p8iXJXT8rQBzbBjG5pHqTNN").unwrap()
+    }
+
+    #[test]
+    fn test_verify() {
+        let program_id = id();
+
+        let (domain_pda_key, domain_pda_bump_seed) =
+            Pubkey::find_program_address(domain_data_pda_seeds!(ORIGIN_DOMAIN), &program_id);
+
+        let MerkleRootMultisigIsmTestData {
+            message,
+            checkpoint,
+            proof,
+            validators,
+            signatures,
+        } = get_merkle_root_multisig_ism_test_data();
+
+        let mut domain_account_lamports = 0;
+        let mut domain_account_data = vec![0_u8; 2048];
+        let domain_pda_account = AccountInfo::new(
+            &domain_pda_key,
+            false,
+            true,
+            &mut domain_account_lamports,
+            &mut domain_account_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+        let init_domain_data = DomainData {
+            bump_seed: domain_pda_bump_seed,
+            validators_and_threshold: ValidatorsAndThreshold {
+                validators,
+                threshold: 2,
+            },
+        };
+        DomainDataAccount::from(init_domain_data)
+            .store(&domain_pda_account, false)
+            .unwrap();
+
+        let message_bytes = message.to_vec();
+
+        // A quorum of signatures in the correct order.
+        // Expect no error.
+        let result = process_instruction(
+            &program_id,
+            &[domain_pda_account.clone()],
+            // Use the InterchainSecurityModuleInstruction enum to ensure the instruction
+            // is handled in compliance with what the Mailbox expects
+            InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+                metadata: MultisigIsmMerkleRootMetadata {
+                    origin_mailbox: checkpoint.mailbox_address,
+                    checkpoint_index: checkpoint.index,
+                    checkpoint_message_id: checkpoint.message_id,
+                    proof,
+                    validator_signatures: vec![
+                        EcdsaSignature::from_bytes(&signatures[0]).unwrap(),
+                        EcdsaSignature::from_bytes(&signatures[1]).unwrap(),
+                    ],
+                }
+                .to_vec(),
+                message: message_bytes.clone(),
+            })
+            .encode()
+            .unwrap()
+            .as_slice(),
+        );
+        assert!(result.is_ok());
+
+        // A quorum of signatures NOT in the correct order.
+        // Expect an error.
+        let result = process_instruction(
+            &program_id,
+            &[domain_pda_account.clone()],
+            // Use the InterchainSecurityModuleInstruction enum to ensure the instruction
+            // is handled in compliance with what the Mailbox expects
+            InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+                metadata: MultisigIsmMerkleRootMetadata {
+                    origin_mailbox: checkpoint.mailbox_address,
+                    checkpoint_index: checkpoint.index,
+                    checkpoint_message_id: checkpoint.message_id,
+                    proof,
+                    validator_signatures: vec![
+                        EcdsaSignature::from_bytes(&signatures[1]).unwrap(),
+                        EcdsaSignature::from_bytes(&signatures[0]).unwrap(),
+                    ],
+                }
+                .to_vec(),
+                message: message_bytes.clone(),
+            })
+            .encode()
+            .unwrap()
+            .as_slice(),
+        );
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), Error::ThresholdNotMet.into());
+
+        // A quorum valid signatures. Includes one invalid signature.
+        // Expect no error.
+        let result = process_instruction(
+            &program_id,
+            &[
+                domain_pda_account.clone(),
+            ],
+            // Use the InterchainSecurityModuleInstruction enum to ensure the instruction
+            // is handled in compliance with what the Mailbox expects
+            InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+                metadata: MultisigIsmMerkleRootMetadata {
+                    origin_mailbox: checkpoint.mailbox_address,
+                    checkpoint_index: checkpoint.index,
+                    checkpoint_message_id: checkpoint.message_id,
+                    proof,
+                    validator_signatures: vec![
+                        EcdsaSignature::from_bytes(&signatures[0]).unwrap(),
+                        EcdsaSignature::from_bytes(&signatures[2]).unwrap(),
+                        // Signature from a non-validator:
+                        //   Address: 0xB92752D900573BC114D18e023D81312bBC32e266
+                        //   Private Key: 0x2e09250a71f712e5f834285cc60f1d62578360c65a0f4836daa0a5caa27199cf
+                        EcdsaSignature::from_bytes(&hex::decode("b952815f48696eaca14b9851df7f5fcef89d5c7e53e07ea9bc9a3b4db747425a7af8a198c35b880b448d41559d5abe44d21e5b4925b3a5637766a7515468b9e51b").unwrap()).unwrap(),
+                    ],
+                }.to_vec(),
+                message: message_bytes.clone(),
+            }).encode().unwrap().as_slice(),
+        );
+        assert!(result.is_ok());
+
+        // A quorum of signatures, but the proof doesn't prove the message's
+        // inclusion in the signed root.
+        // Expect an error.
+        let mut incorrect_proof = proof;
+        incorrect_proof[0] = H256::random();
+        let result = process_instruction(
+            &program_id,
+            &[domain_pda_account.clone()],
+            // Use the InterchainSecurityModuleInstruction enum to ensure the instruction
+            // is handled in compliance with what the Mailbox expects
+            InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+                metadata: MultisigIsmMerkleRootMetadata {
+                    origin_mailbox: checkpoint.mailbox_address,
+                    checkpoint_index: checkpoint.index,
+                    checkpoint_message_id: checkpoint.message_id,
+                    proof: incorrect_proof,
+                    validator_signatures: vec![
+                        EcdsaSignature::from_bytes(&signatures[0]).unwrap(),
+                        EcdsaSignature::from_bytes(&signatures[1]).unwrap(),
+                    ],
+                }
+                .to_vec(),
+                message: message_bytes,
+            })
+            .encode()
+            .unwrap()
+            .as_slice(),
+        );
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), Error::ThresholdNotMet.into());
+
+        // A quorum of signatures, but the message has a different nonce & therefore ID
+        let result = process_instruction(
+            &program_id,
+            &[domain_pda_account.clone()],
+            // Use the InterchainSecurityModuleInstruction enum to ensure the instruction
+            // is handled in compliance with what the Mailbox expects
+            InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+                metadata: MultisigIsmMerkleRootMetadata {
+                    origin_mailbox: checkpoint.mailbox_address,
+                    checkpoint_index: checkpoint.index,
+                    checkpoint_message_id: checkpoint.message_id,
+                    proof,
+                    validator_signatures: vec![
+                        EcdsaSignature::from_bytes(&signatures[0]).unwrap(),
+                        EcdsaSignature::from_bytes(&signatures[1]).unwrap(),
+                    ],
+                }
+                .to_vec(),
+                message: HyperlaneMessage {
+                    nonce: 420,
+                    ..message
+                }
+                .to_vec(),
+            })
+            .encode()
+            .unwrap()
+            .as_slice(),
+        );
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), Error::ThresholdNotMet.into());
+    }
+}
@@ -10,3 +10,7 @@ pub mod error;
 pub mod instruction;
 pub mod metadata;
 pub mod processor;
+
+// Used by the PDA seed macros.
+#[doc(hidden)]
+pub use multisig_ism;
@@ -0,0 +1,28 @@
+//! Hyperlane Sealevel routing ISM specific errors.
+
+use solana_program::program_error::ProgramError;
+
+#[derive(Copy, Clone, Debug, Eq, thiserror::Error, num_derive::FromPrimitive, PartialEq)]
+#[repr(u32)]
+pub enum Error {
+    #[error("Account not found in the correct order")]
+    AccountOutOfOrder = 1,
+    #[error("Account is not owner")]
+    AccountNotOwner = 2,
+    #[error("Program ID is not owner")]
+    ProgramIdNotOwner = 3,
+    #[error("Account not initialized")]
+    AccountNotInitialized = 4,
+    #[error("Already initialized")]
+    AlreadyInitialized = 5,
+    #[error("No ISM configured for the origin domain")]
+    NoRouteForDomain = 6,
+    #[error("Routed ISM program is not executable")]
+    IsmNotExecutable = 7,
+}
+
+impl From<Error> for ProgramError {
+    fn from(err: Error) -> Self {
+        ProgramError::Custom(err as u32)
+    }
+}
@@ -0,0 +1,10 @@
+//! An Interchain Security Module that routes messages to a different
+//! Interchain Security Module depending on the message's origin domain.
+
+#![deny(warnings)]
+#![deny(unsafe_code)]
+
+pub mod accounts;
+pub mod error;
+pub mod instruction;
+pub mod processor;
@@ -0,0 +1,459 @@
+//! Contains functional tests for things that cannot be done
+//! strictly in unit tests. This includes CPIs, like creating
+//! new PDA accounts and invoking the routed ISM.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    system_program,
+};
+
+use hyperlane_core::{Encode, HyperlaneMessage, ModuleType, H256};
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction,
+};
+use hyperlane_sealevel_routing_ism::{
+    access_control_pda_seeds,
+    accounts::{AccessControlAccount, AccessControlData, DomainData, DomainDataAccount},
+    domain_data_pda_seeds,
+    error::Error as RoutingIsmError,
+    instruction::{init_instruction, route_instruction, set_domain_ism_instruction},
+    processor::process_instruction,
+};
+use hyperlane_sealevel_test_ism::{
+    program::{TestIsmError, TestIsmInstruction},
+    test_ism_storage_pda_seeds,
+};
+use hyperlane_test_utils::{
+    assert_transaction_error, get_ism_verify_account_metas, new_funded_keypair,
+    process_instruction as process_test_instruction, simulate_instruction,
+};
+use serializable_account_meta::SimulationReturnData;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+    transaction::TransactionError,
+};
+
+const ORIGIN_DOMAIN: u32 = 1234;
+
+fn routing_ism_id() -> Pubkey {
+    pubkey!("2UuR7tySrbMF9vxLBpSaTNCsSknJbd3jjdvB5mEY1wEJ")
+}
+
+fn test_ism_id() -> Pubkey {
+    pubkey!("3UwY8pNfnQPbw8xKmUGvAX7g1kCxDSSkLyqo4E3PXZP5")
+}
+
+async fn setup_client() -> (BanksClient, Keypair) {
+    let mut program_test = ProgramTest::new(
+        "hyperlane_sealevel_routing_ism",
+        routing_ism_id(),
+        processor!(process_instruction),
+    );
+    program_test.add_program(
+        "hyperlane_sealevel_test_ism",
+        test_ism_id(),
+        processor!(hyperlane_sealevel_test_ism::program::process_instruction),
+    );
+
+    let (banks_client, payer, _recent_blockhash) = program_test.start().await;
+
+    (banks_client, payer)
+}
+
+async fn initialize(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+) -> Result<(Pubkey, u8), BanksClientError> {
+    let program_id = routing_ism_id();
+
+    process_test_instruction(
+        banks_client,
+        init_instruction(program_id, payer.pubkey()).unwrap(),
+        payer,
+        &[payer],
+    )
+    .await?;
+
+    Ok(Pubkey::find_program_address(
+        access_control_pda_seeds!(),
+        &program_id,
+    ))
+}
+
+async fn set_domain_ism(
+    banks_client: &mut BanksClient,
+    owner: &Keypair,
+    domain: u32,
+    ism: Pubkey,
+) -> Result<(Pubkey, u8), BanksClientError> {
+    let program_id = routing_ism_id();
+
+    process_test_instruction(
+        banks_client,
+        set_domain_ism_instruction(program_id, owner.pubkey(), domain, ism).unwrap(),
+        owner,
+        &[owner],
+    )
+    .await?;
+
+    Ok(Pubkey::find_program_address(
+        domain_data_pda_seeds!(domain),
+        &program_id,
+    ))
+}
+
+async fn initialize_test_ism(banks_client: &mut BanksClient, payer: &Keypair) {
+    let (storage_pda_key, _) =
+        Pubkey::find_program_address(test_ism_storage_pda_seeds!(), &test_ism_id());
+
+    process_test_instruction(
+        banks_client,
+        Instruction::new_with_bytes(
+            test_ism_id(),
+            &TestIsmInstruction::Init.try_to_vec().unwrap(),
+            vec![
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(storage_pda_key, false),
+            ],
+        ),
+        payer,
+        &[payer],
+    )
+    .await
+    .unwrap();
+}
+
+async fn set_test_ism_accept(banks_client: &mut BanksClient, payer: &Keypair, accept: bool) {
+    let (storage_pda_key, _) =
+        Pubkey::find_program_address(test_ism_storage_pda_seeds!(), &test_ism_id());
+
+    process_test_instruction(
+        banks_client,
+        Instruction::new_with_bytes(
+            test_ism_id(),
+            &TestIsmInstruction::SetAccept(accept).try_to_vec().unwrap(),
+            vec![AccountMeta::new(storage_pda_key, false)],
+        ),
+        payer,
+        &[payer],
+    )
+    .await
+    .unwrap();
+}
+
+fn test_message(origin: u32) -> HyperlaneMessage {
+    HyperlaneMessage {
+        version: 0,
+        nonce: 0,
+        origin,
+        sender: H256::random(),
+        destination: origin + 1,
+        recipient: H256::random(),
+        body: vec![1, 2, 3, 4, 5],
+    }
+}
+
+/// Gets the accounts required to verify the message with the routing ISM,
+/// the way a relayer is expected to: the routing ISM's own account metas,
+/// followed by the routed ISM and its account metas.
+async fn verify_account_metas(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    message: &HyperlaneMessage,
+) -> Vec<AccountMeta> {
+    let mut account_metas = get_ism_verify_account_metas(
+        banks_client,
+        payer,
+        routing_ism_id(),
+        vec![],
+        message.to_vec(),
+    )
+    .await
+    .unwrap();
+
+    let routed_ism = route(banks_client, payer, message).await.unwrap();
+    account_metas.push(AccountMeta::new_readonly(routed_ism, false));
+    account_metas.extend(
+        get_ism_verify_account_metas(banks_client, payer, routed_ism, vec![], message.to_vec())
+            .await
+            .unwrap(),
+    );
+
+    account_metas
+}
+
+async fn route(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    message: &HyperlaneMessage,
+) -> Result<Pubkey, BanksClientError> {
+    Ok(simulate_instruction::<SimulationReturnData<Pubkey>>(
+        banks_client,
+        payer,
+        route_instruction(routing_ism_id(), message.origin, message.to_vec()).unwrap(),
+    )
+    .await?
+    .unwrap()
+    .return_data)
+}
+
+async fn verify(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    message: &HyperlaneMessage,
+    account_metas: Vec<AccountMeta>,
+) -> Result<(), BanksClientError> {
+    process_test_instruction(
+        banks_client,
+        Instruction::new_with_bytes(
+            routing_ism_id(),
+            &InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+                metadata: vec![],
+                message: message.to_vec(),
+            })
+            .encode()
+            .unwrap(),
+            account_metas,
+        ),
+        payer,
+        &[payer],
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_initialize() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (access_control_pda_key, access_control_pda_bump_seed) =
+        initialize(&mut banks_client, &payer).await.unwrap();
+
+    let access_control_account_data = banks_client
+        .get_account(access_control_pda_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let access_control = AccessControlAccount::fetch_data(&mut &access_control_account_data[..])
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        access_control,
+        Box::new(AccessControlData {
+            bump_seed: access_control_pda_bump_seed,
+            owner: Some(payer.pubkey()),
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_errors_if_called_twice() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+
+    // Create a new payer as a hack to get a new tx ID, because the
+    // instruction data is the same and the recent blockhash is the same
+    let new_payer = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = initialize(&mut banks_client, &new_payer).await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(RoutingIsmError::AlreadyInitialized as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_set_domain_ism() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+
+    let ism = Pubkey::new_unique();
+    let (domain_data_pda_key, domain_data_pda_bump_seed) =
+        set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, ism)
+            .await
+            .unwrap();
+
+    let domain_data_account_data = banks_client
+        .get_account(domain_data_pda_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let domain_data = DomainDataAccount::fetch_data(&mut &domain_data_account_data[..])
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        domain_data,
+        Box::new(DomainData {
+            bump_seed: domain_data_pda_bump_seed,
+            ism,
+        }),
+    );
+
+    // Now change the ISM after the domain data PDA has been created.
+    let new_ism = Pubkey::new_unique();
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, new_ism)
+        .await
+        .unwrap();
+
+    let domain_data_account_data = banks_client
+        .get_account(domain_data_pda_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let domain_data = DomainDataAccount::fetch_data(&mut &domain_data_account_data[..])
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        domain_data,
+        Box::new(DomainData {
+            bump_seed: domain_data_pda_bump_seed,
+            ism: new_ism,
+        }),
+    );
+
+    // And route a message from the domain.
+    let message = test_message(ORIGIN_DOMAIN);
+    assert_eq!(
+        route(&mut banks_client, &payer, &message).await.unwrap(),
+        new_ism
+    );
+}
+
+#[tokio::test]
+async fn test_set_domain_ism_errors_if_not_owner() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = set_domain_ism(&mut banks_client, &non_owner, ORIGIN_DOMAIN, test_ism_id()).await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[tokio::test]
+async fn test_verify() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+    initialize_test_ism(&mut banks_client, &payer).await;
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, test_ism_id())
+        .await
+        .unwrap();
+
+    let message = test_message(ORIGIN_DOMAIN);
+    let account_metas = verify_account_metas(&mut banks_client, &payer, &message).await;
+
+    // The routed ISM accepts the message
+    verify(&mut banks_client, &payer, &message, account_metas.clone())
+        .await
+        .unwrap();
+
+    // The routed ISM rejects the message, which must fail the verification.
+    set_test_ism_accept(&mut banks_client, &payer, false).await;
+    let message = test_message(ORIGIN_DOMAIN);
+    let result = verify(&mut banks_client, &payer, &message, account_metas).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TestIsmError::VerifyNotAccepted as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_incorrect_routed_ism() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+    initialize_test_ism(&mut banks_client, &payer).await;
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, test_ism_id())
+        .await
+        .unwrap();
+
+    let message = test_message(ORIGIN_DOMAIN);
+    let mut account_metas = verify_account_metas(&mut banks_client, &payer, &message).await;
+    // Swap out the routed ISM for a different program.
+    account_metas[1] = AccountMeta::new_readonly(system_program::id(), false);
+
+    let result = verify(&mut banks_client, &payer, &message, account_metas).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(RoutingIsmError::AccountOutOfOrder as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_no_route_for_domain() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await.unwrap();
+    initialize_test_ism(&mut banks_client, &payer).await;
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, test_ism_id())
+        .await
+        .unwrap();
+
+    // A message from a domain that hasn't been set.
+    let message = test_message(ORIGIN_DOMAIN + 1);
+    let (domain_data_pda_key, _) =
+        Pubkey::find_program_address(domain_data_pda_seeds!(message.origin), &routing_ism_id());
+
+    let result = verify(
+        &mut banks_client,
+        &payer,
+        &message,
+        vec![
+            AccountMeta::new_readonly(domain_data_pda_key, false),
+            AccountMeta::new_readonly(test_ism_id(), false),
+        ],
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(RoutingIsmError::NoRouteForDomain as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_ism_type() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    let module_type = simulate_instruction::<SimulationReturnData<u32>>(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            routing_ism_id(),
+            &InterchainSecurityModuleInstruction::Type.encode().unwrap(),
+            vec![],
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+    assert_eq!(module_type, ModuleType::Routing as u32);
+}
@@ -1,4 +1,5 @@
 use std::env;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 pub struct Config {
@@ -6,6 +7,7 @@ pub struct Config {
     pub ci_mode: bool,
     pub ci_mode_timeout: u64,
     pub kathy_messages: u64,
+    pub fuel_contracts_path: Option<PathBuf>,
 }
 
 impl Config {
@@ -25,6 +27,7 @@ impl Config {
                     .map(|r| r.parse::<u64>().unwrap());
                 r.unwrap_or(16)
             },
+            fuel_contracts_path: env::var("E2E_FUEL_CONTRACTS_PATH").ok().map(PathBuf::from),
         })
     }
 }
@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::Duration;
+
+use fuels::prelude::{abigen, Bech32ContractId, ContractId, Provider, WalletUnlocked};
+use fuels::types::Bits256;
+use macro_rules_attribute::apply;
+use tempfile::tempdir;
+
+use crate::logging::log;
+use crate::program::Program;
+use crate::utils::{as_task, concat_path, AgentHandles, ArbitraryData, TaskHandle};
+
+pub const FUEL_CHAIN_NAME: &str = "fueltest1";
+const FUEL_DOMAIN: u32 = 13374;
+const FUEL_NODE_PORT: &str = "4000";
+const FUEL_NODE_URL: &str = "http://127.0.0.1:4000";
+/// Domain of the chain the fuel message is sent to.
+const FUEL_MESSAGE_DESTINATION_DOMAIN: u32 = 13371;
+
+/// Funded account of the fuel-core local testnet chain config.
+pub const FUEL_SIGNER_KEY: &str =
+    "0xde97d8624a438121b86a1956544bd72ed68cd69f2c99555b08b1e8c51ffd511c";
+
+// Fuel contract tuples of:
+// 0: Name of the contract in the agent address config
+// 1: Relative path to the sway project within the hyperlane-sway repo
+const FUEL_HYPERLANE_CONTRACTS: &[(&str, &str)] = &[
+    ("mailbox", "contracts/mailbox"),
+    (
+        "interchainGasPaymaster",
+        "contracts/igp/interchain-gas-paymaster",
+    ),
+    ("validatorAnnounce", "contracts/validator-announce"),
+];
+
+#[apply(as_task)]
+pub fn start_fuel_node() -> AgentHandles {
+    log!("Launching fuel-core...");
+    let node = Program::new("fuel-core")
+        .cmd("run")
+        .arg("db-type", "in-memory")
+        .arg("ip", "127.0.0.1")
+        .arg("port", FUEL_NODE_PORT)
+        .filter_logs(|_| false) // for now do not keep any of the fuel-core logs
+        .spawn("FUL");
+
+    sleep(Duration::from_secs(5));
+    node
+}
+
+abigen!(Contract(
+    name = "FuelMailbox",
+    abi = "chains/hyperlane-fuel/abis/Mailbox.abi.json"
+));
+
+/// Deploy the hyperlane contracts from a local hyperlane-sway checkout and
+/// write an agent config file for the fuel chain. Returns the path to the
+/// config file and the id of the mailbox.
+#[apply(as_task)]
+pub fn deploy_fuel_hyperlane_contracts(
+    contracts_path: PathBuf,
+) -> (PathBuf, String, impl ArbitraryData) {
+    let ids = FUEL_HYPERLANE_CONTRACTS
+        .iter()
+        .map(|&(name, path)| {
+            log!("Deploying fuel {} contract...", name);
+            (
+                name,
+                deploy_fuel_contract(&concat_path(&contracts_path, path)),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mailbox = ids
+        .iter()
+        .find(|(name, _)| *name == "mailbox")
+        .map(|(_, id)| id.clone())
+        .unwrap();
+    let addresses = ids
+        .iter()
+        .map(|(name, id)| format!("\"{name}\": \"{id}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let config = format!(
+        r#"{{"chains": {{"{FUEL_CHAIN_NAME}": {{"name": "{FUEL_CHAIN_NAME}", "domain": {FUEL_DOMAIN}, "addresses": {{{addresses}}}, "protocol": "fuel", "finalityBlocks": 0, "connection": {{"url": "{FUEL_NODE_URL}"}}, "index": {{"from": 0, "chunk": 100}}}}}}}}"#
+    );
+    let config_dir = tempdir().unwrap();
+    let config_path = concat_path(&config_dir, "fuel_config.json");
+    fs::write(&config_path, config).expect("Failed to write fuel agent config");
+    (config_path, mailbox, config_dir)
+}
+
+/// Find the recipient kathy deployed on the test chains in its output.
+pub fn kathy_recipient(kathy_output: &[String]) -> String {
+    kathy_output
+        .iter()
+        .find_map(|line| line.split("send to ").nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .map(str::to_owned)
+        .expect("Failed to find the kathy recipient")
+}
+
+/// Dispatch a message from the fuel mailbox to `recipient` on test1.
+#[apply(as_task)]
+pub fn dispatch_fuel_message(mailbox: String, recipient: String) {
+    log!("Dispatching fuel message to {}...", recipient);
+    let recipient =
+        Bits256::from_hex_str(&format!("0x{:0>64}", recipient.trim_start_matches("0x")))
+            .expect("Invalid recipient address");
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let provider = Provider::connect(FUEL_NODE_URL)
+            .await
+            .expect("Failed to connect to the fuel node");
+        let wallet =
+            WalletUnlocked::new_from_private_key(FUEL_SIGNER_KEY.parse().unwrap(), Some(provider));
+        let mailbox = ContractId::from_str(&mailbox).expect("Invalid fuel mailbox id");
+        FuelMailbox::new(Bech32ContractId::from(mailbox), wallet)
+            .methods()
+            .dispatch(
+                FUEL_MESSAGE_DESTINATION_DOMAIN,
+                recipient,
+                b"hello from fuel".to_vec(),
+            )
+            .call()
+            .await
+            .expect("Failed to dispatch fuel message");
+    });
+}
+
+fn deploy_fuel_contract(project_path: &Path) -> String {
+    let output = Program::new("forc")
+        .cmd("deploy")
+        .flag("unsigned")
+        .arg("node-url", FUEL_NODE_URL)
+        .arg("path", project_path.to_str().unwrap())
+        .run_with_output()
+        .join();
+    output
+        .iter()
+        .find_map(|line| line.split("Contract id: ").nth(1))
+        .map(|id| id.trim().to_owned())
+        .unwrap_or_else(|| panic!("Failed to deploy fuel contract {project_path:?}"))
+}
@@ -4,6 +4,7 @@ use crate::config::Config;
 use maplit::hashmap;
 
 use crate::fetch_metric;
+use crate::fuel::FUEL_CHAIN_NAME;
 use crate::logging::log;
 use crate::solana::solana_termination_invariants_met;
 
@@ -16,7 +17,9 @@ pub fn termination_invariants_met(
 ) -> eyre::Result<bool> {
     let eth_messages_expected = (config.kathy_messages / 2) as u32 * 2;
     let sol_messages_expected = 1;
-    let total_messages_expected = eth_messages_expected + sol_messages_expected;
+    let fuel_messages_expected = config.fuel_contracts_path.is_some() as u32;
+    let total_messages_expected =
+        eth_messages_expected + sol_messages_expected + fuel_messages_expected;
 
     let lengths = fetch_metric("9092", "hyperlane_submitter_queue_length", &hashmap! {})?;
     assert!(!lengths.is_empty(), "Could not find queue length metric");
@@ -40,6 +43,22 @@ pub fn termination_invariants_met(
         return Ok(false);
     }
 
+    let fuel_msg_processed_count = fetch_metric(
+        "9092",
+        "hyperlane_messages_processed_count",
+        &hashmap! {"origin" => FUEL_CHAIN_NAME},
+    )?
+    .iter()
+    .sum::<u32>();
+    if fuel_msg_processed_count != fuel_messages_expected {
+        log!(
+            "Relayer has {} processed fuel messages, expected {}",
+            fuel_msg_processed_count,
+            fuel_messages_expected
+        );
+        return Ok(false);
+    }
+
     let gas_payment_events_count = fetch_metric(
         "9092",
         "hyperlane_contract_sync_stored_events",
@@ -11,6 +11,11 @@
 //! the end conditions are met, the test is a failure. Defaults to 10 min.
 //! - `E2E_KATHY_MESSAGES`: Number of kathy messages to dispatch. Defaults to 16 if CI mode is enabled.
 //! else false.
+//! - `E2E_FUEL_CONTRACTS_PATH`: Path to a local hyperlane-sway checkout. If
+//!   set, a local `fuel-core` node is started, the contracts are deployed to it
+//!   with `forc` and the agents are run against the `fueltest1` chain as well.
+//!   A message dispatched on `fueltest1` has to be relayed to `test1` for the
+//!   test to pass. Requires `fuel-core` and `forc` to be installed.
 
 use std::path::Path;
 use std::{
@@ -29,12 +34,14 @@ use program::Program;
 
 use crate::config::Config;
 use crate::ethereum::start_anvil;
+use crate::fuel::*;
 use crate::invariants::termination_invariants_met;
 use crate::solana::*;
 use crate::utils::{concat_path, make_static, stop_child, AgentHandles, ArbitraryData, TaskHandle};
 
 mod config;
 mod ethereum;
+mod fuel;
 mod invariants;
 mod logging;
 mod metrics;
@@ -65,6 +72,10 @@ const VALIDATOR_KEYS: &[&str] = &[
     // sealevel
     "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
 ];
+/// Validator key for the optional fuel chain, from hardhat/anvil's testing
+/// accounts.
+const FUEL_VALIDATOR_KEY: &str =
+    "0x7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6";
 
 const VALIDATOR_ORIGIN_CHAINS: &[&str] = &["test1", "test2", "test3", "sealeveltest1"];
 
@@ -131,6 +142,19 @@ fn main() -> ExitCode {
 
     let config = Config::load();
 
+    // The fuel node has to be running before its contracts can be deployed
+    // and the agent config for it can be written.
+    let fuel = config.fuel_contracts_path.clone().map(|contracts_path| {
+        let fuel_node = start_fuel_node().join();
+        let (config_path, mailbox, config_dir) =
+            deploy_fuel_hyperlane_contracts(contracts_path).join();
+        (fuel_node, config_path, mailbox, config_dir)
+    });
+    let fuel_config_path = fuel
+        .as_ref()
+        .map(|(_, config_path, _, _)| config_path.clone());
+    let fuel_mailbox = fuel.as_ref().map(|(_, _, mailbox, _)| mailbox.clone());
+
     let solana_checkpoint_path = Path::new(SOLANA_CHECKPOINT_LOCATION);
     fs::remove_dir_all(solana_checkpoint_path).unwrap_or_default();
     let checkpoints_dirs: Vec<DynPath> = (0..VALIDATOR_COUNT - 1)
@@ -143,13 +167,20 @@ fn main() -> ExitCode {
         .map(|i| concat_path(&rocks_db_dir, format!("validator{i}")))
         .collect::<Vec<_>>();
 
-    let common_agent_env = Program::default()
+    let mut common_agent_env = Program::default()
         .env("RUST_BACKTRACE", "full")
         .hyp_env("TRACING_FMT", "compact")
         .hyp_env("TRACING_LEVEL", "debug")
         .hyp_env("CHAINS_TEST1_INDEX_CHUNK", "1")
         .hyp_env("CHAINS_TEST2_INDEX_CHUNK", "1")
         .hyp_env("CHAINS_TEST3_INDEX_CHUNK", "1");
+    let mut relay_chains = vec!["test1", "test2", "test3", "sealeveltest1", "sealeveltest2"];
+    if let Some(fuel_config_path) = &fuel_config_path {
+        common_agent_env = common_agent_env
+            .env("CONFIG_FILES", fuel_config_path.to_str().unwrap())
+            .hyp_env("CHAINS_FUELTEST1_SIGNER_KEY", FUEL_SIGNER_KEY);
+        relay_chains.push(FUEL_CHAIN_NAME);
+    }
 
     let relayer_env = common_agent_env
         .clone()
@@ -177,10 +208,7 @@ fn main() -> ExitCode {
         )
         // default is used for TEST3
         .arg("defaultSigner.key", RELAYER_KEYS[2])
-        .arg(
-            "relayChains",
-            "test1,test2,test3,sealeveltest1,sealeveltest2",
-        );
+        .arg("relayChains", relay_chains.join(","));
 
     let base_validator_env = common_agent_env
         .clone()
@@ -200,6 +228,25 @@ fn main() -> ExitCode {
         .hyp_env("INTERVAL", "5")
         .hyp_env("CHECKPOINTSYNCER_TYPE", "localStorage");
 
+    let fuel_validator_checkpoints_dir = tempdir().unwrap();
+    let fuel_validator_env = fuel_config_path.as_ref().map(|_| {
+        base_validator_env
+            .clone()
+            .hyp_env("METRICS", (9094 + VALIDATOR_COUNT).to_string())
+            .hyp_env(
+                "DB",
+                concat_path(&rocks_db_dir, "validator_fuel")
+                    .to_str()
+                    .unwrap(),
+            )
+            .hyp_env("ORIGINCHAINNAME", FUEL_CHAIN_NAME)
+            .hyp_env("VALIDATOR_KEY", FUEL_VALIDATOR_KEY)
+            .hyp_env(
+                "CHECKPOINTSYNCER_PATH",
+                fuel_validator_checkpoints_dir.path().to_str().unwrap(),
+            )
+    });
+
     let validator_envs = (0..VALIDATOR_COUNT)
         .map(|i| {
             base_validator_env
@@ -231,6 +278,10 @@ fn main() -> ExitCode {
         );
 
     let mut state = State::default();
+    if let Some((fuel_node, _, _, fuel_config_dir)) = fuel {
+        state.push_agent(fuel_node);
+        state.data.push(Box::new(fuel_config_dir));
+    }
 
     log!(
         "Signed checkpoints in {}",
@@ -311,7 +362,13 @@ fn main() -> ExitCode {
         .cmd("kathy")
         .arg("messages", (config.kathy_messages / 2).to_string())
         .arg("timeout", "1000");
-    kathy_env.clone().run().join();
+    if let Some(fuel_mailbox) = fuel_mailbox {
+        // Send a message out of fuel to the recipient kathy deployed
+        let kathy_output = kathy_env.clone().run_with_output().join();
+        dispatch_fuel_message(fuel_mailbox, kathy_recipient(&kathy_output)).join();
+    } else {
+        kathy_env.clone().run().join();
+    }
 
     // spawn the rest of the validators
     for (i, validator_env) in validator_envs.into_iter().enumerate().skip(1) {
@@ -319,6 +376,10 @@ fn main() -> ExitCode {
         state.push_agent(validator);
     }
 
+    if let Some(fuel_validator_env) = fuel_validator_env {
+        state.push_agent(fuel_validator_env.spawn("VLF"));
+    }
+
     state.push_agent(relayer_env.spawn("RLY"));
 
     initiate_solana_hyperlane_transfer(solana_path.clone(), solana_config_path.clone()).join();
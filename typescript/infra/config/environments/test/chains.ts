@@ -1,4 +1,5 @@
 import { ChainMap, ChainMetadata, chainMetadata } from '@hyperlane-xyz/sdk';
+import { ProtocolType } from '@hyperlane-xyz/utils';
 
 import { AgentChainNames, Role } from '../../../src/roles';
 
@@ -8,6 +9,18 @@ export const testConfigs: ChainMap<ChainMetadata> = {
   test3: chainMetadata.test3,
 };
 
+// The fuel chain the e2e test optionally runs. Nothing is deployed to it from
+// here, but the test chains' default ISMs accept messages from it.
+export const fuelTestConfig: ChainMetadata = {
+  chainId: 13374,
+  domainId: 13374,
+  name: 'fueltest1',
+  protocol: ProtocolType.Fuel,
+  displayName: 'Fuel Test 1',
+  rpcUrls: [{ http: 'http://127.0.0.1:4000' }],
+  isTestnet: true,
+};
+
 export type TestChains = keyof typeof testConfigs;
 export const chainNames = Object.keys(testConfigs) as TestChains[];
 
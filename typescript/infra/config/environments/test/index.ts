@@ -5,7 +5,7 @@ import { MultiProvider } from '@hyperlane-xyz/sdk';
 import { EnvironmentConfig } from '../../../src/config';
 
 import { agents } from './agent';
-import { testConfigs } from './chains';
+import { fuelTestConfig, testConfigs } from './chains';
 import { core } from './core';
 import { storageGasOracleConfig } from './gas-oracle';
 import { hooks } from './hooks';
@@ -28,6 +28,7 @@ export const environment: EnvironmentConfig = {
     const provider = mp.getProvider('test1') as JsonRpcProvider;
     const signer = provider.getSigner(0);
     mp.setSharedSigner(signer);
+    mp.addChain(fuelTestConfig);
     return mp;
   },
   storageGasOracleConfig,
@@ -6,6 +6,8 @@ export const chainToValidator: Record<string, string> = {
   test1: '0x15d34AAf54267DB7D7c367839AAf71A00a2C6A65',
   test2: '0x9965507D1a55bcC2695C58ba16FB37d819B0A4dc',
   test3: '0x976EA74026E726554dB657fA54763abd0C3a0aa9',
+  // Validator of the optional fuel chain of the e2e test, anvil account 3
+  fueltest1: '0x90F79bf6EB2c4f870365E785982E1f101E93b906',
 };
 
 export const legacyMultisig = (validatorKey: string): MultisigIsmConfig => {
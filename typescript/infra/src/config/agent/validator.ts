@@ -45,12 +45,14 @@ export interface HelmValidatorValues extends HelmStatefulSetValues {
 
 export type CheckpointSyncerConfig =
   | LocalCheckpointSyncerConfig
-  | S3CheckpointSyncerConfig;
+  | S3CheckpointSyncerConfig
+  | GcsCheckpointSyncerConfig;
 
 // These values are eventually passed to Rust, which expects the values to be camelCase
 export const enum CheckpointSyncerType {
   LocalStorage = 'localStorage',
   S3 = 's3',
+  Gcs = 'gcs',
 }
 
 export interface LocalCheckpointSyncerConfig {
@@ -64,6 +66,12 @@ export interface S3CheckpointSyncerConfig {
   region: string;
 }
 
+export interface GcsCheckpointSyncerConfig {
+  type: CheckpointSyncerType.Gcs;
+  bucket: string;
+  folder?: string;
+}
+
 export class ValidatorConfigHelper extends AgentConfigHelper<
   Array<ValidatorConfig>
 > {
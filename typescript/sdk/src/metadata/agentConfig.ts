@@ -26,11 +26,13 @@ export enum AgentConnectionType {
   Ws = 'ws',
   HttpQuorum = 'httpQuorum',
   HttpFallback = 'httpFallback',
+  HttpCrossChecked = 'httpCrossChecked',
 }
 
 export enum AgentConsensusType {
   Fallback = 'fallback',
   Quorum = 'quorum',
+  CrossChecked = 'crossChecked',
 }
 
 export enum AgentLogLevel {
@@ -52,6 +54,7 @@ export enum AgentLogFormat {
 export enum AgentIndexMode {
   Block = 'block',
   Sequence = 'sequence',
+  Subscription = 'subscription',
 }
 
 export const AgentSignerSchema = z.union([
@@ -75,6 +78,27 @@ export const AgentSignerSchema = z.union([
       type: z.literal('node'),
     })
     .describe('Assume the local node will sign on RPC calls automatically'),
+  z
+    .object({
+      type: z.literal('remote'),
+      url: z.string().url().describe('The JSON-RPC url of the remote signer'),
+      address: ZHash.describe('The address of the key the remote signer holds'),
+      clientCert: z
+        .string()
+        .optional()
+        .describe('Path to the PEM client certificate for TLS client auth'),
+      clientKey: z
+        .string()
+        .optional()
+        .describe('Path to the PEM private key of the client certificate'),
+      caCert: z
+        .string()
+        .optional()
+        .describe('Path to a PEM CA certificate to trust for the remote signer'),
+    })
+    .describe(
+      'A remote signer, such as Web3Signer, that signs messages with eth_sign. It cannot sign transactions.',
+    ),
 ]);
 
 export type AgentSignerV2 = z.infer<typeof AgentSignerSchema>;
@@ -102,6 +126,9 @@ export const AgentChainMetadataSchema = ChainMetadataSchema.merge(
     .nativeEnum(AgentConsensusType)
     .describe('The consensus type to use when multiple RPCs are configured.')
     .optional(),
+  rpcQuorum: ZUint.optional().describe(
+    'How many RPCs have to agree on critical reads when using the crossChecked consensus type. Defaults to a majority.',
+  ),
   signer: AgentSignerSchema.optional().describe(
     'The signer to use for this chain',
   ),
@@ -119,7 +146,31 @@ export const AgentChainMetadataSchema = ChainMetadataSchema.merge(
       .describe(
         'The indexing method to use for this chain; will attempt to choose a suitable default if not specified.',
       ),
+    pollInterval: ZNzUint.optional().describe(
+      'How many seconds to wait before checking for new events once sequence-based indexing has caught up. Defaults to 5.',
+    ),
   }),
+  gasEscalator: z
+    .object({
+      bumpIntervalSecs: ZNzUint.optional().describe(
+        'How long to wait for a transaction to be included before replacing it with one paying a higher gas price. Defaults to 60.',
+      ),
+      bumpPercent: ZUint.gte(10)
+        .optional()
+        .describe(
+          'The percentage to raise the gas price by with each replacement. Defaults to 12.',
+        ),
+      maxBumps: ZUint.optional().describe(
+        'The maximum number of times to replace a transaction. Defaults to 0, which never replaces transactions.',
+      ),
+      maxGasPrice: ZUWei.optional().describe(
+        'The gas price, or max fee per gas, in wei that replacements may not exceed.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Replace transactions that are stuck waiting to be included. Only used by Ethereum chains.',
+    ),
 });
 
 export type AgentChainMetadata = z.infer<typeof AgentChainMetadataSchema>;
@@ -192,6 +243,44 @@ const GasPaymentEnforcementSchema = z.union([
       'An optional matching list, any message that matches will use this policy. By default all messages will match.',
     ),
   }),
+  GasPaymentEnforcementBaseSchema.extend({
+    type: z.literal('valueBased'),
+    tokenPrices: z
+      .record(
+        z.object({
+          price: z
+            .string()
+            .regex(/^(\d+\.?\d*|\.\d+)$/)
+            .describe(
+              'Price of one whole token as a decimal, in a unit shared by all chains, e.g. USD.',
+            ),
+          decimals: z.number().int().nonnegative().optional(),
+        }),
+      )
+      .describe(
+        'Price of the native token of each domain, keyed by domain id.',
+      ),
+    gasPrices: z
+      .record(ZUWei)
+      .optional()
+      .describe(
+        'Gas prices in wei to use instead of the estimated gas price, keyed by destination domain id.',
+      ),
+    paymentFraction: z
+      .string()
+      .regex(/^\d+ ?\/ ?[1-9]\d*$/)
+      .optional()
+      .describe(
+        'Fraction of the estimated cost which must be paid, defaults to `1/1`.',
+      ),
+    maxSenderDeficit: z
+      .string()
+      .regex(/^(\d+\.?\d*|\.\d+)$/)
+      .optional()
+      .describe(
+        'Once delivering the messages of a sender has cost this much more than they paid, in the unit of the token prices, they must pay the full estimated cost.',
+      ),
+  }),
 ]);
 
 export type GasPaymentEnforcement = z.infer<typeof GasPaymentEnforcementSchema>;
@@ -229,12 +318,31 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
   skipTransactionGasLimitFor: CommaSeperatedDomainList.optional().describe(
     'Comma separated List of chain names to skip applying the transaction gas limit to.',
   ),
+  maxSubmissionsInFlight: z
+    .union([z.record(z.number().int().positive()), z.string().nonempty()])
+    .optional()
+    .describe(
+      'JSON object of destination domain ids to the number of transactions that may be in flight at once when delivering to them. Destinations not listed have at most one transaction in flight.',
+    ),
+  maxBatchSize: z
+    .union([z.record(z.number().int().positive()), z.string().nonempty()])
+    .optional()
+    .describe(
+      'JSON object of destination domain ids to the number of messages that may be delivered in a single transaction to them. Destinations not listed have each message delivered on its own.',
+    ),
   allowLocalCheckpointSyncers: z
     .boolean()
     .optional()
     .describe(
       'If true, allows local storage based checkpoint syncers. Not intended for production use.',
     ),
+  operatorApiToken: z
+    .string()
+    .nonempty()
+    .optional()
+    .describe(
+      'Bearer token required by the HTTP API for inspecting and managing the message queues. The API is only served if this is set.',
+    ),
 });
 
 export type RelayerConfig = z.infer<typeof RelayerAgentConfigSchema>;
@@ -248,6 +356,38 @@ export const ScraperAgentConfigSchema = AgentConfigSchema.extend({
 
 export type ScraperConfig = z.infer<typeof ScraperAgentConfigSchema>;
 
+const CheckpointSyncerSchema = z.discriminatedUnion('type', [
+  z
+    .object({
+      type: z.literal('localStorage'),
+      path: z
+        .string()
+        .nonempty()
+        .describe('Path to the local storage location'),
+    })
+    .describe('A local checkpoint syncer'),
+  z
+    .object({
+      type: z.literal('s3'),
+      bucket: z.string().nonempty(),
+      region: z.string().nonempty(),
+    })
+    .describe('A checkpoint syncer that uses S3'),
+  z
+    .object({
+      type: z.literal('gcs'),
+      bucket: z.string().nonempty(),
+      folder: z
+        .string()
+        .nonempty()
+        .optional()
+        .describe('The folder in the bucket to store checkpoints in'),
+    })
+    .describe(
+      'A checkpoint syncer that uses GCS. Writes are authenticated as the service account of the GCP instance.',
+    ),
+]);
+
 export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
@@ -259,27 +399,32 @@ export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
     .nonempty()
     .describe('Name of the chain to validate messages on'),
   validator: AgentSignerSchema.describe('The validator attestation signer'),
-  checkpointSyncer: z.discriminatedUnion('type', [
-    z
-      .object({
-        type: z.literal('localStorage'),
-        path: z
-          .string()
-          .nonempty()
-          .describe('Path to the local storage location'),
-      })
-      .describe('A local checkpoint syncer'),
-    z
-      .object({
-        type: z.literal('s3'),
-        bucket: z.string().nonempty(),
-        region: z.string().nonempty(),
-      })
-      .describe('A checkpoint syncer that uses S3'),
-  ]),
+  checkpointSyncer: CheckpointSyncerSchema,
   interval: ZUint.optional().describe(
     'How long to wait between checking for new checkpoints in seconds.',
   ),
+  verifyCheckpoints: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, only signs checkpoints that match a merkle tree built from the indexed messages.',
+    ),
+  rotation: z
+    .object({
+      validator: AgentSignerSchema.describe(
+        'The validator signer to rotate to',
+      ),
+      checkpointSyncer: CheckpointSyncerSchema.describe(
+        'The checkpoint syncer for checkpoints signed with the new key',
+      ),
+      overlap: ZUint.describe(
+        'How long to sign checkpoints with both keys once the new key is announced, in seconds',
+      ),
+    })
+    .optional()
+    .describe(
+      'A key to rotate to. The current key stops signing once the overlap ends.',
+    ),
 });
 
 export type ValidatorConfig = z.infer<typeof ValidatorAgentConfigSchema>;
@@ -300,7 +445,12 @@ export type AgentConnection =
   | { type: AgentConnectionType.Http; url: string }
   | { type: AgentConnectionType.Ws; url: string }
   | { type: AgentConnectionType.HttpQuorum; urls: string }
-  | { type: AgentConnectionType.HttpFallback; urls: string };
+  | { type: AgentConnectionType.HttpFallback; urls: string }
+  | {
+      type: AgentConnectionType.HttpCrossChecked;
+      urls: string;
+      quorum?: number;
+    };
 
 export interface AgentChainSetupBase {
   name: ChainName;
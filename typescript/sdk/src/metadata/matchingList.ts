@@ -4,18 +4,49 @@
  */
 import { z } from 'zod';
 
-import { ZHash, ZNzUint } from './customZodTypes';
+import { ZHash, ZNzUint, ZUint } from './customZodTypes';
 
-const DomainSchema = z.union([
+const numberFilter = (value: z.ZodNumber) => {
+  const base = z.union([
+    z.literal('*'),
+    value,
+    z.array(value).nonempty(),
+    z
+      .object({ min: value.optional(), max: value.optional() })
+      .strict()
+      .refine((range) => range.min !== undefined || range.max !== undefined, {
+        message: 'A range needs a min or max',
+      }),
+  ]);
+  return z.union([base, z.object({ not: base }).strict()]);
+};
+
+const DomainSchema = numberFilter(ZNzUint);
+
+const VersionSchema = numberFilter(ZUint);
+
+const BaseAddressSchema = z.union([
   z.literal('*'),
-  ZNzUint,
-  z.array(ZNzUint).nonempty(),
+  ZHash,
+  z.array(ZHash).nonempty(),
 ]);
 
 const AddressSchema = z.union([
+  BaseAddressSchema,
+  z.object({ not: BaseAddressSchema }).strict(),
+]);
+
+const HexPrefix = z.string().regex(/^(0x)?([0-9a-fA-F]{2})*$/);
+
+const BaseBodyPrefixSchema = z.union([
   z.literal('*'),
-  ZHash,
-  z.array(ZHash).nonempty(),
+  HexPrefix,
+  z.array(HexPrefix).nonempty(),
+]);
+
+const BodyPrefixSchema = z.union([
+  BaseBodyPrefixSchema,
+  z.object({ not: BaseBodyPrefixSchema }).strict(),
 ]);
 
 const MatchingListElementSchema = z.object({
@@ -23,6 +54,9 @@ const MatchingListElementSchema = z.object({
   senderAddress: AddressSchema.optional(),
   destinationDomain: DomainSchema.optional(),
   recipientAddress: AddressSchema.optional(),
+  messageId: AddressSchema.optional(),
+  version: VersionSchema.optional(),
+  bodyPrefix: BodyPrefixSchema.optional(),
 });
 
 export const MatchingListSchema = z.array(MatchingListElementSchema).nonempty();